@@ -108,6 +108,7 @@ fn bench_file_size_no_file_io(c: &mut Criterion) {
                 file_count,
                 file_size,
             }),
+            profile: None,
             #[cfg(feature = "test")]
             created_at: std::time::SystemTime::now(),
         };
@@ -258,6 +259,7 @@ async fn benchmark_create_backup(
         description: &format!("Benchmark {}", unique_hex().unwrap()),
         blueprint,
         additional_archive_data: None,
+        profile: None,
         #[cfg(feature = "test")]
         created_at: std::time::SystemTime::now(),
     };
@@ -15,8 +15,9 @@ use prose_backup::{
     BackupService,
     archiving::ArchivingContext,
     config::{
-        CachingConfig, CompressionConfig, CompressionZstdConfig, DownloadConfig, HashingAlgorithm,
-        HashingConfig,
+        ArchiveConfig, BytesAmount, CachingConfig, CompressionConfig, CompressionZstdConfig,
+        DownloadConfig, HashingAlgorithm, HashingConfig, ProgressConfig, QuotaConfig,
+        RestorationConfig, SnapshotConfig, SnapshotMethod, TransparencyLogConfig,
     },
     decryption::DecryptionContext,
     restoration::RestorationContext,
@@ -39,6 +40,14 @@ pub fn sinking_service(
         archiving_context: ArchivingContext {
             blueprints: HashMap::new(),
         },
+        archive_config: ArchiveConfig {
+            deterministic: false,
+            max_mtime: None,
+            xattrs: false,
+            acls: false,
+            sparse: false,
+            hardlinks: false,
+        },
         compression_config: CompressionConfig::Zstd {
             config: CompressionZstdConfig { compression_level },
         },
@@ -50,18 +59,35 @@ pub fn sinking_service(
         verification_context: VerificationContext::default(),
         decryption_context: DecryptionContext::default(),
         restoration_context: RestorationContext::default(),
+        restoration_config: RestorationConfig::default(),
+        transparency_log_config: TransparencyLogConfig::default(),
+        snapshot_config: SnapshotConfig {
+            enabled: false,
+            method: SnapshotMethod::Auto,
+            staging_dir: tempfile::env::temp_dir(),
+            lvm_snapshot_size: BytesAmount::GibiBytes(1),
+        },
         download_config: DownloadConfig {
             url_max_ttl: std::time::Duration::ZERO,
         },
+        quota_config: QuotaConfig {
+            max_size: None,
+            warning_threshold: 0.8,
+            growth_estimation_window: std::time::Duration::from_hours(24 * 30),
+        },
+        progress_config: ProgressConfig {
+            interval: std::time::Duration::from_millis(100),
+            throughput_window: std::time::Duration::from_secs(10),
+        },
         backup_store: CachedStore::new(
-            Box::new(SinkStore),
+            Arc::new(SinkStore),
             Arc::new(RwLock::new(StoreCache::default())),
             &CachingConfig {
                 cache_dir: tempfile::env::temp_dir(),
                 max_backup_cache_size: None,
             },
         ),
-        check_store: Box::new(SinkStore),
+        check_store: Arc::new(SinkStore),
     }
 }
 
@@ -81,6 +107,14 @@ pub fn fs_service(
         archiving_context: ArchivingContext {
             blueprints: HashMap::new(),
         },
+        archive_config: ArchiveConfig {
+            deterministic: false,
+            max_mtime: None,
+            xattrs: false,
+            acls: false,
+            sparse: false,
+            hardlinks: false,
+        },
         compression_config: CompressionConfig::Zstd {
             config: CompressionZstdConfig { compression_level },
         },
@@ -92,18 +126,35 @@ pub fn fs_service(
         verification_context: VerificationContext::default(),
         decryption_context: DecryptionContext::default(),
         restoration_context: RestorationContext::default(),
+        restoration_config: RestorationConfig::default(),
+        transparency_log_config: TransparencyLogConfig::default(),
+        snapshot_config: SnapshotConfig {
+            enabled: false,
+            method: SnapshotMethod::Auto,
+            staging_dir: tempfile::env::temp_dir(),
+            lvm_snapshot_size: BytesAmount::GibiBytes(1),
+        },
         download_config: DownloadConfig {
             url_max_ttl: std::time::Duration::ZERO,
         },
+        quota_config: QuotaConfig {
+            max_size: None,
+            warning_threshold: 0.8,
+            growth_estimation_window: std::time::Duration::from_hours(24 * 30),
+        },
+        progress_config: ProgressConfig {
+            interval: std::time::Duration::from_millis(100),
+            throughput_window: std::time::Duration::from_secs(10),
+        },
         backup_store: CachedStore::new(
-            Box::new(store.clone()),
+            Arc::new(store.clone()),
             Arc::new(RwLock::new(StoreCache::default())),
             &CachingConfig {
                 cache_dir: tempfile::env::temp_dir(),
                 max_backup_cache_size: None,
             },
         ),
-        check_store: Box::new(store),
+        check_store: Arc::new(store),
     }
 }
 
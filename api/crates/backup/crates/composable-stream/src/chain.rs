@@ -0,0 +1,133 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+/// A [`Read`]er that reads multiple sources one after the other, like
+/// [`Read::chain`] but for any number of sources.
+///
+/// Sources are pulled from an iterator only when the previous one is
+/// exhausted, which means they can be opened lazily (e.g. files).
+///
+/// [`Read`]: std::io::Read
+/// [`Read::chain`]: std::io::Read::chain
+pub struct Chain<I: Iterator> {
+    current: Option<I::Item>,
+    rest: I,
+}
+
+/// Chain readers, in order.
+///
+/// ```
+/// use std::io::Read as _;
+///
+/// let mut res = String::new();
+/// composable_stream::chain(["foo", "bar", "baz"].map(str::as_bytes))
+///     .read_to_string(&mut res)?;
+///
+/// assert_eq!(res, "foobarbaz");
+/// #
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[inline]
+pub fn chain<I>(sources: I) -> Chain<I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: std::io::Read,
+{
+    let mut rest = sources.into_iter();
+    Chain {
+        current: rest.next(),
+        rest,
+    }
+}
+
+impl<I: Iterator> Chain<I> {
+    /// The source currently being read, if any.
+    #[inline]
+    pub fn current(&self) -> Option<&I::Item> {
+        self.current.as_ref()
+    }
+}
+
+// MARK: - Boilerplate
+
+impl<I> std::io::Read for Chain<I>
+where
+    I: Iterator,
+    I::Item: std::io::Read,
+{
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        while let Some(ref mut reader) = self.current {
+            match reader.read(buf)? {
+                0 => self.current = self.rest.next(),
+                n => return Ok(n),
+            }
+        }
+
+        Ok(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read as _;
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_chain_empty() -> Result<(), std::io::Error> {
+        let mut res = Vec::new();
+        chain(Vec::<&[u8]>::new()).read_to_end(&mut res)?;
+
+        assert!(res.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_chain_skips_empty_sources() -> Result<(), std::io::Error> {
+        let mut res = Vec::new();
+        chain([
+            &[1u8, 2][..],
+            &[],
+            &[],
+            &[3],
+        ])
+        .read_to_end(&mut res)?;
+
+        assert_eq!(res.as_slice(), [1, 2, 3]);
+
+        Ok(())
+    }
+
+    proptest! {
+        /// Chaining readers gives the concatenation of all sources.
+        #[test]
+        fn prop_chain_concatenates(
+            sources in prop::collection::vec(prop::collection::vec(any::<u8>(), 0..64), 0..16),
+            buf_len in 1usize..32,
+        ) {
+            let mut reader = chain(sources.iter().map(Vec::as_slice));
+
+            let mut res = Vec::new();
+            let mut buf = vec![0u8; buf_len];
+            loop {
+                let n = reader.read(&mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                res.extend_from_slice(&buf[..n]);
+            }
+
+            prop_assert_eq!(res, sources.concat());
+        }
+    }
+}
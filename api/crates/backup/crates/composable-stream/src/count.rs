@@ -0,0 +1,110 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use crate::ComposableStreamBuilder;
+
+/// A [`Read`]/[`Write`] wrapper that counts bytes going through it.
+///
+/// [`Read`]: std::io::Read
+/// [`Write`]: std::io::Write
+pub struct Count<S> {
+    inner: S,
+    count: u64,
+}
+
+impl<S> Count<S> {
+    #[inline]
+    pub fn new(inner: S) -> Self {
+        Self { inner, count: 0 }
+    }
+
+    /// Number of bytes read or written so far.
+    #[inline]
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    #[inline]
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    #[inline]
+    pub fn into_parts(self) -> (S, u64) {
+        (self.inner, self.count)
+    }
+}
+
+#[inline]
+pub fn count<S, Err>() -> ComposableStreamBuilder<impl FnOnce(S) -> Result<Count<S>, Err>> {
+    ComposableStreamBuilder {
+        make: move |stream: S| Ok(Count::new(stream)),
+    }
+}
+
+// MARK: - Boilerplate
+
+impl<W: std::io::Write> std::io::Write for Count<W> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.count += n as u64;
+        Ok(n)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for Count<R> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count += n as u64;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read as _, Write as _};
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn prop_count_writes(
+            chunks in prop::collection::vec(prop::collection::vec(any::<u8>(), 0..64), 0..32),
+        ) {
+            let mut writer = Count::new(Vec::<u8>::new());
+            for chunk in chunks.iter() {
+                writer.write_all(chunk).unwrap();
+            }
+
+            let (res, count) = writer.into_parts();
+            prop_assert_eq!(count, res.len() as u64);
+            prop_assert_eq!(res, chunks.concat());
+        }
+
+        #[test]
+        fn prop_count_reads(data in prop::collection::vec(any::<u8>(), 0..1024)) {
+            let mut reader = Count::new(data.as_slice());
+            let mut res = Vec::new();
+            reader.read_to_end(&mut res).unwrap();
+
+            prop_assert_eq!(reader.count(), data.len() as u64);
+            prop_assert_eq!(res, data);
+        }
+    }
+}
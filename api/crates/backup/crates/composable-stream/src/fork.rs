@@ -0,0 +1,400 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use crate::ComposableStreamBuilder;
+
+/// A [`Write`]r that duplicates input into _n_ underlying `Write`rs
+/// (its “branches”).
+///
+/// Branches are either a tuple of [`Branch`]es (up to 8, of any type) or
+/// a [`Vec`] of [`Branch`]es (of the same type).
+///
+/// Unlike [`Tee`](crate::Tee), every branch receives all bytes (using
+/// [`write_all`]) and errors are handled according to each branch’s
+/// [`ErrorPolicy`].
+///
+/// [`Write`]: std::io::Write
+/// [`write_all`]: std::io::Write::write_all
+pub struct Fork<B: Branches>(pub B);
+
+/// What to do when writing to a [`Branch`] fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// Fail the whole [`Fork`].
+    FailFast,
+
+    /// Stop writing to this branch, but keep writing to the other ones.
+    /// The error can be read afterwards (see [`Branch::error`]).
+    BestEffort,
+}
+
+/// A branch of a [`Fork`].
+pub struct Branch<W> {
+    writer: W,
+    policy: ErrorPolicy,
+    error: Option<std::io::Error>,
+}
+
+impl<W> Branch<W> {
+    #[inline]
+    pub fn new(writer: W, policy: ErrorPolicy) -> Self {
+        Self {
+            writer,
+            policy,
+            error: None,
+        }
+    }
+
+    /// Errors in this branch will fail the whole [`Fork`].
+    #[inline]
+    pub fn fail_fast(writer: W) -> Self {
+        Self::new(writer, ErrorPolicy::FailFast)
+    }
+
+    /// Errors in this branch will only stop this branch.
+    #[inline]
+    pub fn best_effort(writer: W) -> Self {
+        Self::new(writer, ErrorPolicy::BestEffort)
+    }
+
+    #[inline]
+    pub fn policy(&self) -> ErrorPolicy {
+        self.policy
+    }
+
+    /// The error which stopped this branch, if any.
+    ///
+    /// NOTE: Only [`ErrorPolicy::BestEffort`] branches can be stopped.
+    ///   Errors in [`ErrorPolicy::FailFast`] branches are returned directly.
+    #[inline]
+    pub fn error(&self) -> Option<&std::io::Error> {
+        self.error.as_ref()
+    }
+
+    #[inline]
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Returns the underlying writer, or the error which stopped this branch.
+    #[inline]
+    pub fn into_result(self) -> Result<W, std::io::Error> {
+        match self.error {
+            None => Ok(self.writer),
+            Some(error) => Err(error),
+        }
+    }
+
+    #[inline]
+    pub fn into_parts(self) -> (W, Option<std::io::Error>) {
+        (self.writer, self.error)
+    }
+}
+
+impl<W: std::io::Write> Branch<W> {
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        if self.error.is_some() {
+            return Ok(());
+        }
+
+        self.handle(|writer| writer.write_all(buf))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if self.error.is_some() {
+            return Ok(());
+        }
+
+        self.handle(W::flush)
+    }
+
+    #[inline]
+    fn handle(&mut self, f: impl FnOnce(&mut W) -> std::io::Result<()>) -> std::io::Result<()> {
+        match (f(&mut self.writer), self.policy) {
+            (Ok(()), _) => Ok(()),
+            (Err(error), ErrorPolicy::FailFast) => Err(error),
+            (Err(error), ErrorPolicy::BestEffort) => {
+                self.error = Some(error);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Branches of a [`Fork`].
+///
+/// NOTE: Implemented for tuples of [`Branch`]es (up to 8) and for
+///   `Vec<Branch<W>>`.
+pub trait Branches {
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()>;
+
+    fn flush(&mut self) -> std::io::Result<()>;
+}
+
+#[inline]
+pub fn fork<A, B: Branches, Err>(
+    branches: impl FnOnce(A) -> B,
+) -> ComposableStreamBuilder<impl FnOnce(A) -> Result<Fork<B>, Err>> {
+    ComposableStreamBuilder {
+        make: move |a: A| Ok(Fork(branches(a))),
+    }
+}
+
+// MARK: Composable fork
+
+impl<M> ComposableStreamBuilder<M> {
+    /// Fork the stream into _n_ branches. The stream passed when building
+    /// is given to `branches`, so it can be placed in any branch.
+    ///
+    /// ```
+    /// use composable_stream::{Branch, Fork};
+    ///
+    /// let Fork((_a, _b)) = composable_stream::builder::<_, std::io::Error>()
+    ///     .fork_with(|a| (Branch::fail_fast(a), Branch::best_effort(Vec::<u8>::new())))
+    ///     .build(Vec::<u8>::new())?;
+    /// #
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[inline]
+    pub fn fork_with<A, B, C, Err>(
+        self,
+        branches: impl FnOnce(A) -> B,
+    ) -> ComposableStreamBuilder<impl FnOnce(A) -> Result<C, Err>>
+    where
+        B: Branches,
+        M: FnOnce(Fork<B>) -> Result<C, Err>,
+    {
+        self.then(fork(branches))
+    }
+}
+
+// MARK: - Boilerplate
+
+impl<B: Branches> std::io::Write for Fork<B> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl<W: std::io::Write> Branches for Vec<Branch<W>> {
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        for branch in self.iter_mut() {
+            branch.write_all(buf)?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        for branch in self.iter_mut() {
+            branch.flush()?;
+        }
+        Ok(())
+    }
+}
+
+macro_rules! impl_branches_for_tuple {
+    ($($w:ident: $i:tt),+) => {
+        impl<$($w: std::io::Write),+> Branches for ($(Branch<$w>,)+) {
+            #[inline]
+            fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+                $(self.$i.write_all(buf)?;)+
+                Ok(())
+            }
+
+            #[inline]
+            fn flush(&mut self) -> std::io::Result<()> {
+                $(self.$i.flush()?;)+
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_branches_for_tuple!(W1: 0);
+impl_branches_for_tuple!(W1: 0, W2: 1);
+impl_branches_for_tuple!(W1: 0, W2: 1, W3: 2);
+impl_branches_for_tuple!(W1: 0, W2: 1, W3: 2, W4: 3);
+impl_branches_for_tuple!(W1: 0, W2: 1, W3: 2, W4: 3, W5: 4);
+impl_branches_for_tuple!(W1: 0, W2: 1, W3: 2, W4: 3, W5: 4, W6: 5);
+impl_branches_for_tuple!(W1: 0, W2: 1, W3: 2, W4: 3, W5: 4, W6: 5, W7: 6);
+impl_branches_for_tuple!(W1: 0, W2: 1, W3: 2, W4: 3, W5: 4, W6: 5, W7: 6, W8: 7);
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use proptest::prelude::*;
+
+    use crate::tests::util::*;
+
+    use super::*;
+
+    /// A writer which fails after `limit` bytes.
+    struct FailingWriter {
+        written: Vec<u8>,
+        limit: usize,
+    }
+
+    impl Write for FailingWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if self.written.len() + buf.len() > self.limit {
+                return Err(std::io::Error::other("Limit reached"));
+            }
+            self.written.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_fork_stream() -> Result<(), std::io::Error> {
+        let mut fork = Fork((
+            Branch::fail_fast(Vec::<u8>::new()),
+            Branch::fail_fast(Vec::<u8>::new()),
+            Branch::fail_fast(Vec::<u8>::new()),
+        ));
+
+        fork.write_all(&[1, 2, 3])?;
+
+        let Fork((res1, res2, res3)) = fork;
+
+        assert_eq!(res1.into_inner().as_slice(), [1, 2, 3]);
+        assert_eq!(res2.into_inner().as_slice(), [1, 2, 3]);
+        assert_eq!(res3.into_inner().as_slice(), [1, 2, 3]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fork_fail_fast() {
+        let mut fork = Fork((
+            Branch::fail_fast(Vec::<u8>::new()),
+            Branch::fail_fast(FailingWriter {
+                written: Vec::new(),
+                limit: 2,
+            }),
+        ));
+
+        assert!(fork.write_all(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_fork_best_effort() -> Result<(), std::io::Error> {
+        let mut fork = Fork((
+            Branch::fail_fast(Vec::<u8>::new()),
+            Branch::best_effort(FailingWriter {
+                written: Vec::new(),
+                limit: 2,
+            }),
+        ));
+
+        fork.write_all(&[1, 2])?;
+        fork.write_all(&[3])?;
+        fork.write_all(&[4])?;
+
+        let Fork((res1, res2)) = fork;
+
+        assert_eq!(res1.into_inner().as_slice(), [1, 2, 3, 4]);
+        assert!(res2.error().is_some());
+        assert_eq!(res2.into_inner().written.as_slice(), [1, 2]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_source_fork_with() -> Result<(), std::io::Error> {
+        let out2 = times_two::<_, std::convert::Infallible>()
+            .build(Vec::<u8>::new())
+            .unwrap_or_else(unreachable);
+        let writer = source(&[1, 2, 3])
+            .then(add_one())
+            .fork_with(|a| (Branch::fail_fast(a), Branch::fail_fast(out2)))
+            .build(Vec::<u8>::new())?;
+
+        let Fork((res1, out2)) = writer.into_inner();
+
+        assert_eq!(res1.into_inner().as_slice(), [2, 3, 4]);
+        assert_eq!(out2.into_inner().into_inner().as_slice(), [4, 6, 8]);
+
+        Ok(())
+    }
+
+    proptest! {
+        /// Every branch sees identical bytes, whatever the chunking.
+        #[test]
+        fn prop_fork_branches_see_identical_bytes(
+            chunks in prop::collection::vec(prop::collection::vec(any::<u8>(), 0..64), 0..32),
+            n_branches in 1usize..8,
+        ) {
+            let mut fork = Fork(
+                (0..n_branches)
+                    .map(|_| Branch::fail_fast(Vec::<u8>::new()))
+                    .collect::<Vec<_>>(),
+            );
+
+            for chunk in chunks.iter() {
+                fork.write_all(chunk).unwrap();
+            }
+            fork.flush().unwrap();
+
+            let expected: Vec<u8> = chunks.concat();
+            for branch in fork.0 {
+                prop_assert_eq!(branch.into_inner(), expected.clone());
+            }
+        }
+
+        /// Best-effort branches failing doesn’t affect other branches.
+        #[test]
+        fn prop_fork_best_effort_isolation(
+            chunks in prop::collection::vec(prop::collection::vec(any::<u8>(), 0..64), 0..32),
+            limit in 0usize..512,
+        ) {
+            let mut fork = Fork((
+                Branch::fail_fast(Vec::<u8>::new()),
+                Branch::best_effort(FailingWriter { written: Vec::new(), limit }),
+                Branch::fail_fast(Vec::<u8>::new()),
+            ));
+
+            for chunk in chunks.iter() {
+                fork.write_all(chunk).unwrap();
+            }
+
+            let expected: Vec<u8> = chunks.concat();
+            let Fork((res1, res2, res3)) = fork;
+            prop_assert_eq!(res1.into_inner(), expected.clone());
+            prop_assert_eq!(res3.into_inner(), expected.clone());
+            prop_assert_eq!(res2.error().is_some(), expected.len() > limit);
+            prop_assert!(expected.starts_with(&res2.into_inner().written));
+        }
+    }
+
+    fn source<W: Write>(
+        data: &[u8],
+    ) -> ComposableStreamBuilder<impl FnOnce(W) -> Result<W, std::io::Error>> {
+        ComposableStreamBuilder {
+            make: move |mut writer: W| {
+                writer.write_all(data)?;
+                Ok(writer)
+            },
+        }
+    }
+}
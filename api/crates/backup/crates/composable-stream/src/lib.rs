@@ -17,14 +17,24 @@
 //!
 //! See [`ComposableStreamBuilder::then`] for an example.
 
+mod chain;
+mod count;
 mod either;
+mod fork;
 mod option;
+mod tap;
 mod tee;
+mod throttle;
 
 pub use self::ComposableStreamBuilder as Builder;
+pub use self::chain::*;
+pub use self::count::*;
 pub use self::either::*;
+pub use self::fork::*;
 pub use self::option::*;
+pub use self::tap::*;
 pub use self::tee::*;
+pub use self::throttle::*;
 
 // MARK: - Builder
 
@@ -0,0 +1,114 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use crate::ComposableStreamBuilder;
+
+/// A [`Read`]/[`Write`] wrapper that passes bytes going through it to
+/// a callback (e.g. to report progress), without altering them.
+///
+/// NOTE: The callback only sees bytes the inner stream accepted (when
+///   writing) or produced (when reading).
+///
+/// [`Read`]: std::io::Read
+/// [`Write`]: std::io::Write
+pub struct Tap<S, F> {
+    inner: S,
+    f: F,
+}
+
+impl<S, F: FnMut(&[u8])> Tap<S, F> {
+    #[inline]
+    pub fn new(inner: S, f: F) -> Self {
+        Self { inner, f }
+    }
+
+    #[inline]
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    #[inline]
+    pub fn into_parts(self) -> (S, F) {
+        (self.inner, self.f)
+    }
+}
+
+#[inline]
+pub fn tap<S, F: FnMut(&[u8]), Err>(
+    f: F,
+) -> ComposableStreamBuilder<impl FnOnce(S) -> Result<Tap<S, F>, Err>> {
+    ComposableStreamBuilder {
+        make: move |stream: S| Ok(Tap::new(stream, f)),
+    }
+}
+
+// MARK: - Boilerplate
+
+impl<W: std::io::Write, F: FnMut(&[u8])> std::io::Write for Tap<W, F> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        (self.f)(&buf[..n]);
+        Ok(n)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<R: std::io::Read, F: FnMut(&[u8])> std::io::Read for Tap<R, F> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        (self.f)(&buf[..n]);
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read as _, Write as _};
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    proptest! {
+        /// The callback sees exactly the bytes which went through.
+        #[test]
+        fn prop_tap_writes(
+            chunks in prop::collection::vec(prop::collection::vec(any::<u8>(), 0..64), 0..32),
+        ) {
+            let mut tapped = Vec::<u8>::new();
+            let mut writer = Tap::new(Vec::<u8>::new(), |buf: &[u8]| tapped.extend_from_slice(buf));
+            for chunk in chunks.iter() {
+                writer.write_all(chunk).unwrap();
+            }
+
+            let res = writer.into_inner();
+            prop_assert_eq!(&res, &chunks.concat());
+            prop_assert_eq!(tapped, res);
+        }
+
+        #[test]
+        fn prop_tap_reads(data in prop::collection::vec(any::<u8>(), 0..1024)) {
+            let mut tapped = Vec::<u8>::new();
+            let mut reader = Tap::new(data.as_slice(), |buf: &[u8]| tapped.extend_from_slice(buf));
+            let mut res = Vec::new();
+            reader.read_to_end(&mut res).unwrap();
+            drop(reader);
+
+            prop_assert_eq!(&res, &data);
+            prop_assert_eq!(tapped, data);
+        }
+    }
+}
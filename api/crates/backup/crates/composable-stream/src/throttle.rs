@@ -0,0 +1,138 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use std::time::{Duration, Instant};
+
+use crate::ComposableStreamBuilder;
+
+/// A [`Read`]/[`Write`] wrapper that limits throughput to a given number of
+/// bytes per second, by blocking the current thread.
+///
+/// NOTE: Throughput is averaged since the first byte went through, meaning
+///   short bursts are allowed after idle periods.
+///
+/// [`Read`]: std::io::Read
+/// [`Write`]: std::io::Write
+pub struct Throttle<S> {
+    inner: S,
+    bytes_per_second: u64,
+    start: Option<Instant>,
+    bytes: u64,
+}
+
+impl<S> Throttle<S> {
+    /// NOTE: A `bytes_per_second` of `0` means no limit.
+    #[inline]
+    pub fn new(inner: S, bytes_per_second: u64) -> Self {
+        Self {
+            inner,
+            bytes_per_second,
+            start: None,
+            bytes: 0,
+        }
+    }
+
+    #[inline]
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Caps the size of a single operation so a call never blocks for
+    /// more than about a second.
+    #[inline]
+    fn max_chunk_len(&self, len: usize) -> usize {
+        match self.bytes_per_second {
+            0 => len,
+            limit => len.min(usize::try_from(limit).unwrap_or(usize::MAX)),
+        }
+    }
+
+    /// Blocks until `n` more bytes can go through without exceeding the limit.
+    fn wait(&mut self, n: usize) {
+        if self.bytes_per_second == 0 {
+            return;
+        }
+
+        let start = *self.start.get_or_insert_with(Instant::now);
+        self.bytes += n as u64;
+
+        let expected = Duration::from_secs_f64(self.bytes as f64 / self.bytes_per_second as f64);
+        if let Some(delay) = expected.checked_sub(start.elapsed()) {
+            std::thread::sleep(delay);
+        }
+    }
+}
+
+#[inline]
+pub fn throttle<S, Err>(
+    bytes_per_second: u64,
+) -> ComposableStreamBuilder<impl FnOnce(S) -> Result<Throttle<S>, Err>> {
+    ComposableStreamBuilder {
+        make: move |stream: S| Ok(Throttle::new(stream, bytes_per_second)),
+    }
+}
+
+// MARK: - Boilerplate
+
+impl<W: std::io::Write> std::io::Write for Throttle<W> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let len = self.max_chunk_len(buf.len());
+        let n = self.inner.write(&buf[..len])?;
+        self.wait(n);
+        Ok(n)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for Throttle<R> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = self.max_chunk_len(buf.len());
+        let n = self.inner.read(&mut buf[..len])?;
+        self.wait(n);
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write as _;
+
+    use super::*;
+
+    #[test]
+    fn test_throttle_limits_throughput() -> Result<(), std::io::Error> {
+        let mut writer = Throttle::new(Vec::<u8>::new(), 1000);
+
+        let start = Instant::now();
+        writer.write_all(&[0u8; 200])?;
+
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        assert_eq!(writer.into_inner().len(), 200);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_throttle_unlimited() -> Result<(), std::io::Error> {
+        let mut writer = Throttle::new(Vec::<u8>::new(), 0);
+
+        writer.write_all(&[1, 2, 3])?;
+
+        assert_eq!(writer.into_inner().as_slice(), [1, 2, 3]);
+
+        Ok(())
+    }
+}
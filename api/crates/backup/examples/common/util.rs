@@ -55,6 +55,7 @@ impl<'a> CreateBackupCommandExt<'a> for prose_backup::CreateBackupCommand<'a> {
             description,
             blueprint,
             additional_archive_data: None,
+            profile: None,
             #[cfg(feature = "test")]
             created_at: std::time::SystemTime::now(),
         }
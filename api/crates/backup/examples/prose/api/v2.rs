@@ -165,6 +165,7 @@ impl ProsePodServerApiV2 {
             description: &description,
             blueprint,
             additional_archive_data: Some(ProsePodApiData(prose_pod_api_data)),
+            profile: None,
             #[cfg(feature = "test")]
             created_at: std::time::SystemTime::now(),
         };
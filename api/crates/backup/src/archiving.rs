@@ -13,6 +13,7 @@ use std::path::{Path, PathBuf};
 use anyhow::{Context as _, anyhow, bail};
 use composable_stream::ComposableStreamBuilder;
 
+use crate::config::ArchiveConfig;
 use crate::decryption::{self, DecryptionContext, DecryptionEventHandler};
 use crate::event_handlers::NoopEventHandler;
 use crate::restoration::ExtractionError;
@@ -66,9 +67,22 @@ impl ArchiveBlueprint {
     }
 }
 
+/// WARN: Do not store data which varies between two backups of the same data
+///   (e.g. creation time, description), as it would make archives
+///   non-reproducible (see [`ArchiveConfig::deterministic`]). Such metadata
+///   belongs in the [`BackupId`].
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub(crate) struct BackupInternalMetadata {
     pub(crate) version: u8,
+
+    /// Name of the profile used to create the backup, if any (see
+    /// [`BackupProfileConfig`](crate::config::BackupProfileConfig)).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) profile: Option<String>,
+
+    /// Blueprint entries contained in the backup, `None` for all entries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) paths: Option<Vec<String>>,
 }
 
 // MARK: - Archiving
@@ -76,6 +90,7 @@ pub(crate) struct BackupInternalMetadata {
 /// Returns the expected size of the archive
 pub(crate) fn check_archiving_will_succeed<D: AdditionalData>(
     blueprint: &ArchiveBlueprint,
+    metadata: &BackupInternalMetadata,
     additional_data: &Option<D>,
 ) -> Result<u64, CannotArchive> {
     let additional_data_size = match additional_data {
@@ -91,16 +106,21 @@ pub(crate) fn check_archiving_will_succeed<D: AdditionalData>(
         }
     }
 
+    let metadata_size = json::to_vec(metadata)
+        .map_err(|err| CannotArchive::FailedComputingExpectedSize(anyhow::Error::from(err)))?
+        .len() as u64;
+
     let expected_size = TarSizeCalculator::estimate_tar_size(&blueprint.paths)
         .map_err(CannotArchive::FailedComputingExpectedSize)?
-        // Add `metadata.json` (≈13 bytes).
-        + TarSizeCalculator::file_entry_size(METADATA_FILE_NAME, 13)
+        + TarSizeCalculator::file_entry_size(METADATA_FILE_NAME, metadata_size)
         + additional_data_size;
 
     Ok(expected_size)
 }
 
-pub trait AdditionalData {
+/// NOTE: Must be [`Send`] and `'static` as archiving happens in a blocking
+///   task (see [`BackupService::create_backup`](crate::BackupService::create_backup)).
+pub trait AdditionalData: Send + 'static {
     /// TIP: Use [`TarSizeCalculator`] if needed.
     ///
     /// [`TarSizeCalculator`]: crate::archiving::TarSizeCalculator
@@ -126,6 +146,7 @@ fn archive_writer<W: Write, D: AdditionalData>(
     builder: &mut tar::Builder<W>,
     blueprint: &ArchiveBlueprint,
     additional_data: Option<D>,
+    config: &ArchiveConfig,
 ) -> Result<(), anyhow::Error> {
     // Add in-memory data first, to avoid filesystem I/O if it fails.
     if let Some(additional_data) = additional_data {
@@ -134,19 +155,33 @@ fn archive_writer<W: Write, D: AdditionalData>(
             .context("Could not archive additional data")?;
     }
 
+    // NOTE: Shared between all paths so hard links are detected
+    //   across blueprint entries.
+    let mut custom_archiver = if config.needs_custom_archiving() {
+        Some(custom::Archiver::new(config))
+    } else {
+        None
+    };
+
     for (archive_path, local_path) in blueprint.paths.iter() {
         let path = Path::new(local_path);
 
         if path.is_file() {
-            builder
-                .append_path_with_name(path, archive_path)
-                .with_context(|| format!("Could not archive file at '{}'", local_path.display()))?;
+            let res = if let Some(ref mut archiver) = custom_archiver {
+                (archiver.append_entry(builder, Path::new(archive_path), path)).map(|_is_dir| ())
+            } else {
+                (builder.append_path_with_name(path, archive_path)).map_err(anyhow::Error::from)
+            };
+            res.with_context(|| format!("Could not archive file at '{}'", local_path.display()))?;
         } else if path.is_dir() {
-            builder
-                .append_dir_all(archive_path, path)
-                .with_context(|| {
-                    format!("Could not archive directory at '{}'", local_path.display())
-                })?;
+            let res = if let Some(ref mut archiver) = custom_archiver {
+                archiver.append_dir_all(builder, Path::new(archive_path), path)
+            } else {
+                (builder.append_dir_all(archive_path, path)).map_err(anyhow::Error::from)
+            };
+            res.with_context(|| {
+                format!("Could not archive directory at '{}'", local_path.display())
+            })?;
         } else {
             bail!("'{}' does not exist.", local_path.display())
         }
@@ -160,21 +195,18 @@ fn archive_writer<W: Write, D: AdditionalData>(
 ///   the rest of the server’s data and creates the backup file.
 pub(crate) fn archive<W: Write, D: AdditionalData>(
     blueprint: &ArchiveBlueprint,
+    metadata: &BackupInternalMetadata,
     additional_data: Option<D>,
+    config: &ArchiveConfig,
 ) -> ComposableStreamBuilder<impl FnOnce(W) -> Result<tar::Builder<W>, CreateBackupError>> {
     ComposableStreamBuilder {
         make: move |writer: W| {
             let mut builder: tar::Builder<_> = tar::Builder::new(writer);
 
-            add_metadata_file(
-                &BackupInternalMetadata {
-                    version: blueprint.version,
-                },
-                &mut builder,
-            )
-            .map_err(CreateBackupError::ArchivingFailed)?;
+            add_metadata_file(metadata, &mut builder)
+                .map_err(CreateBackupError::ArchivingFailed)?;
 
-            archive_writer(&mut builder, blueprint, additional_data)
+            archive_writer(&mut builder, blueprint, additional_data, config)
                 .map_err(CreateBackupError::ArchivingFailed)?;
 
             Ok(builder)
@@ -201,6 +233,368 @@ fn add_metadata_file<W: std::io::Write>(
     Ok(())
 }
 
+/// Archiving for when [`tar::Builder`]’s defaults are not enough (see
+/// [`ArchiveConfig::needs_custom_archiving`]).
+mod custom {
+    use std::collections::HashMap;
+    use std::collections::hash_map::Entry;
+    use std::fs;
+    use std::io::Write;
+    use std::os::unix::fs::MetadataExt as _;
+    use std::path::{Path, PathBuf};
+
+    use anyhow::{Context as _, bail};
+
+    use crate::config::ArchiveConfig;
+
+    use super::{PAX_XATTR_PREFIX, is_acl_xattr, sparse};
+
+    pub(super) struct Archiver<'a> {
+        config: &'a ArchiveConfig,
+
+        /// Archive paths of files with multiple hard links, by `(dev, ino)`.
+        hard_links: HashMap<(u64, u64), PathBuf>,
+    }
+
+    impl<'a> Archiver<'a> {
+        pub(super) fn new(config: &'a ArchiveConfig) -> Self {
+            Self {
+                config,
+                hard_links: HashMap::new(),
+            }
+        }
+
+        /// Same as [`tar::Builder::append_dir_all`], except entries are sorted
+        /// by name and additional metadata is preserved (see [`ArchiveConfig`]).
+        pub(super) fn append_dir_all<W: Write>(
+            &mut self,
+            builder: &mut tar::Builder<W>,
+            archive_path: &Path,
+            local_path: &Path,
+        ) -> Result<(), anyhow::Error> {
+            // NOTE: Depth-first, like `tar::Builder::append_dir_all`.
+            let mut stack = vec![(archive_path.to_path_buf(), local_path.to_path_buf())];
+
+            while let Some((archive_path, local_path)) = stack.pop() {
+                let is_dir = self.append_entry(builder, &archive_path, &local_path)?;
+
+                if is_dir {
+                    let mut children = (fs::read_dir(&local_path).with_context(|| {
+                        format!("Could not read directory '{}'", local_path.display())
+                    })?)
+                    .map(|entry| entry.map(|entry| entry.file_name()))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                    // NOTE: Sort in reverse order as the stack is LIFO.
+                    children.sort_unstable_by(|a, b| b.cmp(a));
+
+                    for file_name in children {
+                        stack.push((archive_path.join(&file_name), local_path.join(&file_name)));
+                    }
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Appends a single file or directory (following symbolic links).
+        ///
+        /// Returns whether or not the entry is a directory.
+        pub(super) fn append_entry<W: Write>(
+            &mut self,
+            builder: &mut tar::Builder<W>,
+            archive_path: &Path,
+            local_path: &Path,
+        ) -> Result<bool, anyhow::Error> {
+            let metadata = fs::metadata(local_path).with_context(|| {
+                format!("Could not read metadata of '{}'", local_path.display())
+            })?;
+
+            if !(metadata.is_dir() || metadata.is_file()) {
+                bail!(
+                    "'{}' is neither a file nor a directory, it cannot be archived \
+                    with the current configuration.",
+                    local_path.display()
+                )
+            }
+
+            let mut header = tar::Header::new_gnu();
+            header.set_metadata_in_mode(&metadata, tar::HeaderMode::Complete);
+            if self.config.deterministic {
+                normalize_header(&mut header, self.config)?;
+            }
+
+            // Store additional links to the same file as hard links.
+            // NOTE: Extended attributes are stored only once, with the data.
+            if self.config.hardlinks && metadata.is_file() && metadata.nlink() > 1 {
+                match self.hard_links.entry((metadata.dev(), metadata.ino())) {
+                    Entry::Occupied(target) => {
+                        header.set_entry_type(tar::EntryType::Link);
+                        header.set_size(0);
+                        builder.append_link(&mut header, archive_path, target.get())?;
+                        return Ok(false);
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(archive_path.to_path_buf());
+                    }
+                }
+            }
+
+            let file = fs::File::open(local_path)
+                .with_context(|| format!("Could not open '{}'", local_path.display()))?;
+
+            if self.config.xattrs || self.config.acls {
+                self.append_xattrs(builder, &file).with_context(|| {
+                    format!(
+                        "Could not read extended attributes of '{}'",
+                        local_path.display()
+                    )
+                })?;
+            }
+
+            if metadata.is_dir() {
+                builder.append_data(&mut header, archive_path, std::io::empty())?;
+                Ok(true)
+            } else if self.config.sparse && sparse::is_sparse(&metadata) {
+                sparse::append(builder, header, archive_path, file, metadata.len())?;
+                Ok(false)
+            } else {
+                builder.append_data(&mut header, archive_path, file)?;
+                Ok(false)
+            }
+        }
+
+        /// Stores extended attributes as `SCHILY.xattr.*` PAX records,
+        /// which apply to the next entry.
+        fn append_xattrs<W: Write>(
+            &self,
+            builder: &mut tar::Builder<W>,
+            file: &fs::File,
+        ) -> Result<(), anyhow::Error> {
+            use xattr::FileExt as _;
+
+            let mut records: Vec<(String, Vec<u8>)> = Vec::new();
+
+            for name in file.list_xattr()? {
+                let Some(name) = name.to_str() else {
+                    tracing::warn!("Skipping non-UTF-8 extended attribute {name:?}.");
+                    continue;
+                };
+
+                let wanted = if is_acl_xattr(name) {
+                    self.config.acls
+                } else {
+                    self.config.xattrs
+                };
+                if !wanted {
+                    continue;
+                }
+
+                // NOTE: The attribute might have been removed since listing.
+                if let Some(value) = file.get_xattr(name)? {
+                    records.push((format!("{PAX_XATTR_PREFIX}{name}"), value));
+                }
+            }
+
+            if records.is_empty() {
+                return Ok(());
+            }
+
+            // NOTE: Sorted for reproducibility.
+            records.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+
+            builder.append_pax_extensions(
+                (records.iter()).map(|(key, value)| (key.as_str(), value.as_slice())),
+            )?;
+
+            Ok(())
+        }
+    }
+
+    fn normalize_header(
+        header: &mut tar::Header,
+        config: &ArchiveConfig,
+    ) -> Result<(), anyhow::Error> {
+        // Normalize ownership.
+        header.set_uid(0);
+        header.set_gid(0);
+        header.set_username("")?;
+        header.set_groupname("")?;
+
+        // Clamp modification time.
+        if let Some(max_mtime) = config.max_mtime {
+            header.set_mtime(header.mtime()?.min(max_mtime));
+        }
+
+        Ok(())
+    }
+}
+
+/// GNU sparse entries (see [`ArchiveConfig::sparse`]).
+///
+/// The sparse map is stored in the entry header (up to 4 data segments)
+/// followed by as many extension headers as needed (21 segments each).
+/// Entry data is the concatenation of all data segments.
+///
+/// NOTE: Unpacking is supported by the `tar` crate out of the box.
+mod sparse {
+    use std::fs;
+    use std::io::{self, Read, Seek as _, Write};
+    use std::os::unix::fs::MetadataExt as _;
+    use std::path::Path;
+
+    use anyhow::bail;
+
+    const BLOCK_SIZE: u64 = 512;
+
+    /// Sizes and offsets are stored as 11 octal digits.
+    const MAX_OCTAL_VALUE: u64 = 0o777_7777_7777;
+
+    /// Whether or not fewer blocks are allocated than the file size requires.
+    ///
+    /// NOTE: Files too big to be described using octal fields are considered
+    ///   not sparse (base-256 is not supported here).
+    pub(super) fn is_sparse(metadata: &fs::Metadata) -> bool {
+        // NOTE: `st_blocks` is always in 512-byte units.
+        metadata.blocks().saturating_mul(512) < metadata.len() && metadata.len() <= MAX_OCTAL_VALUE
+    }
+
+    pub(super) fn append<W: Write>(
+        builder: &mut tar::Builder<W>,
+        mut header: tar::Header,
+        archive_path: &Path,
+        mut file: fs::File,
+        real_size: u64,
+    ) -> Result<(), anyhow::Error> {
+        let segments = data_segments(&mut file, real_size)?;
+        let data_size: u64 = (segments.iter()).map(|(_, len)| len).sum();
+
+        // The sparse map must describe the whole file, so mark the end
+        // of the file if it ends with a hole.
+        let mut map = segments.clone();
+        if (map.last()).is_none_or(|(offset, len)| offset + len < real_size) {
+            map.push((real_size, 0));
+        }
+
+        header.set_entry_type(tar::EntryType::GNUSparse);
+        header.set_size(data_size);
+
+        let Some(gnu) = header.as_gnu_mut() else {
+            bail!("Sparse files require GNU headers.")
+        };
+        write_octal(&mut gnu.realsize, real_size);
+        let (head, tail) = map.split_at(map.len().min(gnu.sparse.len()));
+        for (slot, &(offset, len)) in gnu.sparse.iter_mut().zip(head) {
+            write_octal(&mut slot.offset, offset);
+            write_octal(&mut slot.numbytes, len);
+        }
+        gnu.isextended[0] = u8::from(!tail.is_empty());
+
+        let mut extensions: Vec<u8> = Vec::new();
+        let mut chunks = tail.chunks(21).peekable();
+        while let Some(chunk) = chunks.next() {
+            let mut extension = tar::GnuExtSparseHeader::new();
+            for (slot, &(offset, len)) in extension.sparse.iter_mut().zip(chunk) {
+                write_octal(&mut slot.offset, offset);
+                write_octal(&mut slot.numbytes, len);
+            }
+            extension.isextended[0] = u8::from(chunks.peek().is_some());
+            extensions.extend_from_slice(extension.as_bytes());
+        }
+
+        // NOTE: Extension headers are written right after the entry header,
+        //   before data. As they are block-aligned, padding is unaffected.
+        let data = io::Cursor::new(extensions).chain(SegmentsReader {
+            file,
+            segments: segments.into_iter(),
+            remaining: 0,
+        });
+        builder.append_data(&mut header, archive_path, data)?;
+
+        Ok(())
+    }
+
+    /// Returns `(offset, length)` pairs of non-zero regions of a file, with a
+    /// [`BLOCK_SIZE`] granularity.
+    ///
+    /// NOTE: All lengths are multiples of [`BLOCK_SIZE`] except, possibly,
+    ///   the one of a segment ending at the end of the file.
+    fn data_segments(file: &mut fs::File, len: u64) -> io::Result<Vec<(u64, u64)>> {
+        let mut segments: Vec<(u64, u64)> = Vec::new();
+        let mut reader = io::BufReader::with_capacity(64 * 1024, Read::take(&mut *file, len));
+        let mut block = [0u8; BLOCK_SIZE as usize];
+        let mut offset = 0u64;
+
+        while offset < len {
+            let block_len = (len - offset).min(BLOCK_SIZE) as usize;
+            reader.read_exact(&mut block[..block_len])?;
+
+            if block[..block_len].iter().any(|&byte| byte != 0) {
+                match segments.last_mut() {
+                    Some((start, segment_len)) if *start + *segment_len == offset => {
+                        *segment_len += block_len as u64;
+                    }
+                    _ => segments.push((offset, block_len as u64)),
+                }
+            }
+
+            offset += block_len as u64;
+        }
+
+        Ok(segments)
+    }
+
+    /// Reads data segments one after the other.
+    struct SegmentsReader {
+        file: fs::File,
+        segments: std::vec::IntoIter<(u64, u64)>,
+        remaining: u64,
+    }
+
+    impl Read for SegmentsReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            while self.remaining == 0 {
+                let Some((offset, len)) = self.segments.next() else {
+                    return Ok(0);
+                };
+                self.file.seek(io::SeekFrom::Start(offset))?;
+                self.remaining = len;
+            }
+
+            let max = buf
+                .len()
+                .min(usize::try_from(self.remaining).unwrap_or(usize::MAX));
+            let n = self.file.read(&mut buf[..max])?;
+            if n == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "File was truncated while being archived.",
+                ));
+            }
+            self.remaining -= n as u64;
+
+            Ok(n)
+        }
+    }
+
+    /// Writes a NUL-terminated octal number, like the `tar` crate does.
+    fn write_octal(dst: &mut [u8; 12], value: u64) {
+        debug_assert!(value <= MAX_OCTAL_VALUE);
+        let digits = format!("{value:011o}");
+        dst[..11].copy_from_slice(&digits.as_bytes()[..11]);
+        dst[11] = 0;
+    }
+}
+
+/// Prefix of PAX records containing extended attributes (as written by
+/// GNU `tar --xattrs` and `bsdtar`).
+const PAX_XATTR_PREFIX: &str = "SCHILY.xattr.";
+
+/// Whether or not an extended attribute stores a POSIX ACL.
+fn is_acl_xattr(name: &str) -> bool {
+    matches!(name, "system.posix_acl_access" | "system.posix_acl_default")
+}
+
 // MARK: - Unarchiving
 
 #[derive(Debug)]
@@ -209,7 +603,6 @@ pub struct GetMetadataOutput<'a> {
     pub blueprint: &'a ArchiveBlueprint,
 
     /// Metadata stored inside of the backup.
-    #[allow(dead_code)]
     pub(crate) metadata: BackupInternalMetadata,
 }
 
@@ -222,6 +615,11 @@ pub trait ExtractBackupEventHandler: Send + Sync {
 #[derive(Debug, Default)]
 pub struct ExtractionReport {
     pub extracted_bytes_count: u64,
+
+    /// Attributes stored in the backup which could not be applied when
+    /// extracting (e.g. extended attributes not supported by the destination
+    /// filesystem). Data was still restored.
+    pub unapplied_attributes: Vec<UnappliedAttribute>,
 }
 
 impl ExtractBackupEventHandler for ExtractionReport {
@@ -230,6 +628,114 @@ impl ExtractBackupEventHandler for ExtractionReport {
     }
 }
 
+impl ExtractionReport {
+    pub(crate) fn on_attribute_not_applied(
+        &mut self,
+        backup_id: &BackupId,
+        unapplied_attribute: UnappliedAttribute,
+    ) {
+        let UnappliedAttribute {
+            ref path,
+            ref attribute,
+            ref error,
+        } = unapplied_attribute;
+        tracing::warn!(
+            ?backup_id,
+            "Could not apply {attribute} to '{path}': {error}",
+            path = path.display(),
+        );
+
+        self.unapplied_attributes.push(unapplied_attribute);
+    }
+}
+
+#[derive(Debug)]
+pub struct UnappliedAttribute {
+    /// Path of the extracted entry.
+    pub path: PathBuf,
+
+    pub attribute: ArchiveAttribute,
+
+    pub error: std::io::Error,
+}
+
+/// Entry attributes stored when [`ArchiveConfig`] opts into them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveAttribute {
+    /// An extended attribute (see [`ArchiveConfig::xattrs`]).
+    Xattr(String),
+
+    /// A POSIX ACL (see [`ArchiveConfig::acls`]).
+    Acl(String),
+
+    /// A hard link to another extracted entry (see [`ArchiveConfig::hardlinks`]).
+    /// When it cannot be created, the target file is copied instead.
+    HardLink { target: PathBuf },
+}
+
+impl std::fmt::Display for ArchiveAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Xattr(name) => write!(f, "extended attribute `{name}`"),
+            Self::Acl(name) => write!(f, "ACL `{name}`"),
+            Self::HardLink { target } => write!(f, "hard link to '{}'", target.display()),
+        }
+    }
+}
+
+/// Reads extended attributes stored for an entry (as `SCHILY.xattr.*`
+/// PAX records).
+pub(crate) fn entry_xattrs<R: std::io::Read>(
+    entry: &mut tar::Entry<R>,
+) -> Result<Vec<(String, Vec<u8>)>, std::io::Error> {
+    let mut xattrs = Vec::new();
+
+    let Some(extensions) = entry.pax_extensions()? else {
+        return Ok(xattrs);
+    };
+
+    for extension in extensions {
+        let extension = extension?;
+
+        let Ok(key) = extension.key() else {
+            continue;
+        };
+        if let Some(name) = key.strip_prefix(PAX_XATTR_PREFIX) {
+            xattrs.push((name.to_owned(), extension.value_bytes().to_vec()));
+        }
+    }
+
+    Ok(xattrs)
+}
+
+/// Applies extended attributes read by [`entry_xattrs`] to an extracted
+/// entry, recording failures in `report`.
+pub(crate) fn apply_xattrs(
+    path: &Path,
+    xattrs: Vec<(String, Vec<u8>)>,
+    backup_id: &BackupId,
+    report: &mut ExtractionReport,
+) {
+    for (name, value) in xattrs {
+        if let Err(error) = xattr::set(path, &name, &value) {
+            let attribute = if is_acl_xattr(&name) {
+                ArchiveAttribute::Acl(name)
+            } else {
+                ArchiveAttribute::Xattr(name)
+            };
+
+            report.on_attribute_not_applied(
+                backup_id,
+                UnappliedAttribute {
+                    path: path.to_path_buf(),
+                    attribute,
+                    error,
+                },
+            );
+        }
+    }
+}
+
 pub(crate) fn archive_reader<'r>(
     backup_reader: impl std::io::Read + Send + Sync + 'r,
     backup_id: &'r BackupId,
@@ -22,6 +22,25 @@ pub use crate::util::BytesAmount;
 /// # use toml::toml;
 /// #
 /// # let toml = toml! {
+/// [archive]
+/// // Whether or not to produce reproducible archives (identical data gives
+/// // byte-for-byte identical backups, if encryption and signing are off).
+/// // Entries are sorted and ownership is normalized. Default is `false`.
+/// deterministic = true
+/// // Optional. Modification times more recent than this Unix timestamp
+/// // (in seconds) are clamped to it (like `SOURCE_DATE_EPOCH`).
+/// // Ignored if `deterministic` is `false`.
+/// max_mtime = 0
+/// // Whether or not to preserve extended attributes (except ACLs).
+/// // Default is `false`.
+/// xattrs = true
+/// // Whether or not to preserve POSIX ACLs. Default is `false`.
+/// acls = true
+/// // Whether or not to store sparse files efficiently. Default is `false`.
+/// sparse = true
+/// // Whether or not to preserve hard links. Default is `false`.
+/// hardlinks = true
+///
 /// [compression]
 /// // The algorithm to use when compressing backups.
 /// // Possible values: `"zstd"` (default), `"off"`.
@@ -110,10 +129,114 @@ pub use crate::util::BytesAmount;
 /// // Pass the secret key via an environment variable.
 /// # secret_key = "example"
 ///
+/// [restoration]
+/// // Where to record the progress of restorations, to recover from crashes
+/// // (i.e. roll back or finish interrupted restorations on startup).
+/// // Must be on persistent storage. Default is none (no journal).
+/// journal_path = "/var/lib/prose-backup/restore-journal.json"
+/// // Where to keep the report of the last restoration (stages and how long
+/// // they took). Should be on persistent storage.
+/// // Default is `/var/lib/prose-backup/restore-report.json`.
+/// report_path = "/var/lib/prose-backup/restore-report.json"
+///
+/// // Keep the data replaced by the last restoration, so it can be undone.
+/// [safety_snapshot]
+/// // Default is `false`.
+/// enabled = true
+/// // Must not be in a backed up path, and should be on persistent storage.
+/// // Keeping data is instantaneous if it’s on the same filesystem as
+/// // restored data (it’s copied otherwise).
+/// // Default is `/var/lib/prose-backup/safety-snapshot`.
+/// directory = "/var/lib/prose-backup/safety-snapshot"
+/// // How long the restoration can be undone. Default is 1 day.
+/// // Uses the [ISO 8601 Duration format](https://en.wikipedia.org/wiki/ISO_8601#Durations).
+/// ttl = "P1D"
+///
+/// [keys]
+/// // Where to store keys generated or imported at runtime (see
+/// // `prose_backup::keys`). Must be on persistent storage, private key
+/// // material is stored there. Default is none (key management disabled).
+/// directory = "/var/lib/prose-backup/keys"
+///
+/// // Named profiles, to create backups with different settings. Profiles
+/// // override settings of `compression`, `encryption` and `storage.backups`
+/// // (unspecified keys are inherited).
+/// [profiles.config]
+/// // Blueprint entries to include. Default is all entries.
+/// include = ["prose-config", "prosody-config"]
+/// // Blueprint entries to exclude. Default is none.
+/// exclude = []
+/// compression.algorithm = "zstd"
+/// compression.zstd.compression_level = 19
+/// encryption.pgp.additional_recipients = ["/path/to/other-system.pub.asc"]
+/// storage.provider = "s3"
+/// storage.s3.prefix = "prose-config/"
+///
+/// // Restore the most recent backup when starting on an empty machine
+/// // (disaster recovery). Only used by applications which support it.
+/// [bootstrap]
+/// // Default is `false`.
+/// enabled = true
+/// // Optional. Only consider backups with this prefix.
+/// prefix = "prose-backup"
+/// // Optional. Only consider backups with a valid signature from this key
+/// // (fingerprint of the certificate or of the signing subkey).
+/// required_signer = "0123456789ABCDEF0123456789ABCDEF01234567"
+///
+/// [transparency_log]
+/// // Whether or not to record created and deleted backups in an append-only,
+/// // hash-chained log stored next to integrity checks (signed if signing is
+/// // enabled). Default is `false`.
+/// enabled = true
+///
+/// [snapshot]
+/// // Whether or not to snapshot backed up data before creating a backup, so
+/// // the server only has to be stopped while the snapshot is taken.
+/// // Default is `false`.
+/// enabled = true
+/// // How to snapshot data. Possible values: `"auto"` (default), `"copy"`,
+/// // `"hardlink"`, `"btrfs"`, `"lvm"`. `"auto"` uses Btrfs or LVM snapshots
+/// // when possible, and copies data otherwise.
+/// method = "auto"
+/// // Where to store snapshots. Default is `"/var/lib/prose-backup/snapshots"`.
+/// staging_dir = "/var/lib/prose-backup/snapshots"
+/// // Space reserved for changes made while an LVM snapshot exists.
+/// // Default is `"1GiB"`.
+/// lvm_snapshot_size = "1GiB"
+///
 /// [download]
 /// // Longest allowed validity for a backup download URL. Default is 5 minutes.
 /// // Uses the [ISO 8601 Duration format](https://en.wikipedia.org/wiki/ISO_8601#Durations).
 /// url_max_ttl = "PT5M"
+///
+/// // Comparison of backups (see `BackupService::diff`).
+/// [diff]
+/// // Files for which to include a unified text diff when they changed, by
+/// // file name suffix. Default is `[".cfg.lua", ".toml"]`.
+/// text_file_suffixes = [".cfg.lua", ".toml"]
+/// // Files larger than this don’t get a text diff. Default is `"64KiB"`.
+/// text_max_size = "64KiB"
+///
+/// // Storage usage reporting. Quotas are not enforced, only reported.
+/// [quota]
+/// // Optional. Maximum amount of storage backups and integrity checks
+/// // should use (e.g. `"10GB"`, `"512MiB"`).
+/// max_size = "10GB"
+/// // Ratio of `max_size` above which a warning is reported. Default is `0.8`.
+/// warning_threshold = 0.8
+/// // How far back to look when estimating storage growth. Default is 30 days.
+/// // Uses the [ISO 8601 Duration format](https://en.wikipedia.org/wiki/ISO_8601#Durations).
+/// growth_estimation_window = "P30D"
+///
+/// // Byte-level progress events emitted while creating a backup.
+/// [progress]
+/// // Minimum time between two progress events. Default is 100 milliseconds.
+/// // Uses the [ISO 8601 Duration format](https://en.wikipedia.org/wiki/ISO_8601#Durations).
+/// interval = "PT0.1S"
+/// // Time window over which throughput (and therefore ETA) is computed.
+/// // Default is 10 seconds.
+/// // Uses the [ISO 8601 Duration format](https://en.wikipedia.org/wiki/ISO_8601#Durations).
+/// throughput_window = "PT10S"
 /// # };
 /// #
 /// # let _backup_config = BackupConfig::try_from(toml)?;
@@ -124,6 +247,8 @@ pub use crate::util::BytesAmount;
 #[derive(serde::Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct BackupConfig {
+    pub archive: ArchiveConfig,
+
     pub compression: CompressionConfig,
 
     pub hashing: HashingConfig,
@@ -134,10 +259,37 @@ pub struct BackupConfig {
 
     pub storage: StorageConfig,
 
+    #[serde(default)]
+    pub restoration: RestorationConfig,
+
+    pub safety_snapshot: SafetySnapshotConfig,
+
+    #[serde(default)]
+    pub transparency_log: TransparencyLogConfig,
+
+    #[serde(default)]
+    pub keys: KeysConfig,
+
+    #[serde(default)]
+    pub bootstrap: BootstrapConfig,
+
+    /// Named profiles, to create backups with different settings (see
+    /// [`BackupProfileConfig`]).
+    #[serde(default)]
+    pub profiles: std::collections::BTreeMap<String, BackupProfileConfig>,
+
+    pub snapshot: SnapshotConfig,
+
     pub download: DownloadConfig,
 
+    pub diff: DiffConfig,
+
     pub caching: CachingConfig,
 
+    pub quota: QuotaConfig,
+
+    pub progress: ProgressConfig,
+
     /// Don’t mind this, it’s just there to make `deny_unknown_fields` happy
     /// (we can’t remove keys in `figment`).
     #[doc(hidden)]
@@ -167,9 +319,28 @@ pub fn default_config_static() -> toml::Table {
     let default_hashing_algorithm = "SHA-256";
 
     let cache_dir = tempfile::env::temp_dir().display().to_string();
+    // NOTE: Not in the temporary directory, which is usually on another
+    //   filesystem than backed up data (hard links and Btrfs snapshots
+    //   wouldn’t work) and often in memory (copies would fill it).
+    let staging_dir = "/var/lib/prose-backup/snapshots";
+    // NOTE: Not in the temporary directory, which is usually on another
+    //   filesystem (data would be copied) and cleared on reboot (the
+    //   restoration couldn’t be undone anymore).
+    let safety_snapshot_dir = "/var/lib/prose-backup/safety-snapshot";
+    // NOTE: Not in the temporary directory, or the report of a restoration
+    //   would be lost if the machine reboots (e.g. to investigate why it
+    //   failed).
+    let restore_report_path = "/var/lib/prose-backup/restore-report.json";
 
     #[allow(unused_mut)]
     let mut static_defaults = toml! {
+        [archive]
+        deterministic = false
+        xattrs = false
+        acls = false
+        sparse = false
+        hardlinks = false
+
         [compression]
         // This isn’t the default in most cases, it’s just a fallback.
         algorithm = "off"
@@ -186,11 +357,37 @@ pub fn default_config_static() -> toml::Table {
         // when overriding configuration with environment variables.
         mode = "off"
 
+        [snapshot]
+        enabled = false
+        method = "auto"
+        staging_dir = staging_dir
+        lvm_snapshot_size = "1GiB"
+
         [download]
         url_max_ttl = "PT5M"
 
+        [diff]
+        text_file_suffixes = [".cfg.lua", ".toml"]
+        text_max_size = "64KiB"
+
+        [restoration]
+        report_path = restore_report_path
+
+        [safety_snapshot]
+        enabled = false
+        directory = safety_snapshot_dir
+        ttl = "P1D"
+
         [caching]
         cache_dir = cache_dir
+
+        [quota]
+        warning_threshold = 0.8
+        growth_estimation_window = "P30D"
+
+        [progress]
+        interval = "PT0.1S"
+        throughput_window = "PT10S"
     };
 
     #[cfg(feature = "compression-zstd")]
@@ -292,9 +489,110 @@ pub fn with_dynamic_defaults(mut figment: Figment) -> Result<Figment, Box<figmen
             .remove("pgp");
     }
 
+    // Make profiles inherit unspecified keys of the sections they override
+    // (e.g. `profiles.<name>.storage` from `storage.backups`).
+    if let Ok(profiles) = figment.extract_inner::<figment::value::Dict>("profiles") {
+        for (name, profile) in profiles {
+            let Some(profile) = profile.into_dict() else {
+                continue;
+            };
+
+            for (section, default_key) in [
+                ("compression", "compression"),
+                ("encryption", "encryption"),
+                ("storage", "storage.backups"),
+            ] {
+                if !profile.contains_key(section) {
+                    continue;
+                }
+
+                if let Ok(default) = figment.extract_inner::<figment::value::Value>(default_key) {
+                    figment = figment.join(Serialized::default(
+                        &format!("profiles.{name}.{section}"),
+                        default,
+                    ));
+                }
+            }
+        }
+    }
+
     Ok(figment)
 }
 
+// MARK: Archive
+
+#[derive(Debug, Clone)]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ArchiveConfig {
+    /// Whether or not to produce reproducible archives, meaning identical
+    /// data gives identical archives.
+    ///
+    /// Entries are sorted by name and ownership is normalized (restoring
+    /// doesn’t preserve ownership anyway). Metadata which varies between
+    /// backups (e.g. creation time, description) is never stored in the
+    /// archive.
+    ///
+    /// NOTE: Only files and directories can be archived in this mode
+    ///   (symbolic links are followed).
+    ///
+    /// NOTE: Encryption and signing are not deterministic, therefore backups
+    ///   are reproducible only if those are disabled.
+    pub deterministic: bool,
+
+    /// Unix timestamp (in seconds) more recent modification times are
+    /// clamped to (like [`SOURCE_DATE_EPOCH`]).
+    ///
+    /// Ignored if [`deterministic`](Self::deterministic) is `false`.
+    ///
+    /// [`SOURCE_DATE_EPOCH`]: https://reproducible-builds.org/docs/source-date-epoch/
+    #[serde(default)]
+    pub max_mtime: Option<u64>,
+
+    /// Whether or not to preserve extended attributes (stored as
+    /// `SCHILY.xattr.*` PAX records, like GNU `tar --xattrs` does).
+    ///
+    /// NOTE: POSIX ACLs are controlled by [`acls`](Self::acls).
+    pub xattrs: bool,
+
+    /// Whether or not to preserve POSIX ACLs (i.e. `system.posix_acl_access`
+    /// and `system.posix_acl_default` extended attributes).
+    pub acls: bool,
+
+    /// Whether or not to store sparse files as GNU sparse entries, so holes
+    /// don’t take space in backups and aren’t allocated when restoring.
+    ///
+    /// A file is considered sparse if fewer blocks are allocated than its
+    /// size requires. Holes are then detected by looking for zeroed blocks.
+    ///
+    /// NOTE: Progress estimates don’t account for holes.
+    pub sparse: bool,
+
+    /// Whether or not to archive files with multiple hard links only once.
+    /// Other paths are stored as links to the first one.
+    ///
+    /// NOTE: When restoring, files linked across different destinations
+    ///   (e.g. different filesystems) are copied instead.
+    pub hardlinks: bool,
+}
+
+impl ArchiveConfig {
+    /// Whether or not [`tar::Builder`]’s defaults are enough to honor this
+    /// configuration.
+    pub(crate) fn needs_custom_archiving(&self) -> bool {
+        let Self {
+            deterministic,
+            max_mtime: _,
+            xattrs,
+            acls,
+            sparse,
+            hardlinks,
+        } = *self;
+
+        deterministic || xattrs || acls || sparse || hardlinks
+    }
+}
+
 // MARK: Compression
 
 #[derive(Debug, Clone)]
@@ -328,7 +626,7 @@ pub struct HashingConfig {
     pub algorithm: HashingAlgorithm,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[derive(serde::Deserialize)]
 pub enum HashingAlgorithm {
     #[cfg(feature = "hashing-blake3")]
@@ -521,6 +819,20 @@ pub struct DownloadConfig {
     pub url_max_ttl: std::time::Duration,
 }
 
+// MARK: Diff
+
+#[derive(Debug, Clone)]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DiffConfig {
+    /// File name suffixes of files for which to include a unified text
+    /// diff when they changed (e.g. `".cfg.lua"`).
+    pub text_file_suffixes: Vec<String>,
+
+    /// Files larger than this don’t get a text diff.
+    pub text_max_size: BytesAmount,
+}
+
 // MARK: Caching
 
 #[derive(Debug, Clone)]
@@ -533,6 +845,244 @@ pub struct CachingConfig {
     pub max_backup_cache_size: Option<BytesAmount>,
 }
 
+// MARK: Restoration
+
+#[derive(Debug, Clone, Default)]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RestorationConfig {
+    /// Where to record the progress of restorations, so an interrupted
+    /// restoration can be recovered (see
+    /// [`BackupService::recover_interrupted_restoration`](crate::BackupService::recover_interrupted_restoration)).
+    ///
+    /// WARN: Must not be in a backed up path, and must be on persistent
+    ///   storage (e.g. not in a `tmpfs`).
+    #[serde(default)]
+    pub journal_path: Option<std::path::PathBuf>,
+
+    /// Where to keep the report of the last restoration (see
+    /// [`BackupService::last_restore_report`](crate::BackupService::last_restore_report)),
+    /// `None` to not keep it.
+    ///
+    /// WARN: Must not be in a backed up path, and should be on persistent
+    ///   storage.
+    #[serde(default)]
+    pub report_path: Option<std::path::PathBuf>,
+}
+
+// MARK: Safety snapshot
+
+#[derive(Debug, Clone)]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SafetySnapshotConfig {
+    /// Whether or not to keep the data replaced by a restoration, so it can
+    /// be undone (see
+    /// [`BackupService::undo_last_restore`](crate::BackupService::undo_last_restore)).
+    pub enabled: bool,
+
+    /// Where to keep the data replaced by the last restoration.
+    ///
+    /// NOTE: Data is moved there, which is instantaneous if it’s on the same
+    ///   filesystem as restored data. It’s copied otherwise.
+    ///
+    /// WARN: Must not be in a backed up path, and should be on persistent
+    ///   storage (or the restoration can’t be undone after a reboot).
+    pub directory: std::path::PathBuf,
+
+    /// How long the last restoration can be undone.
+    #[serde(with = "crate::util::serde::iso8601_duration")]
+    pub ttl: std::time::Duration,
+}
+
+// MARK: Keys
+
+#[derive(Debug, Clone, Default)]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KeysConfig {
+    /// Where to store keys generated or imported at runtime (see
+    /// [`crate::keys`]), `None` to disable key management.
+    ///
+    /// WARN: Must not be in a backed up path, and must be on persistent
+    ///   storage (e.g. not in a `tmpfs`).
+    #[serde(default)]
+    pub directory: Option<std::path::PathBuf>,
+}
+
+// MARK: Bootstrap
+
+/// Disaster recovery settings (see
+/// [`BackupService::latest_intact_backup`](crate::BackupService::latest_intact_backup)).
+#[derive(Debug, Clone, Default)]
+#[serde_with::serde_as]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BootstrapConfig {
+    /// Whether or not to restore the most recent intact backup when
+    /// starting with no data.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Only consider backups with this prefix.
+    #[serde(default)]
+    pub prefix: Option<String>,
+
+    /// Only consider backups with a valid signature from this key
+    /// (certificate or signing subkey).
+    #[serde(default)]
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    pub required_signer: Option<openpgp::Fingerprint>,
+}
+
+// MARK: Profiles
+
+/// Settings of backups created using a named profile.
+///
+/// NOTE: The profile name and included blueprint entries are stored in
+///   backups, so restoring a backup created using a profile only replaces
+///   the data it contains.
+#[derive(Debug, Clone)]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BackupProfileConfig {
+    /// Blueprint entries to include (e.g. `"prosody-config"`), `None` for
+    /// all entries.
+    #[serde(default)]
+    pub include: Option<Vec<String>>,
+
+    /// Blueprint entries to exclude (applied after [`include`]).
+    ///
+    /// [`include`]: Self::include
+    #[serde(default)]
+    pub exclude: Vec<String>,
+
+    /// Overrides [`BackupConfig::compression`].
+    #[serde(default)]
+    pub compression: Option<CompressionConfig>,
+
+    /// Overrides [`BackupConfig::encryption`].
+    #[serde(default)]
+    pub encryption: Option<EncryptionConfig>,
+
+    /// Overrides [`StorageConfig::backups`].
+    #[serde(default)]
+    pub storage: Option<StorageSubconfig>,
+}
+
+// MARK: Transparency log
+
+#[derive(Debug, Clone, Default)]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransparencyLogConfig {
+    /// Whether or not to record created and deleted backups in the
+    /// transparency log (see [`crate::transparency`]).
+    ///
+    /// NOTE: Backups created while the log was disabled will be reported as
+    ///   missing from the log.
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+// MARK: Snapshot
+
+#[derive(Debug, Clone)]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SnapshotConfig {
+    /// Whether or not to snapshot backed up data before creating a backup.
+    ///
+    /// NOTE: This is only a hint for callers, which decide when to stop
+    ///   the server (see
+    ///   [`BackupService::create_snapshot`](crate::BackupService::create_snapshot)).
+    pub enabled: bool,
+
+    pub method: SnapshotMethod,
+
+    /// Directory in which snapshots are stored (copies, hard links, Btrfs
+    /// snapshots or LVM snapshot mount points).
+    ///
+    /// Created if it doesn’t exist.
+    ///
+    /// NOTE: Must be on the same filesystem as backed up data when using
+    ///   [`SnapshotMethod::Hardlink`] or [`SnapshotMethod::Btrfs`].
+    pub staging_dir: std::path::PathBuf,
+
+    /// Space reserved for changes made to a logical volume while its LVM
+    /// snapshot exists. The snapshot becomes invalid (and the backup fails)
+    /// if more data is written.
+    pub lvm_snapshot_size: BytesAmount,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(serde::Deserialize)]
+pub enum SnapshotMethod {
+    /// Use a Btrfs snapshot if a backed up path is a Btrfs subvolume,
+    /// an LVM snapshot if it is on an LVM logical volume, or copy it.
+    #[serde(rename = "auto")]
+    Auto,
+
+    /// Copy data. Slow for large data, but works everywhere.
+    #[serde(rename = "copy")]
+    Copy,
+
+    /// Hard link files (directories are recreated). Very fast, but files
+    /// modified in place (instead of being replaced) after the server
+    /// restarts would also be modified in the snapshot.
+    ///
+    /// WARN: Prosody appends to some files (e.g. message archives when
+    ///   using the `internal` storage) in place.
+    #[serde(rename = "hardlink")]
+    Hardlink,
+
+    /// Use read-only Btrfs snapshots. Backed up paths must be subvolumes.
+    #[serde(rename = "btrfs")]
+    Btrfs,
+
+    /// Use LVM snapshots, mounted read-only. Requires free space in the
+    /// volume group (see [`SnapshotConfig::lvm_snapshot_size`]).
+    #[serde(rename = "lvm")]
+    Lvm,
+}
+
+// MARK: Quota
+
+#[derive(Debug, Clone)]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QuotaConfig {
+    /// Maximum amount of storage backups and integrity checks should use.
+    ///
+    /// NOTE: This is not enforced, only reported (see
+    ///   [`BackupService::storage_report`](crate::BackupService::storage_report)).
+    #[serde(default)]
+    pub max_size: Option<BytesAmount>,
+
+    /// Ratio of [`max_size`](Self::max_size) above which a warning is
+    /// reported (e.g. `0.8` for 80%).
+    pub warning_threshold: f64,
+
+    /// How far back to look when estimating storage growth.
+    #[serde(with = "crate::util::serde::iso8601_duration")]
+    pub growth_estimation_window: std::time::Duration,
+}
+
+// MARK: Progress
+
+#[derive(Debug, Clone)]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProgressConfig {
+    /// Minimum time between two progress events.
+    #[serde(with = "crate::util::serde::iso8601_duration")]
+    pub interval: std::time::Duration,
+
+    /// Time window over which throughput (and therefore ETA) is computed.
+    #[serde(with = "crate::util::serde::iso8601_duration")]
+    pub throughput_window: std::time::Duration,
+}
+
 // MARK: Constructors
 
 impl BackupConfig {
@@ -687,4 +1237,40 @@ mod tests {
             format!("unknown variant: found `foo`, expected `{supported}` for key \"default.storage.backups.provider\" in TOML source string")
         );
     }
+
+    #[test]
+    #[cfg(feature = "storage-fs")]
+    fn test_profiles_inherit_defaults() {
+        use toml::toml;
+
+        let config = BackupConfig::try_from(toml! {
+            [storage]
+            provider = "fs"
+            fs.directory = "/var/backups"
+
+            [profiles.config]
+            include = ["prosody-config"]
+            storage.fs.directory = "/var/backups/config"
+
+            [profiles.all]
+        })
+        .unwrap();
+
+        let profile = &config.profiles["config"];
+        assert_eq!(profile.include, Some(vec!["prosody-config".to_owned()]));
+        assert!(profile.compression.is_none());
+        assert!(profile.encryption.is_none());
+        match profile.storage {
+            Some(StorageSubconfig::Fs { ref config }) => {
+                assert_eq!(config.directory.as_os_str(), "/var/backups/config");
+                // Inherited from `storage.backups`.
+                assert!(!config.overwrite);
+            }
+            ref storage => panic!("Unexpected storage: {storage:?}"),
+        }
+
+        let profile = &config.profiles["all"];
+        assert_eq!(profile.include, None);
+        assert!(profile.storage.is_none());
+    }
 }
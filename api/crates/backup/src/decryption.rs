@@ -11,10 +11,12 @@ use crate::stats::ReadStats;
 
 pub(crate) use self::DecryptionContext as Context;
 
+/// NOTE: Contexts are reference-counted so they can be used by
+///   blocking tasks (see [`BackupService`](crate::BackupService)).
 #[non_exhaustive]
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct DecryptionContext {
-    pub pgp: Option<PgpDecryptionContext>,
+    pub pgp: Option<std::sync::Arc<PgpDecryptionContext>>,
 }
 
 #[allow(unused_variables)]
@@ -0,0 +1,633 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+//! Comparison of two backups (e.g. to find out what changed between two
+//! days when investigating an incident).
+//!
+//! See [`BackupService::diff`].
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Context as _;
+
+use crate::archiving::{ArchiveBlueprint, archive_reader, read_metadata};
+use crate::config::{DiffConfig, HashingConfig};
+use crate::decryption::{DecryptionContext, DecryptionReport};
+use crate::event_handlers::NoopEventHandler;
+use crate::restoration::{ExtractionError, RestorationContext};
+use crate::restoration::{filter_migrations, flatten, map_path_bytes};
+use crate::stats::NoopStats;
+use crate::util::spawn_blocking;
+use crate::verification::{VerificationError, VerificationOutput, VerificationReport};
+use crate::{BackupId, BackupService};
+
+/// Number of unchanged lines around changes in text diffs.
+const CONTEXT_LINES: usize = 3;
+
+/// Above this number of lines before × lines after (once unchanged first
+/// and last lines are removed), changed lines are not matched anymore.
+///
+/// NOTE: Matching lines is quadratic, this keeps it under a few
+///   megabytes of memory. Config files are much smaller anyway.
+const MAX_MATCHED_LINES: usize = 1 << 22;
+
+/// Differences between two backups.
+///
+/// See [`BackupService::diff`].
+#[derive(Debug)]
+#[derive(serde::Serialize)]
+pub struct BackupDiffDto {
+    /// Backup compared against.
+    pub from: BackupId,
+
+    /// Backup compared.
+    pub to: BackupId,
+
+    /// Changes per blueprint entry, sorted by key. Entries without changes
+    /// are omitted.
+    pub changes: Vec<BlueprintEntryDiffDto>,
+}
+
+#[derive(Debug)]
+#[derive(serde::Serialize)]
+pub struct BlueprintEntryDiffDto {
+    /// Name of the blueprint entry (e.g. `prosody-data`).
+    ///
+    /// Paths which aren’t part of the blueprint (e.g. additional data)
+    /// are grouped by their first component.
+    pub key: String,
+
+    /// Paths present in [`to`](BackupDiffDto::to) only.
+    pub added: Vec<DiffEntryDto>,
+
+    /// Paths present in [`from`](BackupDiffDto::from) only.
+    pub removed: Vec<DiffEntryDto>,
+
+    /// Paths present in both backups, with a different type, size,
+    /// content or link target.
+    pub modified: Vec<ModifiedEntryDto>,
+}
+
+#[derive(Debug)]
+#[derive(serde::Serialize)]
+pub struct DiffEntryDto {
+    /// Path in the archive (e.g. `prosody-data/localhost/accounts/a.dat`).
+    pub path: String,
+
+    pub kind: EntryKind,
+
+    /// Size of the file, in bytes (`0` if not a file).
+    pub size_bytes: u64,
+}
+
+#[derive(Debug)]
+#[derive(serde::Serialize)]
+pub struct ModifiedEntryDto {
+    /// Path in the archive (e.g. `prose-config/prose.toml`).
+    pub path: String,
+
+    pub kind_before: EntryKind,
+
+    pub kind_after: EntryKind,
+
+    pub size_bytes_before: u64,
+
+    pub size_bytes_after: u64,
+
+    /// Unified diff, for small text files (see [`DiffConfig`]).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_diff: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    File,
+    Directory,
+    Symlink,
+    HardLink,
+    Other,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DiffBackupsError {
+    #[error("Backup `{0}` not found.")]
+    BackupNotFound(BackupId),
+
+    #[error("Backup `{backup_id}` cannot be compared")]
+    InvalidBackup {
+        backup_id: BackupId,
+        #[source]
+        source: ExtractionError,
+    },
+}
+
+/// Entry of a backup, as needed to compare it.
+#[derive(Debug)]
+struct ScannedEntry {
+    kind: EntryKind,
+    size_bytes: u64,
+    digest: Option<Vec<u8>>,
+    link_target: Option<Vec<u8>>,
+
+    /// Contents of small text files (see [`DiffConfig`]).
+    text: Option<String>,
+}
+
+impl ScannedEntry {
+    fn is_same_as(&self, other: &Self) -> bool {
+        self.kind == other.kind
+            && self.size_bytes == other.size_bytes
+            && self.digest == other.digest
+            && self.link_target == other.link_target
+    }
+
+    fn dto(&self, path: &[u8]) -> DiffEntryDto {
+        DiffEntryDto {
+            path: String::from_utf8_lossy(path).into_owned(),
+            kind: self.kind,
+            size_bytes: self.size_bytes,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ScannedBackup {
+    version: u8,
+    entries: BTreeMap<Vec<u8>, ScannedEntry>,
+}
+
+pub(crate) async fn diff(
+    service: &BackupService,
+    a: &BackupId,
+    b: &BackupId,
+) -> Result<BackupDiffDto, DiffBackupsError> {
+    let (mut from, mut to) = tokio::try_join!(scan_backup(service, a), scan_backup(service, b))?;
+
+    // Compare paths using the most recent blueprint.
+    let version = from.version.max(to.version);
+    let blueprint = (service.archiving_context.blueprints.get(&version))
+        .expect("Version checked when scanning");
+    migrate(&mut from, version, &service.restoration_context);
+    migrate(&mut to, version, &service.restoration_context);
+
+    let mut changes: BTreeMap<String, BlueprintEntryDiffDto> = BTreeMap::new();
+    let mut changes_of = |path: &[u8]| {
+        let key = blueprint_key(path, blueprint);
+        (changes.entry(key.clone())).or_insert_with(|| BlueprintEntryDiffDto {
+            key,
+            added: Vec::new(),
+            removed: Vec::new(),
+            modified: Vec::new(),
+        })
+    };
+
+    for (path, before) in from.entries.iter() {
+        match to.entries.get(path) {
+            None => changes_of(path).removed.push(before.dto(path)),
+            Some(after) if !before.is_same_as(after) => {
+                let path_str = String::from_utf8_lossy(path).into_owned();
+                let text_diff = match (&before.text, &after.text) {
+                    (Some(before), Some(after)) => Some(unified_diff(&path_str, before, after)),
+                    _ => None,
+                };
+                changes_of(path).modified.push(ModifiedEntryDto {
+                    path: path_str,
+                    kind_before: before.kind,
+                    kind_after: after.kind,
+                    size_bytes_before: before.size_bytes,
+                    size_bytes_after: after.size_bytes,
+                    text_diff,
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    for (path, after) in to.entries.iter() {
+        if !from.entries.contains_key(path) {
+            changes_of(path).added.push(after.dto(path));
+        }
+    }
+
+    Ok(BackupDiffDto {
+        from: a.clone(),
+        to: b.clone(),
+        changes: changes.into_values().collect(),
+    })
+}
+
+async fn scan_backup(
+    service: &BackupService,
+    backup_id: &BackupId,
+) -> Result<ScannedBackup, DiffBackupsError> {
+    let invalid = |source: ExtractionError| DiffBackupsError::InvalidBackup {
+        backup_id: backup_id.clone(),
+        source,
+    };
+
+    let mut verification_report = VerificationReport::default();
+    let verification_output = match service
+        .download_backup_and_check_integrity(
+            backup_id,
+            backup_id.created_at,
+            &mut verification_report,
+        )
+        .await
+    {
+        Ok(output) => output,
+        Err(VerificationError::BackupNotFound(_)) => {
+            return Err(DiffBackupsError::BackupNotFound(backup_id.clone()));
+        }
+        Err(err) => return Err(invalid(ExtractionError::from(err))),
+    };
+
+    // NOTE: Decryption, decompression and hashing are CPU-bound, run them
+    //   on the blocking thread pool.
+    spawn_blocking({
+        let backup_id = backup_id.clone();
+        let decryption_context = service.decryption_context.clone();
+        let blueprints = service.archiving_context.blueprints.clone();
+        let hashing_config = service.hashing_config.clone();
+        let diff_config = service.diff_config.clone();
+
+        move || {
+            scan(
+                &verification_output,
+                &backup_id,
+                &decryption_context,
+                &blueprints,
+                &hashing_config,
+                &diff_config,
+            )
+        }
+    })
+    .await
+    .map_err(invalid)
+}
+
+/// Reads all entries of a backup, hashing their contents.
+fn scan(
+    VerificationOutput { backup_path, .. }: &VerificationOutput,
+    backup_id: &BackupId,
+    decryption_context: &DecryptionContext,
+    blueprints: &HashMap<u8, ArchiveBlueprint>,
+    hashing_config: &HashingConfig,
+    diff_config: &DiffConfig,
+) -> Result<ScannedBackup, ExtractionError> {
+    use std::io::Read as _;
+
+    let backup_file =
+        std::fs::File::open(backup_path.as_path()).context("Could not open backup file")?;
+
+    let mut archive = archive_reader(
+        backup_file,
+        backup_id,
+        decryption_context,
+        NoopStats,
+        &mut DecryptionReport::default(),
+        NoopStats,
+    )?;
+    let mut entries = archive.entries().map_err(anyhow::Error::from)?;
+
+    let metadata = read_metadata(&mut entries, backup_id, &mut NoopEventHandler)?;
+    if !blueprints.contains_key(&metadata.version) {
+        return Err(ExtractionError::UnknownBackupVersion(metadata.version));
+    }
+
+    let text_max_size = diff_config.text_max_size.as_bytes();
+
+    let mut scanned: BTreeMap<Vec<u8>, ScannedEntry> = BTreeMap::new();
+    for entry in entries {
+        let mut entry = entry?;
+
+        let mut path = entry.path_bytes().into_owned();
+        // NOTE: Directories are stored with a trailing `/`.
+        while path.len() > 1 && path.ends_with(b"/") {
+            path.pop();
+        }
+
+        let kind = match entry.header().entry_type() {
+            tar::EntryType::Regular | tar::EntryType::Continuous | tar::EntryType::GNUSparse => {
+                EntryKind::File
+            }
+            tar::EntryType::Directory => EntryKind::Directory,
+            tar::EntryType::Symlink => EntryKind::Symlink,
+            tar::EntryType::Link => EntryKind::HardLink,
+            _ => EntryKind::Other,
+        };
+        let link_target = entry.link_name_bytes().map(|target| target.into_owned());
+
+        let mut size_bytes = 0;
+        let mut digest = None;
+        let mut text = None;
+        if kind == EntryKind::File {
+            let mut hasher = crate::hashing::digest(hashing_config);
+
+            let is_text_file = (diff_config.text_file_suffixes.iter())
+                .any(|suffix| path.ends_with(suffix.as_bytes()))
+                && entry.size() <= text_max_size;
+
+            if is_text_file {
+                let mut contents = Vec::new();
+                entry.read_to_end(&mut contents)?;
+                std::io::Write::write_all(&mut hasher, &contents)?;
+                size_bytes = contents.len() as u64;
+                text = String::from_utf8(contents).ok();
+            } else {
+                size_bytes = std::io::copy(&mut entry, &mut hasher)?;
+            }
+
+            digest = Some(hasher.finalize());
+        }
+
+        scanned.insert(
+            path,
+            ScannedEntry {
+                kind,
+                size_bytes,
+                digest,
+                link_target,
+                text,
+            },
+        );
+    }
+
+    Ok(ScannedBackup {
+        version: metadata.version,
+        entries: scanned,
+    })
+}
+
+/// Maps paths of a backup created with an older blueprint, so they can be
+/// compared with paths of a backup created with blueprint `version`.
+fn migrate(backup: &mut ScannedBackup, version: u8, context: &RestorationContext) {
+    if backup.version >= version {
+        return;
+    }
+
+    let migrations = flatten(
+        filter_migrations(&context.migrations, backup.version, version)
+            .flat_map(|migration| migration.migrate_paths.iter()),
+    );
+    if migrations.is_empty() {
+        return;
+    }
+
+    backup.entries = std::mem::take(&mut backup.entries)
+        .into_iter()
+        .map(|(path, mut entry)| {
+            // NOTE: Hard links point to archive paths, which need to be
+            //   mapped too.
+            if entry.kind == EntryKind::HardLink {
+                entry.link_target = (entry.link_target.take())
+                    .map(|target| map_path_bytes(&target, migrations.iter(), std::iter::empty()).0);
+            }
+            let path = map_path_bytes(&path, migrations.iter(), std::iter::empty()).0;
+            (path, entry)
+        })
+        .collect();
+    backup.version = version;
+}
+
+/// Name of the blueprint entry `path` belongs to (or its first component if
+/// it’s not part of the blueprint).
+fn blueprint_key(path: &[u8], blueprint: &ArchiveBlueprint) -> String {
+    use std::os::unix::ffi::OsStrExt as _;
+
+    let key = (blueprint.paths.iter())
+        .map(|(archive_path, _)| archive_path.as_bytes())
+        .map(|key| key.strip_suffix(b"/").unwrap_or(key))
+        .filter(|key| match path.strip_prefix(*key) {
+            Some(suffix) => suffix.is_empty() || suffix.starts_with(b"/"),
+            None => false,
+        })
+        .max_by_key(|key| key.len());
+
+    let key = key.unwrap_or_else(|| path.split(|b| *b == b'/').next().unwrap_or(path));
+
+    String::from_utf8_lossy(key).into_owned()
+}
+
+// MARK: Text diffs
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineOp {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Computes a unified diff (like `diff -u`) between two texts.
+fn unified_diff(path: &str, before: &str, after: &str) -> String {
+    use std::fmt::Write as _;
+
+    let ops = line_ops(
+        &before.lines().collect::<Vec<_>>(),
+        &after.lines().collect::<Vec<_>>(),
+    );
+
+    let mut res = format!("--- a/{path}\n+++ b/{path}\n");
+
+    // Line numbers before each operation.
+    let mut line_numbers: Vec<(usize, usize)> = Vec::with_capacity(ops.len() + 1);
+    let (mut old, mut new) = (0, 0);
+    for (op, _) in ops.iter() {
+        line_numbers.push((old, new));
+        match op {
+            LineOp::Equal => (old, new) = (old + 1, new + 1),
+            LineOp::Delete => old += 1,
+            LineOp::Insert => new += 1,
+        }
+    }
+    line_numbers.push((old, new));
+
+    let next_change = |from: usize| {
+        (ops[from..].iter())
+            .position(|(op, _)| *op != LineOp::Equal)
+            .map(|i| from + i)
+    };
+
+    let mut i = 0;
+    while let Some(first_change) = next_change(i) {
+        // Merge changes separated by few unchanged lines.
+        let mut end = first_change;
+        loop {
+            while end < ops.len() && ops[end].0 != LineOp::Equal {
+                end += 1;
+            }
+            match next_change(end) {
+                Some(change) if change - end <= 2 * CONTEXT_LINES => end = change,
+                _ => break,
+            }
+        }
+
+        let start = first_change.saturating_sub(CONTEXT_LINES).max(i);
+        let end = (end + CONTEXT_LINES).min(ops.len());
+
+        let (old_start, new_start) = line_numbers[start];
+        let (old_end, new_end) = line_numbers[end];
+        let (old_len, new_len) = (old_end - old_start, new_end - new_start);
+        // NOTE: Empty ranges start at the line before.
+        let old_start = if old_len == 0 {
+            old_start
+        } else {
+            old_start + 1
+        };
+        let new_start = if new_len == 0 {
+            new_start
+        } else {
+            new_start + 1
+        };
+
+        writeln!(res, "@@ -{old_start},{old_len} +{new_start},{new_len} @@")
+            .expect("Writing to a `String` never fails");
+        for (op, line) in ops[start..end].iter() {
+            let prefix = match op {
+                LineOp::Equal => ' ',
+                LineOp::Delete => '-',
+                LineOp::Insert => '+',
+            };
+            writeln!(res, "{prefix}{line}").expect("Writing to a `String` never fails");
+        }
+
+        i = end;
+    }
+
+    res
+}
+
+/// Matches lines using their longest common subsequence.
+fn line_ops<'a>(before: &[&'a str], after: &[&'a str]) -> Vec<(LineOp, &'a str)> {
+    let prefix_len = (before.iter().zip(after.iter()))
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix_len = (before[prefix_len..].iter().rev())
+        .zip(after[prefix_len..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old = &before[prefix_len..(before.len() - suffix_len)];
+    let new = &after[prefix_len..(after.len() - suffix_len)];
+
+    let mut ops: Vec<(LineOp, &'a str)> = Vec::with_capacity(before.len() + after.len());
+    ops.extend(
+        before[..prefix_len]
+            .iter()
+            .map(|line| (LineOp::Equal, *line)),
+    );
+
+    if old.len().saturating_mul(new.len()) > MAX_MATCHED_LINES {
+        ops.extend(old.iter().map(|line| (LineOp::Delete, *line)));
+        ops.extend(new.iter().map(|line| (LineOp::Insert, *line)));
+    } else {
+        // `lcs[i * (new.len() + 1) + j]` is the length of the longest common
+        // subsequence of `old[i..]` and `new[j..]`.
+        let width = new.len() + 1;
+        let mut lcs = vec![0u32; (old.len() + 1) * width];
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                lcs[i * width + j] = if old[i] == new[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < old.len() && j < new.len() {
+            if old[i] == new[j] {
+                ops.push((LineOp::Equal, old[i]));
+                (i, j) = (i + 1, j + 1);
+            } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+                ops.push((LineOp::Delete, old[i]));
+                i += 1;
+            } else {
+                ops.push((LineOp::Insert, new[j]));
+                j += 1;
+            }
+        }
+        ops.extend(old[i..].iter().map(|line| (LineOp::Delete, *line)));
+        ops.extend(new[j..].iter().map(|line| (LineOp::Insert, *line)));
+    }
+
+    ops.extend(
+        before[(before.len() - suffix_len)..]
+            .iter()
+            .map(|line| (LineOp::Equal, *line)),
+    );
+
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_diff() {
+        let before = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let after = "a\nb\nc\nD\ne\nf\ng\nh\ni\nj\nk\n";
+
+        assert_eq!(
+            unified_diff("foo.toml", before, after),
+            "--- a/foo.toml\n\
+            +++ b/foo.toml\n\
+            @@ -1,10 +1,11 @@\n \
+            a\n \
+            b\n \
+            c\n\
+            -d\n\
+            +D\n \
+            e\n \
+            f\n \
+            g\n \
+            h\n \
+            i\n \
+            j\n\
+            +k\n"
+        );
+    }
+
+    #[test]
+    fn test_unified_diff_hunks() {
+        let before = (1..=20).map(|n| format!("{n}\n")).collect::<String>();
+        let after = (1..=20)
+            .filter(|n| *n != 19)
+            .map(|n| match n {
+                2 => "two\n".to_owned(),
+                n => format!("{n}\n"),
+            })
+            .collect::<String>();
+
+        assert_eq!(
+            unified_diff("foo.toml", &before, &after),
+            "--- a/foo.toml\n\
+            +++ b/foo.toml\n\
+            @@ -1,5 +1,5 @@\n \
+            1\n\
+            -2\n\
+            +two\n \
+            3\n \
+            4\n \
+            5\n\
+            @@ -16,5 +16,4 @@\n \
+            16\n \
+            17\n \
+            18\n\
+            -19\n \
+            20\n"
+        );
+    }
+
+    #[test]
+    fn test_unified_diff_insert_only() {
+        assert_eq!(
+            unified_diff("foo.toml", "", "a\n"),
+            "--- a/foo.toml\n+++ b/foo.toml\n@@ -0,0 +1,1 @@\n+a\n"
+        );
+    }
+}
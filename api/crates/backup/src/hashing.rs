@@ -65,3 +65,75 @@ impl DigestWriter {
         }
     }
 }
+
+impl config::HashingAlgorithm {
+    /// Short name of the algorithm, used as the file extension of digests
+    /// (e.g. `.blake3`) and as the prefix of hex-encoded digests
+    /// (e.g. `blake3:…`).
+    pub(crate) fn extension(self) -> &'static str {
+        match self {
+            #[cfg(feature = "hashing-blake3")]
+            Self::Blake3 => "blake3",
+            #[cfg(feature = "hashing-sha2")]
+            Self::Sha256 => "sha256",
+        }
+    }
+
+    pub(crate) fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            #[cfg(feature = "hashing-blake3")]
+            "blake3" => Some(Self::Blake3),
+            #[cfg(feature = "hashing-sha2")]
+            "sha256" => Some(Self::Sha256),
+            _ => None,
+        }
+    }
+}
+
+/// Hashes `data` and encodes the result as `<algorithm>:<hex>`
+/// (e.g. `blake3:…`).
+pub(crate) fn hex_digest(algorithm: config::HashingAlgorithm, data: &[u8]) -> String {
+    let mut writer = digest(&HashingConfig { algorithm });
+    writer
+        .write_all(data)
+        .expect("Writing to a hasher never fails");
+
+    encode_hex_digest(algorithm, &writer.finalize())
+}
+
+pub(crate) fn encode_hex_digest(algorithm: config::HashingAlgorithm, digest: &[u8]) -> String {
+    use std::fmt::Write as _;
+
+    let mut res = String::with_capacity(algorithm.extension().len() + 1 + 2 * digest.len());
+    res.push_str(algorithm.extension());
+    res.push(':');
+    for byte in digest {
+        write!(res, "{byte:02x}").expect("Writing to a `String` never fails");
+    }
+    res
+}
+
+/// Parses a digest encoded as `<algorithm>:<hex>` (e.g. `blake3:…`), as
+/// returned by [`encode_hex_digest`].
+pub(crate) fn decode_hex_digest(
+    encoded: &str,
+) -> Result<(config::HashingAlgorithm, Vec<u8>), anyhow::Error> {
+    let Some((extension, hex)) = encoded.trim().split_once(':') else {
+        anyhow::bail!("Digest has no algorithm prefix (e.g. `blake3:…`).");
+    };
+
+    let Some(algorithm) = config::HashingAlgorithm::from_extension(extension) else {
+        anyhow::bail!("Unsupported hashing algorithm `{extension}`.");
+    };
+
+    if hex.is_empty() || hex.len() % 2 != 0 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        anyhow::bail!("Digest is not valid hexadecimal.");
+    }
+
+    let digest = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+        .collect::<Result<Vec<u8>, _>>()?;
+
+    Ok((algorithm, digest))
+}
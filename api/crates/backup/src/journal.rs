@@ -0,0 +1,239 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+//! On-disk journal of restorations.
+//!
+//! [`RestoreRevertGuard`](crate::restoration::RestoreRevertGuard) reverts
+//! changes when dropped, which doesn’t help if the process is killed during
+//! a restoration. Each step of a restoration is therefore recorded on disk
+//! before being made, so an interrupted restoration can be rolled back (or
+//! forward if it was committed) the next time the server starts.
+//!
+//! See [`RestorationConfig`](crate::config::RestorationConfig).
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context as _, anyhow};
+
+use crate::BackupId;
+use crate::BackupService;
+use crate::safety_snapshot::{PendingSafetySnapshot, UndoingSafetySnapshot};
+use crate::util::BlockingTask;
+use crate::util::fs::{sync_parent, write_durably};
+
+/// Steps of a restoration, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestorePhase {
+    /// Destinations are known, nothing has changed yet.
+    Staged,
+
+    /// Existing destinations were moved aside (e.g. `.bak`).
+    OldMovedAside,
+
+    /// The backup was extracted in destinations.
+    NewMovedIn,
+
+    /// The restoration succeeded, old data can be deleted.
+    Committed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryOutcome {
+    /// Old data was put back in place.
+    RolledBack,
+
+    /// Old data was deleted.
+    RolledForward,
+}
+
+/// What was done to recover from an interrupted restoration.
+#[derive(Debug, Clone)]
+#[derive(serde::Serialize)]
+pub struct RestoreRecovery {
+    pub backup_id: String,
+
+    /// Last recorded step of the interrupted restoration.
+    pub phase: RestorePhase,
+
+    pub outcome: RecoveryOutcome,
+}
+
+#[derive(Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct JournalData {
+    backup_id: String,
+
+    phase: RestorePhase,
+
+    /// `(path, backup_path_opt)` pairs, like in
+    /// [`RestoreRevertGuard`](crate::restoration::RestoreRevertGuard).
+    paths: Vec<(PathBuf, Option<PathBuf>)>,
+
+    /// Safety snapshot to take when rolling forward (see
+    /// [`crate::safety_snapshot`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    safety_snapshot: Option<PendingSafetySnapshot>,
+
+    /// Set if this is an undo of a restoration (see
+    /// [`crate::safety_snapshot`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    undo: Option<UndoingSafetySnapshot>,
+}
+
+#[derive(Debug)]
+pub(crate) struct RestoreJournal {
+    path: PathBuf,
+    data: JournalData,
+}
+
+impl RestoreJournal {
+    /// Records a [`RestorePhase::Staged`] restoration.
+    pub(crate) fn create(
+        path: &Path,
+        backup_id: &BackupId,
+        paths: &[(PathBuf, Option<PathBuf>)],
+    ) -> Result<Self, anyhow::Error> {
+        // NOTE: Overwriting a journal would make it impossible to recover
+        //   the interrupted restoration it records.
+        if path.exists() {
+            return Err(anyhow!(
+                "Found the journal of an interrupted restoration at {path:?}. \
+                It must be recovered first."
+            ));
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Could not create journal directory {parent:?}"))?;
+        }
+
+        let journal = Self {
+            path: path.to_path_buf(),
+            data: JournalData {
+                backup_id: backup_id.to_string(),
+                phase: RestorePhase::Staged,
+                paths: paths.to_vec(),
+                safety_snapshot: None,
+                undo: None,
+            },
+        };
+
+        journal.write()?;
+
+        Ok(journal)
+    }
+
+    pub(crate) fn set_phase(&mut self, phase: RestorePhase) -> Result<(), anyhow::Error> {
+        self.data.phase = phase;
+        self.write()
+    }
+
+    pub(crate) fn set_safety_snapshot(
+        &mut self,
+        safety_snapshot: PendingSafetySnapshot,
+    ) -> Result<(), anyhow::Error> {
+        self.data.safety_snapshot = Some(safety_snapshot);
+        self.write()
+    }
+
+    /// Records that the safety snapshot was taken.
+    pub(crate) fn clear_safety_snapshot(&mut self) -> Result<(), anyhow::Error> {
+        self.data.safety_snapshot = None;
+        self.write()
+    }
+
+    pub(crate) fn set_undo(&mut self, undo: UndoingSafetySnapshot) -> Result<(), anyhow::Error> {
+        self.data.undo = Some(undo);
+        self.write()
+    }
+
+    /// Deletes the journal, once the restoration is finished (or reverted).
+    pub(crate) fn delete(self) -> Result<(), anyhow::Error> {
+        std::fs::remove_file(&self.path)
+            .and_then(|()| sync_parent(&self.path))
+            .with_context(|| format!("Could not delete restoration journal {:?}", self.path))
+    }
+
+    fn write(&self) -> Result<(), anyhow::Error> {
+        let contents = json::to_vec(&self.data).context("Could not serialize journal")?;
+
+        write_durably(&self.path, &contents, 0o666)
+            .with_context(|| format!("Could not write restoration journal {:?}", self.path))
+    }
+}
+
+// MARK: Recovery
+
+pub(crate) async fn recover_interrupted_restoration(
+    service: &BackupService,
+) -> Result<Option<RestoreRecovery>, anyhow::Error> {
+    let Some(journal_path) = service.restoration_config.journal_path.clone() else {
+        return Ok(None);
+    };
+
+    // NOTE: Moving and deleting files is blocking, and can take time.
+    BlockingTask::<_, ()>::spawn(1, move |_| recover(&journal_path))
+        .join()
+        .await
+}
+
+/// Rolls an interrupted restoration back, or forward if it was committed.
+///
+/// Returns `None` if no restoration was interrupted.
+fn recover(journal_path: &Path) -> Result<Option<RestoreRecovery>, anyhow::Error> {
+    use crate::restoration::{roll_back, roll_forward};
+
+    let contents = match std::fs::read(journal_path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(anyhow::Error::new(err).context(format!(
+                "Could not read restoration journal {journal_path:?}"
+            )));
+        }
+    };
+
+    let data: JournalData = json::from_slice(&contents)
+        .with_context(|| format!("Invalid restoration journal {journal_path:?}"))?;
+
+    tracing::warn!(
+        "Found an interrupted restoration of backup {backup_id:?} (phase: {phase:?}). Recovering…",
+        backup_id = data.backup_id,
+        phase = data.phase,
+    );
+
+    let mut journal = RestoreJournal {
+        path: journal_path.to_path_buf(),
+        data,
+    };
+    let paths = journal.data.paths.clone();
+    let undo = journal.data.undo.clone();
+
+    // NOTE: If this fails, the journal is kept so recovery can be retried.
+    let outcome = if journal.data.phase == RestorePhase::Committed {
+        let safety_snapshot = journal.data.safety_snapshot.clone();
+        roll_forward(&paths, safety_snapshot, undo.as_ref(), Some(&mut journal))?;
+        RecoveryOutcome::RolledForward
+    } else {
+        roll_back(&paths, undo.as_ref())?;
+        RecoveryOutcome::RolledBack
+    };
+
+    let recovery = RestoreRecovery {
+        backup_id: journal.data.backup_id.clone(),
+        phase: journal.data.phase,
+        outcome,
+    };
+
+    journal.delete()?;
+
+    tracing::info!("Recovered interrupted restoration: {recovery:?}");
+
+    Ok(Some(recovery))
+}
@@ -0,0 +1,476 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+//! OpenPGP keys managed at runtime (see
+//! [`KeysConfig`](crate::config::KeysConfig)), in addition to the ones
+//! configured (which can only be changed by editing the configuration).
+//!
+//! Managed keys are stored in a directory only accessible by its owner:
+//!
+//! - Generated keys are stored as Transferable Secret Keys (TSKs), and used
+//!   to encrypt or sign new backups.
+//! - Imported keys are only used as additional encryption recipients (e.g.
+//!   to decrypt backups in a separate environment), they MUST NOT contain
+//!   secret key material.
+//!
+//! Retired keys are no longer used to encrypt or sign new backups, but are
+//! still used to decrypt and verify existing ones.
+//!
+//! NOTE: A [`BackupService`] loads keys when it’s created. Create it again
+//!   (see [`BackupService::from_config`]) to use keys added or retired since.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context as _, anyhow};
+use openpgp::cert::prelude::*;
+use openpgp::policy::Policy;
+
+use crate::BackupService;
+use crate::util::BlockingTask;
+use crate::util::fs::write_durably;
+
+const KEYRING_FILE_NAME: &'static str = "keyring.json";
+
+/// Mode of the keyring directory (only accessible by its owner).
+const DIRECTORY_MODE: u32 = 0o700;
+
+/// Mode of files in the keyring (only readable by their owner).
+const FILE_MODE: u32 = 0o600;
+
+/// Maximum size of an imported key (certificates with a lot of signatures
+/// can be large, but not that large).
+const MAX_IMPORTED_KEY_LENGTH: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyUsage {
+    /// Secret key, used to encrypt and decrypt backups.
+    Encryption,
+
+    /// Secret key, used to sign backups and verify signatures.
+    Signing,
+
+    /// Public key, used as an additional encryption recipient.
+    Recipient,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeySource {
+    /// Configured (see [`BackupConfig`](crate::BackupConfig)).
+    Config,
+
+    /// Generated or imported at runtime (see [`crate::keys`]).
+    Managed,
+}
+
+/// A key used by a [`BackupService`].
+#[derive(Debug, Clone)]
+#[derive(serde::Serialize)]
+pub struct KeyDto {
+    /// Fingerprint of the certificate’s primary key (uppercase hexadecimal).
+    pub fingerprint: String,
+
+    pub usage: KeyUsage,
+
+    pub source: KeySource,
+
+    pub user_ids: Vec<String>,
+
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: time::OffsetDateTime,
+
+    /// `None` if the key never expires (or if it’s invalid).
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub expires_at: Option<time::OffsetDateTime>,
+
+    /// `None` if the key is not retired (configured keys can’t be).
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub retired_at: Option<time::OffsetDateTime>,
+
+    /// Whether or not private key material is stored on the server.
+    pub has_secret: bool,
+}
+
+impl KeyDto {
+    pub(crate) fn new(
+        cert: &openpgp::Cert,
+        usage: KeyUsage,
+        source: KeySource,
+        retired_at: Option<time::OffsetDateTime>,
+        policy: &dyn Policy,
+    ) -> Self {
+        let expires_at = (cert.with_policy(policy, None).ok())
+            .and_then(|cert| cert.primary_key().key_expiration_time());
+
+        Self {
+            fingerprint: cert.fingerprint().to_hex(),
+            usage,
+            source,
+            user_ids: (cert.userids())
+                .map(|ua| String::from_utf8_lossy(ua.userid().value()).into_owned())
+                .collect(),
+            created_at: cert.primary_key().key().creation_time().into(),
+            expires_at: expires_at.map(Into::into),
+            retired_at,
+            has_secret: cert.is_tsk(),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum KeyringError {
+    #[error("Key management is disabled (see `keys.directory`)")]
+    Disabled,
+
+    #[error("Key `{0}` not found")]
+    NotFound(String),
+
+    #[error("Key `{0}` already exists")]
+    AlreadyExists(String),
+
+    /// Configured keys can only be changed by editing the configuration.
+    #[error("Key `{0}` is configured, it cannot be changed at runtime")]
+    Configured(String),
+
+    #[error("Invalid key")]
+    InvalidKey(#[source] anyhow::Error),
+
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+// MARK: Keyring
+
+/// Entry of the keyring file.
+#[derive(Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct KeyringEntry {
+    fingerprint: String,
+
+    usage: KeyUsage,
+
+    #[serde(default, with = "time::serde::timestamp::option")]
+    retired_at: Option<time::OffsetDateTime>,
+}
+
+#[derive(Debug, Default)]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct KeyringFile {
+    keys: Vec<KeyringEntry>,
+}
+
+/// A key stored in a [`Keyring`].
+#[derive(Debug)]
+pub(crate) struct ManagedKey {
+    pub(crate) cert: openpgp::Cert,
+    pub(crate) usage: KeyUsage,
+    pub(crate) retired_at: Option<time::OffsetDateTime>,
+}
+
+impl ManagedKey {
+    #[inline]
+    pub(crate) fn is_active(&self) -> bool {
+        self.retired_at.is_none()
+    }
+}
+
+/// Directory containing managed keys.
+///
+/// NOTE: Mutations are not synchronized, callers must make sure they
+///   don’t happen concurrently.
+#[derive(Debug, Clone)]
+pub struct Keyring {
+    directory: PathBuf,
+}
+
+impl Keyring {
+    /// Opens a keyring, creating its directory if needed.
+    ///
+    /// NOTE: If the directory is accessible by other users, its permissions
+    ///   are restricted.
+    pub fn open(directory: &Path) -> Result<Self, anyhow::Error> {
+        use std::os::unix::fs::{DirBuilderExt as _, PermissionsExt as _};
+
+        std::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(DIRECTORY_MODE)
+            .create(directory)
+            .with_context(|| format!("Could not create keyring directory {directory:?}"))?;
+
+        let permissions = std::fs::metadata(directory)
+            .with_context(|| format!("Could not read keyring directory {directory:?}"))?
+            .permissions();
+        if permissions.mode() & 0o077 != 0 {
+            tracing::warn!(
+                "Keyring directory {directory:?} was accessible by other users \
+                (mode {mode:o}). Restricting permissions.",
+                mode = permissions.mode() & 0o777,
+            );
+            std::fs::set_permissions(directory, std::fs::Permissions::from_mode(DIRECTORY_MODE))
+                .with_context(|| format!("Could not restrict permissions of {directory:?}"))?;
+        }
+
+        Ok(Self {
+            directory: directory.to_path_buf(),
+        })
+    }
+
+    fn key_path(&self, fingerprint: &str) -> PathBuf {
+        self.directory.join(format!("{fingerprint}.asc"))
+    }
+
+    fn read_file(&self) -> Result<KeyringFile, anyhow::Error> {
+        let path = self.directory.join(KEYRING_FILE_NAME);
+
+        match std::fs::read(&path) {
+            Ok(bytes) => {
+                json::from_slice(&bytes).with_context(|| format!("Invalid keyring {path:?}"))
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(KeyringFile::default()),
+            Err(err) => {
+                Err(anyhow::Error::new(err).context(format!("Could not read keyring {path:?}")))
+            }
+        }
+    }
+
+    fn write_file(&self, file: &KeyringFile) -> Result<(), anyhow::Error> {
+        let path = self.directory.join(KEYRING_FILE_NAME);
+
+        let contents = json::to_vec_pretty(file).context("Could not serialize keyring")?;
+
+        write_durably(&path, &contents, FILE_MODE)
+            .with_context(|| format!("Could not write keyring {path:?}"))
+    }
+
+    /// Reads all keys in the keyring (including retired ones).
+    pub(crate) fn load(&self) -> Result<Vec<ManagedKey>, anyhow::Error> {
+        use openpgp::parse::Parse as _;
+
+        let file = self.read_file()?;
+
+        let mut keys = Vec::with_capacity(file.keys.len());
+        for KeyringEntry {
+            fingerprint,
+            usage,
+            retired_at,
+        } in file.keys
+        {
+            let path = self.key_path(&fingerprint);
+            let cert = openpgp::Cert::from_file(&path)
+                .with_context(|| format!("Could not read key {path:?}"))?;
+
+            keys.push(ManagedKey {
+                cert,
+                usage,
+                retired_at,
+            });
+        }
+
+        Ok(keys)
+    }
+
+    /// Adds a key to the keyring, storing its secret key material if any.
+    fn add(&self, cert: &openpgp::Cert, usage: KeyUsage) -> Result<(), KeyringError> {
+        use openpgp::serialize::SerializeInto as _;
+
+        let mut file = self.read_file()?;
+
+        let fingerprint = cert.fingerprint().to_hex();
+        if file
+            .keys
+            .iter()
+            .any(|entry| entry.fingerprint == fingerprint)
+        {
+            return Err(KeyringError::AlreadyExists(fingerprint));
+        }
+
+        let armored = if cert.is_tsk() {
+            cert.as_tsk().armored().to_vec()
+        } else {
+            cert.armored().to_vec()
+        }
+        .context("Could not serialize key")?;
+
+        // NOTE: Write the key first, so the keyring never references
+        //   a missing key.
+        let path = self.key_path(&fingerprint);
+        write_durably(&path, &armored, FILE_MODE)
+            .with_context(|| format!("Could not write key {path:?}"))?;
+
+        file.keys.push(KeyringEntry {
+            fingerprint,
+            usage,
+            retired_at: None,
+        });
+        self.write_file(&file)?;
+
+        Ok(())
+    }
+
+    /// Generates a key to encrypt or sign new backups.
+    pub fn generate(
+        &self,
+        usage: KeyUsage,
+        user_id: &str,
+        validity: Option<Duration>,
+    ) -> Result<openpgp::Cert, KeyringError> {
+        let builder = CertBuilder::new()
+            .add_userid(user_id)
+            .set_validity_period(validity);
+        let builder = match usage {
+            KeyUsage::Encryption => builder.add_storage_encryption_subkey(),
+            KeyUsage::Signing => builder.add_signing_subkey(),
+            KeyUsage::Recipient => {
+                return Err(KeyringError::InvalidKey(anyhow!(
+                    "Recipients must be imported, their secret key material \
+                    must not be stored on the server."
+                )));
+            }
+        };
+
+        let (cert, _revocation) = builder.generate().context("Could not generate key")?;
+
+        self.add(&cert, usage)?;
+
+        tracing::info!("Generated {usage:?} key `{}`.", cert.fingerprint());
+
+        Ok(cert)
+    }
+
+    /// Imports a public key, to use as an additional encryption recipient.
+    pub fn import_recipient(
+        &self,
+        key: &[u8],
+        policy: &dyn Policy,
+    ) -> Result<openpgp::Cert, KeyringError> {
+        use openpgp::parse::Parse as _;
+
+        if key.len() > MAX_IMPORTED_KEY_LENGTH {
+            return Err(KeyringError::InvalidKey(anyhow!(
+                "Key too large (max {MAX_IMPORTED_KEY_LENGTH} bytes)."
+            )));
+        }
+
+        let cert = openpgp::Cert::from_bytes(key).map_err(KeyringError::InvalidKey)?;
+
+        if cert.is_tsk() {
+            return Err(KeyringError::InvalidKey(anyhow!(
+                "Key contains secret key material, import its public key only."
+            )));
+        }
+
+        let can_encrypt = (cert.keys())
+            .with_policy(policy, None)
+            .supported()
+            .alive()
+            .revoked(false)
+            .for_storage_encryption()
+            .next()
+            .is_some();
+        if !can_encrypt {
+            return Err(KeyringError::InvalidKey(anyhow!(
+                "Key has no valid storage encryption key."
+            )));
+        }
+
+        self.add(&cert, KeyUsage::Recipient)?;
+
+        tracing::info!("Imported recipient key `{}`.", cert.fingerprint());
+
+        Ok(cert)
+    }
+
+    /// Marks a key as retired (no-op if it already is).
+    pub fn retire(&self, fingerprint: &openpgp::Fingerprint) -> Result<(), KeyringError> {
+        let mut file = self.read_file()?;
+
+        let fingerprint = fingerprint.to_hex();
+        let Some(entry) = (file.keys.iter_mut()).find(|entry| entry.fingerprint == fingerprint)
+        else {
+            return Err(KeyringError::NotFound(fingerprint));
+        };
+
+        if entry.retired_at.is_some() {
+            return Ok(());
+        }
+        entry.retired_at = Some(SystemTime::now().into());
+
+        self.write_file(&file)?;
+
+        tracing::info!("Retired key `{fingerprint}`.");
+
+        Ok(())
+    }
+}
+
+// MARK: Service
+
+impl BackupService {
+    fn keyring(&self) -> Result<Keyring, KeyringError> {
+        self.keyring.clone().ok_or(KeyringError::Disabled)
+    }
+
+    /// Generates a key to encrypt or sign new backups (see [`crate::keys`]).
+    pub async fn generate_key(
+        &self,
+        usage: KeyUsage,
+        user_id: String,
+        validity: Option<Duration>,
+    ) -> Result<KeyDto, KeyringError> {
+        let keyring = self.keyring()?;
+
+        let cert =
+            BlockingTask::<_, ()>::spawn(1, move |_| keyring.generate(usage, &user_id, validity))
+                .join()
+                .await?;
+
+        Ok(KeyDto::new(
+            &cert,
+            usage,
+            KeySource::Managed,
+            None,
+            &openpgp::policy::StandardPolicy::new(),
+        ))
+    }
+
+    /// Imports a public key, to use as an additional encryption recipient
+    /// (see [`crate::keys`]).
+    pub async fn import_recipient(&self, key: Vec<u8>) -> Result<KeyDto, KeyringError> {
+        let keyring = self.keyring()?;
+
+        let cert = BlockingTask::<_, ()>::spawn(1, move |_| {
+            keyring.import_recipient(&key, &openpgp::policy::StandardPolicy::new())
+        })
+        .join()
+        .await?;
+
+        Ok(KeyDto::new(
+            &cert,
+            KeyUsage::Recipient,
+            KeySource::Managed,
+            None,
+            &openpgp::policy::StandardPolicy::new(),
+        ))
+    }
+
+    /// Marks a managed key as retired (see [`crate::keys`]).
+    pub async fn retire_key(&self, fingerprint: &openpgp::Fingerprint) -> Result<(), KeyringError> {
+        let keyring = self.keyring()?;
+
+        let hex = fingerprint.to_hex();
+        if (self.keys.iter()).any(|key| key.source == KeySource::Config && key.fingerprint == hex) {
+            return Err(KeyringError::Configured(hex));
+        }
+
+        let fingerprint = fingerprint.clone();
+        BlockingTask::<_, ()>::spawn(1, move |_| keyring.retire(&fingerprint))
+            .join()
+            .await
+    }
+}
@@ -28,14 +28,22 @@ pub mod archiving;
 mod compression;
 pub mod config;
 pub mod decryption;
+pub mod diff;
 pub mod encryption;
 pub mod event_handlers;
 mod hashing;
+pub mod journal;
+pub mod keys;
 mod pgp;
+pub mod profiles;
 pub mod restoration;
+pub mod restore_report;
+pub mod safety_snapshot;
 pub mod signing;
+pub mod snapshot;
 pub mod stats;
 pub mod stores;
+pub mod transparency;
 mod util;
 pub mod verification;
 
@@ -51,23 +59,44 @@ pub use self::backup_id::*;
 pub use self::config::BackupConfig;
 pub use self::create::*;
 pub use self::restore::*;
+pub use self::upload::*;
 
 // MARK: Service
 
 /// Backup service. Central component of the library.
 pub struct BackupService {
     pub archiving_context: archiving::Context,
+    pub archive_config: config::ArchiveConfig,
     pub compression_config: config::CompressionConfig,
     pub hashing_config: config::HashingConfig,
-    pub encryption_context: Option<encryption::Context>,
+    /// NOTE: PGP contexts are reference-counted so they can be moved to
+    ///   blocking tasks (which run synchronous pipelines without tying up
+    ///   async workers).
+    pub encryption_context: Option<Arc<encryption::Context>>,
     pub signing_context: signing::Context,
     pub verification_context: verification::Context,
     pub decryption_context: decryption::Context,
     pub restoration_context: restoration::Context,
+    pub restoration_config: config::RestorationConfig,
+    pub safety_snapshot_config: config::SafetySnapshotConfig,
+    pub transparency_log_config: config::TransparencyLogConfig,
+    pub snapshot_config: config::SnapshotConfig,
     pub download_config: config::DownloadConfig,
+    pub diff_config: config::DiffConfig,
+    pub quota_config: config::QuotaConfig,
+    pub progress_config: config::ProgressConfig,
 
-    pub backup_store: stores::CachedStore<Box<dyn stores::ObjectStore>>,
-    pub check_store: Box<dyn stores::ObjectStore>,
+    /// Named profiles (see [`config::BackupProfileConfig`]).
+    pub profiles: HashMap<String, profiles::BackupProfile>,
+
+    /// Keys managed at runtime, `None` if disabled (see [`keys`]).
+    pub keyring: Option<keys::Keyring>,
+
+    /// Keys in use when the service was created (configured and managed).
+    pub keys: Vec<keys::KeyDto>,
+
+    pub backup_store: stores::CachedStore<Arc<dyn stores::ObjectStore>>,
+    pub check_store: Arc<dyn stores::ObjectStore>,
 }
 crate::util::assert_impl!(BackupService: Send);
 crate::util::assert_impl!(BackupService: Sync);
@@ -116,6 +145,11 @@ impl BackupService {
     ///                                      └─────┬─────┘
     ///                                            ◯
     /// ```
+    ///
+    /// Archiving, compression, encryption, hashing and signing are CPU-bound
+    /// and read files synchronously, so they run on Tokio’s blocking thread
+    /// pool. Uploads are async. Dropping the returned future cancels the
+    /// backup creation (and deletes the partial upload).
     #[inline]
     pub async fn create_backup<D: archiving::AdditionalData>(
         &self,
@@ -125,6 +159,43 @@ impl BackupService {
         crate::create::create_backup(self, command, event_handler).await
     }
 
+    /// Store a backup created elsewhere (e.g. by another server), after
+    /// checking it against the given digest and/or detached signature.
+    ///
+    /// The backup is verified as if it was about to be restored (signing
+    /// policy included), then recorded in the transparency log. If anything
+    /// fails, it’s deleted.
+    #[inline]
+    pub async fn upload_backup(
+        &self,
+        command: upload::UploadBackupCommand,
+        backup: impl tokio::io::AsyncRead + Unpin + Send,
+    ) -> Result<upload::UploadBackupSuccess, upload::UploadBackupError> {
+        crate::upload::upload_backup(self, command, backup).await
+    }
+
+    /// Snapshot the paths of a blueprint (see [`config::SnapshotConfig`]).
+    ///
+    /// To reduce downtime, stop the server, snapshot its data, restart it,
+    /// then create a backup using [`snapshot::Snapshot::blueprint`].
+    #[inline]
+    pub async fn create_snapshot(
+        &self,
+        blueprint: &archiving::ArchiveBlueprint,
+    ) -> Result<snapshot::Snapshot, anyhow::Error> {
+        crate::snapshot::create_snapshot(self, blueprint).await
+    }
+
+    /// Deletes snapshots which were never deleted (e.g. if the process was
+    /// killed while creating a backup).
+    ///
+    /// WARN: Must not run while a snapshot is in use (i.e. call it on
+    ///   startup).
+    #[inline]
+    pub async fn purge_stale_snapshots(&self) -> Result<(), anyhow::Error> {
+        crate::snapshot::purge_stale_snapshots(self).await
+    }
+
     /// List all backups, in alphabetically descending order.
     #[inline]
     pub async fn list_backups(
@@ -141,6 +212,20 @@ impl BackupService {
         crate::read::get_details(self, backup_id).await
     }
 
+    /// Find the most recent backup which can be restored (intact and
+    /// trusted), e.g. to restore it on a new machine.
+    ///
+    /// NOTE: Backups are downloaded and verified one by one, most recent
+    ///   first, until one matches. Returns `None` if none does.
+    #[inline]
+    pub async fn latest_intact_backup(
+        &self,
+        prefix: Option<&str>,
+        required_signer: Option<&openpgp::Fingerprint>,
+    ) -> Result<Option<BackupId>, anyhow::Error> {
+        crate::read::latest_intact_backup(self, prefix, required_signer).await
+    }
+
     /// Get a short-lived URL to download a backup.
     #[inline]
     pub async fn get_download_url(
@@ -151,6 +236,51 @@ impl BackupService {
         crate::read::get_download_url(self, backup_id, ttl).await
     }
 
+    /// Get what’s needed to serve a backup over HTTP (size and digest).
+    #[inline]
+    pub async fn get_download_info(
+        &self,
+        backup_id: &BackupId,
+    ) -> Result<BackupDownloadInfoDto, stores::ReadObjectError> {
+        crate::read::get_download_info(self, backup_id).await
+    }
+
+    /// Read bytes in `range` of a backup, as stored (i.e. compressed and
+    /// maybe encrypted), e.g. to serve HTTP range requests.
+    #[inline]
+    pub async fn read_backup_range(
+        &self,
+        backup_id: &BackupId,
+        range: std::ops::Range<u64>,
+    ) -> Result<Box<stores::prelude::DynAsyncObjectReader>, stores::ReadObjectError> {
+        crate::read::read_backup_range(self, backup_id, range).await
+    }
+
+    /// Compare two backups: paths added, removed or modified in `b`
+    /// compared to `a`, grouped by blueprint entry.
+    ///
+    /// NOTE: Both backups are downloaded and verified, then read entirely
+    ///   (but not extracted). Backups created with an older blueprint are
+    ///   migrated so paths can be compared.
+    #[inline]
+    pub async fn diff(
+        &self,
+        a: &BackupId,
+        b: &BackupId,
+    ) -> Result<diff::BackupDiffDto, diff::DiffBackupsError> {
+        crate::diff::diff(self, a, b).await
+    }
+
+    /// Aggregate the storage used by backups and integrity checks, estimate
+    /// its growth and compare it against the configured quota (if any).
+    ///
+    /// NOTE: This lists all objects in both stores, but doesn’t download
+    ///   anything.
+    #[inline]
+    pub async fn storage_report(&self) -> Result<StorageReportDto, anyhow::Error> {
+        crate::report::storage_report(self).await
+    }
+
     #[inline]
     pub async fn restore_backup<EventHandler>(
         &self,
@@ -164,6 +294,12 @@ impl BackupService {
         crate::restore::restore_backup(self, backup_id, blueprint, event_handler).await
     }
 
+    /// Like [`BackupService::restore_backup`], but returns additional data
+    /// found in the backup instead of failing.
+    ///
+    /// The backup is downloaded asynchronously, then decrypted, decompressed
+    /// and extracted on Tokio’s blocking thread pool. Dropping the returned
+    /// future cancels the restoration and reverts all changes.
     #[inline]
     pub async fn restore_backup_partial<EventHandler>(
         &self,
@@ -177,10 +313,84 @@ impl BackupService {
         crate::restore::restore_backup_partial(self, backup_id, blueprint, event_handler).await
     }
 
+    /// Rolls back (or forward, if it was committed) a restoration which was
+    /// interrupted (e.g. because the process was killed), using the
+    /// restoration journal (see [`config::RestorationConfig::journal_path`]).
+    ///
+    /// Call this before starting anything which uses restored data, as it
+    /// might be inconsistent. Returns `None` if no restoration was
+    /// interrupted (or if journaling is disabled).
+    #[inline]
+    pub async fn recover_interrupted_restoration(
+        &self,
+    ) -> Result<Option<journal::RestoreRecovery>, anyhow::Error> {
+        crate::journal::recover_interrupted_restoration(self).await
+    }
+
+    /// Keeps `report` as the report of the last restoration (see
+    /// [`config::RestorationConfig::report_path`]).
+    #[inline]
+    pub async fn save_restore_report(
+        &self,
+        report: &restore_report::RestoreReportDto,
+    ) -> Result<(), anyhow::Error> {
+        crate::restore_report::save_restore_report(self, report).await
+    }
+
+    /// Report of the last restoration, if one was kept (see
+    /// [`save_restore_report`](Self::save_restore_report)).
+    #[inline]
+    pub async fn last_restore_report(
+        &self,
+    ) -> Result<Option<restore_report::RestoreReportDto>, anyhow::Error> {
+        crate::restore_report::last_restore_report(self).await
+    }
+
+    /// Puts back the data replaced by the last restoration, if it was kept
+    /// (see [`config::SafetySnapshotConfig`]) and didn’t expire.
+    ///
+    /// NOTE: Like restorations, this must not happen while something uses
+    ///   restored data.
+    #[inline]
+    pub async fn undo_last_restore(
+        &self,
+    ) -> Result<safety_snapshot::SafetySnapshotDto, safety_snapshot::UndoRestoreError> {
+        crate::safety_snapshot::undo_last_restore(self).await
+    }
+
+    /// Like [`BackupService::undo_last_restore`], but returns the guard
+    /// which reverts changes when dropped. Defuse it to commit the undo.
+    #[inline]
+    pub async fn undo_last_restore_partial(
+        &self,
+    ) -> Result<
+        (
+            safety_snapshot::SafetySnapshotDto,
+            restoration::RestoreRevertGuard,
+        ),
+        safety_snapshot::UndoRestoreError,
+    > {
+        crate::safety_snapshot::undo_last_restore_partial(self).await
+    }
+
+    /// Deletes the data replaced by the last restoration if it expired (see
+    /// [`config::SafetySnapshotConfig::ttl`]).
+    #[inline]
+    pub async fn purge_expired_safety_snapshot(&self) -> Result<(), anyhow::Error> {
+        crate::safety_snapshot::purge_expired_safety_snapshot(self).await
+    }
+
     #[inline]
     pub async fn delete_backup(&self, backup_id: &BackupId) -> Result<(), anyhow::Error> {
         crate::delete::delete_backup(self, backup_id).await
     }
+
+    /// Checks that the transparency log is intact and matches the backups
+    /// actually stored (see [`transparency`]).
+    #[inline]
+    pub async fn verify_log(&self) -> Result<transparency::LogVerificationReport, anyhow::Error> {
+        crate::transparency::verify_log(self).await
+    }
 }
 
 impl BackupService {
@@ -220,86 +430,238 @@ impl BackupService {
     {
         use crate::decryption::PgpDecryptionContext;
         use crate::signing::PgpSigningContext;
-        use crate::stores::*;
         use crate::verification::PgpVerificationContext;
 
-        let encryption_context = match &config.encryption {
-            config::EncryptionConfig::Off => None,
-            config::EncryptionConfig::Pgp { config: pgp } => {
-                let mut recipients = Vec::with_capacity(pgp.additional_recipients.len() + 1);
-
-                recipients.push(get_pgp_cert(&pgp.tsk)?);
-
-                for path in pgp.additional_recipients.iter() {
-                    recipients.push(get_pgp_cert(path)?);
-                }
+        use crate::keys::{KeyDto, KeySource, KeyUsage};
 
-                Some(encryption::Context::Pgp {
-                    recipients,
-                    policy: Box::new(pgp_policy()),
-                })
-            }
+        let keyring = match config.keys.directory.as_deref() {
+            Some(directory) => Some(keys::Keyring::open(directory)?),
+            None => None,
         };
-
-        let pgp_signing_context = match config.signing.pgp.as_ref() {
-            Some(pgp) => {
+        let managed_keys = match keyring.as_ref() {
+            Some(keyring) => keyring.load()?,
+            None => Vec::new(),
+        };
+        let managed_keys_with_usage =
+            |usage: KeyUsage| (managed_keys.iter()).filter(move |key| key.usage == usage);
+
+        // NOTE: Retired keys are not used to encrypt new backups.
+        let additional_recipients: Vec<openpgp::Cert> =
+            (managed_keys_with_usage(KeyUsage::Encryption))
+                .chain(managed_keys_with_usage(KeyUsage::Recipient))
+                .filter(|key| key.is_active())
+                .map(|key| key.cert.clone())
+                .collect();
+
+        let encryption_context = Self::encryption_context(
+            &config.encryption,
+            &additional_recipients,
+            &get_pgp_cert,
+            &pgp_policy,
+        )?;
+
+        // NOTE: The most recent active managed signing key takes precedence
+        //   over the configured one.
+        let managed_signing_tsk = (managed_keys_with_usage(KeyUsage::Signing))
+            .filter(|key| key.is_active())
+            .max_by_key(|key| key.cert.primary_key().key().creation_time())
+            .map(|key| key.cert.clone());
+        let pgp_signing_context = match (managed_signing_tsk, config.signing.pgp.as_ref()) {
+            (Some(tsk), pgp) => Some(Arc::new(PgpSigningContext {
+                tsk,
+                policy: Box::new(pgp_policy()),
+                passphrases: pgp.map(|pgp| pgp.passphrases.clone()).unwrap_or_default(),
+            })),
+            (None, Some(pgp)) => {
                 let pgp_cert = get_pgp_cert(&pgp.tsk)?;
-                Some(PgpSigningContext {
+                Some(Arc::new(PgpSigningContext {
                     tsk: pgp_cert,
                     policy: Box::new(pgp_policy()),
                     passphrases: pgp.passphrases.clone(),
-                })
+                }))
             }
-            None => None,
+            (None, None) => None,
         };
         let signing_context = signing::Context {
             is_signing_mandatory: config.signing.mandatory,
             pgp: pgp_signing_context,
         };
 
-        let pgp_verification_context = match config.signing.pgp.as_ref() {
-            Some(pgp) => {
-                let pgp_cert = get_pgp_cert(&pgp.tsk)?;
-                Some(PgpVerificationContext {
-                    certs: Arc::new(vec![pgp_cert]),
-                    policy: Box::new(pgp_policy()),
-                })
-            }
-            None => None,
+        // NOTE: Backups signed using retired keys must still be verifiable.
+        let mut verification_certs: Vec<openpgp::Cert> = Vec::new();
+        if let Some(pgp) = config.signing.pgp.as_ref() {
+            verification_certs.push(get_pgp_cert(&pgp.tsk)?);
+        }
+        verification_certs
+            .extend(managed_keys_with_usage(KeyUsage::Signing).map(|key| key.cert.clone()));
+        let pgp_verification_context = if verification_certs.is_empty() {
+            None
+        } else {
+            Some(Arc::new(PgpVerificationContext {
+                certs: Arc::new(verification_certs),
+                policy: Box::new(pgp_policy()),
+            }))
         };
         let verification_context = verification::Context {
             pgp: pgp_verification_context,
         };
 
+        anyhow::ensure!(
+            (0.0..=1.0).contains(&config.quota.warning_threshold),
+            "Invalid quota warning threshold `{threshold}`: Must be between `0` and `1`.",
+            threshold = config.quota.warning_threshold,
+        );
+        anyhow::ensure!(
+            !config.progress.throughput_window.is_zero(),
+            "Invalid progress throughput window: Must not be zero.",
+        );
+
+        // NOTE: Profile entries are validated against the latest blueprint,
+        //   as it’s the one backups are created with.
+        let latest_blueprint = (archiving_context.blueprints.iter())
+            .max_by_key(|(version, _)| **version)
+            .map(|(_, blueprint)| blueprint);
+
+        let mut profiles: HashMap<String, profiles::BackupProfile> =
+            HashMap::with_capacity(config.profiles.len());
+        for (name, profile) in config.profiles.iter() {
+            anyhow::ensure!(
+                !name.is_empty() && !name.contains('.'),
+                "Invalid backup profile name `{name}`: Must not be empty or contain dots.",
+            );
+
+            for entry in profile
+                .include
+                .iter()
+                .flatten()
+                .chain(profile.exclude.iter())
+            {
+                let is_known = latest_blueprint.is_none_or(|blueprint| {
+                    (blueprint.paths.iter()).any(|(archive_path, _)| archive_path == entry.as_str())
+                });
+                anyhow::ensure!(
+                    is_known,
+                    "Invalid backup profile `{name}`: Unknown blueprint entry `{entry}`.",
+                );
+            }
+
+            let encryption_context = match profile.encryption {
+                Some(ref encryption) => Self::encryption_context(
+                    encryption,
+                    &additional_recipients,
+                    &get_pgp_cert,
+                    &pgp_policy,
+                )?,
+                None => encryption_context.clone(),
+            };
+
+            let backup_store = match profile.storage {
+                Some(ref storage) => Some(stores::CachedStore::new(
+                    Self::store(storage)?,
+                    Arc::default(),
+                    &config.caching,
+                )),
+                None => None,
+            };
+
+            profiles.insert(
+                name.to_owned(),
+                profiles::BackupProfile {
+                    include: (profile.include.as_ref())
+                        .map(|include| include.iter().map(Into::into).collect()),
+                    exclude: profile.exclude.iter().map(Into::into).collect(),
+                    compression_config: (profile.compression.as_ref())
+                        .unwrap_or(&config.compression)
+                        .to_owned(),
+                    encryption_context,
+                    backup_store,
+                },
+            );
+        }
+
+        // NOTE: Profiles might use other keys, all of them are needed
+        //   to decrypt backups.
         let mut decryption_context = decryption::Context::default();
-        if let config::EncryptionConfig::Pgp { config: pgp } = &config.encryption {
-            let pgp_cert = get_pgp_cert(&pgp.tsk)?;
-            decryption_context.pgp = Some(PgpDecryptionContext {
-                tsks: vec![pgp_cert],
-                policy: Box::new(pgp_policy()),
-                passphrases: pgp.passphrases.clone(),
-            });
+        {
+            let encryption_configs = std::iter::once(&config.encryption).chain(
+                config
+                    .profiles
+                    .values()
+                    .filter_map(|p| p.encryption.as_ref()),
+            );
+
+            let mut tsks: Vec<openpgp::Cert> = Vec::new();
+            let mut passphrases = HashMap::new();
+            for encryption in encryption_configs {
+                if let config::EncryptionConfig::Pgp { config: pgp } = encryption {
+                    let pgp_cert = get_pgp_cert(&pgp.tsk)?;
+                    if !tsks
+                        .iter()
+                        .any(|tsk| tsk.fingerprint() == pgp_cert.fingerprint())
+                    {
+                        tsks.push(pgp_cert);
+                    }
+                    passphrases.extend(pgp.passphrases.clone());
+                }
+            }
+
+            // NOTE: Backups encrypted using retired keys must still
+            //   be decryptable.
+            for key in managed_keys_with_usage(KeyUsage::Encryption) {
+                if !tsks
+                    .iter()
+                    .any(|tsk| tsk.fingerprint() == key.cert.fingerprint())
+                {
+                    tsks.push(key.cert.clone());
+                }
+            }
+
+            if !tsks.is_empty() {
+                decryption_context.pgp = Some(Arc::new(PgpDecryptionContext {
+                    tsks,
+                    policy: Box::new(pgp_policy()),
+                    passphrases,
+                }));
+            }
         }
 
-        let backup_store: Box<dyn ObjectStore> = match config.storage.backups {
-            #[cfg(feature = "storage-s3")]
-            config::StorageSubconfig::S3 { ref config } => Box::new(S3Store::from_config(config)),
-            #[cfg(feature = "storage-fs")]
-            config::StorageSubconfig::Fs { ref config } => {
-                Box::new(FsStore::try_from_config(config, 0o600)?)
+        let keys = {
+            let mut configured: Vec<(&std::path::PathBuf, KeyUsage)> = Vec::new();
+            if let config::EncryptionConfig::Pgp { config: pgp } = &config.encryption {
+                configured.push((&pgp.tsk, KeyUsage::Encryption));
+                configured.extend(
+                    (pgp.additional_recipients.iter()).map(|path| (path, KeyUsage::Recipient)),
+                );
             }
-        };
-        let check_store: Box<dyn ObjectStore> = match config.storage.checks {
-            #[cfg(feature = "storage-s3")]
-            config::StorageSubconfig::S3 { ref config } => Box::new(S3Store::from_config(config)),
-            #[cfg(feature = "storage-fs")]
-            config::StorageSubconfig::Fs { ref config } => {
-                Box::new(FsStore::try_from_config(config, 0o600)?)
+            if let Some(pgp) = config.signing.pgp.as_ref() {
+                configured.push((&pgp.tsk, KeyUsage::Signing));
+            }
+
+            let mut keys = Vec::with_capacity(configured.len() + managed_keys.len());
+            // NOTE: Only get a policy if needed (tests don’t always provide one).
+            let policy = std::cell::LazyCell::new(&pgp_policy);
+            for (path, usage) in configured {
+                let cert = get_pgp_cert(path)?;
+                keys.push(KeyDto::new(&cert, usage, KeySource::Config, None, &*policy));
+            }
+            for key in managed_keys.iter() {
+                keys.push(KeyDto::new(
+                    &key.cert,
+                    key.usage,
+                    KeySource::Managed,
+                    key.retired_at,
+                    &*policy,
+                ));
             }
+            keys
         };
 
+        let backup_store = Self::store(&config.storage.backups)?;
+        let check_store = Self::store(&config.storage.checks)?;
+
         Ok(Self {
             archiving_context,
+            archive_config: config.archive.to_owned(),
             compression_config: config.compression.to_owned(),
             hashing_config: config.hashing.to_owned(),
             encryption_context,
@@ -307,9 +669,66 @@ impl BackupService {
             verification_context,
             decryption_context,
             restoration_context,
+            restoration_config: config.restoration.to_owned(),
+            safety_snapshot_config: config.safety_snapshot.to_owned(),
+            transparency_log_config: config.transparency_log.to_owned(),
             backup_store: stores::CachedStore::new(backup_store, Arc::default(), &config.caching),
             check_store,
+            snapshot_config: config.snapshot.to_owned(),
             download_config: config.download.to_owned(),
+            diff_config: config.diff.to_owned(),
+            quota_config: config.quota.to_owned(),
+            progress_config: config.progress.to_owned(),
+            profiles,
+            keyring,
+            keys,
+        })
+    }
+
+    fn encryption_context<P>(
+        config: &config::EncryptionConfig,
+        additional_recipients: &[openpgp::Cert],
+        get_pgp_cert: impl Fn(&std::path::PathBuf) -> Result<openpgp::Cert, anyhow::Error>,
+        pgp_policy: impl Fn() -> P,
+    ) -> Result<Option<Arc<encryption::Context>>, anyhow::Error>
+    where
+        P: openpgp::policy::Policy + 'static,
+    {
+        match config {
+            config::EncryptionConfig::Off => Ok(None),
+            config::EncryptionConfig::Pgp { config: pgp } => {
+                let mut recipients = Vec::with_capacity(
+                    pgp.additional_recipients.len() + additional_recipients.len() + 1,
+                );
+
+                recipients.push(get_pgp_cert(&pgp.tsk)?);
+
+                for path in pgp.additional_recipients.iter() {
+                    recipients.push(get_pgp_cert(path)?);
+                }
+
+                recipients.extend_from_slice(additional_recipients);
+
+                Ok(Some(Arc::new(encryption::Context::Pgp {
+                    recipients,
+                    policy: Box::new(pgp_policy()),
+                })))
+            }
+        }
+    }
+
+    fn store(
+        config: &config::StorageSubconfig,
+    ) -> Result<Arc<dyn stores::ObjectStore>, anyhow::Error> {
+        use crate::stores::*;
+
+        Ok(match config {
+            #[cfg(feature = "storage-s3")]
+            config::StorageSubconfig::S3 { config } => Arc::new(S3Store::from_config(config)),
+            #[cfg(feature = "storage-fs")]
+            config::StorageSubconfig::Fs { config } => {
+                Arc::new(FsStore::try_from_config(config, 0o600)?)
+            }
         })
     }
 }
@@ -372,6 +791,12 @@ pub mod dtos {
         ///
         /// [`can_be_restored`]: BackupMetadataPartialDto::can_be_restored
         pub can_be_restored: bool,
+
+        /// Whether or not the backup is recorded in the transparency log.
+        ///
+        /// `false` means the backup was created while the log was disabled,
+        /// or behind this service’s back. `None` if the log is disabled.
+        pub is_logged: Option<bool>,
     }
 
     /// [`BackupMetadataPartialDto`] with additional data that requires
@@ -423,6 +848,15 @@ pub mod dtos {
         /// (i.e. encrypted with an known private key).
         pub is_encryption_valid: Option<bool>,
 
+        /// Name of the profile used to create the backup, if any (see
+        /// [`BackupProfileConfig`](crate::config::BackupProfileConfig)).
+        pub profile: Option<String>,
+
+        /// Whether or not the backup only contains some of the data
+        /// (restoring it only replaces this data), `None` if the backup
+        /// could not be read.
+        pub is_partial: Option<bool>,
+
         /// Whether or not the backup can be restored (e.g. `false` if its
         /// signature is invalid).
         ///
@@ -454,23 +888,202 @@ pub mod dtos {
             }
         }
     }
+
+    /// What’s needed to serve a backup over HTTP.
+    ///
+    /// See [`BackupService::get_download_info`](crate::BackupService::get_download_info).
+    #[derive(Debug, Clone)]
+    pub struct BackupDownloadInfoDto {
+        /// Size of the backup as stored, in bytes.
+        pub size_bytes: u64,
+
+        /// Digest of the backup as stored (e.g. `blake3:…`).
+        ///
+        /// `None` if the backup has no digest (e.g. signed only).
+        pub digest: Option<String>,
+    }
+
+    /// Storage used by backups and integrity checks.
+    ///
+    /// See [`BackupService::storage_report`](crate::BackupService::storage_report).
+    #[derive(Debug)]
+    #[derive(serde::Serialize)]
+    pub struct StorageReportDto {
+        /// UTC timestamp at which the report was generated.
+        #[serde(with = "time::serde::rfc3339")]
+        pub generated_at: time::OffsetDateTime,
+
+        /// Total size of all backups and integrity checks, in bytes.
+        pub total_size_bytes: u64,
+
+        /// Storage used by backups (in the backup store).
+        pub backups: StorageUsageDto,
+
+        /// Storage used by integrity checks (in the check store).
+        pub checks: StorageUsageDto,
+
+        /// Storage used per month in which objects were created,
+        /// in chronological order.
+        pub by_month: Vec<MonthlyStorageUsageDto>,
+
+        /// Storage used by backups, grouped by signed/encrypted status.
+        pub by_status: Vec<StatusStorageUsageDto>,
+
+        /// Estimated growth, based on recent history.
+        pub growth: StorageGrowthDto,
+
+        /// Comparison against the configured quota, if any.
+        pub quota: Option<StorageQuotaDto>,
+
+        /// Human-readable warnings (e.g. quota almost reached).
+        pub warnings: Vec<String>,
+    }
+
+    #[derive(Debug, Clone, Copy, Default)]
+    #[derive(serde::Serialize)]
+    pub struct StorageUsageDto {
+        /// Number of objects.
+        pub objects_count: u64,
+
+        /// Total size of the objects, in bytes.
+        pub size_bytes: u64,
+    }
+
+    impl StorageUsageDto {
+        #[inline]
+        pub(crate) fn record(&mut self, size_bytes: u64) {
+            self.objects_count += 1;
+            self.size_bytes = self.size_bytes.saturating_add(size_bytes);
+        }
+    }
+
+    #[derive(Debug)]
+    #[derive(serde::Serialize)]
+    pub struct MonthlyStorageUsageDto {
+        /// Month, in `YYYY-MM` format.
+        ///
+        /// E.g. `2026-03`.
+        pub month: String,
+
+        pub backups: StorageUsageDto,
+
+        pub checks: StorageUsageDto,
+    }
+
+    #[derive(Debug)]
+    #[derive(serde::Serialize)]
+    pub struct StatusStorageUsageDto {
+        pub is_signed: bool,
+
+        pub is_encrypted: bool,
+
+        #[serde(flatten)]
+        pub usage: StorageUsageDto,
+    }
+
+    #[derive(Debug)]
+    #[derive(serde::Serialize)]
+    pub struct StorageGrowthDto {
+        /// Length of the history used to estimate growth, in days.
+        ///
+        /// Might be shorter than the configured window if backups
+        /// haven’t been created for long enough.
+        pub observed_days: u64,
+
+        /// Estimated growth per day, in bytes.
+        pub bytes_per_day: u64,
+
+        /// Estimated growth per month (30 days), in bytes.
+        pub bytes_per_month: u64,
+    }
+
+    #[derive(Debug)]
+    #[derive(serde::Serialize)]
+    pub struct StorageQuotaDto {
+        /// Configured quota, in bytes.
+        pub max_size_bytes: u64,
+
+        /// Ratio of the quota currently used (e.g. `0.42` for 42%).
+        ///
+        /// Can be greater than `1` if the quota is exceeded.
+        pub used_ratio: f64,
+
+        /// Configured warning threshold (e.g. `0.8` for 80%).
+        pub warning_threshold: f64,
+
+        pub status: QuotaStatus,
+
+        /// Estimated number of days before the quota is reached,
+        /// if storage is growing and the quota isn’t exceeded already.
+        pub estimated_days_left: Option<u64>,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[derive(serde::Serialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum QuotaStatus {
+        Ok,
+        Warning,
+        Exceeded,
+    }
+
+    /// Byte-level progress of a backup creation.
+    #[derive(Debug, Clone)]
+    #[derive(serde::Serialize)]
+    pub struct CreateBackupProgressDto {
+        /// Number of bytes read and archived so far.
+        pub bytes_read: u64,
+
+        /// Number of bytes output by the compression step so far.
+        pub bytes_compressed: u64,
+
+        /// Number of bytes sent to the backup store so far (i.e. after
+        /// compression and encryption).
+        pub bytes_uploaded: u64,
+
+        /// Estimated total number of bytes to read.
+        pub total_bytes_estimate: u64,
+
+        /// Rolling throughput (of bytes read), in bytes per second.
+        pub bytes_per_second: u64,
+
+        /// Time elapsed since the backup started, in seconds.
+        pub elapsed_secs: f64,
+
+        /// Estimated time left, in seconds.
+        ///
+        /// `None` if it cannot be estimated (yet).
+        pub eta_secs: Option<u64>,
+    }
 }
 
 mod create {
+    use std::sync::Arc;
+
     use anyhow::Context as _;
     use composable_stream::*;
+    use tokio::sync::mpsc;
 
     use crate::BackupService;
     use crate::archiving::{self, *};
     use crate::backup_id::*;
     use crate::compression::*;
-    use crate::config::CompressionConfig;
+    use crate::config::{ArchiveConfig, CompressionConfig, HashingConfig};
     use crate::dtos::*;
     use crate::encryption::*;
     use crate::hashing::*;
     use crate::signing::pgp::*;
     use crate::stats::*;
     use crate::stores::*;
+    use crate::transparency::LogOperation;
+    use crate::util::BlockingTask;
+
+    /// Size of the chunks sent by the blocking task to the upload task.
+    const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+    /// Number of messages the blocking task can send before it has to wait
+    /// for the upload to catch up.
+    const PIPELINE_BUFFER: usize = 16;
 
     pub(crate) async fn create_backup<D: archiving::AdditionalData>(
         service: &BackupService,
@@ -479,24 +1092,62 @@ mod create {
             description,
             blueprint,
             additional_archive_data,
+            profile: profile_name,
             #[cfg(feature = "test")]
             created_at,
         }: CreateBackupCommand<'_, D>,
         event_handler: &mut impl CreateBackupEventHandler,
     ) -> Result<CreateBackupSuccess, CreateBackupError> {
-        let expected_archive_size =
-            check_archiving_will_succeed(&blueprint, &additional_archive_data)?;
+        use tokio::io::AsyncWriteExt as _;
 
-        #[cfg(not(feature = "test"))]
-        let created_at = std::time::SystemTime::now();
+        let profile = match profile_name {
+            Some(name) => Some(
+                (service.profiles.get(name))
+                    .ok_or_else(|| CreateBackupError::UnknownProfile(name.to_owned()))?,
+            ),
+            None => None,
+        };
 
-        let mut extensions: Vec<Box<str>> = vec![Box::from("tar")];
-        match &service.compression_config {
+        let blueprint = match profile {
+            Some(profile) => profile.filter_blueprint(blueprint),
+            None => blueprint.clone(),
+        };
+        let compression_config = match profile {
+            Some(profile) => &profile.compression_config,
+            None => &service.compression_config,
+        };
+        let encryption_context = match profile {
+            Some(profile) => &profile.encryption_context,
+            None => &service.encryption_context,
+        };
+        let backup_store = (profile.and_then(|profile| profile.backup_store.as_ref()))
+            .unwrap_or(&service.backup_store);
+
+        // NOTE: Store which entries the backup contains, so restoring it
+        //   doesn’t replace the others.
+        let metadata = BackupInternalMetadata {
+            version: blueprint.version,
+            profile: profile_name.map(ToOwned::to_owned),
+            paths: profile.map(|_| {
+                (blueprint.paths.iter())
+                    .map(|(archive_path, _)| archive_path.to_string_lossy().into_owned())
+                    .collect()
+            }),
+        };
+
+        let expected_archive_size =
+            check_archiving_will_succeed(&blueprint, &metadata, &additional_archive_data)?;
+
+        #[cfg(not(feature = "test"))]
+        let created_at = std::time::SystemTime::now();
+
+        let mut extensions: Vec<Box<str>> = vec![Box::from("tar")];
+        match compression_config {
             #[cfg(feature = "compression-zstd")]
             CompressionConfig::Zstd { .. } => extensions.push(Box::from("zst")),
             CompressionConfig::Off => {}
         }
-        match &service.encryption_context {
+        match encryption_context.as_deref() {
             Some(EncryptionContext::Pgp { .. }) => extensions.push(Box::from("pgp")),
             None => {}
         };
@@ -504,6 +1155,7 @@ mod create {
         let backup_id = BackupId {
             prefix: Box::from(prefix),
             created_at: created_at.into(),
+            random_suffix: Some(BackupId::random_suffix()),
             description: Box::from(description),
             extensions,
         };
@@ -512,89 +1164,96 @@ mod create {
         event_handler.on_archive_start(&backup_id, expected_archive_size);
 
         // Try to open sink first, to abort early if something is wrong.
-        let upload_backup = service
-            .backup_store
-            .writer(&raw_backup_id)
+        // NOTE: Never overwrite existing data, even if backup IDs collide.
+        let upload_backup = backup_store
+            .async_writer_if_absent(&raw_backup_id)
             .await
             .inspect_err(|err| tracing::debug!("{err:#}"))
-            .map_err(CreateBackupError::CannotCreateSink)?;
-
-        let start = std::time::Instant::now();
-
-        let archive_writer = archive(&blueprint, additional_archive_data)
-            .then(meter_writes(BackupStatsReader {
-                backup_id: &backup_id,
-                event_handler,
-            }))
-            .then(compress(&service.compression_config))
-            .then(eventually(service.encryption_context.as_ref(), |ctx| {
-                encrypt(ctx, created_at)
-            }))
-            // Record stats so we can know the final size of the backup.
-            .then(meter_writes(WriteStats::new()))
-            .tee_into(digest(&service.hashing_config))
-            .opt_tee(
-                service.signing_context.pgp.as_ref(),
-                |ctx| pgp_sign(ctx, created_at),
-                Vec::<u8>::new(),
-            )
-            .build(upload_backup)?;
-
-        let delete_guard = BackupAutoDeleteGuard::new(service, &backup_id);
-
-        let compression_writer = archive_writer
-            // NOTE: Flushes the stream if needed.
-            .into_inner()
-            .context("Could not init archive")
-            .map_err(CreateBackupError::ArchivingFailed)?;
+            .map_err(sink_error)?;
+        // Count bytes so we can know the final size of the backup.
+        let mut upload_backup = Count::new(upload_backup);
 
-        let compression_writer = compression_writer.into_inner();
+        let mut delete_guard = BackupAutoDeleteGuard::new(service, backup_store, &backup_id);
 
-        let encryption_writer_opt = compression_writer
-            .finalize()
-            .map_err(CreateBackupError::CompressionFailed)?;
+        let start = std::time::Instant::now();
 
-        let (Tee(Tee(backup_upload, pgp_signing_writer_opt), digest_writer), backup_stats) =
-            match encryption_writer_opt {
-                Either::A(encryption_writer) => encryption_writer
-                    .into_inner()
-                    .map_err(CreateBackupError::EncryptionFailed)?,
-                Either::B(writer) => writer,
+        let progress_counters = Arc::new(ProgressCounters::default());
+
+        // NOTE: Archiving, compression, encryption and integrity checks are
+        //   synchronous and CPU-bound, they run in a blocking task which
+        //   sends the backup to this task in chunks.
+        let mut pipeline = BlockingTask::spawn(PIPELINE_BUFFER, {
+            let pipeline = BackupPipeline {
+                blueprint,
+                metadata,
+                additional_archive_data,
+                archive_config: service.archive_config.clone(),
+                compression_config: compression_config.clone(),
+                hashing_config: service.hashing_config.clone(),
+                encryption_context: encryption_context.clone(),
+                signing_context: service.signing_context.pgp.clone(),
+                created_at,
+                progress: ProgressTracker::new(
+                    &service.progress_config,
+                    expected_archive_size,
+                    Arc::clone(&progress_counters),
+                ),
+            };
+            move |sender| pipeline.run(sender)
+        });
+
+        // Upload the backup while it’s being created.
+        while let Some(message) = pipeline.recv().await {
+            match message {
+                PipelineMessage::Data(chunk) => {
+                    let res = (upload_backup.write_all(&chunk).await)
+                        .context("Failed writing backup chunk")
+                        .map_err(CreateBackupError::UploadFailed);
+                    delete_guard.delete_on_err(res).await?;
+                    progress_counters.bytes_uploaded.add(chunk.len());
+                }
+                PipelineMessage::ArchiveProgress(len) => {
+                    event_handler.on_archive_progress(&backup_id, len)
+                }
+                PipelineMessage::Progress(progress) => {
+                    event_handler.on_progress(&backup_id, &progress)
+                }
             }
-            .into_parts();
+        }
+
+        let PipelineOutput {
+            digest,
+            pgp_signature,
+            progress,
+        } = delete_guard.delete_on_err(pipeline.join().await).await?;
 
-        let digest = digest_writer.finalize();
+        let (upload_backup, size_bytes) = upload_backup.into_parts();
 
         let mut digest_ids: Vec<ObjectId> = Vec::new();
         let mut checks_upload_durations: Vec<(ObjectId, std::time::Duration)> = Vec::new();
 
         // Upload digest.
-        let digest_id = match service.hashing_config.algorithm {
-            #[cfg(feature = "hashing-blake3")]
-            crate::config::HashingAlgorithm::Blake3 => raw_backup_id.with_extension("blake3"),
-            #[cfg(feature = "hashing-sha2")]
-            crate::config::HashingAlgorithm::Sha256 => raw_backup_id.with_extension("sha256"),
-        };
-        upload_integrity_check(
+        let digest_id = raw_backup_id.with_extension(service.hashing_config.algorithm.extension());
+        let digest_hex = encode_hex_digest(service.hashing_config.algorithm, &digest);
+        let res = upload_integrity_check(
             digest,
             digest_id,
             &service,
             &mut checks_upload_durations,
             &mut digest_ids,
         )
-        .await?;
+        .await;
+        if let Err(err) = res {
+            return Err(delete_guard.delete_unless_already_exists(err).await);
+        }
 
         let mut signature_ids: Vec<ObjectId> = Vec::new();
 
-        let is_signed = pgp_signing_writer_opt.is_some();
+        let is_signed = pgp_signature.is_some();
 
         // Upload OpenPGP signature.
-        if let OptionalStream::Some(writer) = pgp_signing_writer_opt {
-            let pgp_signature = writer
-                .finalize()
-                .map_err(CreateBackupError::SigningFailed)?;
-
-            upload_integrity_check(
+        if let Some(pgp_signature) = pgp_signature {
+            let res = upload_integrity_check(
                 pgp_signature,
                 // NOTE: OpenPGP will likely forever be the only signing protocol
                 //   we support, but if we ever add one that also uses the `.sig`
@@ -604,18 +1263,39 @@ mod create {
                 &mut checks_upload_durations,
                 &mut signature_ids,
             )
-            .await?;
+            .await;
+            if let Err(err) = res {
+                return Err(delete_guard.delete_unless_already_exists(err).await);
+            }
         }
 
         // Finish uploading backup.
-        () = backup_upload
-            .finalize()
-            .map_err(CreateBackupError::UploadFailed)?;
-        let size_bytes = backup_stats.bytes_written;
+        if let Err(err) = upload_backup.finalize().await {
+            let err = if ObjectAlreadyExists::is(&err) {
+                CreateBackupError::AlreadyExists(err)
+            } else {
+                CreateBackupError::UploadFailed(err)
+            };
+            return Err(delete_guard.delete_unless_already_exists(err).await);
+        }
+        let progress = progress.finish();
+        event_handler.on_progress(&backup_id, &progress);
         let elapsed = start.elapsed();
         tracing::info!("Created backup {backup_id:?} ({size_bytes}B) in {elapsed:?}.");
         event_handler.on_backup_uploaded(&backup_id, size_bytes, elapsed);
 
+        // NOTE: A backup missing from the transparency log would be reported
+        //   as suspicious, better delete it (no-op if the log is disabled).
+        let res = crate::transparency::append(
+            service,
+            LogOperation::Created,
+            &backup_id,
+            Some(digest_hex),
+        )
+        .await
+        .map_err(CreateBackupError::TransparencyLogFailed);
+        delete_guard.delete_on_err(res).await?;
+
         delete_guard.defuse();
 
         // Construct the response.
@@ -627,8 +1307,9 @@ mod create {
                     created_at: created_at.into(),
                     size_bytes,
                     is_signed,
-                    is_encrypted: service.encryption_context.is_some(),
+                    is_encrypted: encryption_context.is_some(),
                     can_be_restored: true,
+                    is_logged: (service.transparency_log_config.enabled).then_some(true),
                 },
             },
             output: CreateBackupOutput {
@@ -639,6 +1320,190 @@ mod create {
         })
     }
 
+    /// Everything needed to create a backup, owned so it can be moved to
+    /// a blocking task.
+    struct BackupPipeline<D> {
+        blueprint: ArchiveBlueprint,
+        metadata: BackupInternalMetadata,
+        additional_archive_data: Option<D>,
+        archive_config: ArchiveConfig,
+        compression_config: CompressionConfig,
+        hashing_config: HashingConfig,
+        encryption_context: Option<Arc<EncryptionContext>>,
+        signing_context: Option<Arc<PgpSigningContext>>,
+        created_at: std::time::SystemTime,
+        progress: ProgressTracker,
+    }
+
+    enum PipelineMessage {
+        /// A chunk of the backup (compressed and encrypted), to upload.
+        Data(Vec<u8>),
+        ArchiveProgress(usize),
+        Progress(CreateBackupProgressDto),
+    }
+
+    struct PipelineOutput {
+        digest: Vec<u8>,
+        pgp_signature: Option<Vec<u8>>,
+        progress: ProgressTracker,
+    }
+
+    impl<D: archiving::AdditionalData> BackupPipeline<D> {
+        /// NOTE: Fails as soon as `sender` is closed (e.g. the upload failed
+        ///   or the backup was cancelled).
+        fn run(
+            self,
+            sender: mpsc::Sender<PipelineMessage>,
+        ) -> Result<PipelineOutput, CreateBackupError> {
+            let Self {
+                blueprint,
+                metadata,
+                additional_archive_data,
+                archive_config,
+                compression_config,
+                hashing_config,
+                encryption_context,
+                signing_context,
+                created_at,
+                progress,
+            } = self;
+
+            let progress_counters = Arc::clone(&progress.counters);
+
+            // Integrity checks are computed while the backup is being uploaded.
+            let digest_writer = digest(&hashing_config);
+            let pgp_signing_writer_opt = match signing_context.as_deref() {
+                Some(ctx) => {
+                    OptionalStream::Some(pgp_sign(ctx, created_at).build(Vec::<u8>::new())?)
+                }
+                None => OptionalStream::None,
+            };
+
+            let archive_writer = archive(
+                &blueprint,
+                &metadata,
+                additional_archive_data,
+                &archive_config,
+            )
+            .then(meter_writes(BackupStatsReader {
+                sender: &sender,
+                progress,
+            }))
+            .then(compress(&compression_config))
+            .then(tap(|buf: &[u8]| {
+                progress_counters.bytes_compressed.add(buf.len())
+            }))
+            .then(eventually(encryption_context.as_deref(), |ctx| {
+                encrypt(ctx, created_at)
+            }))
+            // NOTE: All branches are required for the backup to be valid.
+            .fork_with(|upload_backup| {
+                (
+                    Branch::fail_fast(upload_backup),
+                    Branch::fail_fast(digest_writer),
+                    Branch::fail_fast(pgp_signing_writer_opt),
+                )
+            })
+            .build(ChannelWriter::new(sender.clone()))?;
+
+            let compression_writer = archive_writer
+                // NOTE: Flushes the stream if needed.
+                .into_inner()
+                .context("Could not init archive")
+                .map_err(CreateBackupError::ArchivingFailed)?;
+
+            let (compression_writer, BackupStatsReader { progress, .. }) =
+                compression_writer.into_parts();
+
+            let encryption_writer_opt = compression_writer
+                .finalize()
+                .map_err(CreateBackupError::CompressionFailed)?
+                .into_inner();
+
+            let Fork((upload_backup, digest_writer, pgp_signing_writer_opt)) =
+                match encryption_writer_opt {
+                    Either::A(encryption_writer) => encryption_writer
+                        .into_inner()
+                        .map_err(CreateBackupError::EncryptionFailed)?,
+                    Either::B(writer) => writer,
+                };
+
+            // Send the end of the backup.
+            upload_backup
+                .into_inner()
+                .finish()
+                .context("Failed sending last backup chunk")
+                .map_err(CreateBackupError::UploadFailed)?;
+
+            let digest = digest_writer.into_inner().finalize();
+
+            let pgp_signature = match pgp_signing_writer_opt.into_inner() {
+                OptionalStream::Some(writer) => Some(
+                    writer
+                        .finalize()
+                        .map_err(CreateBackupError::SigningFailed)?,
+                ),
+                OptionalStream::None => None,
+            };
+
+            Ok(PipelineOutput {
+                digest,
+                pgp_signature,
+                progress,
+            })
+        }
+    }
+
+    /// Sends written bytes to the upload task, in chunks of
+    /// [`UPLOAD_CHUNK_SIZE`] bytes.
+    struct ChannelWriter {
+        sender: mpsc::Sender<PipelineMessage>,
+        buf: Vec<u8>,
+    }
+
+    impl ChannelWriter {
+        fn new(sender: mpsc::Sender<PipelineMessage>) -> Self {
+            Self {
+                sender,
+                buf: Vec::with_capacity(UPLOAD_CHUNK_SIZE),
+            }
+        }
+
+        fn send_buf(&mut self) -> std::io::Result<()> {
+            if self.buf.is_empty() {
+                return Ok(());
+            }
+
+            let chunk = std::mem::replace(&mut self.buf, Vec::with_capacity(UPLOAD_CHUNK_SIZE));
+
+            (self.sender.blocking_send(PipelineMessage::Data(chunk))).map_err(|_| {
+                std::io::Error::new(std::io::ErrorKind::BrokenPipe, "Backup upload stopped.")
+            })
+        }
+
+        /// Sends the last (partial) chunk.
+        fn finish(mut self) -> std::io::Result<()> {
+            self.send_buf()
+        }
+    }
+
+    impl std::io::Write for ChannelWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let len = buf.len().min(UPLOAD_CHUNK_SIZE - self.buf.len());
+            self.buf.extend_from_slice(&buf[..len]);
+
+            if self.buf.len() >= UPLOAD_CHUNK_SIZE {
+                self.send_buf()?;
+            }
+
+            Ok(len)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.send_buf()
+        }
+    }
+
     async fn upload_integrity_check(
         data: Vec<u8>,
         check_id: ObjectId,
@@ -650,19 +1515,23 @@ mod create {
 
         let mut uploader = service
             .check_store
-            .writer(&check_id)
+            .async_writer_if_absent(&check_id)
             .await
-            .map_err(CreateBackupError::CannotCreateSink)?;
+            .map_err(sink_error)?;
 
         let mut cursor = std::io::Cursor::new(data);
-        std::io::copy(&mut cursor, &mut uploader)
-            .context("`std::io::copy` failed")
+        tokio::io::copy(&mut cursor, &mut uploader)
+            .await
+            .context("`tokio::io::copy` failed")
             .map_err(CreateBackupError::IntegrityCheckUploadFailed)?;
 
-        uploader
-            .finalize()
-            .context("`finalize` failed")
-            .map_err(CreateBackupError::IntegrityCheckUploadFailed)?;
+        uploader.finalize().await.map_err(|err| {
+            if ObjectAlreadyExists::is(&err) {
+                CreateBackupError::AlreadyExists(err)
+            } else {
+                CreateBackupError::IntegrityCheckUploadFailed(err.context("`finalize` failed"))
+            }
+        })?;
 
         checks_upload_durations.push((check_id.clone(), start.elapsed()));
         uploaded.push(check_id);
@@ -670,6 +1539,14 @@ mod create {
         Ok(())
     }
 
+    fn sink_error(err: anyhow::Error) -> CreateBackupError {
+        if ObjectAlreadyExists::is(&err) {
+            CreateBackupError::AlreadyExists(err)
+        } else {
+            CreateBackupError::CannotCreateSink(err)
+        }
+    }
+
     pub struct CreateBackupCommand<'a, D: archiving::AdditionalData = ()> {
         /// Desired backup prefix (e.g. “prose-backup”).
         pub prefix: &'a str,
@@ -682,6 +1559,11 @@ mod create {
         /// Some more data to insert in the archive before it’s built.
         pub additional_archive_data: Option<D>,
 
+        /// Name of the profile to use (see
+        /// [`BackupProfileConfig`](crate::config::BackupProfileConfig)),
+        /// `None` to use the default settings.
+        pub profile: Option<&'a str>,
+
         /// Timestamp which should be associated with the backup.
         ///
         /// This is only useful in tests, as we have no way to read data as it was
@@ -701,6 +1583,13 @@ mod create {
         #[inline]
         fn on_upload_progress(&mut self, object_id: &ObjectId, uploaded_bytes: usize) {}
 
+        /// Called periodically (see [`ProgressConfig::interval`]) while the
+        /// backup is created, then once more when it’s been uploaded.
+        ///
+        /// [`ProgressConfig::interval`]: crate::config::ProgressConfig::interval
+        #[inline]
+        fn on_progress(&mut self, backup_id: &BackupId, progress: &CreateBackupProgressDto) {}
+
         #[inline]
         fn on_backup_uploaded(
             &mut self,
@@ -717,18 +1606,111 @@ mod create {
         fn on_signature_uploaded(&mut self, object_id: &ObjectId, duration: std::time::Duration) {}
     }
 
-    struct BackupStatsReader<'a, H: CreateBackupEventHandler> {
-        backup_id: &'a BackupId,
-        event_handler: &'a mut H,
+    struct BackupStatsReader<'a> {
+        sender: &'a mpsc::Sender<PipelineMessage>,
+        progress: ProgressTracker,
     }
 
-    impl<'a, H: CreateBackupEventHandler> StreamStats for BackupStatsReader<'a, H> {
+    impl<'a> StreamStats for BackupStatsReader<'a> {
         fn record_chunk(&mut self, len: usize) {
-            self.event_handler.on_archive_progress(self.backup_id, len);
+            // NOTE: Send errors can be ignored, as sending the backup
+            //   will fail too.
+            _ = (self.sender).blocking_send(PipelineMessage::ArchiveProgress(len));
+
+            if let Some(progress) = self.progress.record_read(len) {
+                _ = (self.sender).blocking_send(PipelineMessage::Progress(progress));
+            }
         }
     }
 
-    impl<'a, H: CreateBackupEventHandler> WriterStats for BackupStatsReader<'a, H> {}
+    impl<'a> WriterStats for BackupStatsReader<'a> {}
+
+    /// Byte counters of the steps happening after archiving.
+    #[derive(Debug, Default)]
+    struct ProgressCounters {
+        bytes_compressed: ByteCounter,
+        bytes_uploaded: ByteCounter,
+    }
+
+    /// Computes [`CreateBackupProgressDto`]s, throttled according to the
+    /// [`ProgressConfig`](crate::config::ProgressConfig).
+    struct ProgressTracker {
+        counters: Arc<ProgressCounters>,
+        bytes_read: u64,
+        total_bytes_estimate: u64,
+        start: std::time::Instant,
+        interval: std::time::Duration,
+        last_emitted_at: Option<std::time::Instant>,
+        throughput: RollingThroughput,
+    }
+
+    impl ProgressTracker {
+        fn new(
+            config: &crate::config::ProgressConfig,
+            total_bytes_estimate: u64,
+            counters: Arc<ProgressCounters>,
+        ) -> Self {
+            Self {
+                counters,
+                bytes_read: 0,
+                total_bytes_estimate,
+                start: std::time::Instant::now(),
+                interval: config.interval,
+                last_emitted_at: None,
+                throughput: RollingThroughput::new(config.throughput_window),
+            }
+        }
+
+        /// Records `len` more bytes read, and returns progress if an event
+        /// should be emitted.
+        fn record_read(&mut self, len: usize) -> Option<CreateBackupProgressDto> {
+            self.bytes_read = self.bytes_read.saturating_add(len as u64);
+
+            let now = std::time::Instant::now();
+            match self.last_emitted_at {
+                Some(last) if now.saturating_duration_since(last) < self.interval => None,
+                _ => {
+                    self.last_emitted_at = Some(now);
+                    Some(self.snapshot(now))
+                }
+            }
+        }
+
+        /// Returns the final progress (everything has been uploaded).
+        fn finish(mut self) -> CreateBackupProgressDto {
+            // NOTE: The estimate might have been off.
+            self.total_bytes_estimate = self.bytes_read;
+
+            self.snapshot(std::time::Instant::now())
+        }
+
+        fn snapshot(&mut self, now: std::time::Instant) -> CreateBackupProgressDto {
+            let bytes_per_second = self.throughput.record(now, self.bytes_read);
+
+            // NOTE: The estimate might be off, never report less than what
+            //   we’ve already read.
+            let total_bytes_estimate = self.total_bytes_estimate.max(self.bytes_read);
+
+            let bytes_left = total_bytes_estimate - self.bytes_read;
+            let eta_secs = if bytes_left == 0 {
+                Some(0)
+            } else if bytes_per_second > 0 {
+                Some(bytes_left.div_ceil(bytes_per_second))
+            } else {
+                None
+            };
+
+            CreateBackupProgressDto {
+                bytes_read: self.bytes_read,
+                bytes_compressed: self.counters.bytes_compressed.get(),
+                bytes_uploaded: self.counters.bytes_uploaded.get(),
+                total_bytes_estimate,
+                bytes_per_second,
+                elapsed_secs: now.saturating_duration_since(self.start).as_secs_f64(),
+                eta_secs,
+            }
+        }
+    }
 
     #[derive(Debug)]
     #[derive(serde::Serialize)]
@@ -764,6 +1746,15 @@ mod create {
         #[error("Cannot create backup sink")]
         CannotCreateSink(#[source] anyhow::Error),
 
+        /// An object with the same ID already exists (IDs collided).
+        ///
+        /// NOTE: Nothing was overwritten.
+        #[error("Backup already exists")]
+        AlreadyExists(#[source] anyhow::Error),
+
+        #[error("Unknown backup profile `{0}`")]
+        UnknownProfile(String),
+
         #[error("Cannot archive")]
         CannotArchive(#[from] archiving::errors::CannotArchive),
 
@@ -797,12 +1788,18 @@ mod create {
         #[error("Failed uploading backup integrity check")]
         IntegrityCheckUploadFailed(#[source] anyhow::Error),
 
+        #[error("Failed recording backup in the transparency log")]
+        TransparencyLogFailed(#[source] anyhow::Error),
+
         #[error(transparent)]
         Other(anyhow::Error),
     }
 
-    struct BackupAutoDeleteGuard<'a> {
-        service: &'a BackupService,
+    pub(crate) struct BackupAutoDeleteGuard {
+        // NOTE: Owned so the backup can be deleted in a separate task if the
+        //   guard is dropped (e.g. when creating a backup is cancelled).
+        backup_store: CachedStore<Arc<dyn ObjectStore>>,
+        check_store: Arc<dyn ObjectStore>,
         // NOTE: It’d be nice to take ownership to force defusing the guard to
         //   get back ownership and create the `CreateBackupOutput` but:
         //   1. Without using a separate module, one could still access this
@@ -812,39 +1809,414 @@ mod create {
         //   3. The compiler warns if the guard is unused, ensuring we don’t
         //      forget about defusing it.
         //   Since this code is internal, let’s just not care about it.
-        backup_id: Option<&'a BackupId>,
+        backup_id: Option<BackupId>,
     }
 
-    impl<'a> BackupAutoDeleteGuard<'a> {
-        fn new(service: &'a BackupService, backup_id: &'a BackupId) -> Self {
+    impl BackupAutoDeleteGuard {
+        pub(crate) fn new(
+            service: &BackupService,
+            backup_store: &CachedStore<Arc<dyn ObjectStore>>,
+            backup_id: &BackupId,
+        ) -> Self {
             Self {
-                service,
-                backup_id: Some(backup_id),
+                backup_store: backup_store.clone(),
+                check_store: Arc::clone(&service.check_store),
+                backup_id: Some(backup_id.clone()),
             }
         }
 
-        fn defuse(mut self) {
-            std::mem::take(&mut self.backup_id);
-        }
-    }
+        pub(crate) fn defuse(mut self) {
+            std::mem::take(&mut self.backup_id);
+        }
+
+        /// Deletes the backup if `res` is an error.
+        ///
+        /// NOTE: Unlike when the guard is dropped, the backup is deleted
+        ///   before this returns.
+        pub(crate) async fn delete_on_err<T, E>(&mut self, res: Result<T, E>) -> Result<T, E> {
+            if res.is_err() {
+                self.delete().await;
+            }
+            res
+        }
+
+        /// Do not delete anything if the error was caused by an ID collision,
+        /// as objects with this ID belong to another backup.
+        async fn delete_unless_already_exists(
+            mut self,
+            err: CreateBackupError,
+        ) -> CreateBackupError {
+            if matches!(err, CreateBackupError::AlreadyExists(_)) {
+                self.defuse();
+            } else {
+                self.delete().await;
+            }
+            err
+        }
+
+        async fn delete(&mut self) {
+            if let Some(backup_id) = std::mem::take(&mut self.backup_id) {
+                delete_backup(&self.backup_store, self.check_store.as_ref(), &backup_id).await
+            }
+        }
+    }
+
+    impl Drop for BackupAutoDeleteGuard {
+        fn drop(&mut self) {
+            let Some(backup_id) = std::mem::take(&mut self.backup_id) else {
+                return;
+            };
+
+            let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+                tracing::error!(
+                    "Could not clean up backup `{backup_id}`: Not in an async runtime."
+                );
+                return;
+            };
+
+            let backup_store = self.backup_store.clone();
+            let check_store = Arc::clone(&self.check_store);
+
+            // NOTE: Dropped when the task creating the backup is cancelled,
+            //   which mustn’t block. Deleting the backup is not awaited.
+            drop(runtime.spawn(async move {
+                delete_backup(&backup_store, check_store.as_ref(), &backup_id).await
+            }));
+        }
+    }
+
+    async fn delete_backup(
+        backup_store: &CachedStore<Arc<dyn ObjectStore>>,
+        check_store: &dyn ObjectStore,
+        backup_id: &BackupId,
+    ) {
+        // NOTE: The backup was never recorded in the transparency
+        //   log, don’t record its deletion either.
+        let res = crate::delete::delete_backup_objects(backup_store, check_store, backup_id).await;
+        match res {
+            Ok(_) => tracing::info!("Cleaned up backup `{backup_id}`."),
+            Err(err) => tracing::error!("Failed cleaning up backup `{backup_id}`: {err:#}"),
+        }
+    }
+}
+
+mod upload {
+    use anyhow::{Context as _, anyhow};
+
+    use crate::BackupService;
+    use crate::backup_id::*;
+    use crate::config::{HashingAlgorithm, HashingConfig};
+    use crate::create::{BackupAutoDeleteGuard, CreateBackupOutput};
+    use crate::dtos::*;
+    use crate::hashing::*;
+    use crate::stores::*;
+    use crate::transparency::LogOperation;
+    use crate::util::spawn_blocking;
+    use crate::verification::{MAX_PGP_SIGNATURE_LENGTH, VerificationError, VerificationReport};
+
+    pub(crate) async fn upload_backup<R>(
+        service: &BackupService,
+        UploadBackupCommand {
+            backup_id,
+            digest,
+            signature,
+        }: UploadBackupCommand,
+        mut backup: R,
+    ) -> Result<UploadBackupSuccess, UploadBackupError>
+    where
+        R: tokio::io::AsyncRead + Unpin + Send,
+    {
+        // Validate everything before writing anything.
+        if !is_backup_archive(&backup_id) {
+            return Err(UploadBackupError::InvalidBackupId(anyhow!(
+                "`{backup_id}` is not a backup archive (expected extensions \
+                `tar`, then optionally `zst` and `pgp`)."
+            )));
+        }
+        let digest = match digest {
+            Some(digest) => {
+                Some(decode_hex_digest(&digest).map_err(UploadBackupError::InvalidDigest)?)
+            }
+            None => None,
+        };
+        if let Some(ref signature) = signature {
+            if signature.is_empty() || signature.len() as u64 > MAX_PGP_SIGNATURE_LENGTH {
+                return Err(UploadBackupError::InvalidSignature(anyhow!(
+                    "OpenPGP signatures must be 1 to {MAX_PGP_SIGNATURE_LENGTH} bytes long."
+                )));
+            }
+        }
+        if signature.is_none() {
+            if service.signing_context.is_signing_mandatory {
+                return Err(UploadBackupError::Verification(
+                    VerificationError::BackupNotSigned,
+                ));
+            }
+            if digest.is_none() {
+                return Err(UploadBackupError::MissingIntegrityCheck);
+            }
+        }
+
+        let start = std::time::Instant::now();
+        let raw_backup_id = ObjectId::from(&backup_id);
+        let backup_store = &service.backup_store;
+
+        // Upload the backup.
+        // NOTE: Never overwrite existing data. Dropping the writer before
+        //   it’s finalized deletes the partial upload.
+        let size_bytes = {
+            let mut upload_backup = backup_store
+                .async_writer_if_absent(&raw_backup_id)
+                .await
+                .inspect_err(|err| tracing::debug!("{err:#}"))
+                .map_err(already_exists_or(UploadBackupError::UploadFailed))?;
+
+            let size_bytes = tokio::io::copy(&mut backup, &mut upload_backup)
+                .await
+                .context("Failed uploading backup")
+                .map_err(UploadBackupError::UploadFailed)?;
+
+            upload_backup
+                .finalize()
+                .await
+                .map_err(already_exists_or(UploadBackupError::UploadFailed))?;
+
+            size_bytes
+        };
+
+        // NOTE: From here on, the backup must be deleted if anything fails
+        //   (it’s not verified yet).
+        let mut delete_guard = BackupAutoDeleteGuard::new(service, backup_store, &backup_id);
+
+        let mut digest_ids: Vec<ObjectId> = Vec::new();
+        let mut signature_ids: Vec<ObjectId> = Vec::new();
+
+        // Upload integrity checks, so they can be verified like any other.
+        if let Some((algorithm, ref digest)) = digest {
+            let check_id = raw_backup_id.with_extension(algorithm.extension());
+            let res = upload_check(service, &check_id, digest.clone()).await;
+            delete_guard.delete_on_err(res).await?;
+            digest_ids.push(check_id);
+        }
+        if let Some(signature) = signature {
+            let check_id = raw_backup_id.with_extension("sig");
+            let res = upload_check(service, &check_id, signature).await;
+            delete_guard.delete_on_err(res).await?;
+            signature_ids.push(check_id);
+        }
+
+        // Verify the backup, as if it was about to be restored.
+        let mut verification_report = VerificationReport::default();
+        let res = service
+            .download_backup_and_check_integrity(
+                &backup_id,
+                backup_id.created_at,
+                &mut verification_report,
+            )
+            .await;
+        let verification_output = delete_guard.delete_on_err(res).await?;
+
+        // NOTE: The digest isn’t checked if the signature is valid, but it
+        //   must not be kept if it’s wrong. Also, a digest must always be
+        //   stored using the configured algorithm (see `crate::hashing`).
+        let configured_algorithm = service.hashing_config.algorithm;
+        let mut algorithms = vec![configured_algorithm];
+        if let Some((algorithm, _)) = digest {
+            if algorithm != configured_algorithm {
+                algorithms.push(algorithm);
+            }
+        }
+        let res = spawn_blocking({
+            let backup_path = verification_output.backup_path;
+
+            move || -> Result<_, anyhow::Error> {
+                let mut digests = Vec::with_capacity(algorithms.len());
+                for algorithm in algorithms {
+                    let mut file = std::fs::File::open(backup_path.as_path())
+                        .context("Failed opening downloaded backup")?;
+                    let mut writer = crate::hashing::digest(&HashingConfig { algorithm });
+                    std::io::copy(&mut file, &mut writer).context("Failed hashing backup")?;
+                    digests.push((algorithm, writer.finalize()));
+                }
+                Ok(digests)
+            }
+        })
+        .await
+        .map_err(UploadBackupError::Other);
+        let computed_digests: Vec<(HashingAlgorithm, Vec<u8>)> =
+            delete_guard.delete_on_err(res).await?;
+
+        if let Some((algorithm, ref expected)) = digest {
+            let computed = (computed_digests.iter())
+                .find_map(|(a, digest)| (*a == algorithm).then_some(digest));
+            if computed != Some(expected) {
+                let err =
+                    UploadBackupError::Verification(VerificationError::InvalidChecksum(anyhow!(
+                        "Invalid {ext} checksum: `{backup_id}`.",
+                        ext = algorithm.extension(),
+                    )));
+                return delete_guard.delete_on_err(Err(err)).await;
+            }
+        }
+
+        let (_, configured_digest) = (computed_digests.into_iter())
+            .find(|(algorithm, _)| *algorithm == configured_algorithm)
+            .expect("The configured algorithm is always computed");
+        let digest_hex = encode_hex_digest(configured_algorithm, &configured_digest);
+        if !digest.is_some_and(|(algorithm, _)| algorithm == configured_algorithm) {
+            let check_id = raw_backup_id.with_extension(configured_algorithm.extension());
+            let res = upload_check(service, &check_id, configured_digest).await;
+            delete_guard.delete_on_err(res).await?;
+            digest_ids.push(check_id);
+        }
+
+        let elapsed = start.elapsed();
+        tracing::info!("Uploaded backup {backup_id:?} ({size_bytes}B) in {elapsed:?}.");
+
+        let res = crate::transparency::append(
+            service,
+            LogOperation::Created,
+            &backup_id,
+            Some(digest_hex),
+        )
+        .await
+        .map_err(UploadBackupError::TransparencyLogFailed);
+        delete_guard.delete_on_err(res).await?;
+
+        delete_guard.defuse();
+
+        Ok(UploadBackupSuccess {
+            backup: BackupDto {
+                id: backup_id.clone(),
+                description: backup_id.description.to_string(),
+                metadata: BackupMetadataPartialDto {
+                    created_at: backup_id.created_at.into(),
+                    size_bytes,
+                    is_signed: verification_report.is_signed,
+                    is_encrypted: backup_id.extensions.contains(&Box::from("pgp")),
+                    can_be_restored: true,
+                    is_logged: (service.transparency_log_config.enabled).then_some(true),
+                },
+            },
+            output: CreateBackupOutput {
+                backup_id,
+                digest_ids,
+                signature_ids,
+            },
+        })
+    }
+
+    /// Whether or not the extensions of `backup_id` are the ones of a backup
+    /// (and not of an integrity check for example).
+    fn is_backup_archive(backup_id: &BackupId) -> bool {
+        let extensions: Vec<&str> = backup_id.extensions.iter().map(|ext| &**ext).collect();
+
+        matches!(
+            extensions.as_slice(),
+            ["tar"]
+                | ["tar", "zst"]
+                | ["tar", "pgp"]
+                | [
+                    "tar", "zst", "pgp"
+                ]
+        )
+    }
+
+    async fn upload_check(
+        service: &BackupService,
+        check_id: &ObjectId,
+        data: Vec<u8>,
+    ) -> Result<(), UploadBackupError> {
+        let mut uploader = service
+            .check_store
+            .async_writer_if_absent(check_id)
+            .await
+            .map_err(already_exists_or(
+                UploadBackupError::IntegrityCheckUploadFailed,
+            ))?;
+
+        tokio::io::copy(&mut std::io::Cursor::new(data), &mut uploader)
+            .await
+            .context("`tokio::io::copy` failed")
+            .map_err(UploadBackupError::IntegrityCheckUploadFailed)?;
+
+        uploader.finalize().await.map_err(already_exists_or(
+            UploadBackupError::IntegrityCheckUploadFailed,
+        ))
+    }
+
+    fn already_exists_or(
+        other: fn(anyhow::Error) -> UploadBackupError,
+    ) -> impl FnOnce(anyhow::Error) -> UploadBackupError {
+        move |err| {
+            if ObjectAlreadyExists::is(&err) {
+                UploadBackupError::AlreadyExists(err)
+            } else {
+                other(err)
+            }
+        }
+    }
+
+    pub struct UploadBackupCommand {
+        /// Unique identifier (file name) of the backup, as it was created.
+        ///
+        /// NOTE: OpenPGP signatures are verified at the creation date
+        ///   it contains.
+        pub backup_id: BackupId,
+
+        /// Digest of the backup, encoded as `<algorithm>:<hex>`
+        /// (e.g. `blake3:…`).
+        pub digest: Option<String>,
+
+        /// Detached OpenPGP signature of the backup (armored or binary).
+        pub signature: Option<Vec<u8>>,
+    }
+
+    #[derive(Debug)]
+    #[derive(serde::Serialize)]
+    pub struct UploadBackupSuccess {
+        pub backup: BackupDto<BackupMetadataPartialDto>,
+
+        #[serde(flatten)]
+        pub output: CreateBackupOutput,
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    pub enum UploadBackupError {
+        #[error("Invalid backup ID")]
+        InvalidBackupId(#[source] anyhow::Error),
+
+        #[error("Invalid digest")]
+        InvalidDigest(#[source] anyhow::Error),
+
+        #[error("Invalid signature")]
+        InvalidSignature(#[source] anyhow::Error),
+
+        #[error("Neither a digest nor a signature was provided")]
+        MissingIntegrityCheck,
+
+        /// A backup (or integrity check) with the same ID already exists.
+        ///
+        /// NOTE: Nothing was overwritten.
+        #[error("Backup already exists")]
+        AlreadyExists(#[source] anyhow::Error),
+
+        #[error("Failed uploading backup")]
+        UploadFailed(#[source] anyhow::Error),
+
+        #[error("Failed uploading backup integrity check")]
+        IntegrityCheckUploadFailed(#[source] anyhow::Error),
 
-    impl<'a> Drop for BackupAutoDeleteGuard<'a> {
-        fn drop(&mut self) {
-            let Some(backup_id) = std::mem::take(&mut self.backup_id) else {
-                return;
-            };
+        /// The backup was uploaded but is not intact or not trusted. It was
+        /// deleted.
+        #[error("Backup verification failed")]
+        Verification(#[from] VerificationError),
 
-            tokio::task::block_in_place(move || {
-                tokio::runtime::Handle::current().block_on(async move {
-                    match self.service.delete_backup(backup_id).await {
-                        Ok(_) => tracing::info!("Cleaned up backup `{backup_id}`."),
-                        Err(err) => {
-                            tracing::error!("Failed cleaning up backup `{backup_id}`: {err:#}")
-                        }
-                    }
-                })
-            })
-        }
+        #[error("Failed recording backup in the transparency log")]
+        TransparencyLogFailed(#[source] anyhow::Error),
+
+        #[error(transparent)]
+        Other(anyhow::Error),
     }
 }
 
@@ -873,35 +2245,8 @@ mod read {
 
         use std::str::FromStr as _;
 
-        /// Determines whether an object is a backup based on its name.
-        /// It’s not bulletproof and might break if we make changes to
-        /// compression or encryption but it’s good enough for now.
-        fn is_backup(metadata: &ObjectMetadata) -> bool {
-            match metadata.file_name.rsplit(".").next() {
-                Some(file_ext) => {
-                    for ext in [
-                        #[cfg(feature = "hashing-sha2")]
-                        "sha256",
-                        #[cfg(feature = "hashing-blake3")]
-                        "blake3",
-                        "sig",
-                    ] {
-                        if file_ext == ext {
-                            return false;
-                        }
-                    }
-                    true
-                }
-                None => false,
-            }
-        }
-
-        // NOTE: If using the same bucket and prefix for both the backups and
-        //   integrity checks, `service.backup_store.list_all` will also return
-        //   integrity checks. We need to filter it.
-        let objects = service.backup_store.list_all().await?;
-
-        let backups = objects.into_iter().filter(is_backup).collect::<Vec<_>>();
+        // NOTE: Lists backups in all stores (see `BackupService::profiles`).
+        let backups = service.list_backup_objects().await?;
 
         // NOTE: S3 results are sorted in alphabetically ascending order,
         //   and backup names use Unix timestamps which are alphabetically
@@ -928,6 +2273,12 @@ mod read {
 
         let signing_is_mandatory = service.signing_context.is_signing_mandatory;
 
+        let mut logged_backups = if service.transparency_log_config.enabled {
+            Some(crate::transparency::logged_backups(service).await?)
+        } else {
+            None
+        };
+
         let mut dtos: Vec<BackupDto<BackupMetadataPartialDto>> = Vec::with_capacity(backups.len());
 
         for backup in backups.into_iter().rev() {
@@ -938,6 +2289,9 @@ mod read {
 
             let can_be_restored = (!signing_is_mandatory) || is_signed;
 
+            let is_logged =
+                (logged_backups.as_mut()).map(|logged| logged.remove(&backup_file_name));
+
             let backup_id = match BackupId::from_str(&backup_file_name) {
                 Ok(name) => name,
                 Err(err) => {
@@ -953,20 +2307,55 @@ mod read {
                     is_signed,
                     is_encrypted,
                     can_be_restored,
+                    is_logged,
                 },
                 description: backup_id.description.to_string(),
                 id: backup_id,
             });
         }
 
+        // NOTE: Backups remaining are recorded in the log, but were deleted
+        //   behind this service’s back (see `BackupService::verify_log`).
+        for backup_id in logged_backups.into_iter().flatten() {
+            tracing::warn!("Backup `{backup_id}` is in the transparency log but was not found.");
+        }
+
         Ok(dtos)
     }
 
+    /// Determines whether an object is a backup based on its name.
+    /// It’s not bulletproof and might break if we make changes to
+    /// compression or encryption but it’s good enough for now.
+    pub(crate) fn is_backup(metadata: &ObjectMetadata) -> bool {
+        if crate::transparency::is_log_object(&metadata.file_name) {
+            return false;
+        }
+
+        match metadata.file_name.rsplit(".").next() {
+            Some(file_ext) => {
+                for ext in [
+                    #[cfg(feature = "hashing-sha2")]
+                    "sha256",
+                    #[cfg(feature = "hashing-blake3")]
+                    "blake3",
+                    "sig",
+                ] {
+                    if file_ext == ext {
+                        return false;
+                    }
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
     pub(crate) async fn get_details(
         service: &BackupService,
         backup_id: &BackupId,
     ) -> Result<BackupDto<BackupMetadataFullDto>, anyhow::Error> {
         use crate::archiving::get_metadata;
+        use crate::util::spawn_blocking;
         use crate::verification::VerificationReport;
 
         let mut verification_report = VerificationReport::default();
@@ -980,18 +2369,37 @@ mod read {
 
         let mut decryption_report = DecryptionReport::default();
         let mut is_encryption_valid: Option<bool> = None;
+        let mut profile: Option<String> = None;
+        let mut is_partial: Option<bool> = None;
         let can_be_restored: bool;
         match verification_result {
             Ok(verification_output) => {
-                let extraction_result = get_metadata(
-                    &verification_output,
-                    &backup_id,
-                    &service.decryption_context,
-                    &mut decryption_report,
-                    &service.archiving_context.blueprints,
-                );
+                // NOTE: Decryption is CPU-bound, run it on the blocking
+                //   thread pool.
+                let (extraction_result, report) = spawn_blocking({
+                    let backup_id = backup_id.clone();
+                    let decryption_context = service.decryption_context.clone();
+                    let blueprints = service.archiving_context.blueprints.clone();
+
+                    move || {
+                        let mut decryption_report = DecryptionReport::default();
+                        let extraction_result = get_metadata(
+                            &verification_output,
+                            &backup_id,
+                            &decryption_context,
+                            &mut decryption_report,
+                            &blueprints,
+                        )
+                        .map(|output| output.metadata);
+                        (extraction_result, decryption_report)
+                    }
+                })
+                .await;
+                decryption_report = report;
                 match extraction_result {
-                    Ok(_) => {
+                    Ok(metadata) => {
+                        profile = metadata.profile;
+                        is_partial = Some(metadata.paths.is_some());
                         is_encryption_valid = Some(true);
                         can_be_restored = true;
                     }
@@ -1008,9 +2416,9 @@ mod read {
             }
         }
 
-        let metadata = service
-            .backup_store
-            .metadata(&ObjectId::from(backup_id))
+        let object_id = ObjectId::from(backup_id);
+        let metadata = (service.backup_store_of(&object_id).await?)
+            .metadata(&object_id)
             .await?;
 
         let is_signed = verification_report.is_signed;
@@ -1034,6 +2442,8 @@ mod read {
                     .used_cert_and_subkey
                     .map(|(cert_fingerprint, _)| cert_fingerprint.to_spaced_hex()),
                 is_encryption_valid,
+                profile,
+                is_partial,
             },
             description: backup_id.description.to_string(),
             id: backup_id.to_owned(),
@@ -1042,6 +2452,58 @@ mod read {
         Ok(dto)
     }
 
+    pub(crate) async fn latest_intact_backup(
+        service: &BackupService,
+        prefix: Option<&str>,
+        required_signer: Option<&openpgp::Fingerprint>,
+    ) -> Result<Option<BackupId>, anyhow::Error> {
+        use std::str::FromStr as _;
+
+        use crate::verification::VerificationReport;
+
+        let mut backup_ids = (service.list_backup_objects().await?.into_iter())
+            .filter_map(|metadata| match BackupId::from_str(&metadata.file_name) {
+                Ok(backup_id) => Some(backup_id),
+                Err(err) => {
+                    tracing::warn!("Skipping `{}`: {err:?}", metadata.file_name);
+                    None
+                }
+            })
+            .filter(|backup_id| prefix.is_none_or(|prefix| *backup_id.prefix == *prefix))
+            .collect::<Vec<_>>();
+
+        // NOTE: Backups are listed by name, which is only chronological
+        //   for a given prefix.
+        backup_ids.sort_by_key(|backup_id| std::cmp::Reverse(backup_id.created_at));
+
+        for backup_id in backup_ids {
+            let mut report = VerificationReport::default();
+            let res = service
+                .download_backup_and_check_integrity(&backup_id, backup_id.created_at, &mut report)
+                .await;
+            if let Err(err) = res {
+                tracing::warn!("Skipping backup `{backup_id}`: {err:#}");
+                continue;
+            }
+
+            if let Some(signer) = required_signer {
+                let is_signed_by_signer = (report.known_signing_keys.iter()).any(|key| {
+                    key.is_valid
+                        && (key.cert_fingerprint == *signer
+                            || key.subkey_fingerprint.as_ref() == Some(signer))
+                });
+                if !is_signed_by_signer {
+                    tracing::info!("Skipping backup `{backup_id}`: Not signed by `{signer}`.");
+                    continue;
+                }
+            }
+
+            return Ok(Some(backup_id));
+        }
+
+        Ok(None)
+    }
+
     pub(crate) async fn get_download_url(
         service: &BackupService,
         backup_id: &BackupId,
@@ -1053,24 +2515,59 @@ mod read {
             service.download_config.url_max_ttl,
         );
 
-        service
-            .backup_store
-            .download_url(&ObjectId::from(backup_id), &ttl)
+        let object_id = ObjectId::from(backup_id);
+        (service.backup_store_of(&object_id).await?)
+            .download_url(&object_id, &ttl)
             .await
     }
+
+    pub(crate) async fn get_download_info(
+        service: &BackupService,
+        backup_id: &BackupId,
+    ) -> Result<BackupDownloadInfoDto, ReadObjectError> {
+        let object_id = ObjectId::from(backup_id);
+        let store = (service.backup_store_of(&object_id).await).map_err(ReadObjectError::Other)?;
+
+        let metadata = store.metadata(&object_id).await?;
+
+        let digest = crate::transparency::read_backup_digest(service, backup_id).await;
+
+        Ok(BackupDownloadInfoDto {
+            size_bytes: metadata.size_bytes,
+            digest,
+        })
+    }
+
+    pub(crate) async fn read_backup_range(
+        service: &BackupService,
+        backup_id: &BackupId,
+        range: std::ops::Range<u64>,
+    ) -> Result<Box<prelude::DynAsyncObjectReader>, ReadObjectError> {
+        let object_id = ObjectId::from(backup_id);
+        let store = (service.backup_store_of(&object_id).await).map_err(ReadObjectError::Other)?;
+
+        store.async_range_reader(&object_id, range).await
+    }
 }
 
 mod restore {
     use anyhow::Context as _;
+    use tokio::sync::mpsc;
 
     use crate::BackupService;
     use crate::archiving::*;
     use crate::backup_id::*;
     use crate::decryption::*;
     use crate::restoration::*;
+    use crate::restore_report::*;
     use crate::stats::*;
+    use crate::util::BlockingTask;
     use crate::verification::*;
 
+    /// Number of events the blocking restoration task can send before it
+    /// has to wait for the event handler to catch up.
+    const EVENTS_BUFFER: usize = 16;
+
     #[derive(Debug)]
     pub struct RestoreBackupSuccess {
         pub verification_report: VerificationReport,
@@ -1117,7 +2614,7 @@ mod restore {
             };
 
             // Revert all changes.
-            drop(revert_guard);
+            revert_guard.release().await;
 
             return Err(RestorationError::FoundUnexpectedData(entries));
         }
@@ -1145,24 +2642,79 @@ mod restore {
     {
         let mut verification_report = VerificationReport::default();
         let verification_output = service
-            .download_backup_and_check_integrity(
+            .download_backup_and_check_integrity_(
                 &backup_id,
                 backup_id.created_at,
                 &mut verification_report,
+                &mut |stage, progress| {
+                    event_handler.on_stage(backup_id, &RestoreStageEventDto::new(stage, progress))
+                },
             )
             .await
             .context("Failed downloading backup or checking integrity")?;
 
-        let restoration_output = restore(
-            backup_id,
-            &verification_output,
-            blueprint,
-            &service.restoration_context,
-            &service.decryption_context,
-            &service.archiving_context.blueprints,
-            event_handler,
-        )
-        .context("Failed restoring backup")?;
+        // NOTE: Decryption, decompression and extraction are CPU-bound and
+        //   do blocking I/O, so they run on the blocking thread pool.
+        //   Progress is sent back here to call the event handler.
+        let mut restoration = BlockingTask::spawn(EVENTS_BUFFER, {
+            let backup_id = backup_id.clone();
+            let blueprint = blueprint.clone();
+            let restoration_context = service.restoration_context.clone();
+            let journal_path = service.restoration_config.journal_path.clone();
+            let safety_snapshot_config = (service.safety_snapshot_config.enabled)
+                .then(|| service.safety_snapshot_config.clone());
+            let decryption_context = service.decryption_context.clone();
+            let blueprints = service.archiving_context.blueprints.clone();
+            let progress_interval = service.progress_config.interval;
+
+            move |sender| {
+                restore(
+                    &backup_id,
+                    &verification_output,
+                    &blueprint,
+                    &restoration_context,
+                    &decryption_context,
+                    &blueprints,
+                    journal_path.as_deref(),
+                    safety_snapshot_config.as_ref(),
+                    progress_interval,
+                    &mut ChannelEventHandler { sender },
+                )
+            }
+        });
+
+        while let Some(event) = restoration.recv().await {
+            match event {
+                RestoreEvent::Start(total) => event_handler.on_restoration_start(backup_id, total),
+                RestoreEvent::Progress(len) => {
+                    event_handler.on_restoration_progress(backup_id, len)
+                }
+                RestoreEvent::DecryptionFinished(stats, report) => {
+                    event_handler.on_decryption_finished(backup_id, stats, report)
+                }
+                RestoreEvent::DecompressionFinished(stats) => {
+                    event_handler.on_decompression_finished(backup_id, stats)
+                }
+                RestoreEvent::ExtractionFinished(report) => {
+                    event_handler.on_extraction_finished(backup_id, report)
+                }
+                RestoreEvent::Stage(event) => event_handler.on_stage(backup_id, &event),
+                RestoreEvent::Finished => event_handler.on_restoration_finished(backup_id),
+            }
+
+            if event_handler.is_cancelled() {
+                break;
+            }
+        }
+
+        // NOTE: If cancelled, wait for changes to be reverted (dropping the
+        //   task would revert them in the background).
+        let restoration_output = if event_handler.is_cancelled() {
+            restoration.cancel().await
+        } else {
+            restoration.join().await
+        };
+        let restoration_output = restoration_output.context("Failed restoring backup")?;
 
         Ok(RestoreBackupPartialSuccess {
             verification_report,
@@ -1198,24 +2750,140 @@ mod restore {
 
         #[inline]
         fn on_restoration_finished(&mut self, backup_id: &BackupId) {}
+
+        /// Called when a [`RestoreStage`] starts (`progress = 0.0`),
+        /// progresses (throttled, see
+        /// [`ProgressConfig::interval`](crate::config::ProgressConfig::interval))
+        /// and finishes (`progress = 1.0`).
+        ///
+        /// NOTE: Stages after [`RestoreStage::SwappingDirectories`] are
+        ///   emitted by apps, which can call this method themselves.
+        #[inline]
+        fn on_stage(&mut self, backup_id: &BackupId, event: &RestoreStageEventDto) {}
+
+        /// Whether the restoration should stop as soon as possible (which
+        /// reverts all changes before the restoration returns).
+        ///
+        /// NOTE: Prefer this over dropping the restoration future, which
+        ///   reverts changes in the background.
+        #[inline]
+        fn is_cancelled(&self) -> bool {
+            false
+        }
+    }
+
+    /// Events sent by the blocking restoration task.
+    enum RestoreEvent {
+        Start(u64),
+        Progress(usize),
+        DecryptionFinished(ReadStats, DecryptionReport),
+        DecompressionFinished(ReadStats),
+        ExtractionFinished(ExtractionReport),
+        Stage(RestoreStageEventDto),
+        Finished,
+    }
+
+    /// Forwards restoration events to the async task awaiting
+    /// the restoration.
+    ///
+    /// NOTE: If the receiver is dropped (e.g. the request was cancelled),
+    ///   the restoration is cancelled.
+    struct ChannelEventHandler {
+        sender: mpsc::Sender<RestoreEvent>,
+    }
+
+    impl ChannelEventHandler {
+        #[inline]
+        fn send(&self, event: RestoreEvent) {
+            // NOTE: Cancellation is handled in `is_cancelled`.
+            _ = (self.sender).blocking_send(event);
+        }
+    }
+
+    impl RestoreBackupEventHandler for ChannelEventHandler {
+        fn on_restoration_start(&mut self, _backup_id: &BackupId, total: u64) {
+            self.send(RestoreEvent::Start(total));
+        }
+
+        fn on_restoration_progress(&mut self, _backup_id: &BackupId, len: usize) {
+            self.send(RestoreEvent::Progress(len));
+        }
+
+        fn on_decryption_finished(
+            &mut self,
+            _backup_id: &BackupId,
+            stats: ReadStats,
+            report: DecryptionReport,
+        ) {
+            self.send(RestoreEvent::DecryptionFinished(stats, report));
+        }
+
+        fn on_decompression_finished(&mut self, _backup_id: &BackupId, stats: ReadStats) {
+            self.send(RestoreEvent::DecompressionFinished(stats));
+        }
+
+        fn on_extraction_finished(&mut self, _backup_id: &BackupId, report: ExtractionReport) {
+            self.send(RestoreEvent::ExtractionFinished(report));
+        }
+
+        fn on_restoration_finished(&mut self, _backup_id: &BackupId) {
+            self.send(RestoreEvent::Finished);
+        }
+
+        fn on_stage(&mut self, _backup_id: &BackupId, event: &RestoreStageEventDto) {
+            self.send(RestoreEvent::Stage(event.clone()));
+        }
+
+        #[inline]
+        fn is_cancelled(&self) -> bool {
+            self.sender.is_closed()
+        }
     }
 }
 
 mod delete {
+    use std::sync::Arc;
+
     use crate::BackupService;
     use crate::backup_id::*;
     use crate::stores::*;
 
-    // NOTE: If using Object Lock, this method exits successfully and
-    //   backups / integrity checks remain stored until locks are removed.
     pub(crate) async fn delete_backup(
         service: &BackupService,
         backup_id: &BackupId,
+    ) -> Result<(), anyhow::Error> {
+        use anyhow::Context as _;
+
+        use crate::transparency::{self, LogOperation};
+
+        // NOTE: Record the deletion first, so a failure doesn’t leave
+        //   a backup deleted without a trace (no-op if the log is disabled).
+        if service.transparency_log_config.enabled {
+            let digest = transparency::read_backup_digest(service, backup_id).await;
+            transparency::append(service, LogOperation::Deleted, backup_id, digest)
+                .await
+                .context("Failed recording deletion in the transparency log")?;
+        }
+
+        let backup_store = service.backup_store_of(&ObjectId::from(backup_id)).await?;
+
+        delete_backup_objects(backup_store, service.check_store.as_ref(), backup_id).await
+    }
+
+    /// Deletes a backup and its integrity checks, without recording it in
+    /// the transparency log.
+    ///
+    /// NOTE: If using Object Lock, this method exits successfully and
+    ///   backups / integrity checks remain stored until locks are removed.
+    pub(crate) async fn delete_backup_objects(
+        backup_store: &CachedStore<Arc<dyn ObjectStore>>,
+        check_store: &dyn ObjectStore,
+        backup_id: &BackupId,
     ) -> Result<(), anyhow::Error> {
         let backup_id = ObjectId::from(backup_id);
 
         // Delete the backup object.
-        let deleted_state = service.backup_store.delete(&backup_id).await?;
+        let deleted_state = backup_store.delete(&backup_id).await?;
         match deleted_state {
             crate::stores::DeletedState::Deleted => {}
             crate::stores::DeletedState::MarkedForDeletion => tracing::warn!(
@@ -1231,7 +2899,7 @@ mod delete {
                 deleted,
                 marked_for_deletion,
                 errors,
-            } = service.check_store.delete_all(&backup_id).await?;
+            } = check_store.delete_all(&backup_id).await?;
 
             // Log successes.
             for key in deleted {
@@ -1256,26 +2924,246 @@ mod delete {
     }
 }
 
+mod report {
+    use std::collections::{BTreeMap, HashSet};
+    use std::str::FromStr as _;
+
+    use crate::BackupService;
+    use crate::backup_id::*;
+    use crate::dtos::*;
+    use crate::read::is_backup;
+    use crate::stores::*;
+
+    const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+    pub(crate) async fn storage_report(
+        service: &BackupService,
+    ) -> Result<StorageReportDto, anyhow::Error> {
+        let now = time::UtcDateTime::now();
+        let config = &service.quota_config;
+
+        // NOTE: If using the same bucket and prefix for both the backups and
+        //   integrity checks, listing one store will also return objects from
+        //   the other. We need to filter both lists to avoid counting objects
+        //   twice.
+        let backups = service.list_backup_objects().await?;
+        let checks = (service.check_store.list_all().await?.into_iter())
+            .filter(|metadata| !is_backup(metadata))
+            .collect::<Vec<_>>();
+
+        let check_names: HashSet<&str> = checks.iter().map(|m| m.file_name.as_str()).collect();
+
+        let window_start = now - config.growth_estimation_window;
+
+        let mut backups_usage = StorageUsageDto::default();
+        let mut checks_usage = StorageUsageDto::default();
+        let mut by_month: BTreeMap<(i32, u8), (StorageUsageDto, StorageUsageDto)> = BTreeMap::new();
+        let mut by_status: BTreeMap<(bool, bool), StorageUsageDto> = BTreeMap::new();
+        let mut oldest: Option<time::UtcDateTime> = None;
+        let mut recent_bytes: u64 = 0;
+
+        for ObjectMetadata {
+            file_name,
+            size_bytes,
+        } in backups.iter()
+        {
+            backups_usage.record(*size_bytes);
+
+            let is_signed = check_names.contains(format!("{file_name}.sig").as_str());
+            let is_encrypted = file_name.ends_with(".pgp");
+            (by_status.entry((is_signed, is_encrypted)).or_default()).record(*size_bytes);
+
+            match BackupId::from_str(file_name) {
+                Ok(BackupId { created_at, .. }) => {
+                    let month = (created_at.year(), created_at.month() as u8);
+                    by_month.entry(month).or_default().0.record(*size_bytes);
+
+                    oldest = Some(oldest.map_or(created_at, |t| t.min(created_at)));
+
+                    if created_at >= window_start {
+                        recent_bytes = recent_bytes.saturating_add(*size_bytes);
+                    }
+                }
+                Err(err) => tracing::warn!("Not dating `{file_name}`: {err:?}"),
+            }
+        }
+
+        for ObjectMetadata {
+            file_name,
+            size_bytes,
+        } in checks.iter()
+        {
+            checks_usage.record(*size_bytes);
+
+            // NOTE: Transparency log entries are not dated (not worth
+            //   downloading them for this).
+            if crate::transparency::is_log_object(file_name) {
+                continue;
+            }
+
+            // NOTE: Integrity checks are named after the backup they check,
+            //   therefore they can be parsed as a `BackupId` too.
+            match BackupId::from_str(file_name) {
+                Ok(BackupId { created_at, .. }) => {
+                    let month = (created_at.year(), created_at.month() as u8);
+                    by_month.entry(month).or_default().1.record(*size_bytes);
+
+                    if created_at >= window_start {
+                        recent_bytes = recent_bytes.saturating_add(*size_bytes);
+                    }
+                }
+                Err(err) => tracing::warn!("Not dating `{file_name}`: {err:?}"),
+            }
+        }
+
+        let total_size_bytes = backups_usage
+            .size_bytes
+            .saturating_add(checks_usage.size_bytes);
+
+        // Estimate growth.
+        let growth = {
+            // NOTE: If backups haven’t been created for as long as the window,
+            //   dividing by the whole window would underestimate growth. Use
+            //   the observed history instead, with a minimum of one day to
+            //   avoid extrapolating too much from a single fresh backup.
+            let observed_secs = match oldest {
+                Some(oldest) => {
+                    let secs = crate::util::saturating_i64_to_u64((now - oldest).whole_seconds());
+                    secs.min(config.growth_estimation_window.as_secs())
+                }
+                None => 0,
+            }
+            .max(SECONDS_PER_DAY);
+
+            let bytes_per_day = (recent_bytes as u128 * SECONDS_PER_DAY as u128
+                / observed_secs as u128)
+                .try_into()
+                .unwrap_or(u64::MAX);
+
+            StorageGrowthDto {
+                observed_days: observed_secs / SECONDS_PER_DAY,
+                bytes_per_day,
+                bytes_per_month: bytes_per_day.saturating_mul(30),
+            }
+        };
+
+        // Compare against quota.
+        let mut warnings: Vec<String> = Vec::new();
+        let quota = config.max_size.map(|max_size| {
+            let max_size_bytes = max_size.as_bytes();
+            let used_ratio = total_size_bytes as f64 / max_size_bytes.max(1) as f64;
+
+            let status = if total_size_bytes >= max_size_bytes {
+                QuotaStatus::Exceeded
+            } else if used_ratio >= config.warning_threshold {
+                QuotaStatus::Warning
+            } else {
+                QuotaStatus::Ok
+            };
+
+            let estimated_days_left = match status {
+                QuotaStatus::Exceeded => None,
+                _ if growth.bytes_per_day == 0 => None,
+                _ => Some((max_size_bytes - total_size_bytes) / growth.bytes_per_day),
+            };
+
+            match status {
+                QuotaStatus::Ok => {}
+                QuotaStatus::Warning => warnings.push(format!(
+                    "Backups use {percent:.0}% of the {max_size} quota.",
+                    percent = used_ratio * 100.,
+                )),
+                QuotaStatus::Exceeded => warnings.push(format!(
+                    "Backups exceed the {max_size} quota ({total_size_bytes}B used)."
+                )),
+            }
+
+            StorageQuotaDto {
+                max_size_bytes,
+                used_ratio,
+                warning_threshold: config.warning_threshold,
+                status,
+                estimated_days_left,
+            }
+        });
+
+        for warning in warnings.iter() {
+            tracing::warn!("{warning}");
+        }
+
+        Ok(StorageReportDto {
+            generated_at: now.into(),
+            total_size_bytes,
+            backups: backups_usage,
+            checks: checks_usage,
+            by_month: (by_month.into_iter())
+                .map(
+                    |((year, month), (backups, checks))| MonthlyStorageUsageDto {
+                        month: format!("{year:04}-{month:02}"),
+                        backups,
+                        checks,
+                    },
+                )
+                .collect(),
+            by_status: (by_status.into_iter())
+                .map(|((is_signed, is_encrypted), usage)| StatusStorageUsageDto {
+                    is_signed,
+                    is_encrypted,
+                    usage,
+                })
+                .collect(),
+            growth,
+            quota,
+            warnings,
+        })
+    }
+}
+
 mod backup_id {
     //! Backup ID serialization and deserialization.
 
     use anyhow::Context as _;
 
+    /// Length of [`BackupId::random_suffix`] (in characters).
+    const RANDOM_SUFFIX_LEN: usize = 6;
+
     /// Unique identifier of the backup.
     ///
-    /// E.g. `prose%2Dbackup-1772432392-Automatic%20backup.tar.zst.pgp`.
+    /// E.g. `prose%2Dbackup-1772432392123_3f9a0c-Automatic%20backup.tar.zst.pgp`.
+    ///
+    /// Backups used to be identified with a timestamp at second resolution
+    /// only (e.g. `prose%2Dbackup-1772432392-Automatic%20backup.tar.zst.pgp`),
+    /// which meant two backups created in the same second had the same ID.
+    /// Such IDs can still be parsed (they have no random suffix), and both
+    /// formats sort chronologically when compared as strings.
     #[derive(Clone)]
     pub struct BackupId {
         pub prefix: Box<str>,
 
         pub created_at: time::UtcDateTime,
 
+        /// Short random string distinguishing backups created in the same
+        /// millisecond (e.g. `3f9a0c`).
+        ///
+        /// `None` for backups created before millisecond timestamps were
+        /// introduced (in which case `created_at` is serialized with second
+        /// precision).
+        pub random_suffix: Option<Box<str>>,
+
         pub description: Box<str>,
 
         pub extensions: Vec<Box<str>>,
     }
 
     impl BackupId {
+        /// Generates a new random suffix (lowercase hexadecimal).
+        pub(crate) fn random_suffix() -> Box<str> {
+            use rand::RngExt as _;
+
+            let n: u32 = rand::rng().random_range(0..(1 << (4 * RANDOM_SUFFIX_LEN)));
+            Box::from(format!("{n:0width$x}", width = RANDOM_SUFFIX_LEN))
+        }
+
         fn parse(str: &str) -> Result<Self, anyhow::Error> {
             let Some((prefix, rest)) = str.split_once('-') else {
                 anyhow::bail!("File `{str}` has no prefix.");
@@ -1285,12 +3173,38 @@ mod backup_id {
                 anyhow::bail!("File `{str}` is missing the timestamp prefix.");
             };
 
-            let secs: i64 = timestamp_str
-                .parse()
-                .with_context(|| format!("Could not read integer from `{timestamp_str}`"))?;
+            let (created_at, random_suffix) = match timestamp_str.split_once('_') {
+                // Current format (milliseconds and random suffix).
+                Some((millis_str, random_suffix)) => {
+                    let millis: i128 = millis_str
+                        .parse()
+                        .with_context(|| format!("Could not read integer from `{millis_str}`"))?;
+
+                    if random_suffix.len() != RANDOM_SUFFIX_LEN
+                        || !random_suffix.bytes().all(|b| b.is_ascii_hexdigit())
+                    {
+                        anyhow::bail!("File `{str}` has an invalid random suffix.");
+                    }
+
+                    let created_at =
+                        time::UtcDateTime::from_unix_timestamp_nanos(millis * 1_000_000)
+                            .context("Could not parse timestamp from file name")?;
+
+                    (created_at, Some(Box::from(random_suffix)))
+                }
+
+                // Legacy format (seconds only).
+                None => {
+                    let secs: i64 = timestamp_str.parse().with_context(|| {
+                        format!("Could not read integer from `{timestamp_str}`")
+                    })?;
 
-            let created_at = time::UtcDateTime::from_unix_timestamp(secs)
-                .context("Could not parse timestamp from file name")?;
+                    let created_at = time::UtcDateTime::from_unix_timestamp(secs)
+                        .context("Could not parse timestamp from file name")?;
+
+                    (created_at, None)
+                }
+            };
 
             let Some((description, extensions)) = rest.split_once('.') else {
                 anyhow::bail!("File `{str}` has no extension.");
@@ -1306,6 +3220,7 @@ mod backup_id {
             Ok(BackupId {
                 prefix: Box::from(prefix),
                 created_at,
+                random_suffix,
                 description: Box::from(description),
                 extensions: extensions.split(".").map(Box::from).collect(),
             })
@@ -1317,6 +3232,7 @@ mod backup_id {
             let Self {
                 prefix,
                 created_at,
+                random_suffix,
                 description,
                 extensions,
             } = self;
@@ -1332,17 +3248,35 @@ mod backup_id {
             let prefix = urlencode_component(&prefix);
             let description = urlencode_component(&description);
 
-            // Unix timestamp with second precision as 10 chars covers 2001-09-09
-            // to 2286-11-20 (<2001-09-09 needs 9 chars, >2286-11-20 needs 11).
-            // For correctness, we’ll still format the number as 10 digits with
-            // leading zeros (even if not necessary).
-            let created_at = created_at.unix_timestamp();
-            assert!(created_at <= 9_999_999_999);
-            debug_assert!(created_at > 999_999_999);
-
             let extensions = extensions.join(".");
 
-            write!(f, "{prefix}-{created_at:010}-{description}.{extensions}")
+            match random_suffix {
+                Some(random_suffix) => {
+                    debug_assert_eq!(random_suffix.len(), RANDOM_SUFFIX_LEN);
+
+                    // Unix timestamp with millisecond precision as 13 chars
+                    // covers the same range as the legacy format (see below).
+                    let created_at = created_at.unix_timestamp_nanos() / 1_000_000;
+                    assert!(created_at <= 9_999_999_999_999);
+                    debug_assert!(created_at > 999_999_999_999);
+
+                    write!(
+                        f,
+                        "{prefix}-{created_at:013}_{random_suffix}-{description}.{extensions}"
+                    )
+                }
+                None => {
+                    // Unix timestamp with second precision as 10 chars covers 2001-09-09
+                    // to 2286-11-20 (<2001-09-09 needs 9 chars, >2286-11-20 needs 11).
+                    // For correctness, we’ll still format the number as 10 digits with
+                    // leading zeros (even if not necessary).
+                    let created_at = created_at.unix_timestamp();
+                    assert!(created_at <= 9_999_999_999);
+                    debug_assert!(created_at > 999_999_999);
+
+                    write!(f, "{prefix}-{created_at:010}-{description}.{extensions}")
+                }
+            }
         }
     }
 
@@ -1358,11 +3292,23 @@ mod backup_id {
             let Self {
                 prefix,
                 created_at,
+                random_suffix,
                 description,
                 extensions,
             } = self;
 
-            created_at.unix_timestamp() == other.created_at.unix_timestamp()
+            // NOTE: Only compare timestamps at the precision they are
+            //   serialized with.
+            let created_at_eq = match random_suffix {
+                Some(_) => {
+                    created_at.unix_timestamp_nanos() / 1_000_000
+                        == other.created_at.unix_timestamp_nanos() / 1_000_000
+                }
+                None => created_at.unix_timestamp() == other.created_at.unix_timestamp(),
+            };
+
+            created_at_eq
+                && *random_suffix == other.random_suffix
                 && *description == other.description
                 && *extensions == other.extensions
                 && *prefix == other.prefix
@@ -1447,6 +3393,33 @@ mod backup_id {
                 BackupId {
                     prefix: Box::from("prose-backup"),
                     created_at: time::UtcDateTime::UNIX_EPOCH + time::Duration::seconds(1772432392),
+                    random_suffix: None,
+                    description: Box::from("Automatic backup"),
+                    extensions: vec![
+                        Box::from("tar"),
+                        Box::from("zst"),
+                        Box::from("pgp")
+                    ],
+                }
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        #[cfg(feature = "test")]
+        fn test_backup_id_components_parsing_millis() -> Result<(), anyhow::Error> {
+            use crate::BackupId;
+
+            let raw = "prose%2Dbackup-1772432392123_3f9a0c-Automatic%20backup.tar.zst.pgp";
+            let components = BackupId::parse(raw)?;
+            assert_eq!(
+                components,
+                BackupId {
+                    prefix: Box::from("prose-backup"),
+                    created_at: time::UtcDateTime::UNIX_EPOCH
+                        + time::Duration::milliseconds(1772432392123),
+                    random_suffix: Some(Box::from("3f9a0c")),
                     description: Box::from("Automatic backup"),
                     extensions: vec![
                         Box::from("tar"),
@@ -1455,9 +3428,44 @@ mod backup_id {
                     ],
                 }
             );
+            assert_eq!(components.to_string(), raw);
+
+            // Invalid random suffixes are rejected.
+            assert!(BackupId::parse("prose-1772432392123_3f9a-Backup.tar").is_err());
+            assert!(BackupId::parse("prose-1772432392123_3f9a0z-Backup.tar").is_err());
+
+            Ok(())
+        }
+
+        #[test]
+        fn test_backup_id_sorting() -> Result<(), anyhow::Error> {
+            use crate::BackupId;
+
+            // Legacy and current IDs must sort chronologically.
+            let mut ids = vec![
+                "prose-1772432393-Backup.tar",
+                "prose-1772432392999_000000-Backup.tar",
+                "prose-1772432392-Backup.tar",
+                "prose-1772432392001_ffffff-Backup.tar",
+            ];
+            ids.sort();
+
+            let timestamps = (ids.iter())
+                .map(|id| BackupId::parse(id).map(|id| id.created_at))
+                .collect::<Result<Vec<_>, _>>()?;
+            assert!(timestamps.is_sorted());
 
             Ok(())
         }
+
+        #[test]
+        fn test_backup_id_random_suffix() {
+            use crate::BackupId;
+
+            let suffix = BackupId::random_suffix();
+            assert_eq!(suffix.len(), super::RANDOM_SUFFIX_LEN);
+            assert!(suffix.bytes().all(|b| b.is_ascii_hexdigit()));
+        }
     }
 }
 
@@ -1468,6 +3476,7 @@ impl std::fmt::Debug for BackupService {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let Self {
             archiving_context,
+            archive_config,
             compression_config,
             hashing_config,
             encryption_context,
@@ -1475,13 +3484,24 @@ impl std::fmt::Debug for BackupService {
             verification_context,
             decryption_context,
             restoration_context,
+            restoration_config,
+            safety_snapshot_config,
+            transparency_log_config,
+            snapshot_config,
             download_config,
+            diff_config,
+            quota_config,
+            progress_config,
+            profiles,
+            keyring,
+            keys,
             backup_store,
             check_store,
         } = self;
 
         f.debug_struct("BackupService")
             .field("archiving_context", archiving_context)
+            .field("archive_config", archive_config)
             .field("compression_config", compression_config)
             .field("hashing_config", hashing_config)
             .field("encryption_context", encryption_context)
@@ -1489,7 +3509,17 @@ impl std::fmt::Debug for BackupService {
             .field("verification_context", verification_context)
             .field("decryption_context", decryption_context)
             .field("restoration_context", restoration_context)
+            .field("restoration_config", restoration_config)
+            .field("safety_snapshot_config", safety_snapshot_config)
+            .field("transparency_log_config", transparency_log_config)
+            .field("snapshot_config", snapshot_config)
             .field("download_config", download_config)
+            .field("diff_config", diff_config)
+            .field("quota_config", quota_config)
+            .field("progress_config", progress_config)
+            .field("profiles", profiles)
+            .field("keyring", keyring)
+            .field("keys", keys)
             .field("backup_store", backup_store)
             .field("check_store", check_store)
             .finish()
@@ -0,0 +1,109 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+//! Named backup profiles (see
+//! [`BackupProfileConfig`](crate::config::BackupProfileConfig)).
+
+use std::collections::BTreeMap;
+use std::ffi::{OsStr, OsString};
+use std::sync::Arc;
+
+use crate::BackupService;
+use crate::archiving::ArchiveBlueprint;
+use crate::config::CompressionConfig;
+use crate::encryption::EncryptionContext;
+use crate::read::is_backup;
+use crate::stores::{CachedStore, ObjectMetadata, ObjectStore};
+
+#[derive(Debug)]
+pub struct BackupProfile {
+    /// Blueprint entries to include, `None` for all entries.
+    pub include: Option<Vec<OsString>>,
+
+    /// Blueprint entries to exclude (applied after [`include`]).
+    ///
+    /// [`include`]: Self::include
+    pub exclude: Vec<OsString>,
+
+    pub compression_config: CompressionConfig,
+
+    pub encryption_context: Option<Arc<EncryptionContext>>,
+
+    /// Where to store backups, `None` for the default backup store.
+    pub backup_store: Option<CachedStore<Arc<dyn ObjectStore>>>,
+}
+
+impl BackupProfile {
+    /// Whether or not a blueprint entry is part of backups created using
+    /// this profile.
+    pub fn includes(&self, archive_path: &OsStr) -> bool {
+        let is_included = match self.include {
+            Some(ref include) => include.iter().any(|name| name == archive_path),
+            None => true,
+        };
+
+        is_included && !self.exclude.iter().any(|name| name == archive_path)
+    }
+
+    /// Keeps only the blueprint entries included in this profile.
+    pub fn filter_blueprint(&self, blueprint: &ArchiveBlueprint) -> ArchiveBlueprint {
+        ArchiveBlueprint {
+            version: blueprint.version,
+            paths: (blueprint.paths.iter())
+                .filter(|(archive_path, _)| self.includes(archive_path))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+impl BackupService {
+    /// Stores backups can be in (the default one first).
+    pub(crate) fn backup_stores(&self) -> impl Iterator<Item = &CachedStore<Arc<dyn ObjectStore>>> {
+        std::iter::once(&self.backup_store)
+            .chain((self.profiles.values()).filter_map(|profile| profile.backup_store.as_ref()))
+    }
+
+    /// Finds the store containing a backup (the default one if not found).
+    pub(crate) async fn backup_store_of(
+        &self,
+        object_id: &str,
+    ) -> Result<&CachedStore<Arc<dyn ObjectStore>>, anyhow::Error> {
+        // NOTE: Avoid a request when there is only one store.
+        if (self.profiles.values()).all(|profile| profile.backup_store.is_none()) {
+            return Ok(&self.backup_store);
+        }
+
+        for store in self.backup_stores() {
+            if store.exists(object_id).await? {
+                return Ok(store);
+            }
+        }
+
+        Ok(&self.backup_store)
+    }
+
+    /// Lists backups in all stores, sorted by name.
+    ///
+    /// NOTE: Backup and integrity checks might share a store, integrity
+    ///   checks are filtered out.
+    pub(crate) async fn list_backup_objects(&self) -> Result<Vec<ObjectMetadata>, anyhow::Error> {
+        // NOTE: Stores might overlap (e.g. a profile only changes the
+        //   compression level), deduplicate backups by name.
+        let mut backups: BTreeMap<String, ObjectMetadata> = BTreeMap::new();
+
+        for store in self.backup_stores() {
+            for metadata in store.list_all().await? {
+                if is_backup(&metadata) {
+                    backups
+                        .entry(metadata.file_name.clone())
+                        .or_insert(metadata);
+                }
+            }
+        }
+
+        Ok(backups.into_values().collect())
+    }
+}
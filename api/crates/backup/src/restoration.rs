@@ -5,25 +5,32 @@
 
 use std::collections::HashMap;
 use std::ffi::{OsStr, OsString};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context as _, anyhow};
 
 #[cfg(debug_assertions)]
 use crate::archiving::log_extracted_entry;
 use crate::archiving::{
-    ArchiveBlueprint, BackupInternalMetadata, ExtractBackupEventHandler, ExtractionReport,
-    archive_reader, read_metadata,
+    ArchiveAttribute, ArchiveBlueprint, BackupInternalMetadata, ExtractBackupEventHandler,
+    ExtractionReport, UnappliedAttribute, apply_xattrs, archive_reader, entry_xattrs,
+    read_metadata,
 };
+use crate::config::SafetySnapshotConfig;
 use crate::decryption::{DecryptionContext, DecryptionReport};
-use crate::stats::{MeteredStream, ReadStats, StreamStats};
-use crate::util::{self, concat_byte_slices, concat_osstr, debug_panic, is_same_device};
+use crate::journal::{RestoreJournal, RestorePhase};
+use crate::restore_report::{RestoreStage, RestoreStageEventDto};
+use crate::safety_snapshot::{PendingSafetySnapshot, UndoingSafetySnapshot};
+use crate::stats::{ByteCounter, ReadStats};
+use crate::util::{
+    self, BlockingTask, concat_byte_slices, concat_osstr, debug_panic, is_same_device,
+};
 use crate::verification::VerificationOutput;
 use crate::{BackupId, RestoreBackupEventHandler};
 
 pub(crate) use self::RestorationContext as Context;
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct RestorationContext {
     /// WARN: This `Vec` MUST always be sorted.
     pub migrations: Vec<ArchiveMigration>,
@@ -54,12 +61,25 @@ impl ArchiveMigration {
 #[derive(Debug)]
 pub struct RestorationOutput {
     /// Metadata stored inside of the backup.
-    #[allow(dead_code)]
     pub(crate) metadata: BackupInternalMetadata,
 
     pub additional_data: Option<(tempfile::TempDir, RestoreRevertGuard)>,
 }
 
+impl RestorationOutput {
+    /// Whether or not the backup contained a blueprint entry (backups
+    /// created using a profile only contain some entries).
+    ///
+    /// NOTE: `archive_path` is the name of the entry in the blueprint
+    ///   the backup was created with (migrations are not applied).
+    pub fn includes(&self, archive_path: &str) -> bool {
+        match self.metadata.paths {
+            Some(ref paths) => paths.iter().any(|name| name == archive_path),
+            None => true,
+        }
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum RestorationError {
     #[error("Could not backup `{path}` before restoration (to prevent data loss)")]
@@ -92,6 +112,9 @@ pub(crate) fn restore(
     context: &RestorationContext,
     decryption_context: &DecryptionContext,
     blueprints: &HashMap<u8, ArchiveBlueprint>,
+    journal_path: Option<&Path>,
+    safety_snapshot_config: Option<&SafetySnapshotConfig>,
+    progress_interval: std::time::Duration,
     event_handler: &mut impl RestoreBackupEventHandler,
 ) -> Result<RestorationOutput, RestorationError> {
     use std::collections::HashSet;
@@ -105,17 +128,38 @@ pub(crate) fn restore(
         .len();
     event_handler.on_restoration_start(backup_id, backup_size);
 
+    // NOTE: Decryption is streamed into extraction, both stages progress
+    //   as the backup file is read.
+    let is_encrypted = backup_id.extensions.contains(&Box::from("pgp"));
+    if is_encrypted {
+        event_handler.on_stage(
+            backup_id,
+            &RestoreStageEventDto::new(RestoreStage::Decrypting, 0.),
+        );
+    }
+    let extraction_counters = ExtractionCounters::default();
+    event_handler.on_stage(
+        backup_id,
+        &extraction_counters.event(RestoreStage::Extracting, 0.),
+    );
+
     let backup_file = std::fs::File::open(backup_path.as_path())
         .context("Could not open backup file")
         .inspect_err(debug_panic)?;
 
-    let backup_reader = MeteredStream::new(
-        backup_file,
-        RawReadStats {
-            backup_id,
-            event_handler,
+    let backup_reader = RawReader {
+        inner: backup_file,
+        backup_id,
+        event_handler,
+        stage_progress: StageProgress {
+            bytes_read: 0,
+            backup_size,
+            is_encrypted,
+            interval: progress_interval,
+            last_emitted_at: std::time::Instant::now(),
+            extraction_counters: &extraction_counters,
         },
-    );
+    };
 
     let mut decryption_report = DecryptionReport::default();
     let mut decryption_stats = ReadStats::default();
@@ -160,14 +204,47 @@ pub(crate) fn restore(
     // NOTE: This is important in case the blueprint specifies e.g. `foo/`
     //   then an “override” for `foo/a` (in this order).
     let path_mappings = {
-        let mut paths = blueprint.paths.clone();
+        let mut paths = match metadata.paths {
+            // NOTE: The backup only contains some blueprint entries (e.g. it
+            //   was created using a profile), don’t replace the others.
+            Some(ref included) => {
+                use std::os::unix::ffi::OsStrExt as _;
+
+                let included = (included.iter())
+                    .map(|name| {
+                        map_path_bytes(name.as_bytes(), migrations.iter(), std::iter::empty()).0
+                    })
+                    .collect::<Vec<_>>();
+
+                (blueprint.paths.iter())
+                    .filter(|(archive_path, _)| {
+                        included.iter().any(|name| name == archive_path.as_bytes())
+                    })
+                    .cloned()
+                    .collect()
+            }
+            None => blueprint.paths.clone(),
+        };
         paths.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
         tracing::debug!(?backup_id, "Path mappings: {:#?}", util::fmt::AsMap(&paths));
         paths
     };
 
     // Backup destination paths to revert in case an error happens.
-    let mut revert_guard = backup_destinations(path_mappings.iter())?;
+    let mut revert_guard = backup_destinations(
+        path_mappings.iter().map(|(_, dst)| dst),
+        backup_id,
+        journal_path,
+    )?;
+
+    // Keep replaced data once the restoration is committed, if enabled.
+    if let Some(config) = safety_snapshot_config {
+        revert_guard.set_safety_snapshot(PendingSafetySnapshot {
+            directory: config.directory.clone(),
+            backup_id: backup_id.to_string(),
+            destinations: (path_mappings.iter()).map(|(_, dst)| dst.clone()).collect(),
+        })?;
+    }
 
     // Store in a boolean if an entry was extracted in the temporary directory.
     // This saves us from having to read the temporary directory to check if
@@ -188,6 +265,7 @@ pub(crate) fn restore(
     );
     for entry in entries {
         let mut entry = entry?;
+        extraction_counters.entries.add(1);
 
         let original_path = entry.path()?.to_path_buf();
 
@@ -200,8 +278,34 @@ pub(crate) fn restore(
             }
         };
 
+        // Hard links point to archive paths, which need to be mapped too.
+        if entry.header().entry_type() == tar::EntryType::Link {
+            let Some(target) = entry.link_name_bytes() else {
+                return Err(RestorationError::ExtractionFailed(
+                    ExtractionError::InvalidBackup(anyhow!(
+                        "Hard link {original_path:?} has no target."
+                    )),
+                ));
+            };
+            let (target_path, target_dst) =
+                map_path_bytes(&target, migrations.iter(), path_mappings.iter());
+            let target_dst = target_dst.as_deref().unwrap_or(tmp_dir.path());
+
+            unpack_hard_link(
+                &safe_join(dst, &entry.path_bytes())?,
+                &safe_join(target_dst, &target_path)?,
+                backup_id,
+                &mut extraction_report,
+            )
+            .with_context(|| format!("Failed extracting hard link {original_path:?}"))?;
+
+            continue;
+        }
+
+        let xattrs = entry_xattrs(&mut entry)?;
+
         // Unpack the archive entry.
-        entry.unpack_in(dst).with_context(|| {
+        let unpacked = entry.unpack_in(dst).with_context(|| {
             format!(
                 "Failed extracting {original_path:?} as {entry_path:?} in {dst:?}",
                 entry_path = entry
@@ -210,8 +314,14 @@ pub(crate) fn restore(
             )
         })?;
 
+        if unpacked && !xattrs.is_empty() {
+            let path = safe_join(dst, &entry.path_bytes())?;
+            apply_xattrs(&path, xattrs, backup_id, &mut extraction_report);
+        }
+
         if let Ok(entry_size) = entry.header().entry_size() {
             extraction_report.on_extraction_progress(backup_id, entry_size);
+            extraction_counters.bytes.add(entry_size as usize);
         }
 
         #[cfg(debug_assertions)]
@@ -219,6 +329,17 @@ pub(crate) fn restore(
     }
     drop(archive);
 
+    if is_encrypted {
+        event_handler.on_stage(
+            backup_id,
+            &RestoreStageEventDto::new(RestoreStage::Decrypting, 1.),
+        );
+    }
+    event_handler.on_stage(
+        backup_id,
+        &extraction_counters.event(RestoreStage::Extracting, 1.),
+    );
+
     // Make sure all expected paths were present.
     {
         let mut missing_paths: HashSet<&PathBuf> = HashSet::new();
@@ -236,6 +357,13 @@ pub(crate) fn restore(
         }
     }
 
+    event_handler.on_stage(
+        backup_id,
+        &RestoreStageEventDto::new(RestoreStage::SwappingDirectories, 0.),
+    );
+
+    revert_guard.set_phase(RestorePhase::NewMovedIn)?;
+
     event_handler.on_decryption_finished(backup_id, decryption_stats, decryption_report);
     event_handler.on_decompression_finished(backup_id, decompression_stats);
     event_handler.on_extraction_finished(backup_id, extraction_report);
@@ -253,6 +381,13 @@ pub(crate) fn restore(
         tracing::debug!(?backup_id, "Restoration finished.");
 
         revert_guard.defuse();
+        // NOTE: Replaced data is deleted (or kept) when the guard is dropped.
+        drop(revert_guard);
+
+        event_handler.on_stage(
+            backup_id,
+            &RestoreStageEventDto::new(RestoreStage::SwappingDirectories, 1.),
+        );
 
         event_handler.on_restoration_finished(backup_id);
 
@@ -267,7 +402,7 @@ pub(crate) fn restore(
 
 // MARK: - Helpers
 
-fn filter_migrations<'a>(
+pub(crate) fn filter_migrations<'a>(
     migrations: impl IntoIterator<Item = &'a ArchiveMigration>,
     from: u8,
     to: u8,
@@ -282,7 +417,7 @@ fn filter_migrations<'a>(
 ///
 /// NOTE: This is O(n²/2), but tends to O(n) as more migrations are added.
 #[must_use]
-fn flatten<'a, 'b, S: AsRef<OsStr> + 'a>(
+pub(crate) fn flatten<'a, 'b, S: AsRef<OsStr> + 'a>(
     migrations: impl Iterator<Item = &'a (S, S)>,
 ) -> Vec<(Box<OsStr>, Box<OsStr>)> {
     use std::os::unix::ffi::OsStrExt as _;
@@ -371,10 +506,45 @@ fn map_path<'a, 'b, R: std::io::Read>(
     migrations: impl Iterator<Item = &'a (Box<OsStr>, Box<OsStr>)>,
     path_mappings: impl Iterator<Item = &'b (OsString, PathBuf)>,
 ) -> Option<PathBuf> {
+    let original_path = entry.path_bytes();
+
+    let (new_path, destination) = map_path_bytes(&original_path, migrations, path_mappings);
+
+    if new_path != *original_path {
+        if tracing::enabled!(tracing::Level::TRACE) {
+            if let Some(ref destination) = destination {
+                tracing::trace!(
+                    "Mapping {:?} as {:?} in {:?}",
+                    String::from_utf8_lossy(&original_path),
+                    String::from_utf8_lossy(&new_path),
+                    destination.display()
+                );
+            } else {
+                tracing::trace!(
+                    "Mapping {:?} to {:?}",
+                    String::from_utf8_lossy(&original_path),
+                    String::from_utf8_lossy(&new_path)
+                );
+            }
+        }
+
+        entry.set_path_bytes(new_path);
+    }
+
+    destination
+}
+
+/// Same as [`map_path`], but for any archive path (e.g. hard link targets).
+///
+/// Returns the new path and its destination.
+#[must_use]
+pub(crate) fn map_path_bytes<'a, 'b>(
+    original_path: &[u8],
+    migrations: impl Iterator<Item = &'a (Box<OsStr>, Box<OsStr>)>,
+    path_mappings: impl Iterator<Item = &'b (OsString, PathBuf)>,
+) -> (Vec<u8>, Option<PathBuf>) {
     use std::os::unix::ffi::OsStrExt as _;
-    use std::path::Path;
 
-    let original_path = entry.path_bytes();
     let mut new_path = original_path.to_vec();
 
     // Apply migrations.
@@ -437,28 +607,62 @@ fn map_path<'a, 'b, R: std::io::Read>(
         }
     }
 
-    if new_path != *original_path {
-        if tracing::enabled!(tracing::Level::TRACE) {
-            if let Some(ref destination) = destination {
-                tracing::trace!(
-                    "Mapping {:?} as {:?} in {:?}",
-                    String::from_utf8_lossy(&original_path),
-                    String::from_utf8_lossy(&new_path),
-                    destination.display()
-                );
-            } else {
-                tracing::trace!(
-                    "Mapping {:?} to {:?}",
-                    String::from_utf8_lossy(&original_path),
-                    String::from_utf8_lossy(&new_path)
-                );
+    (new_path, destination)
+}
+
+/// Joins an archive path to an extraction directory, refusing paths which
+/// would end up outside of it (like [`tar::Entry::unpack_in`] does).
+fn safe_join(dst: &Path, archive_path: &[u8]) -> Result<PathBuf, ExtractionError> {
+    use std::os::unix::ffi::OsStrExt as _;
+    use std::path::Component;
+
+    let archive_path = Path::new(OsStr::from_bytes(archive_path));
+
+    let mut path = dst.to_path_buf();
+    for component in archive_path.components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir | Component::RootDir => {}
+            Component::ParentDir | Component::Prefix(_) => {
+                return Err(ExtractionError::InvalidBackup(anyhow!(
+                    "Archive path {archive_path:?} escapes the extraction directory."
+                )));
             }
         }
+    }
 
-        entry.set_path_bytes(new_path);
+    Ok(path)
+}
+
+/// Creates a hard link to an already extracted file. If that’s not possible
+/// (e.g. paths were mapped to different filesystems), the file is copied.
+fn unpack_hard_link(
+    path: &Path,
+    target: &Path,
+    backup_id: &BackupId,
+    report: &mut ExtractionReport,
+) -> Result<(), anyhow::Error> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
     }
 
-    destination
+    if let Err(error) = std::fs::hard_link(target, path) {
+        std::fs::copy(target, path)
+            .with_context(|| format!("Could not copy {target:?} to {path:?}"))?;
+
+        report.on_attribute_not_applied(
+            backup_id,
+            UnappliedAttribute {
+                path: path.to_path_buf(),
+                attribute: ArchiveAttribute::HardLink {
+                    target: target.to_path_buf(),
+                },
+                error,
+            },
+        );
+    }
+
+    Ok(())
 }
 
 /// A structure that holds the data necessary to revert all changes made
@@ -472,6 +676,19 @@ pub struct RestoreRevertGuard {
     /// This is a list of `(path, backup_path_opt)` pairs.
     paths: Vec<(PathBuf, Option<PathBuf>)>,
 
+    /// On-disk record of [`Self::paths`] and of the restoration progress,
+    /// to recover if the process is killed before this is dropped (see
+    /// [`crate::journal`]).
+    journal: Option<RestoreJournal>,
+
+    /// Where to keep backed up paths once defused, instead of deleting them
+    /// (see [`crate::safety_snapshot`]).
+    safety_snapshot: Option<PendingSafetySnapshot>,
+
+    /// Set when undoing a restoration, to move data back to the safety
+    /// snapshot before reverting (see [`crate::safety_snapshot`]).
+    undo: Option<UndoingSafetySnapshot>,
+
     /// Indicate if everything went successfully or not. If defused (which
     /// should be the case), dropping this will delete backed up paths. If not,
     /// It will delete created paths and recover backups.
@@ -481,34 +698,93 @@ pub struct RestoreRevertGuard {
 impl RestoreRevertGuard {
     pub fn defuse(&mut self) {
         self.is_defused = true;
+
+        // NOTE: From now on, an interrupted restoration is rolled forward.
+        if let Err(err) = self.set_phase(RestorePhase::Committed) {
+            tracing::error!("{err:?}");
+        }
+    }
+
+    /// Drops the guard on Tokio’s blocking thread pool, as keeping a safety
+    /// snapshot can copy data (see [`crate::safety_snapshot`]).
+    ///
+    /// NOTE: Prefer this over dropping the guard in async code.
+    pub async fn release(self) {
+        BlockingTask::<(), ()>::spawn(1, move |_| drop(self))
+            .join()
+            .await
+    }
+
+    pub(crate) fn set_phase(&mut self, phase: RestorePhase) -> Result<(), anyhow::Error> {
+        match self.journal.as_mut() {
+            Some(journal) => journal.set_phase(phase),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn set_safety_snapshot(
+        &mut self,
+        safety_snapshot: PendingSafetySnapshot,
+    ) -> Result<(), anyhow::Error> {
+        if let Some(journal) = self.journal.as_mut() {
+            journal.set_safety_snapshot(safety_snapshot.clone())?;
+        }
+        self.safety_snapshot = Some(safety_snapshot);
+        Ok(())
+    }
+
+    pub(crate) fn set_undo(&mut self, undo: UndoingSafetySnapshot) -> Result<(), anyhow::Error> {
+        if let Some(journal) = self.journal.as_mut() {
+            journal.set_undo(undo.clone())?;
+        }
+        self.undo = Some(undo);
+        Ok(())
     }
 }
 
 impl Drop for RestoreRevertGuard {
     fn drop(&mut self) {
-        if self.is_defused {
-            for (_, backup_path_opt) in self.paths.iter() {
-                if let Some(backup_path) = backup_path_opt {
-                    if backup_path.exists() {
-                        if let Err(err) = util::fs::remove(backup_path) {
-                            tracing::error!("Could not delete path backup {backup_path:?}: {err:?}")
-                        }
-                    }
+        let res = if self.is_defused {
+            roll_forward(
+                &self.paths,
+                self.safety_snapshot.take(),
+                self.undo.as_ref(),
+                self.journal.as_mut(),
+            )
+        } else {
+            roll_back(&self.paths, self.undo.as_ref())
+        };
+
+        match (res, self.journal.take()) {
+            (Ok(()), Some(journal)) => {
+                if let Err(err) = journal.delete() {
+                    tracing::error!("{err:?}");
+                }
+            }
+            (Ok(()), None) => {}
+            (Err(err), journal_opt) => {
+                tracing::error!("{err:?}");
+
+                if journal_opt.is_some() {
+                    // NOTE: Keeping the journal allows retrying on startup.
+                    tracing::warn!("Restoration will be recovered on next startup.");
                 }
             }
-        } else {
-            revert(self.paths.iter());
         }
     }
 }
 
 /// Backup destination paths to revert in case an error happens.
-fn backup_destinations<'a>(
-    path_mappings: impl Iterator<Item = &'a (OsString, PathBuf)>,
+pub(crate) fn backup_destinations<'a>(
+    destinations: impl Iterator<Item = &'a PathBuf>,
+    backup_id: &BackupId,
+    journal_path: Option<&Path>,
 ) -> Result<RestoreRevertGuard, RestorationError> {
-    let mut revert_guard = RestoreRevertGuard::default();
+    // Plan all moves first, so they can be journaled before being made.
+    // NOTE: Also stores the context to give to errors when moving paths.
+    let mut moves: Vec<(PathBuf, Option<PathBuf>, &'static str)> = Vec::new();
 
-    for (_, dst) in path_mappings {
+    for dst in destinations {
         if dst.exists() {
             let Some(parent) = dst.parent() else {
                 continue;
@@ -525,7 +801,7 @@ fn backup_destinations<'a>(
                     source: anyhow::Error::new(err).context("Failed testing device"),
                 })?
             {
-                let dst_bak = util::fs::backup_path(dst)
+                let dst_bak = util::fs::unused_backup_path(dst)
                     // NOTE: If an error happens here, it aborts the backup
                     //   restoration and reverts all changes made until then.
                     .map_err(|err| RestorationError::PathBackupFailed {
@@ -533,7 +809,7 @@ fn backup_destinations<'a>(
                         source: anyhow::Error::new(err).context("Failed backing up dir"),
                     })?;
 
-                (revert_guard.paths).push((PathBuf::clone(dst), Some(dst_bak)));
+                moves.push((PathBuf::clone(dst), Some(dst_bak), "Failed backing up dir"));
             } else {
                 // NOTE: Read all children instead of iterating because we’ll
                 //   be creating more children while iterating (potentially
@@ -547,7 +823,7 @@ fn backup_destinations<'a>(
 
                     let child_path = &child.path();
 
-                    let child_bak = util::fs::backup_path(child_path)
+                    let child_bak = util::fs::unused_backup_path(child_path)
                         // NOTE: If an error happens here, it aborts the backup
                         //   restoration and reverts all changes made until then.
                         .map_err(|err| RestorationError::PathBackupFailed {
@@ -555,7 +831,11 @@ fn backup_destinations<'a>(
                             source: anyhow::Error::new(err).context("Failed backing up child"),
                         })?;
 
-                    (revert_guard.paths).push((PathBuf::clone(child_path), Some(child_bak)));
+                    moves.push((
+                        PathBuf::clone(child_path),
+                        Some(child_bak),
+                        "Failed backing up child",
+                    ));
                 }
             }
         } else {
@@ -563,32 +843,168 @@ fn backup_destinations<'a>(
                 std::fs::create_dir_all(parent).context("Could not create restore destinations")?;
             }
 
-            revert_guard.paths.push((PathBuf::clone(dst), None));
+            moves.push((PathBuf::clone(dst), None, ""));
         }
     }
 
+    let mut revert_guard = RestoreRevertGuard {
+        paths: (moves.iter())
+            .map(|(path, backup_path_opt, _)| (path.clone(), backup_path_opt.clone()))
+            .collect(),
+        journal: None,
+        safety_snapshot: None,
+        undo: None,
+        is_defused: false,
+    };
+
+    if let Some(journal_path) = journal_path {
+        let journal = RestoreJournal::create(journal_path, backup_id, &revert_guard.paths)?;
+        revert_guard.journal = Some(journal);
+    }
+
+    for (path, backup_path_opt, error_context) in moves {
+        if let Some(backup_path) = backup_path_opt {
+            std::fs::rename(&path, &backup_path)
+                // NOTE: If an error happens here, it aborts the backup
+                //   restoration and reverts all changes made until then.
+                .map_err(|err| RestorationError::PathBackupFailed {
+                    path,
+                    source: anyhow::Error::new(err).context(error_context),
+                })?;
+        }
+    }
+
+    revert_guard.set_phase(RestorePhase::OldMovedAside)?;
+
     Ok(revert_guard)
 }
 
+/// Finishes a committed restoration (or undo), also when recovering from the
+/// restoration journal.
+///
+/// NOTE: `paths` are `(path, backup_path_opt)` pairs, like in
+///   [`RestoreRevertGuard`].
+pub(crate) fn roll_forward(
+    paths: &[(PathBuf, Option<PathBuf>)],
+    safety_snapshot: Option<PendingSafetySnapshot>,
+    undo: Option<&UndoingSafetySnapshot>,
+    journal: Option<&mut RestoreJournal>,
+) -> Result<(), anyhow::Error> {
+    if let Some(safety_snapshot) = safety_snapshot {
+        // NOTE: Backed up paths which could not be kept are deleted.
+        match safety_snapshot.keep(paths) {
+            // NOTE: Keeping it again (e.g. if deleting path backups fails
+            //   and recovery is retried) would discard it.
+            Ok(()) => {
+                if let Some(journal) = journal {
+                    journal.clear_safety_snapshot()?;
+                }
+            }
+            Err(err) => tracing::error!("Could not keep a safety snapshot: {err:?}"),
+        }
+    }
+
+    // NOTE: Only the manifest is left, but it would point to missing paths.
+    if let Some(undo) = undo {
+        if let Err(err) = undo.roll_forward() {
+            tracing::error!("{err:?}");
+        }
+    }
+
+    delete_path_backups(paths.iter())
+}
+
+/// Reverts an interrupted restoration (or undo), also when recovering from
+/// the restoration journal.
+///
+/// NOTE: `paths` are `(path, backup_path_opt)` pairs, like in
+///   [`RestoreRevertGuard`].
+#[cold]
+pub(crate) fn roll_back(
+    paths: &[(PathBuf, Option<PathBuf>)],
+    undo: Option<&UndoingSafetySnapshot>,
+) -> Result<(), anyhow::Error> {
+    // NOTE: Reverting would delete data which is still in place, keep
+    //   everything so it can be retried.
+    if let Some(undo) = undo {
+        undo.roll_back()?;
+    }
+
+    revert(paths.iter())
+}
+
+/// Deletes created paths and puts backed up paths back in place.
+///
 /// Note that this is best-effort, meaning we’re already doing error recovery
-/// at this point so we can’t recover from subsequent internal errors.
+/// at this point so we can’t recover from subsequent internal errors. Errors
+/// are logged, and an error is returned if any path could not be reverted.
+///
+/// NOTE: This is idempotent, as it can be interrupted (e.g. process killed)
+///   then called again when recovering from the restoration journal.
 #[cold]
-fn revert<'a>(paths: impl Iterator<Item = &'a (PathBuf, Option<PathBuf>)>) {
+pub(crate) fn revert<'a>(
+    paths: impl Iterator<Item = &'a (PathBuf, Option<PathBuf>)>,
+) -> Result<(), anyhow::Error> {
     use std::fs;
 
+    let mut failures: usize = 0;
+
     for (path, backup_path_opt) in paths {
+        // If the path wasn’t moved aside (e.g. restoration interrupted while
+        // moving paths aside), it wasn’t changed.
+        if let Some(backup_path) = backup_path_opt {
+            if !backup_path.exists() {
+                continue;
+            }
+        }
+
         if path.exists() {
             if let Err(err) = util::fs::remove(path) {
-                tracing::error!("Could not delete created path {path:?}: {err:?}")
+                tracing::error!("Could not delete created path {path:?}: {err:?}");
+                failures += 1;
+                continue;
             }
         }
 
         if let Some(backup_path) = backup_path_opt {
             if let Err(err) = fs::rename(&backup_path, &path) {
-                tracing::error!("Could not recover {path:?}: {err:?}")
+                tracing::error!("Could not recover {path:?}: {err:?}");
+                failures += 1;
             };
         }
     }
+
+    if failures > 0 {
+        Err(anyhow!("Could not revert {failures} path(s)."))
+    } else {
+        Ok(())
+    }
+}
+
+/// Deletes backed up paths, once a restoration succeeded.
+///
+/// NOTE: Like [`revert`], this is best-effort and idempotent.
+pub(crate) fn delete_path_backups<'a>(
+    paths: impl Iterator<Item = &'a (PathBuf, Option<PathBuf>)>,
+) -> Result<(), anyhow::Error> {
+    let mut failures: usize = 0;
+
+    for (_, backup_path_opt) in paths {
+        if let Some(backup_path) = backup_path_opt {
+            if backup_path.exists() {
+                if let Err(err) = util::fs::remove(backup_path) {
+                    tracing::error!("Could not delete path backup {backup_path:?}: {err:?}");
+                    failures += 1;
+                }
+            }
+        }
+    }
+
+    if failures > 0 {
+        Err(anyhow!("Could not delete {failures} path backup(s)."))
+    } else {
+        Ok(())
+    }
 }
 
 // MARK: - Extraction (unarchiving)
@@ -614,15 +1030,93 @@ impl From<std::io::Error> for ExtractionError {
     }
 }
 
-struct RawReadStats<'a, H: RestoreBackupEventHandler> {
+/// Reads the raw backup file, reporting progress and failing as soon as
+/// the restoration is cancelled (which reverts all changes).
+struct RawReader<'a, R, H: RestoreBackupEventHandler> {
+    inner: R,
     backup_id: &'a BackupId,
     event_handler: &'a mut H,
+    stage_progress: StageProgress<'a>,
 }
 
-impl<'a, H: RestoreBackupEventHandler> StreamStats for RawReadStats<'a, H> {
-    fn record_chunk(&mut self, len: usize) {
+impl<'a, R: std::io::Read, H: RestoreBackupEventHandler> std::io::Read for RawReader<'a, R, H> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.event_handler.is_cancelled() {
+            return Err(std::io::Error::other("Restoration cancelled."));
+        }
+
+        let n = self.inner.read(buf)?;
+
         self.event_handler
-            .on_restoration_progress(self.backup_id, len);
+            .on_restoration_progress(self.backup_id, n);
+
+        for event in self.stage_progress.record_read(n) {
+            self.event_handler.on_stage(self.backup_id, &event);
+        }
+
+        Ok(n)
+    }
+}
+
+/// Counters updated while extracting, so [`RawReader`] can report them.
+#[derive(Debug, Default)]
+struct ExtractionCounters {
+    bytes: ByteCounter,
+    entries: ByteCounter,
+}
+
+impl ExtractionCounters {
+    fn event(&self, stage: RestoreStage, progress: f64) -> RestoreStageEventDto {
+        RestoreStageEventDto {
+            extracted_bytes: Some(self.bytes.get()),
+            extracted_entries: Some(self.entries.get()),
+            ..RestoreStageEventDto::new(stage, progress)
+        }
+    }
+}
+
+/// Computes progress of [`RestoreStage::Decrypting`] and
+/// [`RestoreStage::Extracting`], throttled according to the
+/// [`ProgressConfig`](crate::config::ProgressConfig).
+///
+/// NOTE: Progress is estimated from the number of bytes read from the
+///   backup file, as the size of its decompressed contents isn’t known.
+struct StageProgress<'a> {
+    bytes_read: u64,
+    backup_size: u64,
+    is_encrypted: bool,
+    interval: std::time::Duration,
+    last_emitted_at: std::time::Instant,
+    extraction_counters: &'a ExtractionCounters,
+}
+
+impl<'a> StageProgress<'a> {
+    /// Records `len` more bytes read, and returns events to emit (if any).
+    fn record_read(&mut self, len: usize) -> Vec<RestoreStageEventDto> {
+        self.bytes_read = self.bytes_read.saturating_add(len as u64);
+
+        // NOTE: Stages are finished explicitly (see `restore`).
+        if len == 0 || self.bytes_read >= self.backup_size {
+            return Vec::with_capacity(0);
+        }
+
+        let now = std::time::Instant::now();
+        if now.saturating_duration_since(self.last_emitted_at) < self.interval {
+            return Vec::with_capacity(0);
+        }
+        self.last_emitted_at = now;
+
+        let progress = self.bytes_read as f64 / self.backup_size as f64;
+
+        let mut events = Vec::with_capacity(2);
+        if self.is_encrypted {
+            events.push(RestoreStageEventDto::new(
+                RestoreStage::Decrypting,
+                progress,
+            ));
+        }
+        events.push((self.extraction_counters).event(RestoreStage::Extracting, progress));
+        events
     }
 }
 
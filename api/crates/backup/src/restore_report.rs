@@ -0,0 +1,240 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+//! Stages of backup restorations, and report of the last one.
+//!
+//! [`RestoreBackupEventHandler::on_stage`] receives [`RestoreStageEventDto`]s
+//! as a restoration goes, which [`RestoreReportDto`] records. Reports are
+//! kept after restorations (see
+//! [`RestorationConfig::report_path`](crate::config::RestorationConfig::report_path)),
+//! to find out afterwards which stage was slow or failed.
+
+use std::path::Path;
+
+use anyhow::Context as _;
+
+use crate::util::fs::write_durably;
+use crate::util::spawn_blocking;
+use crate::{BackupId, BackupService, RestoreBackupEventHandler};
+
+/// Stages of a backup restoration, in order.
+///
+/// NOTE: Some stages overlap (backups are decrypted while they are
+///   extracted), and some are skipped (e.g. `verifying_signature` if the
+///   backup isn’t signed).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestoreStage {
+    /// Downloading the backup in the cache.
+    Downloading,
+    /// Checking the backup against its BLAKE3 or SHA-256 digest.
+    VerifyingDigest,
+    /// Checking the backup against its OpenPGP signature.
+    VerifyingSignature,
+    /// Decrypting the backup (only if it’s encrypted).
+    Decrypting,
+    /// Extracting the backup archive in place of existing data.
+    Extracting,
+    /// Committing restored data, and deleting (or keeping, see
+    /// [`SafetySnapshotConfig`](crate::config::SafetySnapshotConfig)) the
+    /// data it replaced.
+    SwappingDirectories,
+    /// Restarting what uses restored data.
+    ///
+    /// NOTE: Not emitted by this library, apps emit it themselves.
+    RestartingBackend,
+    /// Running migrations once restored data is in use.
+    ///
+    /// NOTE: Not emitted by this library, apps emit it themselves.
+    RunningMigrations,
+}
+
+#[derive(Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct RestoreStageEventDto {
+    pub stage: RestoreStage,
+
+    /// UTC timestamp at which the event happened.
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: time::OffsetDateTime,
+
+    /// Progress of the stage, from `0.0` (started) to `1.0` (finished).
+    pub progress: f64,
+
+    /// Number of bytes extracted so far (only when extracting).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extracted_bytes: Option<u64>,
+
+    /// Number of archive entries extracted so far (only when extracting).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extracted_entries: Option<u64>,
+}
+
+impl RestoreStageEventDto {
+    pub fn new(stage: RestoreStage, progress: f64) -> Self {
+        Self {
+            stage,
+            timestamp: time::OffsetDateTime::now_utc(),
+            progress,
+            extracted_bytes: None,
+            extracted_entries: None,
+        }
+    }
+
+    #[inline]
+    fn is_boundary(&self) -> bool {
+        self.progress <= 0. || self.progress >= 1.
+    }
+}
+
+/// Report of a backup restoration.
+#[derive(Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct RestoreReportDto {
+    pub backup_id: String,
+
+    /// UTC timestamp at which the restoration started.
+    #[serde(with = "time::serde::rfc3339")]
+    pub started_at: time::OffsetDateTime,
+
+    /// UTC timestamp at which the restoration finished, `None` if it
+    /// didn’t finish (e.g. the report was saved while restoring).
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub finished_at: Option<time::OffsetDateTime>,
+
+    /// Why the restoration failed, `None` if it succeeded.
+    pub error: Option<String>,
+
+    /// NOTE: Only the last progress event of a stage is kept (in addition
+    ///   to the ones starting and finishing it), so reports stay small.
+    pub events: Vec<RestoreStageEventDto>,
+}
+
+impl RestoreReportDto {
+    pub fn new(backup_id: &BackupId) -> Self {
+        Self {
+            backup_id: backup_id.to_string(),
+            started_at: time::OffsetDateTime::now_utc(),
+            finished_at: None,
+            error: None,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, event: &RestoreStageEventDto) {
+        if !event.is_boundary() {
+            // Replace the previous progress event of this stage, if any.
+            let previous = (self.events.iter_mut().rev())
+                .find(|previous| previous.stage == event.stage)
+                .filter(|previous| !previous.is_boundary());
+            if let Some(previous) = previous {
+                *previous = event.clone();
+                return;
+            }
+        }
+
+        self.events.push(event.clone());
+    }
+
+    pub fn finish(&mut self, error: Option<String>) {
+        self.finished_at = Some(time::OffsetDateTime::now_utc());
+        self.error = error;
+    }
+}
+
+impl RestoreBackupEventHandler for RestoreReportDto {
+    fn on_stage(&mut self, _backup_id: &BackupId, event: &RestoreStageEventDto) {
+        self.record(event);
+    }
+}
+
+// MARK: Persistence
+
+pub(crate) async fn save_restore_report(
+    service: &BackupService,
+    report: &RestoreReportDto,
+) -> Result<(), anyhow::Error> {
+    let Some(path) = service.restoration_config.report_path.clone() else {
+        return Ok(());
+    };
+
+    let contents = json::to_vec(report).context("Could not serialize restore report")?;
+
+    spawn_blocking(move || write(&path, &contents)).await
+}
+
+pub(crate) async fn last_restore_report(
+    service: &BackupService,
+) -> Result<Option<RestoreReportDto>, anyhow::Error> {
+    let Some(path) = service.restoration_config.report_path.clone() else {
+        return Ok(None);
+    };
+
+    spawn_blocking(move || read(&path)).await
+}
+
+fn write(path: &Path, contents: &[u8]) -> Result<(), anyhow::Error> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| format!("Could not create {parent:?}"))?;
+    }
+
+    write_durably(path, contents, 0o600)
+        .with_context(|| format!("Could not write restore report {path:?}"))
+}
+
+fn read(path: &Path) -> Result<Option<RestoreReportDto>, anyhow::Error> {
+    let contents = match std::fs::read(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(
+                anyhow::Error::new(err).context(format!("Could not read restore report {path:?}"))
+            );
+        }
+    };
+
+    json::from_slice(&contents).with_context(|| format!("Invalid restore report {path:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_keeps_last_progress_event() {
+        use std::str::FromStr as _;
+
+        let backup_id =
+            BackupId::from_str("prose%2Dbackup-1772432392123_3f9a0c-Backup.tar.zst").unwrap();
+        let mut report = RestoreReportDto::new(&backup_id);
+
+        for progress in [
+            0., 0.25, 0.5, 1.,
+        ] {
+            report.record(&RestoreStageEventDto::new(
+                RestoreStage::Extracting,
+                progress,
+            ));
+        }
+        report.record(&RestoreStageEventDto::new(
+            RestoreStage::SwappingDirectories,
+            0.,
+        ));
+
+        let events = (report.events.iter())
+            .map(|event| (event.stage, event.progress))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            vec![
+                (RestoreStage::Extracting, 0.),
+                (RestoreStage::Extracting, 0.5),
+                (RestoreStage::Extracting, 1.),
+                (RestoreStage::SwappingDirectories, 0.),
+            ]
+        );
+    }
+}
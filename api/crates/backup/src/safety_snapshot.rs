@@ -0,0 +1,384 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+//! Snapshot of the data replaced by the last restoration.
+//!
+//! A restoration moves existing data aside before extracting a backup, then
+//! deletes it once the restoration is committed. If the wrong backup was
+//! restored, today’s data would be gone. When enabled, this data is moved to
+//! a dedicated directory instead, so the restoration can be undone until it
+//! expires.
+//!
+//! See [`SafetySnapshotConfig`](crate::config::SafetySnapshotConfig).
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+
+use crate::BackupService;
+use crate::config::SafetySnapshotConfig;
+use crate::journal::RestorePhase;
+use crate::restoration::{RestorationError, RestoreRevertGuard, backup_destinations};
+use crate::snapshot::move_tree;
+use crate::util::BlockingTask;
+use crate::util::fs::write_durably;
+
+/// Name of the file describing the safety snapshot, in
+/// [`SafetySnapshotConfig::directory`].
+///
+/// NOTE: It’s written last, a directory without it is an incomplete
+///   snapshot.
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Restoration which can be undone.
+#[derive(Debug, Clone)]
+#[derive(serde::Serialize)]
+pub struct SafetySnapshotDto {
+    /// Backup which was restored.
+    pub backup_id: String,
+
+    /// UTC timestamp at which the restoration was committed.
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: time::OffsetDateTime,
+
+    /// UTC timestamp after which the restoration cannot be undone anymore.
+    #[serde(with = "time::serde::rfc3339")]
+    pub expires_at: time::OffsetDateTime,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UndoRestoreError {
+    #[error("No restoration can be undone.")]
+    NoSnapshot,
+
+    #[error("Restoration of backup `{backup_id}` cannot be undone anymore.")]
+    Expired { backup_id: String },
+
+    #[error("Could not move restored data aside")]
+    Restoration(#[from] RestorationError),
+
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Manifest {
+    backup_id: String,
+
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: time::OffsetDateTime,
+
+    /// Destinations of the restoration. All of them are replaced when
+    /// undoing it, even if they didn’t exist before.
+    destinations: Vec<PathBuf>,
+
+    /// `(path, snapshot_path)` pairs, for paths which existed before the
+    /// restoration.
+    paths: Vec<(PathBuf, PathBuf)>,
+}
+
+/// Safety snapshot to take once a restoration is committed (see
+/// [`RestoreRevertGuard`](crate::restoration::RestoreRevertGuard)).
+///
+/// NOTE: Journaled, so it’s taken even if the process is killed after the
+///   restoration is committed (see [`crate::journal`]).
+#[derive(Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct PendingSafetySnapshot {
+    /// See [`SafetySnapshotConfig::directory`].
+    pub directory: PathBuf,
+    pub backup_id: String,
+    pub destinations: Vec<PathBuf>,
+}
+
+/// Safety snapshot being put back in place (see [`undo_last_restore`]).
+///
+/// NOTE: Journaled before anything is moved in place, so an interrupted
+///   undo can be rolled back without losing the snapshot.
+#[derive(Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct UndoingSafetySnapshot {
+    /// See [`SafetySnapshotConfig::directory`].
+    pub directory: PathBuf,
+
+    /// `(path, snapshot_path)` pairs, like in the manifest.
+    pub paths: Vec<(PathBuf, PathBuf)>,
+}
+
+impl PendingSafetySnapshot {
+    /// Moves backed up paths to [`SafetySnapshotConfig::directory`],
+    /// replacing the previous safety snapshot.
+    ///
+    /// NOTE: `paths` are `(path, backup_path_opt)` pairs, like in
+    ///   [`RestoreRevertGuard`](crate::restoration::RestoreRevertGuard).
+    pub(crate) fn keep(self, paths: &[(PathBuf, Option<PathBuf>)]) -> Result<(), anyhow::Error> {
+        let dir = self.directory.clone();
+
+        // NOTE: Only the last restoration can be undone.
+        discard(&dir)?;
+
+        let res = self.keep_in(&dir, paths);
+
+        // NOTE: An incomplete snapshot can’t be used.
+        if res.is_err() {
+            if let Err(err) = discard(&dir) {
+                tracing::error!("{err:?}");
+            }
+        }
+
+        res
+    }
+
+    fn keep_in(
+        self,
+        dir: &Path,
+        paths: &[(PathBuf, Option<PathBuf>)],
+    ) -> Result<(), anyhow::Error> {
+        let start = std::time::Instant::now();
+
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Could not create safety snapshot directory {dir:?}"))?;
+
+        let mut kept: Vec<(PathBuf, PathBuf)> = Vec::with_capacity(paths.len());
+        for (i, (path, backup_path_opt)) in paths.iter().enumerate() {
+            let Some(backup_path) = backup_path_opt else {
+                continue;
+            };
+
+            let snapshot_path = dir.join(i.to_string());
+            move_tree(backup_path, &snapshot_path)?;
+
+            kept.push((path.clone(), snapshot_path));
+        }
+
+        let manifest = Manifest {
+            backup_id: self.backup_id,
+            created_at: time::OffsetDateTime::now_utc(),
+            destinations: self.destinations,
+            paths: kept,
+        };
+        manifest.write(dir)?;
+
+        tracing::info!(
+            "Kept a safety snapshot in {dir:?} in {:?}.",
+            start.elapsed()
+        );
+
+        Ok(())
+    }
+}
+
+impl UndoingSafetySnapshot {
+    /// Moves paths which were put back in place to the safety snapshot, so
+    /// the restoration can still be undone.
+    ///
+    /// NOTE: Must happen before reverting, which deletes restored paths.
+    ///   Like [`revert`](crate::restoration::revert), this is best-effort
+    ///   and idempotent.
+    pub(crate) fn roll_back(&self) -> Result<(), anyhow::Error> {
+        let mut failures: usize = 0;
+
+        for (path, snapshot_path) in self.paths.iter() {
+            // NOTE: If the snapshot path still exists, it wasn’t moved in
+            //   (or not completely), and reverting will delete `path`.
+            if snapshot_path.exists() || !path.exists() {
+                continue;
+            }
+
+            if let Err(err) = move_tree(path, snapshot_path) {
+                tracing::error!("Could not move {path:?} back to the safety snapshot: {err:?}");
+                failures += 1;
+            }
+        }
+
+        if failures > 0 {
+            Err(anyhow::anyhow!(
+                "Could not move {failures} path(s) back to the safety snapshot."
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Deletes what’s left of the safety snapshot, once the undo succeeded.
+    pub(crate) fn roll_forward(&self) -> Result<(), anyhow::Error> {
+        discard(&self.directory)
+    }
+}
+
+impl Manifest {
+    fn read(dir: &Path) -> Result<Option<Self>, anyhow::Error> {
+        let path = dir.join(MANIFEST_FILE_NAME);
+
+        let contents = match std::fs::read(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(anyhow::Error::new(err)
+                    .context(format!("Could not read safety snapshot manifest {path:?}")));
+            }
+        };
+
+        json::from_slice(&contents)
+            .with_context(|| format!("Invalid safety snapshot manifest {path:?}"))
+    }
+
+    fn write(&self, dir: &Path) -> Result<(), anyhow::Error> {
+        let path = dir.join(MANIFEST_FILE_NAME);
+
+        let contents = json::to_vec(self).context("Could not serialize manifest")?;
+
+        write_durably(&path, &contents, 0o600)
+            .with_context(|| format!("Could not write safety snapshot manifest {path:?}"))
+    }
+
+    fn dto(&self, ttl: std::time::Duration) -> SafetySnapshotDto {
+        SafetySnapshotDto {
+            backup_id: self.backup_id.clone(),
+            created_at: self.created_at,
+            expires_at: self.created_at + ttl,
+        }
+    }
+}
+
+/// Deletes the safety snapshot (complete or not).
+fn discard(dir: &Path) -> Result<(), anyhow::Error> {
+    match std::fs::remove_dir_all(dir) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => {
+            Err(anyhow::Error::new(err)
+                .context(format!("Could not delete safety snapshot {dir:?}")))
+        }
+    }
+}
+
+// MARK: Undo
+
+pub(crate) async fn undo_last_restore(
+    service: &BackupService,
+) -> Result<SafetySnapshotDto, UndoRestoreError> {
+    let (dto, mut revert_guard) = undo_last_restore_partial(service).await?;
+
+    revert_guard.defuse();
+    // NOTE: Deletes restored data and what’s left of the safety snapshot.
+    revert_guard.release().await;
+
+    tracing::info!(
+        "Undid restoration of backup `{backup_id}`.",
+        backup_id = dto.backup_id
+    );
+
+    Ok(dto)
+}
+
+pub(crate) async fn undo_last_restore_partial(
+    service: &BackupService,
+) -> Result<(SafetySnapshotDto, RestoreRevertGuard), UndoRestoreError> {
+    let config = service.safety_snapshot_config.clone();
+    let journal_path = service.restoration_config.journal_path.clone();
+
+    // NOTE: Moving files is blocking, and can take time.
+    BlockingTask::<_, ()>::spawn(1, move |_| undo(&config, journal_path.as_deref()))
+        .join()
+        .await
+}
+
+/// Swaps restored data with the data it replaced.
+///
+/// NOTE: Restored data is moved aside like during a restoration (and
+///   journaled), so it’s put back in place if anything fails. The returned
+///   guard must be defused to commit the undo.
+fn undo(
+    config: &SafetySnapshotConfig,
+    journal_path: Option<&Path>,
+) -> Result<(SafetySnapshotDto, RestoreRevertGuard), UndoRestoreError> {
+    use std::str::FromStr as _;
+
+    let dir = config.directory.as_path();
+
+    let Some(manifest) = Manifest::read(dir)? else {
+        return Err(UndoRestoreError::NoSnapshot);
+    };
+
+    let dto = manifest.dto(config.ttl);
+    if dto.expires_at <= time::OffsetDateTime::now_utc() {
+        discard(dir)?;
+        return Err(UndoRestoreError::Expired {
+            backup_id: dto.backup_id,
+        });
+    }
+
+    tracing::info!(
+        "Undoing restoration of backup `{backup_id}`…",
+        backup_id = dto.backup_id
+    );
+
+    let backup_id = crate::BackupId::from_str(&manifest.backup_id)
+        .context("Invalid safety snapshot manifest")?;
+
+    let mut revert_guard =
+        backup_destinations(manifest.destinations.iter(), &backup_id, journal_path)?;
+
+    // NOTE: Reverting deletes moved in paths, they are moved back first so
+    //   the restoration can still be undone (even if the process is killed).
+    revert_guard.set_undo(UndoingSafetySnapshot {
+        directory: dir.to_path_buf(),
+        paths: manifest.paths.clone(),
+    })?;
+
+    for (path, snapshot_path) in manifest.paths.iter() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Could not create {parent:?}"))?;
+        }
+
+        move_tree(snapshot_path, path)?;
+    }
+
+    revert_guard.set_phase(RestorePhase::NewMovedIn)?;
+
+    Ok((dto, revert_guard))
+}
+
+// MARK: Purge
+
+pub(crate) async fn purge_expired_safety_snapshot(
+    service: &BackupService,
+) -> Result<(), anyhow::Error> {
+    let config = service.safety_snapshot_config.clone();
+
+    BlockingTask::<_, ()>::spawn(1, move |_| purge_expired(&config))
+        .join()
+        .await
+}
+
+fn purge_expired(config: &SafetySnapshotConfig) -> Result<(), anyhow::Error> {
+    let dir = config.directory.as_path();
+
+    if !dir.exists() {
+        return Ok(());
+    }
+
+    match Manifest::read(dir)? {
+        Some(manifest) => {
+            let dto = manifest.dto(config.ttl);
+            if dto.expires_at <= time::OffsetDateTime::now_utc() {
+                tracing::info!(
+                    "Deleting expired safety snapshot (backup `{backup_id}`)…",
+                    backup_id = dto.backup_id
+                );
+                discard(dir)
+            } else {
+                Ok(())
+            }
+        }
+        None => {
+            tracing::warn!("Deleting incomplete safety snapshot {dir:?}…");
+            discard(dir)
+        }
+    }
+}
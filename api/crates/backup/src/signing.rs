@@ -15,7 +15,7 @@ pub(crate) use self::SigningContext as Context;
 #[derive(Debug, Default)]
 pub struct SigningContext {
     pub is_signing_mandatory: bool,
-    pub pgp: Option<PgpSigningContext>,
+    pub pgp: Option<std::sync::Arc<PgpSigningContext>>,
 }
 
 pub use self::pgp::PgpSigningContext;
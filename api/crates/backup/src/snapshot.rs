@@ -0,0 +1,747 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+//! Snapshots of backed up data.
+//!
+//! Creating a backup takes time (archiving, compression, encryption, upload),
+//! during which backed up data must not change. Snapshotting data allows
+//! stopping the server only while the snapshot is taken, then creating the
+//! backup from the snapshot while the server is back up.
+//!
+//! See [`SnapshotConfig`](crate::config::SnapshotConfig).
+
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::os::unix::fs::MetadataExt as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context as _, anyhow};
+
+use crate::BackupService;
+use crate::archiving::ArchiveBlueprint;
+use crate::config::{SnapshotConfig, SnapshotMethod};
+use crate::util::BlockingTask;
+
+/// Prefix of the directories snapshots are stored in (see
+/// [`SnapshotConfig::staging_dir`]).
+const STAGING_DIR_PREFIX: &str = "prose-backup-snapshot-";
+
+/// Infix of LVM snapshot names (`<lv>-prose-backup-<timestamp>-<suffix>`).
+const LVM_SNAPSHOT_INFIX: &str = "-prose-backup-";
+
+/// A snapshot of the paths of an [`ArchiveBlueprint`].
+///
+/// Prefer [`Snapshot::discard`] to delete it, as it can take some time.
+/// Dropping it also deletes the snapshot, but in the background if done
+/// in async code.
+pub struct Snapshot {
+    /// Same as the original blueprint, but pointing to the snapshot.
+    blueprint: ArchiveBlueprint,
+
+    /// Actions to run (in reverse order) to delete the snapshot, before
+    /// [`Self::staging_dir`] is deleted.
+    cleanups: Vec<Cleanup>,
+
+    /// NOTE: Only `None` once the snapshot is being deleted.
+    staging_dir: Option<tempfile::TempDir>,
+}
+
+impl Snapshot {
+    /// Blueprint to use when creating a backup from this snapshot.
+    #[inline]
+    pub fn blueprint(&self) -> &ArchiveBlueprint {
+        &self.blueprint
+    }
+
+    /// Deletes the snapshot on Tokio’s blocking thread pool.
+    pub async fn discard(mut self) {
+        let deletion = self.take_deletion();
+
+        BlockingTask::<(), ()>::spawn(1, move |_| deletion.run())
+            .join()
+            .await
+    }
+
+    fn staging_dir(&self) -> &Path {
+        (self.staging_dir.as_ref())
+            .expect("Snapshot staging directory accessed after deletion")
+            .path()
+    }
+
+    /// Takes what’s needed to delete the snapshot, leaving nothing to delete
+    /// when it’s dropped.
+    fn take_deletion(&mut self) -> SnapshotDeletion {
+        SnapshotDeletion {
+            cleanups: std::mem::take(&mut self.cleanups),
+            staging_dir: self.staging_dir.take(),
+        }
+    }
+}
+
+pub(crate) async fn create_snapshot(
+    service: &BackupService,
+    blueprint: &ArchiveBlueprint,
+) -> Result<Snapshot, anyhow::Error> {
+    let blueprint = blueprint.clone();
+    let config = service.snapshot_config.clone();
+
+    // NOTE: Copying files is blocking, and takes time.
+    BlockingTask::<_, ()>::spawn(1, move |_| Snapshot::create(&blueprint, &config))
+        .join()
+        .await
+}
+
+impl Snapshot {
+    fn create(
+        blueprint: &ArchiveBlueprint,
+        config: &SnapshotConfig,
+    ) -> Result<Self, anyhow::Error> {
+        let start = std::time::Instant::now();
+
+        std::fs::create_dir_all(&config.staging_dir).with_context(|| {
+            format!(
+                "Could not create snapshot staging directory {:?}",
+                config.staging_dir
+            )
+        })?;
+        let staging_dir = tempfile::Builder::new()
+            .prefix(STAGING_DIR_PREFIX)
+            .tempdir_in(&config.staging_dir)
+            .context("Could not create snapshot staging directory")?;
+
+        // NOTE: Built incrementally so cleanups run if something fails.
+        let mut snapshot = Self {
+            blueprint: ArchiveBlueprint {
+                version: blueprint.version,
+                paths: Vec::with_capacity(blueprint.paths.len()),
+            },
+            cleanups: Vec::new(),
+            staging_dir: Some(staging_dir),
+        };
+
+        // LVM snapshots, by logical volume (`vg/lv`). Each logical volume
+        // is snapshotted only once.
+        let mut lvm_snapshots: HashMap<String, PathBuf> = HashMap::new();
+
+        for (i, (name, src)) in blueprint.paths.iter().enumerate() {
+            // Let archiving report missing paths.
+            if !matches!(src.try_exists(), Ok(true)) {
+                tracing::debug!("Not snapshotting {src:?}: Path doesn’t exist.");
+                (snapshot.blueprint.paths).push((name.clone(), src.clone()));
+                continue;
+            }
+
+            let dst = snapshot.staging_dir().join(i.to_string());
+
+            let dst = match config.method {
+                SnapshotMethod::Copy => {
+                    copy_tree(src, &dst, CopyMode::Copy, &mut HashMap::new())?;
+                    dst
+                }
+                SnapshotMethod::Hardlink => {
+                    copy_tree(src, &dst, CopyMode::Hardlink, &mut HashMap::new())?;
+                    dst
+                }
+                SnapshotMethod::Btrfs => {
+                    if !is_btrfs_subvolume(src) {
+                        return Err(anyhow!("{src:?} is not a Btrfs subvolume."));
+                    }
+                    snapshot.btrfs_snapshot(src, &dst)?;
+                    dst
+                }
+                SnapshotMethod::Lvm => {
+                    let Some(volume) = LvmVolume::of(src) else {
+                        return Err(anyhow!("{src:?} is not on an LVM logical volume."));
+                    };
+                    snapshot.lvm_snapshot(src, &volume, config, &mut lvm_snapshots)?
+                }
+                SnapshotMethod::Auto => {
+                    snapshot.auto_snapshot(src, dst, config, &mut lvm_snapshots)?
+                }
+            };
+
+            tracing::debug!("Snapshotted {src:?} in {dst:?}.");
+            (snapshot.blueprint.paths).push((name.clone(), dst));
+        }
+
+        tracing::info!("Created snapshot in {:?}.", start.elapsed());
+
+        Ok(snapshot)
+    }
+
+    /// Uses a Btrfs or LVM snapshot if possible, falls back to a copy.
+    fn auto_snapshot(
+        &mut self,
+        src: &Path,
+        dst: PathBuf,
+        config: &SnapshotConfig,
+        lvm_snapshots: &mut HashMap<String, PathBuf>,
+    ) -> Result<PathBuf, anyhow::Error> {
+        if is_btrfs_subvolume(src) {
+            match self.btrfs_snapshot(src, &dst) {
+                Ok(()) => return Ok(dst),
+                Err(err) => tracing::warn!(
+                    "Could not create a Btrfs snapshot of {src:?}, copying instead: {err:#}"
+                ),
+            }
+        } else if let Some(volume) = LvmVolume::of(src) {
+            match self.lvm_snapshot(src, &volume, config, lvm_snapshots) {
+                Ok(dst) => return Ok(dst),
+                Err(err) => tracing::warn!(
+                    "Could not create an LVM snapshot of {src:?}, copying instead: {err:#}"
+                ),
+            }
+        }
+
+        copy_tree(src, &dst, CopyMode::Copy, &mut HashMap::new())?;
+
+        Ok(dst)
+    }
+
+    fn btrfs_snapshot(&mut self, src: &Path, dst: &Path) -> Result<(), anyhow::Error> {
+        run(
+            "btrfs",
+            [
+                OsStr::new("subvolume"),
+                OsStr::new("snapshot"),
+                OsStr::new("-r"),
+                src.as_os_str(),
+                dst.as_os_str(),
+            ],
+        )?;
+
+        (self.cleanups).push(Cleanup::BtrfsSubvolume(dst.to_path_buf()));
+
+        Ok(())
+    }
+
+    /// Returns the path of `src` in the snapshot.
+    fn lvm_snapshot(
+        &mut self,
+        src: &Path,
+        volume: &LvmVolume,
+        config: &SnapshotConfig,
+        lvm_snapshots: &mut HashMap<String, PathBuf>,
+    ) -> Result<PathBuf, anyhow::Error> {
+        // NOTE: Mount points are canonical.
+        let src =
+            std::fs::canonicalize(src).with_context(|| format!("Could not resolve {src:?}"))?;
+        let relative_path = src
+            .strip_prefix(&volume.mount_point)
+            .context("Invalid LVM mount point")?;
+
+        if let Some(mount_point) = lvm_snapshots.get(&volume.full_name()) {
+            return Ok(mount_point.join(relative_path));
+        }
+
+        // NOTE: The random suffix prevents collisions between snapshots
+        //   created in the same second.
+        let snapshot_name = format!(
+            "{lv}{LVM_SNAPSHOT_INFIX}{n}-{suffix}",
+            lv = volume.lv_name,
+            n = crate::util::unix_timestamp(),
+            suffix = crate::BackupId::random_suffix(),
+        );
+        let size = format!("{}b", config.lvm_snapshot_size.as_bytes());
+
+        run(
+            "lvcreate",
+            [
+                "--snapshot",
+                "--size",
+                size.as_str(),
+                "--name",
+                snapshot_name.as_str(),
+                volume.full_name().as_str(),
+            ]
+            .map(OsStr::new),
+        )?;
+
+        let snapshot_volume = format!("{vg}/{snapshot_name}", vg = volume.vg_name);
+        (self.cleanups).push(Cleanup::LvmVolume(snapshot_volume.clone()));
+
+        let mount_point = self
+            .staging_dir()
+            .join(format!("lvm-{}", lvm_snapshots.len()));
+        std::fs::create_dir(&mount_point).context("Could not create LVM snapshot mount point")?;
+
+        // NOTE: The snapshot is an exact copy of a mounted filesystem, which
+        //   some filesystems need to be told about.
+        let options = match volume.fs_type.as_str() {
+            "ext3" | "ext4" => "ro,noload",
+            "xfs" => "ro,nouuid,norecovery",
+            _ => "ro",
+        };
+        run(
+            "mount",
+            [
+                OsStr::new("-o"),
+                OsStr::new(options),
+                OsStr::new(&format!("/dev/{snapshot_volume}")),
+                mount_point.as_os_str(),
+            ],
+        )?;
+        (self.cleanups).push(Cleanup::Mount(mount_point.clone()));
+
+        let src_in_snapshot = mount_point.join(relative_path);
+        lvm_snapshots.insert(volume.full_name(), mount_point);
+
+        Ok(src_in_snapshot)
+    }
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        let deletion = self.take_deletion();
+
+        // Already deleted (see `Snapshot::discard`).
+        if deletion.cleanups.is_empty() && deletion.staging_dir.is_none() {
+            return;
+        }
+
+        // NOTE: Deleting a snapshot runs commands and deletes files, which
+        //   would block the async runtime (e.g. if a request is cancelled).
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                tracing::debug!(
+                    "Snapshot dropped without being discarded. Deleting it in the background…"
+                );
+                drop(runtime.spawn_blocking(move || deletion.run()));
+            }
+            Err(_) => deletion.run(),
+        }
+    }
+}
+
+/// Everything needed to delete a [`Snapshot`].
+struct SnapshotDeletion {
+    cleanups: Vec<Cleanup>,
+    staging_dir: Option<tempfile::TempDir>,
+}
+
+impl SnapshotDeletion {
+    /// NOTE: Blocking.
+    fn run(self) {
+        for cleanup in self.cleanups.into_iter().rev() {
+            if let Err(err) = cleanup.run() {
+                tracing::error!("Failed cleaning up snapshot: {err:#}");
+            }
+        }
+
+        if let Some(staging_dir) = self.staging_dir {
+            let path = staging_dir.path().to_path_buf();
+            tracing::debug!("Deleting snapshot staging directory {path:?}…");
+            if let Err(err) = staging_dir.close() {
+                tracing::error!("Could not delete snapshot staging directory {path:?}: {err}");
+            }
+        }
+    }
+}
+
+enum Cleanup {
+    BtrfsSubvolume(PathBuf),
+    /// `vg/lv`.
+    LvmVolume(String),
+    Mount(PathBuf),
+}
+
+impl Cleanup {
+    fn run(self) -> Result<(), anyhow::Error> {
+        match self {
+            Self::BtrfsSubvolume(path) => run(
+                "btrfs",
+                [
+                    OsStr::new("subvolume"),
+                    OsStr::new("delete"),
+                    path.as_os_str(),
+                ],
+            ),
+            Self::LvmVolume(volume) => run(
+                "lvremove",
+                [
+                    "--yes",
+                    volume.as_str(),
+                ]
+                .map(OsStr::new),
+            ),
+            Self::Mount(path) => run("umount", [path.as_os_str()]),
+        }
+        .map(drop)
+    }
+}
+
+// MARK: Purge
+
+/// Deletes snapshots which were never deleted (e.g. if the process was
+/// killed while creating a backup): staging directories, LVM snapshots
+/// and their mount points, Btrfs snapshots.
+///
+/// WARN: Must not run while a snapshot is in use (i.e. only on startup).
+pub(crate) async fn purge_stale_snapshots(service: &BackupService) -> Result<(), anyhow::Error> {
+    let config = service.snapshot_config.clone();
+
+    BlockingTask::<_, ()>::spawn(1, move |_| purge_stale(&config))
+        .join()
+        .await
+}
+
+fn purge_stale(config: &SnapshotConfig) -> Result<(), anyhow::Error> {
+    let stale_dirs = stale_staging_dirs(&config.staging_dir)?;
+
+    // Unmount LVM snapshots (deepest mount points first).
+    let mut mount_points: Vec<PathBuf> = mount_points()
+        .unwrap_or_else(|err| {
+            tracing::warn!("Could not list mount points: {err:#}");
+            Vec::new()
+        })
+        .into_iter()
+        .filter(|path| stale_dirs.iter().any(|dir| path.starts_with(dir)))
+        .collect();
+    mount_points.sort_unstable_by_key(|path| std::cmp::Reverse(path.components().count()));
+    for mount_point in mount_points {
+        tracing::info!("Unmounting stale snapshot {mount_point:?}…");
+        if let Err(err) = Cleanup::Mount(mount_point).run() {
+            tracing::error!("Failed cleaning up stale snapshot: {err:#}");
+        }
+    }
+
+    // Delete LVM snapshots.
+    for volume in stale_lvm_snapshots() {
+        tracing::info!("Deleting stale LVM snapshot `{volume}`…");
+        if let Err(err) = Cleanup::LvmVolume(volume).run() {
+            tracing::error!("Failed cleaning up stale snapshot: {err:#}");
+        }
+    }
+
+    // Delete Btrfs snapshots, then staging directories.
+    for dir in stale_dirs {
+        let entries = std::fs::read_dir(&dir).with_context(|| format!("Could not read {dir:?}"))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if is_btrfs_subvolume(&path) {
+                tracing::info!("Deleting stale Btrfs snapshot {path:?}…");
+                if let Err(err) = Cleanup::BtrfsSubvolume(path).run() {
+                    tracing::error!("Failed cleaning up stale snapshot: {err:#}");
+                }
+            }
+        }
+
+        tracing::info!("Deleting stale snapshot staging directory {dir:?}…");
+        if let Err(err) = std::fs::remove_dir_all(&dir) {
+            tracing::error!("Could not delete stale snapshot staging directory {dir:?}: {err}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Snapshot staging directories in `staging_dir` (canonical).
+fn stale_staging_dirs(staging_dir: &Path) -> Result<Vec<PathBuf>, anyhow::Error> {
+    // NOTE: Mount points are canonical.
+    let staging_dir = match std::fs::canonicalize(staging_dir) {
+        Ok(path) => path,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(anyhow::Error::new(err).context(format!(
+                "Could not resolve snapshot staging directory {staging_dir:?}"
+            )));
+        }
+    };
+
+    let mut dirs = Vec::new();
+    let entries = std::fs::read_dir(&staging_dir)
+        .with_context(|| format!("Could not read {staging_dir:?}"))?;
+    for entry in entries {
+        let entry = entry.with_context(|| format!("Could not read {staging_dir:?}"))?;
+        let is_snapshot =
+            (entry.file_name().to_str()).is_some_and(|name| name.starts_with(STAGING_DIR_PREFIX));
+        if is_snapshot && entry.file_type().is_ok_and(|t| t.is_dir()) {
+            dirs.push(entry.path());
+        }
+    }
+
+    Ok(dirs)
+}
+
+/// Mount points of all mounted filesystems.
+fn mount_points() -> Result<Vec<PathBuf>, anyhow::Error> {
+    use std::os::unix::ffi::OsStringExt as _;
+
+    let mounts =
+        std::fs::read_to_string("/proc/self/mounts").context("Could not read mount points")?;
+
+    // NOTE: Spaces, tabs, newlines and backslashes are escaped in octal
+    //   (e.g. `\040` for a space).
+    let unescape = |field: &str| -> PathBuf {
+        let bytes = field.as_bytes();
+        let mut res = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            let escaped = (bytes.get(i + 1..i + 4))
+                .and_then(|digits| std::str::from_utf8(digits).ok())
+                .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+            match (bytes[i], escaped) {
+                (b'\\', Some(byte)) => {
+                    res.push(byte);
+                    i += 4;
+                }
+                (byte, _) => {
+                    res.push(byte);
+                    i += 1;
+                }
+            }
+        }
+        PathBuf::from(std::ffi::OsString::from_vec(res))
+    };
+
+    Ok((mounts.lines())
+        .filter_map(|line| line.split(' ').nth(1))
+        .map(unescape)
+        .collect())
+}
+
+/// LVM snapshots created by [`Snapshot::lvm_snapshot`] (`vg/lv`).
+fn stale_lvm_snapshots() -> Vec<String> {
+    let lvs = match run(
+        "lvs",
+        [
+            "--noheadings",
+            "--options",
+            "vg_name,lv_name,origin",
+        ]
+        .map(OsStr::new),
+    ) {
+        Ok(lvs) => lvs,
+        // NOTE: LVM isn’t installed in most environments.
+        Err(err) => {
+            tracing::debug!("Not looking for stale LVM snapshots: {err:#}");
+            return Vec::new();
+        }
+    };
+
+    (lvs.lines())
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let (vg_name, lv_name, origin) = (fields.next()?, fields.next()?, fields.next()?);
+            let is_ours = lv_name.starts_with(&format!("{origin}{LVM_SNAPSHOT_INFIX}"));
+            is_ours.then(|| format!("{vg_name}/{lv_name}"))
+        })
+        .collect()
+}
+
+// MARK: Copy
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CopyMode {
+    Copy,
+    Hardlink,
+}
+
+/// Recursively copies `src` to `dst`, preserving symbolic links, hard links,
+/// permissions, modification times, extended attributes and ownership (if
+/// permitted).
+///
+/// In [`CopyMode::Hardlink`] mode, regular files are hard linked instead of
+/// copied (directories are still created).
+fn copy_tree(
+    src: &Path,
+    dst: &Path,
+    mode: CopyMode,
+    hard_links: &mut HashMap<(u64, u64), PathBuf>,
+) -> Result<(), anyhow::Error> {
+    use std::fs;
+
+    let metadata = fs::symlink_metadata(src).with_context(|| format!("Could not stat {src:?}"))?;
+    let file_type = metadata.file_type();
+
+    if file_type.is_dir() {
+        fs::create_dir(dst).with_context(|| format!("Could not create {dst:?}"))?;
+
+        for entry in fs::read_dir(src).with_context(|| format!("Could not read {src:?}"))? {
+            let entry = entry.with_context(|| format!("Could not read {src:?}"))?;
+            copy_tree(
+                &entry.path(),
+                &dst.join(entry.file_name()),
+                mode,
+                hard_links,
+            )?;
+        }
+    } else if file_type.is_symlink() {
+        let target = fs::read_link(src).with_context(|| format!("Could not read {src:?}"))?;
+        std::os::unix::fs::symlink(target, dst)
+            .with_context(|| format!("Could not create {dst:?}"))?;
+
+        // NOTE: Permissions of symbolic links are irrelevant.
+        return Ok(());
+    } else if file_type.is_file() {
+        if mode == CopyMode::Hardlink {
+            return fs::hard_link(src, dst)
+                .with_context(|| format!("Could not hard link {src:?} to {dst:?}"));
+        }
+
+        // Preserve hard links inside the snapshot.
+        if metadata.nlink() > 1 {
+            let key = (metadata.dev(), metadata.ino());
+            if let Some(first) = hard_links.get(&key) {
+                return fs::hard_link(first, dst)
+                    .with_context(|| format!("Could not hard link {first:?} to {dst:?}"));
+            }
+            hard_links.insert(key, dst.to_path_buf());
+        }
+
+        fs::copy(src, dst).with_context(|| format!("Could not copy {src:?} to {dst:?}"))?;
+    } else {
+        // NOTE: Sockets, FIFOs and devices hold no data.
+        tracing::warn!("Not snapshotting {src:?}: Unsupported file type.");
+        return Ok(());
+    }
+
+    copy_metadata(src, dst, &metadata).with_context(|| format!("Could not copy {src:?} metadata"))
+}
+
+fn copy_metadata(src: &Path, dst: &Path, metadata: &std::fs::Metadata) -> std::io::Result<()> {
+    use std::fs;
+
+    if let Ok(names) = xattr::list(src) {
+        for name in names {
+            if let Some(value) = xattr::get(src, &name)? {
+                if let Err(err) = xattr::set(dst, &name, &value) {
+                    tracing::debug!("Could not copy xattr {name:?} of {src:?}: {err}");
+                }
+            }
+        }
+    }
+
+    // NOTE: Only permitted if running as root.
+    if let Err(err) = std::os::unix::fs::chown(dst, Some(metadata.uid()), Some(metadata.gid())) {
+        tracing::trace!("Could not change owner of {dst:?}: {err}");
+    }
+
+    // NOTE: Set times before permissions, in case the file is read-only.
+    fs::File::open(dst)?.set_times(
+        fs::FileTimes::new()
+            .set_accessed(metadata.accessed()?)
+            .set_modified(metadata.modified()?),
+    )?;
+
+    // NOTE: Set permissions last as changing owners clears setuid bits.
+    fs::set_permissions(dst, metadata.permissions())
+}
+
+/// Moves `src` to `dst`, copying it if they are on different filesystems.
+pub(crate) fn move_tree(src: &Path, dst: &Path) -> Result<(), anyhow::Error> {
+    match std::fs::rename(src, dst) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::CrossesDevices => {
+            tracing::debug!("Copying {src:?} to {dst:?} (different filesystems)…");
+            copy_tree(src, dst, CopyMode::Copy, &mut HashMap::new())?;
+            crate::util::fs::remove(src).with_context(|| format!("Could not delete {src:?}"))
+        }
+        Err(err) => {
+            Err(anyhow::Error::new(err).context(format!("Could not move {src:?} to {dst:?}")))
+        }
+    }
+}
+
+// MARK: Detection
+
+/// Whether or not `path` is the root of a Btrfs subvolume (which can be
+/// snapshotted).
+fn is_btrfs_subvolume(path: &Path) -> bool {
+    // NOTE: Btrfs subvolume roots always have inode number 256. Checking it
+    //   first avoids spawning a process in most cases.
+    let is_candidate = matches!(std::fs::metadata(path), Ok(m) if m.is_dir() && m.ino() == 256);
+
+    is_candidate
+        && run(
+            "btrfs",
+            [
+                OsStr::new("subvolume"),
+                OsStr::new("show"),
+                path.as_os_str(),
+            ],
+        )
+        .is_ok()
+}
+
+struct LvmVolume {
+    vg_name: String,
+    lv_name: String,
+    fs_type: String,
+    mount_point: PathBuf,
+}
+
+impl LvmVolume {
+    /// Finds the LVM logical volume `path` is stored on, if any.
+    fn of(path: &Path) -> Option<Self> {
+        // NOTE: Mount points are canonical.
+        let path = std::fs::canonicalize(path).ok()?;
+
+        let findmnt = run(
+            "findmnt",
+            [
+                OsStr::new("--noheadings"),
+                OsStr::new("--raw"),
+                OsStr::new("--output"),
+                OsStr::new("SOURCE,TARGET,FSTYPE"),
+                OsStr::new("--target"),
+                path.as_os_str(),
+            ],
+        )
+        .ok()?;
+        let mut fields = findmnt.split_whitespace();
+        let (device, mount_point, fs_type) = (fields.next()?, fields.next()?, fields.next()?);
+
+        let lvs = run(
+            "lvs",
+            [
+                "--noheadings",
+                "--options",
+                "vg_name,lv_name",
+                device,
+            ]
+            .map(OsStr::new),
+        )
+        .ok()?;
+        let mut fields = lvs.split_whitespace();
+        let (vg_name, lv_name) = (fields.next()?, fields.next()?);
+
+        Some(Self {
+            vg_name: vg_name.to_owned(),
+            lv_name: lv_name.to_owned(),
+            fs_type: fs_type.to_owned(),
+            mount_point: PathBuf::from(mount_point),
+        })
+    }
+
+    /// `vg/lv`.
+    fn full_name(&self) -> String {
+        format!("{}/{}", self.vg_name, self.lv_name)
+    }
+}
+
+// MARK: Helpers
+
+/// Runs a command, returning its standard output.
+fn run<I, S>(program: &str, args: I) -> Result<String, anyhow::Error>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("Could not run `{program}`"))?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "`{program}` failed ({status}): {stderr}",
+            status = output.status,
+            stderr = String::from_utf8_lossy(&output.stderr).trim(),
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -7,7 +7,10 @@
 //!
 //! It measures things like the number of bytes read and the time spent reading.
 
+use std::collections::VecDeque;
 use std::io::{self, Read, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
 use composable_stream::ComposableStreamBuilder;
 
@@ -219,6 +222,67 @@ pub(crate) fn meter_writes<W, Err, Stats: WriterStats>(
     }
 }
 
+// MARK: Progress
+
+/// A byte counter which can be shared between stages of a stream (e.g. to
+/// read how many bytes were compressed from the archiving stage).
+#[derive(Debug, Default)]
+pub struct ByteCounter(AtomicU64);
+
+impl ByteCounter {
+    #[inline]
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub fn add(&self, len: usize) {
+        self.0.fetch_add(len as u64, Ordering::Relaxed);
+    }
+}
+
+/// Throughput (in bytes per second) over a sliding time window.
+#[derive(Debug)]
+pub struct RollingThroughput {
+    window: Duration,
+
+    /// Total number of bytes processed at a given instant (oldest first).
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl RollingThroughput {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Records the total number of bytes processed so far, and returns the
+    /// throughput (in bytes per second) over the window.
+    pub fn record(&mut self, now: Instant, total_bytes: u64) -> u64 {
+        self.samples.push_back((now, total_bytes));
+
+        // Forget samples out of the window, but keep the most recent one
+        // so the whole window is covered.
+        while (self.samples.get(1))
+            .is_some_and(|(instant, _)| now.saturating_duration_since(*instant) >= self.window)
+        {
+            self.samples.pop_front();
+        }
+
+        // NOTE: `samples` cannot be empty, we just pushed to it.
+        let (since, bytes_since) = self.samples[0];
+        let elapsed = now.saturating_duration_since(since).as_secs_f64();
+
+        if elapsed > 0. {
+            (total_bytes.saturating_sub(bytes_since) as f64 / elapsed) as u64
+        } else {
+            0
+        }
+    }
+}
+
 // MARK: - No-op helpers
 
 pub struct NoopStats;
@@ -18,6 +18,7 @@ use crate::{config::CachingConfig, util::debug_panic_or_log_error};
 
 use super::prelude::*;
 
+#[derive(Clone)]
 pub struct CachedStore<S> {
     store: S,
     cache: Arc<RwLock<StoreCache>>,
@@ -193,6 +194,65 @@ where
         })
     }
 
+    /// Downloads an object in the cache (if not already cached), without
+    /// blocking the async runtime.
+    ///
+    /// NOTE: If the returned future is dropped before completion, the
+    ///   partially downloaded file is deleted.
+    pub async fn download(&self, key: &str) -> Result<Arc<PathGuard>, ReadObjectError> {
+        use tokio::io::AsyncWriteExt as _;
+
+        if let Some(path) = self.cached_path(key).await {
+            tracing::debug!(
+                "Object `{key}` was cached in `{path}`.",
+                path = path.display()
+            );
+            return Ok(path);
+        }
+
+        let object_path = self.cache_dir.join(key);
+
+        tracing::debug!(
+            "Will cache object `{key}` in `{path}`.",
+            path = object_path.display()
+        );
+        // NOTE: Same options as in `cached_reader` (see comments there).
+        let mut cache_file = tokio::fs::OpenOptions::new()
+            .create_new(true)
+            .write(true)
+            .mode(0o600)
+            .open(&object_path)
+            .await
+            .context("Failed opening a file path to cache the object at")
+            .map_err(ReadObjectError::Other)?;
+        // NOTE: Create the guard right after the file, so it gets deleted
+        //   if anything fails (or if the download is cancelled).
+        let path = Arc::new(PathGuard::new(object_path));
+
+        let mut reader = self.store.async_reader(key).await?;
+
+        let size = tokio::io::copy(&mut reader, &mut cache_file)
+            .await
+            .context("Failed downloading object")
+            .map_err(ReadObjectError::Other)?;
+        (cache_file.flush().await)
+            .context("Failed flushing cache file")
+            .map_err(ReadObjectError::Other)?;
+        drop(cache_file);
+
+        self.cache(key.to_owned(), Arc::clone(&path), size).await;
+
+        Ok(path)
+    }
+
+    async fn cached_path(&self, key: &str) -> Option<Arc<PathGuard>> {
+        let cache = self.cache.read().await;
+
+        (cache.entries.iter())
+            .find(|entry| entry.key == key)
+            .map(|entry| Arc::clone(&entry.path))
+    }
+
     /// Persists the cache entry.
     pub async fn persist_cache<R>(&self, reader: CachedReader<R>) -> Arc<PathGuard> {
         match reader {
@@ -274,6 +334,24 @@ where
         self.store.writer(key).await
     }
 
+    #[inline]
+    async fn writer_if_absent(&self, key: &str) -> Result<Box<DynObjectWriter>, anyhow::Error> {
+        self.store.writer_if_absent(key).await
+    }
+
+    #[inline]
+    async fn async_writer(&self, key: &str) -> Result<Box<DynAsyncObjectWriter>, anyhow::Error> {
+        self.store.async_writer(key).await
+    }
+
+    #[inline]
+    async fn async_writer_if_absent(
+        &self,
+        key: &str,
+    ) -> Result<Box<DynAsyncObjectWriter>, anyhow::Error> {
+        self.store.async_writer_if_absent(key).await
+    }
+
     #[inline]
     async fn reader(&self, key: &str) -> Result<Box<DynObjectReader>, ReadObjectError> {
         match self.cached_reader(key).await {
@@ -282,6 +360,56 @@ where
         }
     }
 
+    /// NOTE: Reads from the cache if possible, but doesn’t populate it
+    ///   (use [`CachedStore::download`] for that).
+    #[inline]
+    async fn async_reader(&self, key: &str) -> Result<Box<DynAsyncObjectReader>, ReadObjectError> {
+        if let Some(path) = self.cached_path(key).await {
+            match tokio::fs::File::open(path.as_ref()).await {
+                Ok(file) => return Ok(Box::new(AsyncCachedReader { file, _path: path })),
+                Err(err) => debug_panic_or_log_error!(
+                    "Failed opening `{path}`: {err:?}",
+                    path = path.display()
+                ),
+            }
+        }
+
+        self.store.async_reader(key).await
+    }
+
+    /// NOTE: Like [`CachedStore::async_reader`], reads from the cache if
+    ///   possible but doesn’t populate it.
+    #[inline]
+    async fn async_range_reader(
+        &self,
+        key: &str,
+        range: std::ops::Range<u64>,
+    ) -> Result<Box<DynAsyncObjectReader>, ReadObjectError> {
+        use tokio::io::{AsyncReadExt as _, AsyncSeekExt as _};
+
+        if let Some(path) = self.cached_path(key).await {
+            let file = match tokio::fs::File::open(path.as_ref()).await {
+                Ok(mut file) => file
+                    .seek(std::io::SeekFrom::Start(range.start))
+                    .await
+                    .map(|_| file),
+                Err(err) => Err(err),
+            };
+            match file {
+                Ok(file) => {
+                    let reader = AsyncCachedReader { file, _path: path };
+                    return Ok(Box::new(reader.take(range.end.saturating_sub(range.start))));
+                }
+                Err(err) => debug_panic_or_log_error!(
+                    "Failed reading `{path}`: {err:?}",
+                    path = path.display()
+                ),
+            }
+        }
+
+        self.store.async_range_reader(key, range).await
+    }
+
     #[inline]
     async fn exists(&self, key: &str) -> Result<bool, anyhow::Error> {
         self.store.exists(key).await
@@ -367,6 +495,24 @@ where
     }
 }
 
+/// A cached file, opened for async reading.
+struct AsyncCachedReader {
+    file: tokio::fs::File,
+    // NOTE: See `CachedReader::Cached::path`.
+    _path: Arc<PathGuard>,
+}
+
+impl tokio::io::AsyncRead for AsyncCachedReader {
+    #[inline]
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::pin::Pin::new(&mut self.get_mut().file).poll_read(cx, buf)
+    }
+}
+
 impl<R> std::fmt::Debug for CachedReader<R> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
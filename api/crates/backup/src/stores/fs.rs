@@ -65,6 +65,89 @@ impl FsStore {
     }
 }
 
+impl FsStore {
+    fn open_options(&self, file_name: &str, overwrite: bool) -> (PathBuf, fs::OpenOptions) {
+        assert!(
+            !file_name.starts_with("/"),
+            "File name should not start with a `/`"
+        );
+        // Safety check: Do not allow unsafe permission bits.
+        assert!(self.mode & 0o117 == 0, "{:#o} & 0o117 != 0", self.mode);
+
+        let path = self.directory.join(file_name);
+
+        tracing::trace!("Opening `{}` (write)…", path.display());
+
+        // NOTE: `create_new` is atomic (`O_CREAT | O_EXCL`), which means two
+        //   concurrent writers can never both open the same file.
+        let mut options = File::options();
+        (options.create(true))
+            .create_new(!overwrite)
+            .write(true)
+            .truncate(overwrite)
+            .mode(self.mode);
+
+        (path, options)
+    }
+
+    fn writer_(
+        &self,
+        file_name: &str,
+        overwrite: bool,
+    ) -> Result<Box<DynObjectWriter>, anyhow::Error> {
+        let (path, options) = self.open_options(file_name, overwrite);
+
+        match options.open(path) {
+            Ok(writer) => Ok(Box::new(writer)),
+            Err(err) => Err(open_write_error(err, file_name)),
+        }
+    }
+
+    async fn async_writer_(
+        &self,
+        file_name: &str,
+        overwrite: bool,
+    ) -> Result<Box<DynAsyncObjectWriter>, anyhow::Error> {
+        let (path, options) = self.open_options(file_name, overwrite);
+
+        match tokio::fs::OpenOptions::from(options).open(path).await {
+            Ok(writer) => Ok(Box::new(writer)),
+            Err(err) => Err(open_write_error(err, file_name)),
+        }
+    }
+
+    fn reader_path(&self, file_name: &str) -> PathBuf {
+        assert!(
+            !file_name.starts_with("/"),
+            "File name should not start with a `/`"
+        );
+
+        let path = self.directory.join(file_name);
+
+        tracing::trace!("Opening `{}` (read)…", path.display());
+
+        path
+    }
+}
+
+fn open_write_error(err: std::io::Error, file_name: &str) -> anyhow::Error {
+    if err.kind() == std::io::ErrorKind::AlreadyExists {
+        anyhow::Error::new(ObjectAlreadyExists {
+            key: file_name.to_owned(),
+        })
+    } else {
+        anyhow::Error::from(err).context("Failed opening file (write)")
+    }
+}
+
+fn open_read_error(err: std::io::Error) -> ReadObjectError {
+    if err.kind() == std::io::ErrorKind::NotFound {
+        ReadObjectError::ObjectNotFound(anyhow::Error::from(err))
+    } else {
+        ReadObjectError::Other(anyhow::Error::from(err).context("Failed opening file (read)"))
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ModeResult {
     Ok,
@@ -92,50 +175,68 @@ fn validate_mode(mode: &u32, min_permissions: &u32) -> ModeResult {
 #[async_trait::async_trait]
 impl ObjectStore for FsStore {
     async fn writer(&self, file_name: &str) -> Result<Box<DynObjectWriter>, anyhow::Error> {
-        assert!(
-            !file_name.starts_with("/"),
-            "File name should not start with a `/`"
-        );
-        // Safety check: Do not allow unsafe permission bits.
-        assert!(self.mode & 0o117 == 0, "{:#o} & 0o117 != 0", self.mode);
-
-        let path = self.directory.join(file_name);
+        self.writer_(file_name, self.overwrite)
+    }
 
-        tracing::trace!("Opening `{}` (write)…", path.display());
+    async fn writer_if_absent(
+        &self,
+        file_name: &str,
+    ) -> Result<Box<DynObjectWriter>, anyhow::Error> {
+        self.writer_(file_name, false)
+    }
 
-        let writer = File::options()
-            .create(true)
-            .create_new(!self.overwrite)
-            .write(true)
-            .truncate(self.overwrite)
-            .mode(self.mode)
-            .open(path)
-            .context("Failed opening file (write)")?;
+    async fn async_writer(
+        &self,
+        file_name: &str,
+    ) -> Result<Box<DynAsyncObjectWriter>, anyhow::Error> {
+        self.async_writer_(file_name, self.overwrite).await
+    }
 
-        Ok(Box::new(writer))
+    async fn async_writer_if_absent(
+        &self,
+        file_name: &str,
+    ) -> Result<Box<DynAsyncObjectWriter>, anyhow::Error> {
+        self.async_writer_(file_name, false).await
     }
 
     async fn reader(&self, file_name: &str) -> Result<Box<DynObjectReader>, ReadObjectError> {
-        assert!(
-            !file_name.starts_with("/"),
-            "File name should not start with a `/`"
-        );
-
-        let path = self.directory.join(file_name);
-
-        tracing::trace!("Opening `{}` (read)…", path.display());
+        match File::open(self.reader_path(file_name)) {
+            Ok(reader) => Ok(Box::new(reader)),
+            Err(err) => Err(open_read_error(err)),
+        }
+    }
 
-        match File::open(path) {
+    async fn async_reader(
+        &self,
+        file_name: &str,
+    ) -> Result<Box<DynAsyncObjectReader>, ReadObjectError> {
+        match tokio::fs::File::open(self.reader_path(file_name)).await {
             Ok(reader) => Ok(Box::new(reader)),
-            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
-                Err(ReadObjectError::ObjectNotFound(anyhow::Error::from(err)))
-            }
-            Err(err) => Err(ReadObjectError::Other(
-                anyhow::Error::from(err).context("Failed opening file (read)"),
-            )),
+            Err(err) => Err(open_read_error(err)),
         }
     }
 
+    async fn async_range_reader(
+        &self,
+        file_name: &str,
+        range: std::ops::Range<u64>,
+    ) -> Result<Box<DynAsyncObjectReader>, ReadObjectError> {
+        use tokio::io::{AsyncReadExt as _, AsyncSeekExt as _};
+
+        let mut file = match tokio::fs::File::open(self.reader_path(file_name)).await {
+            Ok(file) => file,
+            Err(err) => return Err(open_read_error(err)),
+        };
+
+        file.seek(std::io::SeekFrom::Start(range.start))
+            .await
+            .map_err(|err| {
+                ReadObjectError::Other(anyhow::Error::new(err).context("Failed seeking"))
+            })?;
+
+        Ok(Box::new(file.take(range.end.saturating_sub(range.start))))
+    }
+
     async fn exists(&self, key: &str) -> Result<bool, anyhow::Error> {
         Ok(self.directory.join(key).exists())
     }
@@ -280,6 +381,19 @@ impl super::Finalizable for File {
 
 impl super::ObjectWriter for File {}
 
+#[async_trait::async_trait]
+impl super::AsyncFinalizable for tokio::fs::File {
+    async fn finalize(mut self: Box<Self>) -> Result<(), anyhow::Error> {
+        use tokio::io::AsyncWriteExt as _;
+
+        // NOTE: `tokio::fs::File` writes in the background, flushing
+        //   is the only way to know if the last write succeeded.
+        (self.flush().await).context("Failed flushing file")
+    }
+}
+
+impl super::AsyncObjectWriter for tokio::fs::File {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -12,10 +12,16 @@ pub mod fs;
 pub mod s3;
 
 pub mod prelude {
-    pub use super::{BulkDeleteOutput, DeletedState, ObjectMetadata, ObjectStore, ReadObjectError};
+    pub use super::{
+        BulkDeleteOutput, DeletedState, ObjectAlreadyExists, ObjectMetadata, ObjectStore,
+        ReadObjectError,
+    };
 
     pub type DynObjectWriter = dyn super::ObjectWriter;
     pub type DynObjectReader = dyn std::io::Read + Send + Sync;
+
+    pub type DynAsyncObjectWriter = dyn super::AsyncObjectWriter;
+    pub type DynAsyncObjectReader = dyn tokio::io::AsyncRead + Send + Unpin;
 }
 
 pub use self::cache::{CachedStore, StoreCache};
@@ -29,9 +35,58 @@ pub use self::s3::S3Store;
 pub trait ObjectStore: std::fmt::Debug + Send + Sync {
     async fn writer(&self, key: &str) -> Result<Box<DynObjectWriter>, anyhow::Error>;
 
+    /// Same as [`ObjectStore::writer`], but writing never overwrites an
+    /// existing object (“create-if-absent”), whatever the configuration.
+    ///
+    /// If an object already exists at `key`, either opening the writer or
+    /// finalizing it fails with an [`ObjectAlreadyExists`] error (see
+    /// [`ObjectAlreadyExists::is`]).
+    ///
+    /// WARN: Implementations must check for existence atomically with the
+    ///   write (checking before writing is not enough).
+    async fn writer_if_absent(&self, key: &str) -> Result<Box<DynObjectWriter>, anyhow::Error>;
+
+    /// Async counterpart of [`ObjectStore::writer`].
+    async fn async_writer(&self, key: &str) -> Result<Box<DynAsyncObjectWriter>, anyhow::Error>;
+
+    /// Async counterpart of [`ObjectStore::writer_if_absent`].
+    ///
+    /// WARN: Implementations must check for existence atomically with the
+    ///   write (checking before writing is not enough).
+    async fn async_writer_if_absent(
+        &self,
+        key: &str,
+    ) -> Result<Box<DynAsyncObjectWriter>, anyhow::Error>;
+
     /// Returns `None` if key does not exist.
     async fn reader(&self, key: &str) -> Result<Box<DynObjectReader>, ReadObjectError>;
 
+    /// Async counterpart of [`ObjectStore::reader`].
+    async fn async_reader(&self, key: &str) -> Result<Box<DynAsyncObjectReader>, ReadObjectError>;
+
+    /// Same as [`ObjectStore::async_reader`], but only reads bytes in
+    /// `range` (e.g. to serve HTTP range requests).
+    ///
+    /// NOTE: This default implementation reads (and discards) bytes before
+    ///   `range.start`. Stores which can seek should override it.
+    async fn async_range_reader(
+        &self,
+        key: &str,
+        range: std::ops::Range<u64>,
+    ) -> Result<Box<DynAsyncObjectReader>, ReadObjectError> {
+        use tokio::io::AsyncReadExt as _;
+
+        let mut reader = self.async_reader(key).await?;
+
+        tokio::io::copy(&mut (&mut reader).take(range.start), &mut tokio::io::sink())
+            .await
+            .map_err(|err| {
+                ReadObjectError::Other(anyhow::Error::new(err).context("Failed seeking"))
+            })?;
+
+        Ok(Box::new(reader.take(range.end.saturating_sub(range.start))))
+    }
+
     /// Returns `None` if key does not exist or object too large.
     #[inline]
     async fn reader_if_not_too_large<'a>(
@@ -52,6 +107,26 @@ pub trait ObjectStore: std::fmt::Debug + Send + Sync {
         }
     }
 
+    /// Async counterpart of [`ObjectStore::reader_if_not_too_large`].
+    #[inline]
+    async fn async_reader_if_not_too_large<'a>(
+        &self,
+        key: &'a str,
+        max_size: u64,
+    ) -> Result<Box<DynAsyncObjectReader>, ReadSizedObjectError<'a>> {
+        let size = self.metadata(key).await?.size_bytes;
+
+        if size <= max_size {
+            (self.async_reader(key).await).map_err(ReadSizedObjectError::ReadFailed)
+        } else {
+            Err(ReadSizedObjectError::ObjectTooLarge {
+                key,
+                size,
+                max_size,
+            })
+        }
+    }
+
     async fn exists(&self, key: &str) -> Result<bool, anyhow::Error>;
 
     async fn find(&self, prefix: &str) -> Result<Vec<ObjectMetadata>, anyhow::Error>;
@@ -126,6 +201,20 @@ pub struct BulkDeleteOutput {
     pub errors: Vec<anyhow::Error>,
 }
 
+/// An object already exists at the given key, and it was not overwritten.
+#[derive(Debug, thiserror::Error)]
+#[error("Object `{key}` already exists.")]
+pub struct ObjectAlreadyExists {
+    pub key: String,
+}
+
+impl ObjectAlreadyExists {
+    /// Whether or not `err` was caused by an object already existing.
+    pub fn is(err: &anyhow::Error) -> bool {
+        err.chain().any(|cause| cause.is::<Self>())
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ReadObjectError {
     #[error(transparent)]
@@ -154,6 +243,16 @@ pub trait Finalizable {
 
 pub trait ObjectWriter: std::io::Write + Finalizable + Send + Sync {}
 
+#[async_trait::async_trait]
+pub trait AsyncFinalizable {
+    async fn finalize(self: Box<Self>) -> Result<(), anyhow::Error>;
+}
+
+/// NOTE: Unlike [`ObjectWriter`]s, async writers don’t have to be [`Sync`]
+///   as they are never shared between threads (e.g. S3 writers hold
+///   the future of the part being uploaded).
+pub trait AsyncObjectWriter: tokio::io::AsyncWrite + AsyncFinalizable + Send + Unpin {}
+
 // MARK: - Boilerplate
 
 impl std::ops::Deref for ObjectId {
@@ -87,17 +87,28 @@ impl S3Store {
 #[async_trait::async_trait]
 impl ObjectStore for S3Store {
     async fn writer(&self, key: &str) -> Result<Box<DynObjectWriter>, anyhow::Error> {
-        let writer = S3Writer::new(
-            self.client.clone(),
-            &self.bucket,
-            format!("{}{key}", self.prefix),
-            self.object_lock.as_ref(),
-            self.object_lock_legal_hold_status.as_ref(),
-        )
-        .await?;
+        let writer = self.writer_(key, false).await?;
+        Ok(Box::new(writer))
+    }
+
+    async fn writer_if_absent(&self, key: &str) -> Result<Box<DynObjectWriter>, anyhow::Error> {
+        let writer = self.writer_(key, true).await?;
         Ok(Box::new(writer))
     }
 
+    async fn async_writer(&self, key: &str) -> Result<Box<DynAsyncObjectWriter>, anyhow::Error> {
+        let writer = self.writer_(key, false).await?;
+        Ok(Box::new(S3AsyncWriter::new(writer)))
+    }
+
+    async fn async_writer_if_absent(
+        &self,
+        key: &str,
+    ) -> Result<Box<DynAsyncObjectWriter>, anyhow::Error> {
+        let writer = self.writer_(key, true).await?;
+        Ok(Box::new(S3AsyncWriter::new(writer)))
+    }
+
     async fn reader(&self, key: &str) -> Result<Box<DynObjectReader>, ReadObjectError> {
         match self.exists_(key).await {
             Ok(_) => {
@@ -112,6 +123,51 @@ impl ObjectStore for S3Store {
         }
     }
 
+    async fn async_reader(&self, key: &str) -> Result<Box<DynAsyncObjectReader>, ReadObjectError> {
+        match (self.client.get_object())
+            .bucket(&self.bucket)
+            .key(format!("{}{key}", self.prefix))
+            .send()
+            .await
+        {
+            Ok(output) => Ok(Box::new(output.body.into_async_read())),
+            Err(SdkError::ServiceError(e)) if e.err().is_no_such_key() => Err(
+                ReadObjectError::ObjectNotFound(anyhow::Error::from(SdkError::ServiceError(e))),
+            ),
+            Err(err) => Err(ReadObjectError::Other(
+                anyhow::Error::from(err).context("Failed to open S3 object for reading"),
+            )),
+        }
+    }
+
+    async fn async_range_reader(
+        &self,
+        key: &str,
+        range: std::ops::Range<u64>,
+    ) -> Result<Box<DynAsyncObjectReader>, ReadObjectError> {
+        // NOTE: HTTP ranges can’t be empty.
+        if range.is_empty() {
+            return Ok(Box::new(tokio::io::empty()));
+        }
+
+        match (self.client.get_object())
+            .bucket(&self.bucket)
+            .key(format!("{}{key}", self.prefix))
+            // NOTE: HTTP ranges are inclusive.
+            .range(format!("bytes={}-{}", range.start, range.end - 1))
+            .send()
+            .await
+        {
+            Ok(output) => Ok(Box::new(output.body.into_async_read())),
+            Err(SdkError::ServiceError(e)) if e.err().is_no_such_key() => Err(
+                ReadObjectError::ObjectNotFound(anyhow::Error::from(SdkError::ServiceError(e))),
+            ),
+            Err(err) => Err(ReadObjectError::Other(
+                anyhow::Error::from(err).context("Failed to open S3 object for reading"),
+            )),
+        }
+    }
+
     async fn exists(&self, key: &str) -> Result<bool, anyhow::Error> {
         match self.exists_(key).await {
             Ok(_) => Ok(true),
@@ -335,6 +391,27 @@ impl ObjectStore for S3Store {
 }
 
 impl S3Store {
+    async fn writer_(&self, key: &str, if_absent: bool) -> Result<S3Writer, anyhow::Error> {
+        // NOTE: This check is not atomic, it only allows failing early
+        //   (instead of after uploading the whole object). Atomicity is
+        //   guaranteed by the conditional write when completing the upload.
+        if if_absent && self.exists(key).await? {
+            return Err(anyhow::Error::new(ObjectAlreadyExists {
+                key: key.to_owned(),
+            }));
+        }
+
+        S3Writer::new(
+            self.client.clone(),
+            &self.bucket,
+            format!("{}{key}", self.prefix),
+            self.object_lock.as_ref(),
+            self.object_lock_legal_hold_status.as_ref(),
+            if_absent,
+        )
+        .await
+    }
+
     async fn exists_(
         &self,
         key: &str,
@@ -358,6 +435,11 @@ impl S3Store {
 
 // MARK: Writer
 
+/// Synchronous S3 writer (see [`ObjectStore::writer`]).
+///
+/// WARN: Blocks the current thread on S3 requests (using
+///   [`tokio::task::block_in_place`]), so only use it outside of async code.
+///   Backups are created using [`S3AsyncWriter`].
 pub struct S3Writer {
     client: s3::Client,
     bucket: String,
@@ -370,6 +452,9 @@ pub struct S3Writer {
         Option<s3::operation::put_object_retention::builders::PutObjectRetentionFluentBuilder>,
     put_object_legal_hold:
         Option<s3::operation::put_object_legal_hold::builders::PutObjectLegalHoldFluentBuilder>,
+    /// Whether or not completing the upload should fail if an object
+    /// already exists at `key` (conditional write).
+    if_absent: bool,
 }
 
 impl S3Writer {
@@ -379,6 +464,7 @@ impl S3Writer {
         key: impl Into<String>,
         object_lock: Option<&crate::config::S3ObjectLockConfig>,
         object_lock_legal_hold_status: Option<&ObjectLockLegalHoldStatus>,
+        if_absent: bool,
     ) -> Result<Self, anyhow::Error> {
         let bucket = bucket.into();
         let key = key.into();
@@ -426,6 +512,7 @@ impl S3Writer {
             part_number: 1,
             put_object_retention,
             put_object_legal_hold,
+            if_absent,
         })
     }
 
@@ -471,7 +558,8 @@ impl S3Writer {
     pub async fn complete(mut self) -> Result<(), anyhow::Error> {
         self.flush_part().await?;
 
-        self.client
+        let mut request = self
+            .client
             .complete_multipart_upload()
             .bucket(&self.bucket)
             .key(&self.key)
@@ -480,10 +568,45 @@ impl S3Writer {
                 s3::types::CompletedMultipartUpload::builder()
                     .set_parts(Some(self.parts))
                     .build(),
-            )
-            .send()
-            .await
-            .context("S3 multipart upload complete failed")?;
+            );
+        if self.if_absent {
+            // NOTE: Conditional write, see
+            //   <https://docs.aws.amazon.com/AmazonS3/latest/userguide/conditional-writes.html>.
+            request = request.if_none_match("*");
+        }
+
+        match request.send().await {
+            Ok(_) => {}
+            // NOTE: S3 returns `412 Precondition Failed` if the object
+            //   already exists, or `409 Conflict` if another conditional
+            //   write to the same key succeeded concurrently.
+            Err(err)
+                if self.if_absent
+                    && (err.raw_response())
+                        .is_some_and(|res| matches!(res.status().as_u16(), 409 | 412)) =>
+            {
+                tracing::debug!(key = self.key, "S3 conditional write failed: {err:?}");
+
+                // Do not leave the uploaded parts behind (they are billed).
+                if let Err(err) = (self.client.abort_multipart_upload())
+                    .bucket(&self.bucket)
+                    .key(&self.key)
+                    .upload_id(&self.upload_id)
+                    .send()
+                    .await
+                {
+                    tracing::warn!(
+                        key = self.key,
+                        "Failed aborting S3 multipart upload: {err:?}"
+                    );
+                }
+
+                return Err(anyhow::Error::new(ObjectAlreadyExists { key: self.key }));
+            }
+            Err(err) => {
+                return Err(anyhow::Error::from(err).context("S3 multipart upload complete failed"));
+            }
+        }
 
         tracing::trace!(key = self.key, "S3 multipart upload completed.");
 
@@ -553,8 +676,157 @@ impl super::Finalizable for S3Writer {
 
 impl super::ObjectWriter for S3Writer {}
 
+// MARK: Async writer
+
+type PartUpload =
+    std::pin::Pin<Box<dyn Future<Output = (Box<S3Writer>, Result<(), anyhow::Error>)> + Send>>;
+
+/// Async counterpart of [`S3Writer`].
+///
+/// Parts are uploaded in the background while the next one is being
+/// buffered, and writing waits for the previous upload to finish (which
+/// means at most two parts are held in memory).
+///
+/// NOTE: If dropped before being finalized (e.g. the backup was cancelled),
+///   the multipart upload is aborted so uploaded parts don’t get billed.
+pub struct S3AsyncWriter {
+    state: S3AsyncWriterState,
+    abort:
+        Option<s3::operation::abort_multipart_upload::builders::AbortMultipartUploadFluentBuilder>,
+}
+
+enum S3AsyncWriterState {
+    Idle(Box<S3Writer>),
+    Uploading(PartUpload),
+    Finalized,
+}
+
+impl S3AsyncWriter {
+    fn new(writer: S3Writer) -> Self {
+        let abort = (writer.client.abort_multipart_upload())
+            .bucket(&writer.bucket)
+            .key(&writer.key)
+            .upload_id(&writer.upload_id);
+
+        Self {
+            state: S3AsyncWriterState::Idle(Box::new(writer)),
+            abort: Some(abort),
+        }
+    }
+
+    /// Waits for the part being uploaded (if any).
+    fn poll_idle(&mut self, cx: &mut std::task::Context<'_>) -> std::task::Poll<io::Result<()>> {
+        match self.state {
+            S3AsyncWriterState::Idle(_) => std::task::Poll::Ready(Ok(())),
+            S3AsyncWriterState::Uploading(ref mut upload) => {
+                let (writer, res) = std::task::ready!(upload.as_mut().poll(cx));
+                self.state = S3AsyncWriterState::Idle(writer);
+                std::task::Poll::Ready(res.map_err(io::Error::other))
+            }
+            S3AsyncWriterState::Finalized => {
+                std::task::Poll::Ready(Err(io::Error::other("S3 writer already finalized")))
+            }
+        }
+    }
+}
+
+impl tokio::io::AsyncWrite for S3AsyncWriter {
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        std::task::ready!(this.poll_idle(cx))?;
+
+        let S3AsyncWriterState::Idle(mut writer) =
+            std::mem::replace(&mut this.state, S3AsyncWriterState::Finalized)
+        else {
+            unreachable!("`poll_idle` returned `Ready(Ok)`")
+        };
+
+        writer.buf.extend_from_slice(buf);
+
+        if writer.buf.len() >= UPLOAD_PART_SIZE {
+            this.state = S3AsyncWriterState::Uploading(Box::pin(async move {
+                let res = writer.flush_part().await;
+                (writer, res)
+            }));
+        } else {
+            this.state = S3AsyncWriterState::Idle(writer);
+        }
+
+        std::task::Poll::Ready(Ok(buf.len()))
+    }
+
+    /// NOTE: Only waits for the part being uploaded (if any). Buffered data
+    ///   is uploaded when finalizing, as S3 rejects parts smaller than 5MiB
+    ///   (except the last one).
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        self.get_mut().poll_idle(cx)
+    }
+
+    fn poll_shutdown(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        self.get_mut().poll_idle(cx)
+    }
+}
+
+#[async_trait::async_trait]
+impl super::AsyncFinalizable for S3AsyncWriter {
+    /// NOTE: Flushes the stream if needed.
+    async fn finalize(mut self: Box<Self>) -> Result<(), anyhow::Error> {
+        std::future::poll_fn(|cx| self.poll_idle(cx)).await?;
+
+        let S3AsyncWriterState::Idle(writer) =
+            std::mem::replace(&mut self.state, S3AsyncWriterState::Finalized)
+        else {
+            unreachable!("`poll_idle` returned `Ready(Ok)`")
+        };
+
+        writer.complete().await?;
+
+        // Upload completed, nothing to abort anymore.
+        self.abort = None;
+
+        Ok(())
+    }
+}
+
+impl super::AsyncObjectWriter for S3AsyncWriter {}
+
+impl Drop for S3AsyncWriter {
+    fn drop(&mut self) {
+        let Some(abort) = self.abort.take() else {
+            return;
+        };
+
+        // NOTE: Best-effort (cannot recover on cleanup).
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(err) = abort.send().await {
+                        tracing::warn!("Failed aborting S3 multipart upload: {err:?}");
+                    }
+                });
+            }
+            Err(err) => tracing::warn!("Could not abort S3 multipart upload: {err}"),
+        }
+    }
+}
+
 // MARK: Reader
 
+/// Synchronous S3 reader (see [`ObjectStore::reader`]).
+///
+/// WARN: Blocks the current thread on S3 requests (using
+///   [`tokio::task::block_in_place`]), so only use it outside of async code.
+///   Backups are restored using [`ObjectStore::async_reader`].
 pub struct S3Reader {
     client: s3::Client,
     bucket: String,
@@ -0,0 +1,574 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+//! Transparency log of backups.
+//!
+//! Signatures prove who created a backup, but someone with write access to
+//! the stores could delete backups without anyone noticing. When enabled
+//! (see [`TransparencyLogConfig`](crate::config::TransparencyLogConfig)),
+//! every backup creation and deletion is recorded in an append-only log
+//! stored in the check store. Each entry contains the hash of the previous
+//! one and is signed if signing is enabled, therefore altering or removing
+//! an entry breaks the chain (see [`verify_log`]).
+//!
+//! NOTE: Removing the last entries of the log cannot be detected from the
+//!   log itself. Enable Object Lock on the check store to prevent it.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use anyhow::{Context as _, anyhow};
+
+use crate::BackupId;
+use crate::BackupService;
+use crate::config::HashingAlgorithm;
+use crate::hashing::{encode_hex_digest, hex_digest};
+use crate::signing::PgpSigningContext;
+use crate::stores::{AsyncFinalizable as _, ObjectAlreadyExists, ObjectStore};
+use crate::util::spawn_blocking;
+use crate::verification::{MAX_PGP_SIGNATURE_LENGTH, PgpVerificationContext};
+
+/// Prefix of the keys of log entries (and their signatures).
+///
+/// NOTE: Backup IDs contain a timestamp after the first `-`, therefore
+///   integrity checks (deleted by prefix) can never start with this.
+const LOG_ENTRY_PREFIX: &str = "transparency-log-";
+
+/// Entries are tiny, refuse to download large objects a malicious actor
+/// might have stored instead.
+const MAX_LOG_ENTRY_LENGTH: u64 = 4 * 1024;
+
+/// Digests are 32 bytes long for all supported algorithms.
+const MAX_DIGEST_LENGTH: u64 = 64;
+
+/// How many times to retry appending when another process appended an
+/// entry at the same position.
+const MAX_APPEND_ATTEMPTS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogOperation {
+    Created,
+    Deleted,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct LogEntry {
+    /// Position of the entry in the log, starting at `0`.
+    pub seq: u64,
+
+    pub operation: LogOperation,
+
+    pub backup_id: String,
+
+    /// Digest of the backup (e.g. `blake3:…`), if known.
+    pub digest: Option<String>,
+
+    /// Fingerprint of the certificate which signed the entry, if any.
+    pub signer: Option<String>,
+
+    /// UTC timestamp at which the operation was recorded.
+    #[serde(with = "time::serde::timestamp")]
+    pub timestamp: time::OffsetDateTime,
+
+    /// Hash of the previous entry (e.g. `blake3:…`), `None` for the first
+    /// entry of the log.
+    pub previous_hash: Option<String>,
+}
+
+/// Result of [`verify_log`].
+#[derive(Debug, Default)]
+#[derive(serde::Serialize)]
+pub struct LogVerificationReport {
+    /// Number of entries found in the log.
+    pub entries_count: u64,
+
+    /// Problems found in the log itself.
+    pub issues: Vec<LogIssue>,
+
+    /// Backups which exist but were never recorded in the log.
+    pub unlogged_backups: Vec<String>,
+
+    /// Backups recorded as created (and not deleted) in the log, but which
+    /// don’t exist.
+    pub missing_backups: Vec<String>,
+}
+
+impl LogVerificationReport {
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+            && self.unlogged_backups.is_empty()
+            && self.missing_backups.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LogIssue {
+    /// Entries `from..=to` are missing (e.g. they were deleted).
+    MissingEntries { from: u64, to: u64 },
+
+    /// The entry couldn’t be read or parsed.
+    InvalidEntry { seq: u64, error: String },
+
+    /// The entry doesn’t contain the hash of the previous one (e.g. the
+    /// previous entry was altered).
+    BrokenChain { seq: u64 },
+
+    /// The entry is not signed (but signing is mandatory or the entry
+    /// claims to be signed).
+    MissingSignature { seq: u64 },
+
+    /// The signature of the entry is not valid or not trusted.
+    InvalidSignature { seq: u64, error: String },
+}
+
+// MARK: Append
+
+pub(crate) async fn append(
+    service: &BackupService,
+    operation: LogOperation,
+    backup_id: &BackupId,
+    digest: Option<String>,
+) -> Result<(), anyhow::Error> {
+    if !service.transparency_log_config.enabled {
+        return Ok(());
+    }
+
+    let signing_context = service.signing_context.pgp.clone();
+    let signer = (signing_context.as_deref()).map(|context| context.tsk.fingerprint().to_hex());
+
+    for _ in 0..MAX_APPEND_ATTEMPTS {
+        let (seq, previous_hash) = match read_last_entry(service).await? {
+            Some((seq, bytes)) => {
+                let seq = (seq.checked_add(1)).context("Transparency log is full")?;
+                (
+                    seq,
+                    Some(hex_digest(service.hashing_config.algorithm, &bytes)),
+                )
+            }
+            None => (0, None),
+        };
+
+        let now = SystemTime::now();
+        let entry = LogEntry {
+            seq,
+            operation,
+            backup_id: backup_id.to_string(),
+            digest: digest.clone(),
+            signer: signer.clone(),
+            timestamp: now.into(),
+            previous_hash,
+        };
+        let bytes = json::to_vec(&entry).context("Could not serialize log entry")?;
+
+        // NOTE: Sign before writing the entry, so signing errors don’t leave
+        //   unsigned entries behind.
+        let signature = match signing_context.clone() {
+            Some(context) => {
+                let bytes = bytes.clone();
+                Some(spawn_blocking(move || sign(&context, &bytes, now)).await?)
+            }
+            None => None,
+        };
+
+        // NOTE: Never overwrite existing entries. If another process
+        //   appended an entry in the meantime, try again after it.
+        let key = entry_key(seq);
+        match write_if_absent(service.check_store.as_ref(), &key, bytes).await {
+            Ok(()) => {}
+            Err(err) if ObjectAlreadyExists::is(&err) => {
+                tracing::debug!("Log entry `{key}` already exists. Retrying…");
+                continue;
+            }
+            Err(err) => return Err(err.context(format!("Could not write log entry `{key}`"))),
+        }
+
+        if let Some(signature) = signature {
+            write_if_absent(
+                service.check_store.as_ref(),
+                &format!("{key}.sig"),
+                signature,
+            )
+            .await
+            .with_context(|| format!("Could not write signature of log entry `{key}`"))?;
+        }
+
+        tracing::debug!("Recorded backup `{backup_id}` as {operation:?} in `{key}`.");
+
+        return Ok(());
+    }
+
+    Err(anyhow!(
+        "Could not append to the transparency log after {MAX_APPEND_ATTEMPTS} attempts."
+    ))
+}
+
+/// Reads the digest uploaded alongside a backup, so it can be recorded
+/// when the backup is deleted.
+pub(crate) async fn read_backup_digest(
+    service: &BackupService,
+    backup_id: &BackupId,
+) -> Option<String> {
+    let algorithm = service.hashing_config.algorithm;
+    let check_name = format!("{backup_id}.{ext}", ext = algorithm.extension());
+
+    match service
+        .read_check(&check_name, MAX_DIGEST_LENGTH, "Checksum")
+        .await
+    {
+        Ok(digest) => digest.map(|digest| encode_hex_digest(algorithm, &digest)),
+        Err(err) => {
+            tracing::debug!("Could not read digest of `{backup_id}`: {err:#}");
+            None
+        }
+    }
+}
+
+fn sign(
+    context: &PgpSigningContext,
+    data: &[u8],
+    time: SystemTime,
+) -> Result<Vec<u8>, anyhow::Error> {
+    use std::io::Write as _;
+
+    let mut signature: Vec<u8> = Vec::new();
+    let mut message = context.new_writer(&mut signature, time)?;
+    message.write_all(data)?;
+    message.finalize()?;
+
+    Ok(signature)
+}
+
+async fn write_if_absent(
+    store: &dyn ObjectStore,
+    key: &str,
+    data: Vec<u8>,
+) -> Result<(), anyhow::Error> {
+    use tokio::io::AsyncWriteExt as _;
+
+    let mut writer = store.async_writer_if_absent(key).await?;
+    writer.write_all(&data).await?;
+    writer.finalize().await
+}
+
+// MARK: Read
+
+fn entry_key(seq: u64) -> String {
+    // NOTE: Zero-padded so keys are sorted like entries.
+    format!("{LOG_ENTRY_PREFIX}{seq:020}.json")
+}
+
+fn parse_entry_key(key: &str) -> Option<u64> {
+    let seq = key.strip_prefix(LOG_ENTRY_PREFIX)?.strip_suffix(".json")?;
+
+    if seq.len() == 20 && seq.bytes().all(|b| b.is_ascii_digit()) {
+        seq.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// Whether or not an object is a log entry (or the signature of one).
+pub(crate) fn is_log_object(file_name: &str) -> bool {
+    file_name.starts_with(LOG_ENTRY_PREFIX)
+}
+
+/// Lists the keys of log entries, sorted by sequence number, and the keys
+/// of signatures.
+async fn list_log_objects(
+    service: &BackupService,
+) -> Result<(BTreeMap<u64, String>, HashSet<String>), anyhow::Error> {
+    let objects = service.check_store.find(LOG_ENTRY_PREFIX).await?;
+
+    let mut entries: BTreeMap<u64, String> = BTreeMap::new();
+    let mut signatures: HashSet<String> = HashSet::new();
+
+    for object in objects {
+        let key = object.file_name;
+
+        if let Some(seq) = parse_entry_key(&key) {
+            entries.insert(seq, key);
+        } else if key.ends_with(".sig") {
+            signatures.insert(key);
+        } else {
+            tracing::warn!("Unexpected object `{key}` in transparency log.");
+        }
+    }
+
+    Ok((entries, signatures))
+}
+
+async fn read_object(
+    service: &BackupService,
+    key: &str,
+    max_size: u64,
+    kind: &str,
+) -> Result<Vec<u8>, anyhow::Error> {
+    match service.read_check(key, max_size, kind).await? {
+        Some(bytes) => Ok(bytes),
+        None => Err(anyhow!("{kind} `{key}` not found or too large.")),
+    }
+}
+
+async fn read_last_entry(service: &BackupService) -> Result<Option<(u64, Vec<u8>)>, anyhow::Error> {
+    let (entries, _) = list_log_objects(service).await?;
+
+    let Some((seq, key)) = entries.last_key_value() else {
+        return Ok(None);
+    };
+
+    let bytes = read_object(service, key, MAX_LOG_ENTRY_LENGTH, "Log entry").await?;
+
+    Ok(Some((*seq, bytes)))
+}
+
+struct RawLog {
+    /// Keys and contents of entries, by sequence number.
+    entries: BTreeMap<u64, (String, Result<Vec<u8>, anyhow::Error>)>,
+
+    signature_keys: HashSet<String>,
+}
+
+/// Reads all entries of the log, without verifying them.
+async fn read_log(service: &BackupService) -> Result<RawLog, anyhow::Error> {
+    let (keys, signature_keys) = list_log_objects(service).await?;
+
+    let mut entries = BTreeMap::new();
+    for (seq, key) in keys {
+        let bytes = read_object(service, &key, MAX_LOG_ENTRY_LENGTH, "Log entry").await;
+        entries.insert(seq, (key, bytes));
+    }
+
+    Ok(RawLog {
+        entries,
+        signature_keys,
+    })
+}
+
+/// Replays the log to find which backups should exist.
+///
+/// NOTE: Doesn’t verify the log, see [`verify_log`] for that.
+pub(crate) async fn logged_backups(
+    service: &BackupService,
+) -> Result<HashSet<String>, anyhow::Error> {
+    let RawLog { entries, .. } = read_log(service).await?;
+
+    let mut backups: HashSet<String> = HashSet::new();
+    for (seq, (_, bytes)) in entries {
+        let entry = match bytes.and_then(|bytes| parse_entry(seq, &bytes)) {
+            Ok(entry) => entry,
+            Err(err) => {
+                tracing::warn!("Skipping invalid log entry #{seq}: {err:#}");
+                continue;
+            }
+        };
+        apply(&mut backups, &entry);
+    }
+
+    Ok(backups)
+}
+
+fn parse_entry(seq: u64, bytes: &[u8]) -> Result<LogEntry, anyhow::Error> {
+    let entry: LogEntry = json::from_slice(bytes).context("Invalid JSON")?;
+
+    if entry.seq != seq {
+        return Err(anyhow!(
+            "Entry stored at position {seq} claims to be at position {}.",
+            entry.seq
+        ));
+    }
+
+    Ok(entry)
+}
+
+fn apply(backups: &mut HashSet<String>, entry: &LogEntry) {
+    match entry.operation {
+        LogOperation::Created => backups.insert(entry.backup_id.clone()),
+        LogOperation::Deleted => backups.remove(&entry.backup_id),
+    };
+}
+
+// MARK: Verify
+
+/// Checks that the log is intact (no missing or altered entry, valid
+/// signatures), and that it matches the backups actually stored.
+pub async fn verify_log(service: &BackupService) -> Result<LogVerificationReport, anyhow::Error> {
+    let RawLog {
+        entries,
+        signature_keys,
+    } = read_log(service).await?;
+
+    let mut signatures: HashMap<u64, Vec<u8>> = HashMap::new();
+    if service.verification_context.pgp.is_some() {
+        for (seq, (key, _)) in entries.iter() {
+            let signature_key = format!("{key}.sig");
+            if !signature_keys.contains(&signature_key) {
+                continue;
+            }
+
+            match read_object(
+                service,
+                &signature_key,
+                MAX_PGP_SIGNATURE_LENGTH,
+                "OpenPGP signature",
+            )
+            .await
+            {
+                Ok(signature) => {
+                    signatures.insert(*seq, signature);
+                }
+                Err(err) => tracing::warn!("{err:#}"),
+            }
+        }
+    }
+
+    let stored_backups: Vec<String> = (service.list_backup_objects().await?.into_iter())
+        .map(|metadata| metadata.file_name)
+        .collect();
+
+    // NOTE: Signature verification is CPU-bound, run it on the blocking
+    //   thread pool.
+    let entries = (entries.into_iter())
+        .map(|(seq, (_, bytes))| (seq, bytes))
+        .collect();
+    let verification_context = service.verification_context.pgp.clone();
+    let is_signing_mandatory = service.signing_context.is_signing_mandatory;
+    let report = spawn_blocking(move || {
+        verify_entries(
+            entries,
+            signatures,
+            verification_context,
+            is_signing_mandatory,
+            stored_backups,
+        )
+    })
+    .await;
+
+    Ok(report)
+}
+
+fn verify_entries(
+    entries: BTreeMap<u64, Result<Vec<u8>, anyhow::Error>>,
+    signatures: HashMap<u64, Vec<u8>>,
+    verification_context: Option<Arc<PgpVerificationContext>>,
+    is_signing_mandatory: bool,
+    stored_backups: Vec<String>,
+) -> LogVerificationReport {
+    let mut report = LogVerificationReport {
+        entries_count: entries.len() as u64,
+        ..Default::default()
+    };
+
+    let mut logged_backups: HashSet<String> = HashSet::new();
+    let mut expected_seq: u64 = 0;
+    // NOTE: `None` if the previous entry is missing or couldn’t be read.
+    let mut previous_bytes: Option<Vec<u8>> = None;
+
+    for (seq, bytes) in entries {
+        if seq > expected_seq {
+            report.issues.push(LogIssue::MissingEntries {
+                from: expected_seq,
+                to: seq - 1,
+            });
+            previous_bytes = None;
+        }
+        expected_seq = seq.saturating_add(1);
+
+        let bytes = match bytes {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                report.issues.push(LogIssue::InvalidEntry {
+                    seq,
+                    error: format!("{err:#}"),
+                });
+                previous_bytes = None;
+                continue;
+            }
+        };
+
+        let entry = match parse_entry(seq, &bytes) {
+            Ok(entry) => entry,
+            Err(err) => {
+                report.issues.push(LogIssue::InvalidEntry {
+                    seq,
+                    error: format!("{err:#}"),
+                });
+                previous_bytes = None;
+                continue;
+            }
+        };
+
+        // Check the hash chain.
+        match (seq, &entry.previous_hash, &previous_bytes) {
+            (0, None, _) => {}
+            (0, Some(_), _) => report.issues.push(LogIssue::BrokenChain { seq }),
+            (_, None, _) => report.issues.push(LogIssue::BrokenChain { seq }),
+            (_, Some(previous_hash), Some(previous_bytes)) => {
+                let algorithm = (previous_hash.split_once(':'))
+                    .and_then(|(ext, _)| HashingAlgorithm::from_extension(ext));
+
+                match algorithm {
+                    Some(algorithm) if hex_digest(algorithm, previous_bytes) == *previous_hash => {}
+                    Some(_) => report.issues.push(LogIssue::BrokenChain { seq }),
+                    None => report.issues.push(LogIssue::InvalidEntry {
+                        seq,
+                        error: format!("Unsupported hash `{previous_hash}`."),
+                    }),
+                }
+            }
+            // NOTE: Already reported (missing or invalid previous entry).
+            (_, Some(_), None) => {}
+        }
+
+        // Check the signature.
+        if let Some(context) = verification_context.as_deref() {
+            match signatures.get(&seq) {
+                Some(signature) => {
+                    if let Err(err) = verify_signature(context, signature, &bytes, &entry) {
+                        report.issues.push(LogIssue::InvalidSignature {
+                            seq,
+                            error: format!("{err:#}"),
+                        });
+                    }
+                }
+                None if is_signing_mandatory || entry.signer.is_some() => {
+                    report.issues.push(LogIssue::MissingSignature { seq });
+                }
+                None => {}
+            }
+        }
+
+        apply(&mut logged_backups, &entry);
+        previous_bytes = Some(bytes);
+    }
+
+    let stored_backups: HashSet<String> = stored_backups.into_iter().collect();
+
+    report.unlogged_backups = (stored_backups.difference(&logged_backups).cloned()).collect();
+    report.unlogged_backups.sort();
+
+    report.missing_backups = (logged_backups.difference(&stored_backups).cloned()).collect();
+    report.missing_backups.sort();
+
+    report
+}
+
+fn verify_signature(
+    context: &PgpVerificationContext,
+    signature: &[u8],
+    bytes: &[u8],
+    entry: &LogEntry,
+) -> Result<(), anyhow::Error> {
+    use crate::verification::PgpSignatureVerifier;
+
+    let mut verifier = PgpSignatureVerifier::new(context, signature, entry.timestamp.into())?;
+    verifier.verify_reader(&mut std::io::Cursor::new(bytes))
+}
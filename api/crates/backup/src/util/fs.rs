@@ -18,6 +18,15 @@ pub fn remove(path: &std::path::Path) -> Result<(), std::io::Error> {
 }
 
 pub fn backup_path(path: &std::path::Path) -> Result<std::path::PathBuf, std::io::Error> {
+    let backup_path = unused_backup_path(path)?;
+
+    std::fs::rename(path, &backup_path)?;
+
+    Ok(backup_path)
+}
+
+/// Returns a path [`backup_path`] can move `path` to, without moving it.
+pub fn unused_backup_path(path: &std::path::Path) -> Result<std::path::PathBuf, std::io::Error> {
     use std::fs;
     use std::path::{Path, PathBuf};
 
@@ -32,11 +41,46 @@ pub fn backup_path(path: &std::path::Path) -> Result<std::path::PathBuf, std::io
         use_unique_name(&mut backup_path, &path)
     }
 
-    fs::rename(path, &backup_path)?;
-
     Ok(backup_path)
 }
 
+/// Writes a file such that a crash leaves either the old or the new
+/// contents on disk.
+///
+/// NOTE: `mode` only applies if the file didn’t exist (it’s subject to
+///   the process’ umask).
+pub(crate) fn write_durably(
+    path: &std::path::Path,
+    contents: &[u8],
+    mode: u32,
+) -> std::io::Result<()> {
+    use std::io::Write as _;
+    use std::os::unix::fs::OpenOptionsExt as _;
+
+    let tmp_path = path.with_added_extension("tmp");
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(mode)
+        .open(&tmp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+
+    std::fs::rename(&tmp_path, path)?;
+
+    sync_parent(path)
+}
+
+/// Makes a rename or deletion in the parent directory of `path` durable.
+pub(crate) fn sync_parent(path: &std::path::Path) -> std::io::Result<()> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => std::fs::File::open(parent)?.sync_all(),
+        _ => Ok(()),
+    }
+}
+
 /// Deletes a path (file or directory) when dropped.
 ///
 /// NOTE: To defuse, use `std::mem::take(&mut self.path)`.
@@ -10,11 +10,13 @@ mod measurements;
 mod octal;
 pub mod serde;
 pub mod tar;
+mod task;
 
 pub use self::fs::*;
 pub use self::measurements::BytesAmount;
 #[cfg(feature = "storage-fs")]
 pub use self::octal::Octal;
+pub(crate) use self::task::{BlockingTask, spawn_blocking};
 
 /// Efficiently concatenates two byte slices.
 pub fn concat_byte_slices(a: &[u8], b: &[u8]) -> Vec<u8> {
@@ -34,7 +36,6 @@ pub fn concat_osstr(a: &std::ffi::OsStr, b: &std::ffi::OsStr) -> std::ffi::OsStr
 
 /// Casting with `as` can yield incorrect values and similar issues
 /// happen with `clamp`. This function ensures no overflow happens.
-#[cfg(any(feature = "storage-s3", test))]
 pub fn saturating_i64_to_u64(value: i64) -> u64 {
     value.max(0) as u64
 }
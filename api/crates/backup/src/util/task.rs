@@ -0,0 +1,121 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// A task running on Tokio’s blocking thread pool (see
+/// [`tokio::task::spawn_blocking`]), which sends messages to the async
+/// task awaiting it.
+///
+/// This allows running synchronous pipelines (archiving, compression,
+/// extraction…) without tying up async workers, while still reporting
+/// progress or streaming data to async code.
+///
+/// NOTE: Dropping a [`BlockingTask`] (e.g. when the future awaiting it is
+///   cancelled) closes the channel, which the task notices as soon as it
+///   tries to send a message. Blocking tasks cannot be aborted, so the task
+///   is detached and finishes (e.g. reverts a partial restoration) in the
+///   background. Use [`BlockingTask::cancel`] to wait for cleanups.
+pub(crate) struct BlockingTask<T, M> {
+    receiver: mpsc::Receiver<M>,
+    handle: Option<JoinHandle<T>>,
+}
+
+impl<T, M> BlockingTask<T, M>
+where
+    T: Send + 'static,
+    M: Send + 'static,
+{
+    pub fn spawn<F>(buffer: usize, f: F) -> Self
+    where
+        F: FnOnce(mpsc::Sender<M>) -> T + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel(buffer);
+
+        let handle = tokio::task::spawn_blocking(move || f(sender));
+
+        Self {
+            receiver,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl<T, M> BlockingTask<T, M> {
+    /// Receives the next message. Returns `None` once the task is done
+    /// sending messages (i.e. it dropped its sender).
+    #[inline]
+    pub async fn recv(&mut self) -> Option<M> {
+        self.receiver.recv().await
+    }
+
+    /// Makes the task stop as soon as it tries to send a message, then
+    /// waits for it to finish (e.g. to revert its changes).
+    pub async fn cancel(mut self) -> T {
+        self.receiver.close();
+        self.join().await
+    }
+
+    /// Waits for the task to finish and returns its result.
+    ///
+    /// NOTE: Messages sent but not received yet are discarded.
+    pub async fn join(mut self) -> T {
+        // NOTE: Do not take the handle before it resolves, so `Drop` still
+        //   closes the channel if this future is cancelled.
+        let handle = (self.handle.as_mut()).expect("Handle is only taken here or on drop");
+
+        let res = handle.await;
+        self.handle = None;
+
+        match res {
+            Ok(output) => output,
+            Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+            Err(err) => panic!("Blocking task failed: {err}"),
+        }
+    }
+}
+
+/// Runs a blocking function on Tokio’s blocking thread pool (see
+/// [`tokio::task::spawn_blocking`]), propagating panics.
+///
+/// NOTE: Unlike [`BlockingTask`], the function can’t be told to stop, it
+///   runs to completion even if the returned future is dropped. Use it only
+///   for side-effect-free computations (e.g. hashing).
+pub(crate) async fn spawn_blocking<F, T>(f: F) -> T
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(output) => output,
+        Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+        Err(err) => panic!("Blocking task failed: {err}"),
+    }
+}
+
+impl<T, M> Drop for BlockingTask<T, M> {
+    fn drop(&mut self) {
+        let Some(handle) = self.handle.take() else {
+            return;
+        };
+
+        if handle.is_finished() {
+            return;
+        }
+
+        // Make the task stop as soon as it tries to send a message.
+        self.receiver.close();
+
+        // NOTE: We can’t await in `drop`, and blocking would tie up an async
+        //   worker. The task is detached and finishes in the background.
+        tracing::debug!("Cancelled blocking task will finish in the background.");
+        tokio::task::spawn(async move {
+            if let Err(err) = handle.await {
+                tracing::error!("Cancelled blocking task failed: {err}");
+            }
+        });
+    }
+}
@@ -8,8 +8,9 @@
 use std::sync::Arc;
 
 use crate::BackupService;
-use crate::stores::{ObjectId, ReadObjectError, ReadSizedObjectError};
-use crate::util::PathGuard;
+use crate::restore_report::RestoreStage;
+use crate::stores::{ObjectId, ObjectStore as _, ReadObjectError, ReadSizedObjectError};
+use crate::util::{PathGuard, spawn_blocking};
 
 pub(crate) use self::VerificationContext as Context;
 
@@ -20,12 +21,12 @@ pub(crate) use self::VerificationContext as Context;
 /// might be charged, it’s important to avoid downloading excessively large
 /// files a malicious actor might have stored. We also prevent Denial of Service
 /// if we stay stuck at downloading a very very large file.
-const MAX_PGP_SIGNATURE_LENGTH: u64 = 2 * 1024;
+pub(crate) const MAX_PGP_SIGNATURE_LENGTH: u64 = 2 * 1024;
 
 #[non_exhaustive]
 #[derive(Debug, Default)]
 pub struct VerificationContext {
-    pub pgp: Option<PgpVerificationContext>,
+    pub pgp: Option<Arc<PgpVerificationContext>>,
 }
 
 pub struct VerificationOutput {
@@ -94,25 +95,43 @@ impl BackupService {
         backup_id: &crate::BackupId,
         created_at: impl Into<std::time::SystemTime>,
         report: &mut VerificationReport,
+    ) -> Result<VerificationOutput, VerificationError> {
+        self.download_backup_and_check_integrity_(backup_id, created_at, report, &mut |_, _| {})
+            .await
+    }
+
+    /// [`download_backup_and_check_integrity`](Self::download_backup_and_check_integrity),
+    /// calling `on_stage` when a [`RestoreStage`] starts or finishes.
+    pub(crate) async fn download_backup_and_check_integrity_(
+        &self,
+        backup_id: &crate::BackupId,
+        created_at: impl Into<std::time::SystemTime>,
+        report: &mut VerificationReport,
+        on_stage: &mut (dyn FnMut(RestoreStage, f64) + Send),
     ) -> Result<VerificationOutput, VerificationError> {
         use anyhow::{Context as _, anyhow};
 
+        let created_at: std::time::SystemTime = created_at.into();
         let backup_id = ObjectId::from(backup_id);
 
         // Make sure the backup exists.
         // Integrity checks cannot be deleted; checking this first avoids
         // unnecessary network calls (potentially billed) and computation.
-        let mut backup_reader = match self.backup_store.cached_reader(&backup_id).await {
-            Ok(reader) => reader,
-            Err(ReadObjectError::ObjectNotFound(err)) => {
-                return Err(VerificationError::BackupNotFound(err));
+        let backup_store = (self.backup_store_of(&backup_id).await)
+            .map_err(|err| VerificationError::Other(err.context("Failed finding backup store")))?;
+        match backup_store.exists(&backup_id).await {
+            Ok(true) => {}
+            Ok(false) => {
+                return Err(VerificationError::BackupNotFound(anyhow!(
+                    "Backup `{backup_id}` not found."
+                )));
             }
-            Err(ReadObjectError::Other(err)) => {
+            Err(err) => {
                 return Err(VerificationError::Other(
-                    err.context("Failed opening backup reader"),
+                    err.context("Failed checking if backup exists"),
                 ));
             }
-        };
+        }
 
         // Look for an OpenPGP signature.
         'pgp_sig: {
@@ -124,60 +143,47 @@ impl BackupService {
             let check_name = backup_id.with_extension("sig");
 
             // Read the signature.
-            let signature: &Vec<u8> = {
-                let reader = self
-                    .check_store
-                    .reader_if_not_too_large(&check_name, MAX_PGP_SIGNATURE_LENGTH)
-                    .await;
-
-                let mut reader = match reader {
-                    Ok(reader) => reader,
-                    Err(err @ ReadSizedObjectError::ObjectTooLarge { .. }) => {
-                        tracing::debug!(
-                            "OpenPGP signature file `{check_name}` too large. Skipping. (Source: {err:#})"
-                        );
-                        break 'pgp_sig;
-                    }
-                    Err(ReadSizedObjectError::ReadFailed(
-                        err @ ReadObjectError::ObjectNotFound(_),
-                    )) => {
-                        tracing::debug!(
-                            "OpenPGP signature file `{check_name}` not found. Skipping. (Source: {err:#})"
-                        );
-                        break 'pgp_sig;
-                    }
-                    Err(ReadSizedObjectError::ReadFailed(ReadObjectError::Other(err))) => {
-                        return Err(VerificationError::Other(err.context(format!(
-                            "Failed opening OpenPGP signature reader for `{check_name}`"
-                        ))));
-                    }
-                };
-                report.is_signed = true;
-
-                let mut signature: Vec<u8> = Vec::new();
-
-                let signature_len = std::io::copy(&mut reader, &mut signature)
-                    .context("Failed reading OpenPGP signature")
-                    .map_err(VerificationError::Other)?;
-                debug_assert_ne!(signature_len, 0);
-
-                &*report.signature.insert(signature)
+            let Some(signature) = self
+                .read_check(&check_name, MAX_PGP_SIGNATURE_LENGTH, "OpenPGP signature")
+                .await?
+            else {
+                break 'pgp_sig;
             };
+            report.is_signed = true;
+            debug_assert!(!signature.is_empty());
+            report.signature = Some(signature.clone());
 
-            // Create the verifier, applying the policy at the
+            // Validate the signature, applying the policy at the
             // creation date of the backup.
-            // NOTE: Validates the signature, which avoids reading the
-            //   backup entirely if the signature itself is invalid.
-            let mut verifier =
-                pgp::PgpSignatureVerifier::new(context, signature.as_slice(), created_at.into())
-                    .context(format!("Invalid OpenPGP signature: `{check_name}`"))
-                    .map_err(VerificationError::InvalidSignature)?;
+            // NOTE: This avoids downloading the backup entirely if
+            //   the signature itself is invalid.
+            pgp::PgpSignatureVerifier::new(context, signature.as_slice(), created_at)
+                .context(format!("Invalid OpenPGP signature: `{check_name}`"))
+                .map_err(VerificationError::InvalidSignature)?;
+
+            let backup_path = self.download_backup(&backup_id, on_stage).await?;
 
             // Verify the signature.
-            let pgp_verification_res = verifier.verify_reader(&mut backup_reader);
+            on_stage(RestoreStage::VerifyingSignature, 0.);
+            let (known_signing_keys, pgp_verification_res) = spawn_blocking({
+                let context = Arc::clone(context);
+                let backup_path = Arc::clone(&backup_path);
+
+                move || -> Result<_, VerificationError> {
+                    let mut verifier =
+                        pgp::PgpSignatureVerifier::new(&context, signature.as_slice(), created_at)
+                            .map_err(VerificationError::InvalidSignature)?;
+
+                    let mut backup_reader = open_backup(&backup_path)?;
+                    let res = verifier.verify_reader(&mut backup_reader);
+
+                    Ok((verifier.report().known_signing_keys, res))
+                }
+            })
+            .await?;
+            on_stage(RestoreStage::VerifyingSignature, 1.);
 
-            let pgp_report = verifier.report();
-            report.known_signing_keys = pgp_report.known_signing_keys;
+            report.known_signing_keys = known_signing_keys;
 
             match pgp_verification_res {
                 Ok(()) => report.is_intact = true,
@@ -186,14 +192,6 @@ impl BackupService {
                         tracing::debug!(
                             "All OpenPGP signing keys for `{check_name}` are untrusted. Falling back to integrity checks. (Source: {err:#})"
                         );
-
-                        backup_reader = self
-                            .backup_store
-                            .persist_cache_and_rewind(backup_reader)
-                            .await
-                            .context("Failed saving cache")
-                            .map_err(VerificationError::Other)?;
-
                         break 'pgp_sig;
                     } else {
                         return Err(VerificationError::InvalidSignature(err.context(format!(
@@ -205,8 +203,6 @@ impl BackupService {
 
             tracing::debug!("OpenPGP signature verified.");
 
-            let backup_path = self.backup_store.persist_cache(backup_reader).await;
-
             // Don’t process any other integrity check.
             return Ok(VerificationOutput { backup_path });
         }
@@ -221,66 +217,45 @@ impl BackupService {
             let check_name = backup_id.with_extension("blake3");
 
             // Read stored hash.
-            let expected_hash: Vec<u8> = {
-                let reader = self
-                    .check_store
-                    .reader_if_not_too_large(&check_name, blake3::OUT_LEN as u64)
-                    .await;
-
-                let mut reader = match reader {
-                    Ok(reader) => reader,
-                    Err(err @ ReadSizedObjectError::ObjectTooLarge { .. }) => {
-                        tracing::debug!(
-                            "BLAKE3 checksum file `{check_name}` too large. Skipping. (Source: {err:#})"
-                        );
-                        break 'blake3_check;
-                    }
-                    Err(
-                        err @ ReadSizedObjectError::ReadFailed(ReadObjectError::ObjectNotFound(_)),
-                    ) => {
-                        tracing::debug!(
-                            "BLAKE3 checksum file `{check_name}` not found. Skipping. (Source: {err:#})"
-                        );
-                        break 'blake3_check;
-                    }
-                    Err(ReadSizedObjectError::ReadFailed(ReadObjectError::Other(err))) => {
-                        return Err(VerificationError::Other(err.context(format!(
-                            "Failed opening BLAKE3 checksum reader for `{check_name}`"
-                        ))));
-                    }
-                };
-
-                let mut expected_hash: Vec<u8> = Vec::new();
-                let hash_len = std::io::copy(&mut reader, &mut expected_hash)
-                    .context("Failed reading BLAKE3 checksum")
-                    .map_err(VerificationError::Other)?;
-
-                // Abort early if the hash is invalid.
-                if hash_len != blake3::OUT_LEN as u64 {
-                    return Err(VerificationError::InvalidChecksum(anyhow!(
-                        "Invalid BLAKE3 checksum: `{check_name}`."
-                    )));
-                }
-
-                expected_hash
+            let Some(expected_hash) = self
+                .read_check(&check_name, blake3::OUT_LEN as u64, "BLAKE3 checksum")
+                .await?
+            else {
+                break 'blake3_check;
             };
 
+            // Abort early if the hash is invalid.
+            if expected_hash.len() != blake3::OUT_LEN {
+                return Err(VerificationError::InvalidChecksum(anyhow!(
+                    "Invalid BLAKE3 checksum: `{check_name}`."
+                )));
+            }
+
+            let backup_path = self.download_backup(&backup_id, on_stage).await?;
+
             // Compute the hash again.
-            let computed_hash: blake3::Hash = {
-                let mut verifier = blake3::Hasher::new();
+            on_stage(RestoreStage::VerifyingDigest, 0.);
+            let computed_hash: blake3::Hash = spawn_blocking({
+                let backup_id = backup_id.clone();
+                let backup_path = Arc::clone(&backup_path);
 
-                let copied = std::io::copy(&mut backup_reader, &mut verifier)
-                    .context(format!("Failed reading backup: `{backup_id}`"))
-                    .map_err(VerificationError::Other)?;
-                debug_assert_ne!(copied, 0);
+                move || -> Result<_, VerificationError> {
+                    let mut backup_reader = open_backup(&backup_path)?;
+                    let mut verifier = blake3::Hasher::new();
 
-                let computed_hash = verifier.finalize();
+                    let copied = std::io::copy(&mut backup_reader, &mut verifier)
+                        .context(format!("Failed reading backup: `{backup_id}`"))
+                        .map_err(VerificationError::Other)?;
+                    debug_assert_ne!(copied, 0);
 
-                #[cfg(debug_assertions)]
-                assert_ne!(computed_hash, blake3::Hasher::new().finalize());
+                    Ok(verifier.finalize())
+                }
+            })
+            .await?;
+            on_stage(RestoreStage::VerifyingDigest, 1.);
 
-                computed_hash
-            };
+            #[cfg(debug_assertions)]
+            assert_ne!(computed_hash, blake3::Hasher::new().finalize());
 
             // Verify the checksum.
             if computed_hash.as_bytes() != expected_hash.as_slice() {
@@ -292,8 +267,6 @@ impl BackupService {
             tracing::debug!("BLAKE3 checksum verified.");
             report.is_intact = true;
 
-            let backup_path = self.backup_store.persist_cache(backup_reader).await;
-
             // Don’t process any other integrity check.
             return Ok(VerificationOutput { backup_path });
         }
@@ -305,70 +278,49 @@ impl BackupService {
             let check_name = backup_id.with_extension("sha256");
 
             // Read stored hash.
-            let expected_hash: Vec<u8> = {
-                let reader = self
-                    .check_store
-                    .reader_if_not_too_large(&check_name, Sha256::output_size() as u64)
-                    .await;
-
-                let mut reader = match reader {
-                    Ok(reader) => reader,
-                    Err(err @ ReadSizedObjectError::ObjectTooLarge { .. }) => {
-                        tracing::debug!(
-                            "SHA-256 checksum file `{check_name}` too large. Skipping. (Source: {err:#})"
-                        );
-                        break 'sha256_check;
-                    }
-                    Err(
-                        err @ ReadSizedObjectError::ReadFailed(ReadObjectError::ObjectNotFound(_)),
-                    ) => {
-                        tracing::debug!(
-                            "SHA-256 checksum file `{check_name}` not found. Skipping. (Source: {err:#})"
-                        );
-                        break 'sha256_check;
-                    }
-                    Err(ReadSizedObjectError::ReadFailed(ReadObjectError::Other(err))) => {
-                        return Err(VerificationError::Other(err.context(format!(
-                            "Failed opening SHA-256 checksum reader for `{check_name}`"
-                        ))));
-                    }
-                };
-
-                let mut expected_hash: Vec<u8> = Vec::new();
-                let hash_len = std::io::copy(&mut reader, &mut expected_hash)
-                    .context("Failed reading SHA-256 checksum")
-                    .map_err(VerificationError::Other)?;
-
-                // Abort early if the hash is invalid.
-                if hash_len != Sha256::output_size() as u64 {
-                    return Err(VerificationError::InvalidChecksum(anyhow!(
-                        "Invalid SHA-256 checksum: `{check_name}`."
-                    )));
-                }
-
-                expected_hash
+            let Some(expected_hash) = self
+                .read_check(
+                    &check_name,
+                    Sha256::output_size() as u64,
+                    "SHA-256 checksum",
+                )
+                .await?
+            else {
+                break 'sha256_check;
             };
 
+            // Abort early if the hash is invalid.
+            if expected_hash.len() != Sha256::output_size() {
+                return Err(VerificationError::InvalidChecksum(anyhow!(
+                    "Invalid SHA-256 checksum: `{check_name}`."
+                )));
+            }
+
+            let backup_path = self.download_backup(&backup_id, on_stage).await?;
+
             // Compute the hash again.
-            let computed_hash: sha2::digest::Output<Sha256> = {
-                let mut verifier = digest_io::IoWrapper(Sha256::new());
+            on_stage(RestoreStage::VerifyingDigest, 0.);
+            let computed_hash: sha2::digest::Output<Sha256> = spawn_blocking({
+                let backup_id = backup_id.clone();
+                let backup_path = Arc::clone(&backup_path);
+
+                move || -> Result<_, VerificationError> {
+                    let mut backup_reader = open_backup(&backup_path)?;
+                    let mut verifier = digest_io::IoWrapper(Sha256::new());
 
-                // Read the backup to a temporary file, but also feed it to the
-                // SHA-256 hasher in parallel.
-                {
                     let copied = std::io::copy(&mut backup_reader, &mut verifier)
                         .context(format!("Failed reading backup: `{backup_id}`"))
                         .map_err(VerificationError::Other)?;
                     debug_assert_ne!(copied, 0);
-                }
-
-                let computed_hash = verifier.0.finalize();
 
-                #[cfg(debug_assertions)]
-                assert_ne!(computed_hash, Sha256::new().finalize());
+                    Ok(verifier.0.finalize())
+                }
+            })
+            .await?;
+            on_stage(RestoreStage::VerifyingDigest, 1.);
 
-                computed_hash
-            };
+            #[cfg(debug_assertions)]
+            assert_ne!(computed_hash, Sha256::new().finalize());
 
             // Verify the checksum.
             if computed_hash.as_ref() != expected_hash {
@@ -380,8 +332,6 @@ impl BackupService {
             tracing::debug!("SHA-256 checksum verified.");
             report.is_intact = true;
 
-            let backup_path = self.backup_store.persist_cache(backup_reader).await;
-
             // Don’t process any other integrity check.
             return Ok(VerificationOutput { backup_path });
         }
@@ -390,6 +340,85 @@ impl BackupService {
             "Could not check the integrity of the backup."
         )))
     }
+
+    /// Reads an integrity check in memory.
+    ///
+    /// Returns `None` if the check should be skipped (not found or too large).
+    pub(crate) async fn read_check(
+        &self,
+        check_name: &str,
+        max_size: u64,
+        check_kind: &str,
+    ) -> Result<Option<Vec<u8>>, VerificationError> {
+        use anyhow::Context as _;
+        use tokio::io::AsyncReadExt as _;
+
+        let reader = self
+            .check_store
+            .async_reader_if_not_too_large(check_name, max_size)
+            .await;
+
+        let mut reader = match reader {
+            Ok(reader) => reader,
+            Err(err @ ReadSizedObjectError::ObjectTooLarge { .. }) => {
+                tracing::debug!(
+                    "{check_kind} file `{check_name}` too large. Skipping. (Source: {err:#})"
+                );
+                return Ok(None);
+            }
+            Err(err @ ReadSizedObjectError::ReadFailed(ReadObjectError::ObjectNotFound(_))) => {
+                tracing::debug!(
+                    "{check_kind} file `{check_name}` not found. Skipping. (Source: {err:#})"
+                );
+                return Ok(None);
+            }
+            Err(ReadSizedObjectError::ReadFailed(ReadObjectError::Other(err))) => {
+                return Err(VerificationError::Other(err.context(format!(
+                    "Failed opening {check_kind} reader for `{check_name}`"
+                ))));
+            }
+        };
+
+        let mut check: Vec<u8> = Vec::new();
+        reader
+            .read_to_end(&mut check)
+            .await
+            .context(format!("Failed reading {check_kind}"))
+            .map_err(VerificationError::Other)?;
+
+        Ok(Some(check))
+    }
+
+    /// Downloads the backup in the cache (if not already cached).
+    async fn download_backup(
+        &self,
+        backup_id: &ObjectId,
+        on_stage: &mut (dyn FnMut(RestoreStage, f64) + Send),
+    ) -> Result<Arc<PathGuard>, VerificationError> {
+        let backup_store = (self.backup_store_of(backup_id).await)
+            .map_err(|err| VerificationError::Other(err.context("Failed finding backup store")))?;
+        on_stage(RestoreStage::Downloading, 0.);
+        match backup_store.download(backup_id).await {
+            Ok(backup_path) => {
+                on_stage(RestoreStage::Downloading, 1.);
+                Ok(backup_path)
+            }
+            Err(ReadObjectError::ObjectNotFound(err)) => {
+                Err(VerificationError::BackupNotFound(err))
+            }
+            Err(ReadObjectError::Other(err)) => Err(VerificationError::Other(
+                err.context("Failed downloading backup"),
+            )),
+        }
+    }
+}
+
+fn open_backup(backup_path: &PathGuard) -> Result<std::fs::File, VerificationError> {
+    use anyhow::Context as _;
+
+    std::fs::File::open(backup_path.as_path())
+        .context("Failed opening downloaded backup")
+        .map_err(VerificationError::Other)
 }
 
 pub use self::pgp::*;
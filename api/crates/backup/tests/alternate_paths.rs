@@ -89,6 +89,7 @@ async fn alternate_path_single_store() {
             description: "Test backup",
             blueprint: &blueprint.clone(),
             additional_archive_data: Option::<()>::None,
+            profile: None,
             created_at: now - Duration::from_mins(90),
         };
         service
@@ -171,6 +172,7 @@ async fn alternate_path_unknown_archive_entry_no_error() {
             description: "Test backup",
             blueprint: &blueprint.clone(),
             additional_archive_data: Option::<()>::None,
+            profile: None,
             created_at: now - Duration::from_mins(90),
         };
         service
@@ -189,6 +191,314 @@ async fn alternate_path_unknown_archive_entry_no_error() {
         .unwrap();
 }
 
+/// Ensures that a restoration interrupted before being committed (e.g.
+/// process killed) is rolled back when recovering, and that one interrupted
+/// after being committed is rolled forward.
+#[tokio::test(flavor = "multi_thread")]
+async fn alternate_path_interrupted_restoration() {
+    use prose_backup::RestoreBackupPartialSuccess;
+    use prose_backup::journal::{RecoveryOutcome, RestorePhase};
+
+    let context = init();
+    let TestContext {
+        now,
+        ref test_data_path,
+        ..
+    } = context;
+
+    let journal_path = test_data_path.join("restore-journal.json");
+
+    println!();
+    let backup_config = {
+        let journal_path = journal_path.display().to_string();
+        let mut toml = toml! {
+            [storage]
+            provider = "fs"
+            fs.directory = "store"
+
+            [restoration]
+            journal_path = journal_path
+        };
+
+        map_storage_directories_in_test_dir(&mut toml, test_data_path).unwrap();
+
+        BackupConfig::try_from(toml).unwrap()
+    };
+    tracing::debug!("Parsed config: {backup_config:#?}");
+
+    let blueprint = ArchiveBlueprint::new(
+        1,
+        [
+            ("foo-data", "foo"),
+            ("bar-data", "bar"),
+        ],
+    )
+    .src_relative_to(&test_data_path);
+
+    create_files(
+        &test_data_path,
+        [
+            "foo/", "foo/a", "bar/", "bar/a",
+        ],
+    )
+    .unwrap();
+
+    let blueprints = BlueprintsBuilder::new().insert(blueprint.clone()).build();
+
+    let service = BackupService::from_config_custom(
+        &backup_config,
+        ArchivingContext { blueprints },
+        RestorationContext { migrations: vec![] },
+        |_| unreachable!(),
+        || -> openpgp::policy::StandardPolicy { unreachable!() },
+    )
+    .unwrap();
+
+    let original_data = unique_hex().unwrap();
+    let foo_a = test_data_path.join("foo/a");
+    std::fs::write(&foo_a, &original_data).unwrap();
+
+    println!();
+    let CreateBackupSuccess {
+        output: creation_output,
+        ..
+    } = {
+        let command = CreateBackupCommand {
+            prefix: "prose-backup",
+            description: "Test backup",
+            blueprint: &blueprint.clone(),
+            additional_archive_data: Option::<()>::None,
+            profile: None,
+            created_at: now - Duration::from_mins(90),
+        };
+        service
+            .create_backup(command, &mut NoopEventHandler)
+            .await
+            .unwrap()
+    };
+    let CreateBackupOutput { backup_id, .. } = creation_output;
+
+    // Restore only `foo`, so `bar-data` is additional data and the
+    // restoration isn’t committed before we get control back.
+    let mut foo_blueprint = blueprint.clone();
+    foo_blueprint.paths.truncate(1);
+
+    for commit in [false, true] {
+        std::fs::write(&foo_a, "overriden").unwrap();
+
+        println!();
+        let RestoreBackupPartialSuccess {
+            mut restoration_output,
+            ..
+        } = service
+            .restore_backup_partial(&backup_id, &foo_blueprint, &mut NoopEventHandler)
+            .await
+            .unwrap();
+        let (_tmp_dir, mut revert_guard) = restoration_output.additional_data.take().unwrap();
+        assert_eq!(std::fs::read_to_string(&foo_a).unwrap(), original_data);
+
+        if commit {
+            revert_guard.defuse();
+        }
+
+        // Simulate a crash (nothing is reverted nor cleaned up).
+        std::mem::forget(revert_guard);
+        assert!(journal_path.exists());
+        assert!(test_data_path.join("foo.bak").exists());
+
+        println!();
+        let recovery = service
+            .recover_interrupted_restoration()
+            .await
+            .unwrap()
+            .expect("Restoration should have been recovered");
+        assert_eq!(recovery.backup_id, backup_id.to_string());
+
+        if commit {
+            assert_eq!(recovery.phase, RestorePhase::Committed);
+            assert_eq!(recovery.outcome, RecoveryOutcome::RolledForward);
+            assert_eq!(std::fs::read_to_string(&foo_a).unwrap(), original_data);
+        } else {
+            assert_eq!(recovery.phase, RestorePhase::NewMovedIn);
+            assert_eq!(recovery.outcome, RecoveryOutcome::RolledBack);
+            assert_eq!(std::fs::read_to_string(&foo_a).unwrap(), "overriden");
+        }
+
+        assert!(!journal_path.exists());
+        assert!(!test_data_path.join("foo.bak").exists());
+
+        // Nothing left to recover.
+        let recovery = service.recover_interrupted_restoration().await.unwrap();
+        assert!(recovery.is_none(), "{recovery:?}");
+    }
+}
+
+/// Ensures the data replaced by a restoration interrupted after being
+/// committed is still kept in the safety snapshot, and that an interrupted
+/// undo doesn’t lose it.
+#[tokio::test(flavor = "multi_thread")]
+async fn alternate_path_interrupted_undo() {
+    use prose_backup::RestoreBackupPartialSuccess;
+    use prose_backup::journal::{RecoveryOutcome, RestorePhase};
+
+    let context = init();
+    let TestContext {
+        now,
+        ref test_data_path,
+        ..
+    } = context;
+
+    let journal_path = test_data_path.join("restore-journal.json");
+    let safety_snapshot_dir = test_data_path.join("safety-snapshot");
+
+    println!();
+    let backup_config = {
+        let journal_path = journal_path.display().to_string();
+        let safety_snapshot_dir = safety_snapshot_dir.display().to_string();
+        let mut toml = toml! {
+            [storage]
+            provider = "fs"
+            fs.directory = "store"
+
+            [restoration]
+            journal_path = journal_path
+
+            [safety_snapshot]
+            enabled = true
+            directory = safety_snapshot_dir
+        };
+
+        map_storage_directories_in_test_dir(&mut toml, test_data_path).unwrap();
+
+        BackupConfig::try_from(toml).unwrap()
+    };
+    tracing::debug!("Parsed config: {backup_config:#?}");
+
+    let blueprint = ArchiveBlueprint::new(
+        1,
+        [
+            ("foo-data", "foo"),
+            ("bar-data", "bar"),
+        ],
+    )
+    .src_relative_to(&test_data_path);
+
+    create_files(
+        &test_data_path,
+        [
+            "foo/", "foo/a", "bar/", "bar/a",
+        ],
+    )
+    .unwrap();
+
+    let blueprints = BlueprintsBuilder::new().insert(blueprint.clone()).build();
+
+    let service = BackupService::from_config_custom(
+        &backup_config,
+        ArchivingContext { blueprints },
+        RestorationContext { migrations: vec![] },
+        |_| unreachable!(),
+        || -> openpgp::policy::StandardPolicy { unreachable!() },
+    )
+    .unwrap();
+
+    let foo_a = test_data_path.join("foo/a");
+    std::fs::write(&foo_a, "foo v1").unwrap();
+
+    println!();
+    let CreateBackupSuccess {
+        output: creation_output,
+        ..
+    } = {
+        let command = CreateBackupCommand {
+            prefix: "prose-backup",
+            description: "Test backup",
+            blueprint: &blueprint.clone(),
+            additional_archive_data: Option::<()>::None,
+            profile: None,
+            created_at: now - Duration::from_mins(90),
+        };
+        service
+            .create_backup(command, &mut NoopEventHandler)
+            .await
+            .unwrap()
+    };
+    let CreateBackupOutput { backup_id, .. } = creation_output;
+
+    // Restore only `foo`, so `bar-data` is additional data and the
+    // restoration isn’t committed before we get control back.
+    let mut foo_blueprint = blueprint.clone();
+    foo_blueprint.paths.truncate(1);
+
+    std::fs::write(&foo_a, "foo v2").unwrap();
+
+    println!();
+    let RestoreBackupPartialSuccess {
+        mut restoration_output,
+        ..
+    } = service
+        .restore_backup_partial(&backup_id, &foo_blueprint, &mut NoopEventHandler)
+        .await
+        .unwrap();
+    let (_tmp_dir, mut revert_guard) = restoration_output.additional_data.take().unwrap();
+    revert_guard.defuse();
+
+    // Simulate a crash after the restoration was committed.
+    std::mem::forget(revert_guard);
+
+    println!();
+    let recovery = service
+        .recover_interrupted_restoration()
+        .await
+        .unwrap()
+        .expect("Restoration should have been recovered");
+    assert_eq!(recovery.outcome, RecoveryOutcome::RolledForward);
+    assert_eq!(std::fs::read_to_string(&foo_a).unwrap(), "foo v1");
+    assert!(!test_data_path.join("foo.bak").exists());
+    // NOTE: Replaced data was kept instead of being deleted.
+    assert!(safety_snapshot_dir.join("manifest.json").exists());
+
+    for commit in [false, true] {
+        println!();
+        let (safety_snapshot, mut revert_guard) =
+            service.undo_last_restore_partial().await.unwrap();
+        assert_eq!(safety_snapshot.backup_id, backup_id.to_string());
+        assert_eq!(std::fs::read_to_string(&foo_a).unwrap(), "foo v2");
+
+        if commit {
+            revert_guard.defuse();
+        }
+
+        // Simulate a crash (nothing is reverted nor cleaned up).
+        std::mem::forget(revert_guard);
+        assert!(journal_path.exists());
+
+        println!();
+        let recovery = service
+            .recover_interrupted_restoration()
+            .await
+            .unwrap()
+            .expect("Undo should have been recovered");
+        assert_eq!(recovery.backup_id, backup_id.to_string());
+
+        if commit {
+            assert_eq!(recovery.phase, RestorePhase::Committed);
+            assert_eq!(recovery.outcome, RecoveryOutcome::RolledForward);
+            assert_eq!(std::fs::read_to_string(&foo_a).unwrap(), "foo v2");
+            assert!(!safety_snapshot_dir.exists());
+        } else {
+            assert_eq!(recovery.phase, RestorePhase::NewMovedIn);
+            assert_eq!(recovery.outcome, RecoveryOutcome::RolledBack);
+            assert_eq!(std::fs::read_to_string(&foo_a).unwrap(), "foo v1");
+            // NOTE: The restoration can still be undone.
+            assert!(safety_snapshot_dir.join("manifest.json").exists());
+        }
+
+        assert!(!journal_path.exists());
+        assert!(!test_data_path.join("foo.bak").exists());
+    }
+}
+
 /// Ensures the library falls back to integrity checking if the signature
 /// comes from an unknown key.
 ///
@@ -263,6 +573,7 @@ async fn alternate_path_lost_signing_key() {
             description: "Test backup",
             blueprint: &blueprint.clone(),
             additional_archive_data: Option::<()>::None,
+            profile: None,
             created_at: now - Duration::from_mins(90),
         };
         service
@@ -287,10 +598,10 @@ async fn alternate_path_lost_signing_key() {
     }
 
     let mut pgp_verification_context =
-        std::mem::take(&mut service.verification_context.pgp).unwrap();
+        Arc::into_inner(std::mem::take(&mut service.verification_context.pgp).unwrap()).unwrap();
     assert_eq!(pgp_verification_context.certs.len(), 1);
     pgp_verification_context.certs = Arc::new(Vec::with_capacity(0));
-    service.verification_context.pgp = Some(pgp_verification_context);
+    service.verification_context.pgp = Some(Arc::new(pgp_verification_context));
 
     {
         println!();
@@ -366,6 +677,7 @@ async fn alternate_path_change_hashing_algorithm() {
                 description: "Test backup",
                 blueprint,
                 additional_archive_data: Option::<()>::None,
+                profile: None,
                 created_at,
             };
             service
@@ -553,6 +865,7 @@ async fn alternate_path_transitive_migrations() {
             description: "Test backup",
             blueprint: &blueprints.get(&2).unwrap(),
             additional_archive_data: Option::<()>::None,
+            profile: None,
             created_at: now - Duration::from_mins(90),
         };
         service
@@ -668,6 +981,7 @@ async fn alternate_path_openpgp_encrypted_secret_key() {
                 description: "Test backup",
                 blueprint: &blueprint.clone(),
                 additional_archive_data: Option::<()>::None,
+                profile: None,
                 created_at: now - Duration::from_mins(90),
             },
             &mut NoopEventHandler,
@@ -680,10 +994,7 @@ async fn alternate_path_openpgp_encrypted_secret_key() {
     // Test error on signing if passhrase missing.
     {
         println!();
-        let pw = service
-            .signing_context
-            .pgp
-            .as_mut()
+        let pw = Arc::get_mut(service.signing_context.pgp.as_mut().unwrap())
             .unwrap()
             .passphrases
             .remove(&cert.fingerprint())
@@ -696,6 +1007,7 @@ async fn alternate_path_openpgp_encrypted_secret_key() {
                     description: "Test backup 2",
                     blueprint: &blueprint.clone(),
                     additional_archive_data: Option::<()>::None,
+                    profile: None,
                     created_at: now - Duration::from_mins(90),
                 },
                 &mut NoopEventHandler,
@@ -707,10 +1019,7 @@ async fn alternate_path_openpgp_encrypted_secret_key() {
             "Cannot sign".to_owned()
         );
 
-        service
-            .signing_context
-            .pgp
-            .as_mut()
+        Arc::get_mut(service.signing_context.pgp.as_mut().unwrap())
             .unwrap()
             .passphrases
             .insert(cert.fingerprint(), pw);
@@ -719,10 +1028,7 @@ async fn alternate_path_openpgp_encrypted_secret_key() {
     // Test error on restoration if passhrase missing.
     {
         println!();
-        let pw = service
-            .decryption_context
-            .pgp
-            .as_mut()
+        let pw = Arc::get_mut(service.decryption_context.pgp.as_mut().unwrap())
             .unwrap()
             .passphrases
             .remove(&cert.fingerprint())
@@ -741,10 +1047,7 @@ async fn alternate_path_openpgp_encrypted_secret_key() {
             leading to it being dismissed."
         );
 
-        service
-            .decryption_context
-            .pgp
-            .as_mut()
+        Arc::get_mut(service.decryption_context.pgp.as_mut().unwrap())
             .unwrap()
             .passphrases
             .insert(cert.fingerprint(), pw);
@@ -5,8 +5,10 @@
 
 use std::collections::HashMap;
 
-use prose_backup::archiving::ExtractionReport;
+use prose_backup::archiving::{ExtractionReport, UnappliedAttribute};
 use prose_backup::decryption::DecryptionReport;
+use prose_backup::dtos::CreateBackupProgressDto;
+use prose_backup::restore_report::RestoreStageEventDto;
 use prose_backup::stats::{ReadStats, StreamStats};
 use prose_backup::stores::ObjectId;
 use prose_backup::{BackupId, CreateBackupEventHandler, RestoreBackupEventHandler};
@@ -19,6 +21,7 @@ pub struct DebugCreateBackupEventHandler {
     pub effective_archive_size: u64,
     pub object_sizes: HashMap<ObjectId, u64>,
     pub upload_durations: Vec<(ObjectId, std::time::Duration)>,
+    pub progress_events: Vec<CreateBackupProgressDto>,
 }
 
 impl CreateBackupEventHandler for DebugCreateBackupEventHandler {
@@ -35,6 +38,10 @@ impl CreateBackupEventHandler for DebugCreateBackupEventHandler {
         *self.object_sizes.entry(object_id.clone()).or_default() += uploaded_bytes as u64;
     }
 
+    fn on_progress(&mut self, _backup_id: &BackupId, progress: &CreateBackupProgressDto) {
+        self.progress_events.push(progress.clone());
+    }
+
     fn on_backup_uploaded(
         &mut self,
         backup_id: &BackupId,
@@ -63,6 +70,8 @@ pub struct DebugExtractBackupEventHandler {
     pub decryption_stats: ReadStats,
     pub decompression_stats: ReadStats,
     pub extracted_bytes_count: u64,
+    pub unapplied_attributes: Vec<UnappliedAttribute>,
+    pub stage_events: Vec<RestoreStageEventDto>,
 }
 
 impl RestoreBackupEventHandler for DebugExtractBackupEventHandler {
@@ -86,5 +95,10 @@ impl RestoreBackupEventHandler for DebugExtractBackupEventHandler {
 
     fn on_extraction_finished(&mut self, _backup_id: &BackupId, report: ExtractionReport) {
         self.extracted_bytes_count = report.extracted_bytes_count;
+        self.unapplied_attributes = report.unapplied_attributes;
+    }
+
+    fn on_stage(&mut self, _backup_id: &BackupId, event: &RestoreStageEventDto) {
+        self.stage_events.push(event.clone());
     }
 }
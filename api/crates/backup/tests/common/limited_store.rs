@@ -3,34 +3,28 @@
 // Copyright: 2026, Rémi Bardon <remi@remibardon.name>
 // License: Mozilla Public License v2.0 (MPL v2.0)
 
-use prose_backup::stores::{Finalizable, ObjectWriter, prelude::*};
+use std::sync::Arc;
+
+use prose_backup::stores::{
+    AsyncFinalizable, AsyncObjectWriter, Finalizable, ObjectWriter, prelude::*,
+};
 
 /// A store that only allows writing a certain amount of bytes. This is useful
 /// to test failure cases.
 #[derive(Debug)]
 pub struct LimitedStore {
-    inner: Box<dyn ObjectStore>,
+    inner: Arc<dyn ObjectStore>,
     limit: u64,
     fail_finalize: bool,
 }
 
 impl LimitedStore {
-    pub fn wrap(store: &mut Box<dyn ObjectStore>, limit: u64, fail_finalize: bool) {
-        unsafe {
-            // Move out without dropping.
-            let inner = std::ptr::read(store);
-
-            // Construct new value.
-            let new = Box::new(Self {
-                inner,
-                limit,
-                fail_finalize,
-            });
-
-            // Write the new value WITHOUT dropping the destination,
-            // since we already moved out of it above.
-            std::ptr::write(store, new);
-        }
+    pub fn wrap(store: &mut Arc<dyn ObjectStore>, limit: u64, fail_finalize: bool) {
+        *store = Arc::new(Self {
+            inner: Arc::clone(store),
+            limit,
+            fail_finalize,
+        });
     }
 }
 
@@ -47,10 +41,47 @@ impl ObjectStore for LimitedStore {
         }))
     }
 
+    async fn writer_if_absent(&self, key: &str) -> Result<Box<DynObjectWriter>, anyhow::Error> {
+        let inner = self.inner.writer_if_absent(key).await?;
+        Ok(Box::new(LimitedWriter {
+            inner,
+            progress: 0,
+            limit: self.limit,
+            fail_finalize: self.fail_finalize,
+        }))
+    }
+
+    async fn async_writer(&self, key: &str) -> Result<Box<DynAsyncObjectWriter>, anyhow::Error> {
+        let inner = self.inner.async_writer(key).await?;
+        Ok(Box::new(LimitedAsyncWriter {
+            inner,
+            progress: 0,
+            limit: self.limit,
+            fail_finalize: self.fail_finalize,
+        }))
+    }
+
+    async fn async_writer_if_absent(
+        &self,
+        key: &str,
+    ) -> Result<Box<DynAsyncObjectWriter>, anyhow::Error> {
+        let inner = self.inner.async_writer_if_absent(key).await?;
+        Ok(Box::new(LimitedAsyncWriter {
+            inner,
+            progress: 0,
+            limit: self.limit,
+            fail_finalize: self.fail_finalize,
+        }))
+    }
+
     async fn reader(&self, key: &str) -> Result<Box<DynObjectReader>, ReadObjectError> {
         unimplemented!()
     }
 
+    async fn async_reader(&self, key: &str) -> Result<Box<DynAsyncObjectReader>, ReadObjectError> {
+        unimplemented!()
+    }
+
     async fn exists(&self, key: &str) -> Result<bool, anyhow::Error> {
         unimplemented!()
     }
@@ -124,3 +155,62 @@ impl Finalizable for LimitedWriter {
 }
 
 impl ObjectWriter for LimitedWriter {}
+
+struct LimitedAsyncWriter {
+    inner: Box<DynAsyncObjectWriter>,
+    progress: u64,
+    limit: u64,
+    fail_finalize: bool,
+}
+
+impl tokio::io::AsyncWrite for LimitedAsyncWriter {
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        match std::task::ready!(std::pin::Pin::new(&mut this.inner).poll_write(cx, buf)) {
+            ok @ Ok(len) => {
+                // SAFETY: We’ll never hit `u64::MAX` in tests.
+                this.progress += len as u64;
+
+                if this.progress > this.limit {
+                    std::task::Poll::Ready(Err(std::io::Error::other(
+                        "LimitedWriter limit reached.",
+                    )))
+                } else {
+                    std::task::Poll::Ready(ok)
+                }
+            }
+            err => std::task::Poll::Ready(err),
+        }
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::pin::Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::pin::Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncFinalizable for LimitedAsyncWriter {
+    async fn finalize(self: Box<Self>) -> Result<(), anyhow::Error> {
+        if self.fail_finalize {
+            Err(anyhow::Error::msg("LimitedWriter fail."))
+        } else {
+            self.inner.finalize().await
+        }
+    }
+}
+
+impl AsyncObjectWriter for LimitedAsyncWriter {}
@@ -87,6 +87,7 @@ async fn compatibility_openpgp() {
             description: "Test backup",
             blueprint: &blueprint.clone(),
             additional_archive_data: Option::<()>::None,
+            profile: None,
             created_at: now - Duration::from_mins(90),
         };
         service
@@ -148,3 +149,266 @@ async fn compatibility_openpgp() {
         sq_packet_dump(&signature_path, &cert_path);
     }
 }
+
+/// Ensures that, when `archive.deterministic` is enabled, backups of
+/// identical trees are identical (byte for byte), regardless of the order
+/// in which files were created or when backups were created.
+#[tokio::test(flavor = "multi_thread")]
+async fn reproducible_archives() {
+    let context = init();
+    let TestContext {
+        now,
+        ref test_data_path,
+        ..
+    } = context;
+
+    println!();
+    let backup_config = {
+        let mut toml = toml! {
+            [archive]
+            deterministic = true
+            max_mtime = 0
+
+            [storage]
+            provider = "fs"
+            fs.directory = "store"
+        };
+
+        map_storage_directories_in_test_dir(&mut toml, test_data_path).unwrap();
+
+        BackupConfig::try_from(toml)
+    }
+    .unwrap();
+    tracing::info!("Parsed config: {backup_config:#?}");
+
+    // Create two identical trees, in a different order.
+    let files = [
+        ("foo/a", "a"),
+        ("foo/b/c", "c"),
+        ("foo/b/d", "d"),
+        ("foo/e", "e"),
+    ];
+    for (root, files) in [
+        ("first", Vec::from_iter(files)),
+        ("second", Vec::from_iter(files.into_iter().rev())),
+    ] {
+        for (path, contents) in files {
+            let path = test_data_path.join(root).join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+    }
+
+    let first_blueprint =
+        ArchiveBlueprint::new(1, [("foo-data", "first/foo")]).src_relative_to(&test_data_path);
+    let second_blueprint =
+        ArchiveBlueprint::new(1, [("foo-data", "second/foo")]).src_relative_to(&test_data_path);
+
+    let blueprints = BlueprintsBuilder::new()
+        .insert(first_blueprint.clone())
+        .build();
+
+    let service = BackupService::from_config_custom(
+        &backup_config,
+        ArchivingContext { blueprints },
+        RestorationContext { migrations: vec![] },
+        |_| unreachable!(),
+        || -> openpgp::policy::StandardPolicy { unreachable!() },
+    )
+    .unwrap();
+
+    println!();
+    let mut outputs: Vec<CreateBackupOutput> = Vec::with_capacity(2);
+    for (blueprint, description, created_at) in [
+        (
+            &first_blueprint,
+            "First backup",
+            now - Duration::from_mins(90),
+        ),
+        (
+            &second_blueprint,
+            "Second backup",
+            now - Duration::from_mins(30),
+        ),
+    ] {
+        let command = CreateBackupCommand {
+            prefix: "prose-backup",
+            description,
+            blueprint,
+            additional_archive_data: Option::<()>::None,
+            profile: None,
+            created_at,
+        };
+        let CreateBackupSuccess { output, .. } = service
+            .create_backup(command, &mut NoopEventHandler)
+            .await
+            .unwrap();
+        outputs.push(output);
+    }
+
+    let read_object = |id: &dyn std::fmt::Display| -> Vec<u8> {
+        std::fs::read(test_data_path.join("store").join(id.to_string())).unwrap()
+    };
+
+    let [first, second] = outputs.as_slice() else {
+        unreachable!()
+    };
+    assert_ne!(first.backup_id.to_string(), second.backup_id.to_string());
+
+    // Check digests.
+    assert_eq!(first.digest_ids.len(), second.digest_ids.len());
+    for (first_digest_id, second_digest_id) in first.digest_ids.iter().zip(&second.digest_ids) {
+        assert_eq!(read_object(first_digest_id), read_object(second_digest_id));
+    }
+
+    // Check backups.
+    assert_eq!(
+        read_object(&first.backup_id),
+        read_object(&second.backup_id)
+    );
+}
+
+/// Ensures that, when `archive.deterministic` is enabled, modification times
+/// older than `archive.max_mtime` are kept as is (not clamped) and backups of
+/// identical trees are still identical.
+#[tokio::test(flavor = "multi_thread")]
+async fn reproducible_archives_unclamped_mtimes() {
+    let context = init();
+    let TestContext {
+        now,
+        ref test_data_path,
+        ..
+    } = context;
+
+    // NOTE: Older than `max_mtime` (2027-01-15).
+    const MTIME: u64 = 1_700_000_000;
+
+    println!();
+    let backup_config = {
+        let mut toml = toml! {
+            [archive]
+            deterministic = true
+            max_mtime = 1800000000
+
+            [storage]
+            provider = "fs"
+            fs.directory = "store"
+        };
+        map_storage_directories_in_test_dir(&mut toml, test_data_path).unwrap();
+
+        BackupConfig::try_from(toml)
+    }
+    .unwrap();
+    tracing::info!("Parsed config: {backup_config:#?}");
+
+    // Create three identical trees, in a different order. The first two have
+    // the same modification times, the third one was modified later (but
+    // still before `max_mtime`).
+    let files = [
+        ("foo/a", "a"),
+        ("foo/b/c", "c"),
+        ("foo/b/d", "d"),
+        ("foo/e", "e"),
+    ];
+    for (root, files, mtime) in [
+        ("first", Vec::from_iter(files), MTIME),
+        ("second", Vec::from_iter(files.into_iter().rev()), MTIME),
+        ("third", Vec::from_iter(files), MTIME + 60),
+    ] {
+        for (path, contents) in files {
+            let path = test_data_path.join(root).join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+
+        // NOTE: Set after all files are created, as creating a file changes
+        //   the modification time of its parent directory.
+        let mtime = std::time::UNIX_EPOCH + Duration::from_secs(mtime);
+        for path in [
+            "foo", "foo/a", "foo/b", "foo/b/c", "foo/b/d", "foo/e",
+        ] {
+            std::fs::File::open(test_data_path.join(root).join(path))
+                .unwrap()
+                .set_modified(mtime)
+                .unwrap();
+        }
+    }
+
+    let blueprint = |root: &str| {
+        ArchiveBlueprint::new(1, [("foo-data", format!("{root}/foo"))])
+            .src_relative_to(&test_data_path)
+    };
+    let first_blueprint = blueprint("first");
+    let second_blueprint = blueprint("second");
+    let third_blueprint = blueprint("third");
+
+    let blueprints = BlueprintsBuilder::new()
+        .insert(first_blueprint.clone())
+        .build();
+
+    let service = BackupService::from_config_custom(
+        &backup_config,
+        ArchivingContext { blueprints },
+        RestorationContext { migrations: vec![] },
+        |_| unreachable!(),
+        || -> openpgp::policy::StandardPolicy { unreachable!() },
+    )
+    .unwrap();
+
+    println!();
+    let mut outputs: Vec<CreateBackupOutput> = Vec::with_capacity(3);
+    for (blueprint, description, created_at) in [
+        (
+            &first_blueprint,
+            "First backup",
+            now - Duration::from_mins(90),
+        ),
+        (
+            &second_blueprint,
+            "Second backup",
+            now - Duration::from_mins(60),
+        ),
+        (
+            &third_blueprint,
+            "Third backup",
+            now - Duration::from_mins(30),
+        ),
+    ] {
+        let command = CreateBackupCommand {
+            prefix: "prose-backup",
+            description,
+            blueprint,
+            additional_archive_data: Option::<()>::None,
+            profile: None,
+            created_at,
+        };
+        let CreateBackupSuccess { output, .. } = service
+            .create_backup(command, &mut NoopEventHandler)
+            .await
+            .unwrap();
+        outputs.push(output);
+    }
+
+    let read_object = |id: &dyn std::fmt::Display| -> Vec<u8> {
+        std::fs::read(test_data_path.join("store").join(id.to_string())).unwrap()
+    };
+
+    let [
+        first,
+        second,
+        third,
+    ] = outputs.as_slice()
+    else {
+        unreachable!()
+    };
+
+    // Identical trees with identical modification times give identical
+    // backups.
+    assert_eq!(
+        read_object(&first.backup_id),
+        read_object(&second.backup_id)
+    );
+
+    // Modification times weren’t clamped, so they make a difference.
+    assert_ne!(read_object(&first.backup_id), read_object(&third.backup_id));
+}
@@ -62,6 +62,7 @@ async fn error_path_backup_missing_file() {
             description: "Test backup",
             blueprint: &blueprint.clone(),
             additional_archive_data: Option::<()>::None,
+            profile: None,
             created_at: now - Duration::from_mins(90),
         };
         service.create_backup(command, &mut NoopEventHandler).await
@@ -129,6 +130,7 @@ async fn error_path_restore_missing_file() {
             description: "Test backup",
             blueprint: &blueprint.clone(),
             additional_archive_data: Option::<()>::None,
+            profile: None,
             created_at: now - Duration::from_mins(90),
         };
         service
@@ -200,6 +202,7 @@ async fn error_path_upload_fail() {
                 description: "Test backup",
                 blueprint: &blueprint.clone(),
                 additional_archive_data: Option::<()>::None,
+                profile: None,
                 created_at: now - Duration::from_mins(90),
             },
             &mut NoopEventHandler,
@@ -210,7 +213,7 @@ async fn error_path_upload_fail() {
     tracing::info!("Error: {err}");
     assert_eq!(
         err.as_str(),
-        "Failed uploading backup integrity check: `std::io::copy` failed: LimitedWriter limit reached."
+        "Failed uploading backup integrity check: `tokio::io::copy` failed: LimitedWriter limit reached."
     );
 
     let files = std::fs::read_dir(test_data_path.join("store"))
@@ -219,3 +222,36 @@ async fn error_path_upload_fail() {
         .collect::<Vec<_>>();
     assert_eq!(files.len(), 0, "{files:#?}");
 }
+
+/// Tests that colliding object keys never overwrite existing data, even if
+/// the store is configured to overwrite objects.
+#[tokio::test(flavor = "multi_thread")]
+async fn error_path_object_already_exists() {
+    use std::io::Write as _;
+
+    use prose_backup::stores::{Finalizable as _, FsStore, prelude::*};
+
+    let context = init();
+    let TestContext {
+        ref test_data_path, ..
+    } = context;
+
+    std::fs::create_dir_all(test_data_path).unwrap();
+
+    let store = FsStore {
+        directory: test_data_path.to_owned(),
+        overwrite: true,
+        mode: 0o600,
+    };
+
+    let mut writer = store.writer_if_absent("object").await.unwrap();
+    writer.write_all(b"foo").unwrap();
+    writer.finalize().unwrap();
+
+    let res = store.writer_if_absent("object").await;
+    let err = res.err().expect("Second write should fail");
+    assert!(ObjectAlreadyExists::is(&err), "{err:#}");
+
+    let contents = std::fs::read_to_string(test_data_path.join("object")).unwrap();
+    assert_eq!(contents, "foo");
+}
@@ -167,6 +167,7 @@ async fn happy_path_atomic_restore() {
             description: "Test backup",
             blueprint: &blueprint.clone(),
             additional_archive_data: Option::<()>::None,
+            profile: None,
             created_at: now - Duration::from_mins(90),
         };
         service
@@ -293,6 +294,7 @@ async fn happy_path_file_permissions() {
             description: "Test backup",
             blueprint: &blueprint.clone(),
             additional_archive_data: Option::<()>::None,
+            profile: None,
             created_at: now - Duration::from_mins(90),
         };
         service
@@ -324,6 +326,1493 @@ async fn happy_path_file_permissions() {
     );
 }
 
+/// Tests that, when opted into, backup restorations restore extended
+/// attributes, hard links and sparse files.
+#[tokio::test(flavor = "multi_thread")]
+async fn happy_path_file_attributes() {
+    use std::os::unix::fs::MetadataExt as _;
+
+    let context = init();
+    let TestContext {
+        now,
+        ref test_data_path,
+        ..
+    } = context;
+
+    println!();
+    let backup_config = {
+        let mut toml = toml! {
+            [archive]
+            xattrs = true
+            acls = true
+            sparse = true
+            hardlinks = true
+
+            // NOTE: Compression would hide holes being archived as zeroes.
+            [compression]
+            algorithm = "off"
+
+            [storage]
+            provider = "fs"
+            fs.directory = "store"
+        };
+
+        map_storage_directories_in_test_dir(&mut toml, test_data_path).unwrap();
+
+        BackupConfig::try_from(toml).unwrap()
+    };
+    tracing::debug!("Parsed config: {backup_config:#?}");
+
+    let blueprint =
+        ArchiveBlueprint::new(1, [("foo-data", "foo")]).src_relative_to(&test_data_path);
+
+    create_files(&test_data_path, ["foo/", "foo/a"]).unwrap();
+
+    // A sparse file with data in the middle of two holes.
+    let foo_sparse = test_data_path.join("foo/sparse");
+    let sparse_len: u64 = 4 * 1024 * 1024;
+    {
+        use std::io::{Seek as _, Write as _};
+
+        let mut file = std::fs::File::create(&foo_sparse).unwrap();
+        file.set_len(sparse_len).unwrap();
+        file.seek(std::io::SeekFrom::Start(sparse_len / 2)).unwrap();
+        file.write_all(b"data").unwrap();
+    }
+    let sparse_contents = std::fs::read(&foo_sparse).unwrap();
+    // NOTE: Not all filesystems support sparse files.
+    let is_sparse = |metadata: &std::fs::Metadata| metadata.blocks() * 512 < metadata.len();
+    let sparse_supported = is_sparse(&std::fs::metadata(&foo_sparse).unwrap());
+    if !sparse_supported {
+        tracing::warn!("Sparse files not supported.");
+    }
+
+    // A hard link.
+    let foo_a = test_data_path.join("foo/a");
+    let foo_link = test_data_path.join("foo/link");
+    std::fs::hard_link(&foo_a, &foo_link).unwrap();
+
+    // An extended attribute.
+    // NOTE: Not all filesystems support user extended attributes.
+    let xattrs_supported = xattr::set(&foo_a, "user.prose.test", b"value")
+        .inspect_err(|err| tracing::warn!("Extended attributes not supported: {err}"))
+        .is_ok();
+
+    let blueprints = BlueprintsBuilder::new().insert(blueprint.clone()).build();
+
+    let service = BackupService::from_config_custom(
+        &backup_config,
+        ArchivingContext { blueprints },
+        RestorationContext { migrations: vec![] },
+        |_| unreachable!(),
+        || -> openpgp::policy::StandardPolicy { unreachable!() },
+    )
+    .unwrap();
+
+    println!();
+    let CreateBackupSuccess {
+        output: creation_output,
+        ..
+    } = {
+        let command = CreateBackupCommand {
+            prefix: "prose-backup",
+            description: "Test backup",
+            blueprint: &blueprint.clone(),
+            additional_archive_data: Option::<()>::None,
+            profile: None,
+            created_at: now - Duration::from_mins(90),
+        };
+        service
+            .create_backup(command, &mut NoopEventHandler)
+            .await
+            .unwrap()
+    };
+    let CreateBackupOutput { backup_id, .. } = creation_output;
+
+    // Holes must not be stored in the backup.
+    let backup_len = std::fs::metadata(test_data_path.join("store").join(backup_id.to_string()))
+        .unwrap()
+        .len();
+    assert!(backup_len < sparse_len / 2, "Backup is {backup_len}B.");
+
+    // Restore the backup.
+    println!();
+    let mut event_handler = DebugExtractBackupEventHandler::default();
+    let res = service
+        .restore_backup(&backup_id, &blueprint, &mut event_handler)
+        .await;
+    assert!(res.is_ok(), "Error: {:#?}", res.err().unwrap());
+    assert!(
+        event_handler.unapplied_attributes.is_empty(),
+        "Unapplied attributes: {:#?}",
+        event_handler.unapplied_attributes
+    );
+
+    // Test that the sparse file was restored (with its holes).
+    assert_eq!(std::fs::read(&foo_sparse).unwrap(), sparse_contents);
+    if sparse_supported {
+        let metadata = std::fs::metadata(&foo_sparse).unwrap();
+        assert!(
+            is_sparse(&metadata),
+            "Restored file is not sparse ({blocks} blocks for {len}B).",
+            blocks = metadata.blocks(),
+            len = metadata.len(),
+        );
+    }
+
+    // Test that the hard link was restored.
+    let foo_a_metadata = std::fs::metadata(&foo_a).unwrap();
+    let foo_link_metadata = std::fs::metadata(&foo_link).unwrap();
+    assert_eq!(foo_a_metadata.ino(), foo_link_metadata.ino());
+    assert_eq!(foo_a_metadata.nlink(), 2);
+
+    // Test that the extended attribute was restored.
+    if xattrs_supported {
+        assert_eq!(
+            xattr::get(&foo_a, "user.prose.test").unwrap().as_deref(),
+            Some(b"value".as_slice())
+        );
+    }
+}
+
+/// Tests that backups can be created from snapshots, which are unaffected
+/// by changes made after they are taken.
+#[tokio::test(flavor = "multi_thread")]
+async fn happy_path_snapshot() {
+    for method in [
+        "copy", "hardlink",
+    ] {
+        test_happy_path_snapshot_(method).await
+    }
+}
+
+async fn test_happy_path_snapshot_(method: &str) {
+    let context = init();
+    let TestContext {
+        now,
+        ref test_data_path,
+        ..
+    } = context;
+
+    println!();
+    let backup_config = {
+        let mut toml = toml! {
+            [storage]
+            provider = "fs"
+            fs.directory = "store"
+
+            [snapshot]
+            enabled = true
+        };
+        toml["snapshot"]["method"] = toml::Value::from(method);
+        // NOTE: Hard links cannot cross filesystems.
+        toml["snapshot"]["staging_dir"] = toml::Value::from(test_data_path.display().to_string());
+
+        map_storage_directories_in_test_dir(&mut toml, test_data_path).unwrap();
+
+        BackupConfig::try_from(toml).unwrap()
+    };
+    tracing::debug!("Parsed config: {backup_config:#?}");
+
+    let blueprint =
+        ArchiveBlueprint::new(1, [("foo-data", "foo")]).src_relative_to(&test_data_path);
+
+    create_files(
+        &test_data_path,
+        [
+            "foo/", "foo/a", "foo/b",
+        ],
+    )
+    .unwrap();
+
+    let blueprints = BlueprintsBuilder::new().insert(blueprint.clone()).build();
+
+    let service = BackupService::from_config_custom(
+        &backup_config,
+        ArchivingContext { blueprints },
+        RestorationContext { migrations: vec![] },
+        |_| unreachable!(),
+        || -> openpgp::policy::StandardPolicy { unreachable!() },
+    )
+    .unwrap();
+
+    let original_data = unique_hex().unwrap();
+    let foo_a = test_data_path.join("foo/a");
+    std::fs::write(&foo_a, &original_data).unwrap();
+
+    println!();
+    let snapshot = service.create_snapshot(&blueprint).await.unwrap();
+    let snapshot_path = snapshot.blueprint().paths[0].1.clone();
+    assert_ne!(snapshot_path, blueprint.paths[0].1);
+
+    // Change data after the snapshot was taken.
+    // NOTE: Hard links only protect against files being replaced.
+    match method {
+        "hardlink" => {
+            let tmp = test_data_path.join("foo/a.tmp");
+            std::fs::write(&tmp, "changed").unwrap();
+            std::fs::rename(&tmp, &foo_a).unwrap();
+        }
+        _ => std::fs::write(&foo_a, "changed").unwrap(),
+    }
+    std::fs::remove_file(test_data_path.join("foo/b")).unwrap();
+    create_files(&test_data_path, ["foo/c"]).unwrap();
+
+    println!();
+    let CreateBackupSuccess {
+        output: creation_output,
+        ..
+    } = {
+        let command = CreateBackupCommand {
+            prefix: "prose-backup",
+            description: "Test backup",
+            blueprint: snapshot.blueprint(),
+            additional_archive_data: Option::<()>::None,
+            profile: None,
+            created_at: now - Duration::from_mins(90),
+        };
+        service
+            .create_backup(command, &mut NoopEventHandler)
+            .await
+            .unwrap()
+    };
+    let CreateBackupOutput { backup_id, .. } = creation_output;
+
+    // Test that the snapshot is deleted.
+    snapshot.discard().await;
+    assert!(!snapshot_path.exists());
+
+    // Test that snapshots which were never deleted are purged.
+    let stale_snapshot = service.create_snapshot(&blueprint).await.unwrap();
+    let stale_snapshot_path = stale_snapshot.blueprint().paths[0].1.clone();
+    // NOTE: Simulates the process being killed before deleting the snapshot.
+    std::mem::forget(stale_snapshot);
+    assert!(stale_snapshot_path.exists());
+    service.purge_stale_snapshots().await.unwrap();
+    assert!(!stale_snapshot_path.exists());
+    assert!(test_data_path.join("foo/a").exists());
+
+    // Restore the backup.
+    println!();
+    let res = service
+        .restore_backup(&backup_id, &blueprint, &mut NoopEventHandler)
+        .await;
+    assert!(res.is_ok(), "Error: {:#?}", res.err().unwrap());
+
+    // Test that data was restored as it was when the snapshot was taken.
+    assert_eq!(std::fs::read_to_string(&foo_a).unwrap(), original_data);
+    assert!(test_data_path.join("foo/b").exists());
+    assert!(!test_data_path.join("foo/c").exists());
+}
+
+/// Tests that storage usage is reported correctly.
+#[tokio::test(flavor = "multi_thread")]
+async fn happy_path_storage_report() {
+    use prose_backup::dtos::{QuotaStatus, StorageReportDto};
+
+    let context = init();
+    let TestContext {
+        now,
+        ref test_data_path,
+        ..
+    } = context;
+
+    println!();
+    let backup_config = {
+        // NOTE: Uses a single store to check that objects aren’t counted twice.
+        let mut toml = toml! {
+            [storage]
+            provider = "fs"
+            fs.directory = "store"
+
+            [quota]
+            max_size = "1GB"
+        };
+
+        map_storage_directories_in_test_dir(&mut toml, test_data_path).unwrap();
+
+        BackupConfig::try_from(toml).unwrap()
+    };
+    tracing::debug!("Parsed config: {backup_config:#?}");
+
+    let blueprint =
+        ArchiveBlueprint::new(1, [("foo-data", "foo")]).src_relative_to(&test_data_path);
+
+    create_files(&test_data_path, ["foo/", "foo/a"]).unwrap();
+
+    let blueprints = BlueprintsBuilder::new().insert(blueprint.clone()).build();
+
+    let service = BackupService::from_config_custom(
+        &backup_config,
+        ArchivingContext { blueprints },
+        RestorationContext { migrations: vec![] },
+        |_| unreachable!(),
+        || -> openpgp::policy::StandardPolicy { unreachable!() },
+    )
+    .unwrap();
+
+    println!();
+    let mut backups_size_bytes = 0;
+    for created_at in [
+        now - Duration::from_mins(90),
+        now - Duration::from_mins(30),
+    ] {
+        let command = CreateBackupCommand {
+            prefix: "prose-backup",
+            description: "Test backup",
+            blueprint: &blueprint,
+            additional_archive_data: Option::<()>::None,
+            profile: None,
+            created_at,
+        };
+        let CreateBackupSuccess { backup, .. } = service
+            .create_backup(command, &mut NoopEventHandler)
+            .await
+            .unwrap();
+        backups_size_bytes += backup.metadata.size_bytes;
+    }
+
+    println!();
+    let StorageReportDto {
+        total_size_bytes,
+        backups,
+        checks,
+        by_month,
+        by_status,
+        growth,
+        quota,
+        warnings,
+        ..
+    } = service.storage_report().await.unwrap();
+
+    assert_eq!(backups.objects_count, 2);
+    assert_eq!(backups.size_bytes, backups_size_bytes);
+    // One digest per backup (no signing).
+    assert_eq!(checks.objects_count, 2);
+    assert_eq!(total_size_bytes, backups.size_bytes + checks.size_bytes);
+
+    let months_total: u64 = (by_month.iter())
+        .map(|usage| usage.backups.size_bytes + usage.checks.size_bytes)
+        .sum();
+    assert_eq!(months_total, total_size_bytes);
+
+    assert_eq!(by_status.len(), 1);
+    assert!(!by_status[0].is_signed);
+    assert!(!by_status[0].is_encrypted);
+    assert_eq!(by_status[0].usage.objects_count, 2);
+
+    // All objects are recent and history is shorter than a day.
+    assert_eq!(growth.observed_days, 1);
+    assert_eq!(growth.bytes_per_day, total_size_bytes);
+
+    let quota = quota.unwrap();
+    assert_eq!(quota.status, QuotaStatus::Ok);
+    assert!(quota.estimated_days_left.is_some());
+    assert!(warnings.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn happy_path_download_range() {
+    use prose_backup::dtos::BackupDownloadInfoDto;
+    use tokio::io::AsyncReadExt as _;
+
+    let context = init();
+    let TestContext {
+        now,
+        ref test_data_path,
+        ..
+    } = context;
+
+    println!();
+    let backup_config = {
+        let mut toml = toml! {
+            [storage]
+            provider = "fs"
+            fs.directory = "store"
+        };
+
+        map_storage_directories_in_test_dir(&mut toml, test_data_path).unwrap();
+
+        BackupConfig::try_from(toml).unwrap()
+    };
+    tracing::debug!("Parsed config: {backup_config:#?}");
+
+    let blueprint =
+        ArchiveBlueprint::new(1, [("foo-data", "foo")]).src_relative_to(&test_data_path);
+
+    create_files(
+        &test_data_path,
+        [
+            "foo/", "foo/a", "foo/b",
+        ],
+    )
+    .unwrap();
+
+    let blueprints = BlueprintsBuilder::new().insert(blueprint.clone()).build();
+
+    let service = BackupService::from_config_custom(
+        &backup_config,
+        ArchivingContext { blueprints },
+        RestorationContext { migrations: vec![] },
+        |_| unreachable!(),
+        || -> openpgp::policy::StandardPolicy { unreachable!() },
+    )
+    .unwrap();
+
+    println!();
+    let command = CreateBackupCommand {
+        prefix: "prose-backup",
+        description: "Test backup",
+        blueprint: &blueprint,
+        additional_archive_data: Option::<()>::None,
+        profile: None,
+        created_at: now,
+    };
+    let CreateBackupSuccess { backup, .. } = service
+        .create_backup(command, &mut NoopEventHandler)
+        .await
+        .unwrap();
+
+    println!();
+    let BackupDownloadInfoDto { size_bytes, digest } =
+        service.get_download_info(&backup.id).await.unwrap();
+    assert_eq!(size_bytes, backup.metadata.size_bytes);
+    assert!(digest.is_some_and(|digest| digest.contains(':')));
+
+    let mut full = Vec::new();
+    (service
+        .read_backup_range(&backup.id, 0..size_bytes)
+        .await
+        .unwrap())
+    .read_to_end(&mut full)
+    .await
+    .unwrap();
+    assert_eq!(full.len() as u64, size_bytes);
+
+    let range = (size_bytes / 3)..(size_bytes / 2);
+    let mut partial = Vec::new();
+    (service
+        .read_backup_range(&backup.id, range.clone())
+        .await
+        .unwrap())
+    .read_to_end(&mut partial)
+    .await
+    .unwrap();
+    assert_eq!(partial, full[(range.start as usize)..(range.end as usize)]);
+
+    // Ranges past the end are truncated.
+    let mut tail = Vec::new();
+    (service
+        .read_backup_range(&backup.id, (size_bytes - 1)..(size_bytes + 10))
+        .await
+        .unwrap())
+    .read_to_end(&mut tail)
+    .await
+    .unwrap();
+    assert_eq!(tail, full[(size_bytes as usize - 1)..]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn happy_path_diff() {
+    use prose_backup::diff::{BackupDiffDto, EntryKind};
+
+    let context = init();
+    let TestContext {
+        now,
+        ref test_data_path,
+        ..
+    } = context;
+
+    println!();
+    let backup_config = {
+        let mut toml = toml! {
+            [storage]
+            provider = "fs"
+            fs.directory = "store"
+        };
+
+        map_storage_directories_in_test_dir(&mut toml, test_data_path).unwrap();
+
+        BackupConfig::try_from(toml).unwrap()
+    };
+    tracing::debug!("Parsed config: {backup_config:#?}");
+
+    let blueprint = ArchiveBlueprint::new(
+        1,
+        [
+            ("foo-data", "foo"),
+            ("bar-data", "bar"),
+        ],
+    )
+    .src_relative_to(&test_data_path);
+
+    create_files(
+        &test_data_path,
+        [
+            "foo/", "foo/a", "bar/", "bar/b",
+        ],
+    )
+    .unwrap();
+    std::fs::write(test_data_path.join("foo/prose.toml"), "a = 1\nb = 2\n").unwrap();
+
+    let blueprints = BlueprintsBuilder::new().insert(blueprint.clone()).build();
+
+    let service = BackupService::from_config_custom(
+        &backup_config,
+        ArchivingContext { blueprints },
+        RestorationContext { migrations: vec![] },
+        |_| unreachable!(),
+        || -> openpgp::policy::StandardPolicy { unreachable!() },
+    )
+    .unwrap();
+
+    let create_backup = async |created_at: SystemTime| {
+        let command = CreateBackupCommand {
+            prefix: "prose-backup",
+            description: "Test backup",
+            blueprint: &blueprint,
+            additional_archive_data: Option::<()>::None,
+            profile: None,
+            created_at,
+        };
+        let CreateBackupSuccess { backup, .. } = service
+            .create_backup(command, &mut NoopEventHandler)
+            .await
+            .unwrap();
+        backup.id
+    };
+
+    println!();
+    let monday = create_backup(now - Duration::from_hours(24)).await;
+
+    std::fs::write(test_data_path.join("foo/prose.toml"), "a = 1\nb = 3\n").unwrap();
+    std::fs::remove_file(test_data_path.join("bar/b")).unwrap();
+    std::fs::write(test_data_path.join("bar/c"), "c").unwrap();
+
+    let tuesday = create_backup(now).await;
+
+    println!();
+    let BackupDiffDto { from, to, changes } = service.diff(&monday, &tuesday).await.unwrap();
+    assert_eq!(from, monday);
+    assert_eq!(to, tuesday);
+
+    let keys = changes.iter().map(|c| c.key.as_str()).collect::<Vec<_>>();
+    assert_eq!(
+        keys,
+        [
+            "bar-data", "foo-data"
+        ]
+    );
+
+    let bar = &changes[0];
+    let added = bar
+        .added
+        .iter()
+        .map(|e| e.path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(added, ["bar-data/c"]);
+    assert_eq!(bar.added[0].kind, EntryKind::File);
+    assert_eq!(bar.added[0].size_bytes, 1);
+    let removed = bar
+        .removed
+        .iter()
+        .map(|e| e.path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(removed, ["bar-data/b"]);
+    assert!(bar.modified.is_empty(), "{:#?}", bar.modified);
+
+    let foo = &changes[1];
+    assert!(foo.added.is_empty(), "{:#?}", foo.added);
+    assert!(foo.removed.is_empty(), "{:#?}", foo.removed);
+    assert_eq!(foo.modified.len(), 1, "{:#?}", foo.modified);
+    assert_eq!(foo.modified[0].path, "foo-data/prose.toml");
+    assert_eq!(
+        foo.modified[0].text_diff.as_deref(),
+        Some(
+            "--- a/foo-data/prose.toml\n\
+            +++ b/foo-data/prose.toml\n\
+            @@ -1,2 +1,2 @@\n \
+            a = 1\n\
+            -b = 2\n\
+            +b = 3\n"
+        )
+    );
+
+    // Comparing a backup with itself finds nothing.
+    let BackupDiffDto { changes, .. } = service.diff(&tuesday, &tuesday).await.unwrap();
+    assert!(changes.is_empty(), "{changes:#?}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn happy_path_transparency_log() {
+    use prose_backup::transparency::LogIssue;
+
+    let context = init();
+    let TestContext {
+        now,
+        ref test_data_path,
+        ..
+    } = context;
+
+    println!();
+    let backup_config = {
+        let mut toml = toml! {
+            [signing]
+            pgp.enabled = true
+            pgp.tsk = "sign.pgp"
+
+            [storage.backups]
+            provider = "fs"
+            fs.directory = "backups"
+
+            [storage.checks]
+            provider = "fs"
+            fs.directory = "checks"
+
+            [transparency_log]
+            enabled = true
+        };
+
+        map_storage_directories_in_test_dir(&mut toml, test_data_path).unwrap();
+
+        BackupConfig::try_from(toml).unwrap()
+    };
+    tracing::debug!("Parsed config: {backup_config:#?}");
+
+    let blueprint =
+        ArchiveBlueprint::new(1, [("foo-data", "foo")]).src_relative_to(&test_data_path);
+
+    create_files(&test_data_path, ["foo/", "foo/a"]).unwrap();
+
+    let blueprints = BlueprintsBuilder::new().insert(blueprint.clone()).build();
+
+    let certs: HashMap<PathBuf, openpgp::Cert> =
+        make_test_certs([("sign.pgp", now - Duration::from_hours(23))]).unwrap();
+    save_certs(test_data_path, &certs);
+
+    let pgp_policy = openpgp::policy::StandardPolicy::new();
+
+    let service = BackupService::from_config_custom(
+        &backup_config,
+        ArchivingContext { blueprints },
+        RestorationContext { migrations: vec![] },
+        |path| {
+            certs
+                .get(path)
+                .cloned()
+                .ok_or(anyhow!("Unknown cert: `{}`.", path.display()))
+        },
+        || pgp_policy.clone(),
+    )
+    .unwrap();
+
+    println!();
+    let mut backup_ids: Vec<BackupId> = Vec::new();
+    for created_at in [
+        now - Duration::from_mins(90),
+        now - Duration::from_mins(30),
+    ] {
+        let command = CreateBackupCommand {
+            prefix: "prose-backup",
+            description: "Test backup",
+            blueprint: &blueprint,
+            additional_archive_data: Option::<()>::None,
+            profile: None,
+            created_at,
+        };
+        let CreateBackupSuccess { backup, .. } = service
+            .create_backup(command, &mut NoopEventHandler)
+            .await
+            .unwrap();
+        assert_eq!(backup.metadata.is_logged, Some(true));
+        backup_ids.push(backup.id);
+    }
+
+    let backups = service.list_backups().await.unwrap();
+    assert_eq!(backups.len(), 2);
+    assert!(backups.iter().all(|b| b.metadata.is_logged == Some(true)));
+
+    service.delete_backup(&backup_ids[0]).await.unwrap();
+
+    let report = service.verify_log().await.unwrap();
+    tracing::info!("Log verification report: {report:#?}");
+    assert_eq!(report.entries_count, 3);
+    assert!(report.is_valid());
+
+    // Delete a backup behind the service’s back.
+    std::fs::remove_file(
+        test_data_path
+            .join("backups")
+            .join(backup_ids[1].to_string()),
+    )
+    .unwrap();
+
+    let report = service.verify_log().await.unwrap();
+    assert_eq!(report.missing_backups, vec![backup_ids[1].to_string()]);
+    assert!(report.issues.is_empty());
+
+    // Delete an entry of the log.
+    let checks_path = test_data_path.join("checks");
+    std::fs::remove_file(checks_path.join("transparency-log-00000000000000000001.json")).unwrap();
+
+    let report = service.verify_log().await.unwrap();
+    assert_eq!(report.entries_count, 2);
+    assert_eq!(
+        report.issues,
+        vec![LogIssue::MissingEntries { from: 1, to: 1 }]
+    );
+
+    // Alter an entry of the log.
+    let entry_path = checks_path.join("transparency-log-00000000000000000000.json");
+    let entry = std::fs::read_to_string(&entry_path).unwrap();
+    std::fs::write(&entry_path, entry.replace("created", "deleted")).unwrap();
+
+    let report = service.verify_log().await.unwrap();
+    assert!(
+        (report.issues.iter())
+            .any(|issue| matches!(issue, LogIssue::InvalidSignature { seq: 0, .. })),
+        "{report:#?}"
+    );
+}
+
+/// Tests that backups created using a profile only contain (and restore) the
+/// data included in the profile, and are stored in the profile’s store.
+#[tokio::test(flavor = "multi_thread")]
+async fn happy_path_profiles() {
+    use prose_backup::CreateBackupError;
+
+    let context = init();
+    let TestContext {
+        now,
+        ref test_data_path,
+        ..
+    } = context;
+
+    let profile_store_path = test_data_path.join("foo-store");
+    std::fs::create_dir_all(&profile_store_path).unwrap();
+
+    println!();
+    let backup_config = {
+        let profile_store = profile_store_path.display().to_string();
+        let mut toml = toml! {
+            [storage]
+            provider = "fs"
+            fs.directory = "store"
+
+            [profiles.foo]
+            include = ["foo-data"]
+            compression.algorithm = "off"
+            storage.fs.directory = profile_store
+        };
+
+        map_storage_directories_in_test_dir(&mut toml, test_data_path).unwrap();
+
+        BackupConfig::try_from(toml).unwrap()
+    };
+    tracing::debug!("Parsed config: {backup_config:#?}");
+
+    let blueprint = ArchiveBlueprint::new(
+        1,
+        [
+            ("foo-data", "foo"),
+            ("bar-data", "bar"),
+        ],
+    )
+    .src_relative_to(&test_data_path);
+
+    create_files(
+        &test_data_path,
+        [
+            "foo/", "foo/a", "bar/", "bar/a",
+        ],
+    )
+    .unwrap();
+    let foo_a = test_data_path.join("foo/a");
+    let bar_a = test_data_path.join("bar/a");
+    std::fs::write(&foo_a, "foo v1").unwrap();
+    std::fs::write(&bar_a, "bar v1").unwrap();
+
+    let blueprints = BlueprintsBuilder::new().insert(blueprint.clone()).build();
+
+    let service = BackupService::from_config_custom(
+        &backup_config,
+        ArchivingContext { blueprints },
+        RestorationContext { migrations: vec![] },
+        |_| unreachable!(),
+        || -> openpgp::policy::StandardPolicy { unreachable!() },
+    )
+    .unwrap();
+
+    println!();
+    let CreateBackupSuccess { backup, .. } = {
+        let command = CreateBackupCommand {
+            prefix: "prose-backup",
+            description: "Test backup",
+            blueprint: &blueprint,
+            additional_archive_data: Option::<()>::None,
+            profile: Some("foo"),
+            created_at: now - Duration::from_mins(90),
+        };
+        service
+            .create_backup(command, &mut NoopEventHandler)
+            .await
+            .unwrap()
+    };
+    let backup_id = backup.id;
+
+    // Compression is off in the profile.
+    assert_eq!(backup_id.extensions, vec![Box::from("tar")]);
+
+    // The backup is stored in the profile’s store.
+    let backup_file_name = backup_id.to_string();
+    assert!(profile_store_path.join(&backup_file_name).exists());
+    assert!(
+        !test_data_path
+            .join("store")
+            .join(&backup_file_name)
+            .exists()
+    );
+
+    let backups = service.list_backups().await.unwrap();
+    assert_eq!(backups.len(), 1);
+    assert_eq!(backups[0].id, backup_id);
+
+    let details = service.get_details(&backup_id).await.unwrap();
+    assert_eq!(details.metadata.profile.as_deref(), Some("foo"));
+    assert_eq!(details.metadata.is_partial, Some(true));
+    assert!(details.metadata.can_be_restored);
+
+    // Restoring only replaces data contained in the backup.
+    std::fs::write(&foo_a, "foo v2").unwrap();
+    std::fs::write(&bar_a, "bar v2").unwrap();
+
+    println!();
+    let res = service
+        .restore_backup(&backup_id, &blueprint, &mut NoopEventHandler)
+        .await;
+    assert!(res.is_ok(), "Error: {:#?}", res.err().unwrap());
+
+    assert_eq!(std::fs::read_to_string(&foo_a).unwrap(), "foo v1");
+    assert_eq!(std::fs::read_to_string(&bar_a).unwrap(), "bar v2");
+
+    // Unknown profiles are rejected.
+    let command = CreateBackupCommand {
+        prefix: "prose-backup",
+        description: "Test backup",
+        blueprint: &blueprint,
+        additional_archive_data: Option::<()>::None,
+        profile: Some("bar"),
+        created_at: now - Duration::from_mins(30),
+    };
+    let res = service.create_backup(command, &mut NoopEventHandler).await;
+    assert!(
+        matches!(res, Err(CreateBackupError::UnknownProfile(ref name)) if name == "bar"),
+        "{res:?}"
+    );
+
+    service.delete_backup(&backup_id).await.unwrap();
+    assert!(!profile_store_path.join(&backup_file_name).exists());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn happy_path_key_management() {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    use openpgp::serialize::SerializeInto as _;
+    use prose_backup::keys::{KeySource, KeyUsage, KeyringError};
+
+    let context = init();
+    let TestContext {
+        ref test_data_path, ..
+    } = context;
+
+    let keys_path = test_data_path.join("keys");
+
+    println!();
+    let backup_config = {
+        let keys_dir = keys_path.display().to_string();
+        let mut toml = toml! {
+            [storage]
+            provider = "fs"
+            fs.directory = "store"
+
+            [keys]
+            directory = keys_dir
+        };
+
+        map_storage_directories_in_test_dir(&mut toml, test_data_path).unwrap();
+
+        BackupConfig::try_from(toml).unwrap()
+    };
+    tracing::debug!("Parsed config: {backup_config:#?}");
+
+    let blueprint =
+        ArchiveBlueprint::new(1, [("foo-data", "foo")]).src_relative_to(&test_data_path);
+
+    create_files(&test_data_path, ["foo/", "foo/a"]).unwrap();
+
+    let blueprints = BlueprintsBuilder::new().insert(blueprint.clone()).build();
+
+    let new_service = || {
+        BackupService::from_config_custom(
+            &backup_config,
+            ArchivingContext {
+                blueprints: blueprints.clone(),
+            },
+            RestorationContext { migrations: vec![] },
+            |_| unreachable!(),
+            openpgp::policy::StandardPolicy::new,
+        )
+        .unwrap()
+    };
+
+    let service = new_service();
+    assert!(service.keys.is_empty());
+
+    // Private key material is only accessible by its owner.
+    let mode = std::fs::metadata(&keys_path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o700);
+
+    println!();
+    let signing_key = service
+        .generate_key(
+            KeyUsage::Signing,
+            "Prose Backup <backup@example.org>".to_owned(),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(signing_key.source, KeySource::Managed);
+    assert!(signing_key.has_secret);
+
+    let key_path = keys_path.join(format!("{}.asc", signing_key.fingerprint));
+    let mode = std::fs::metadata(&key_path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    // Only public keys can be imported.
+    let (other_cert, _) = openpgp::cert::CertBuilder::general_purpose(Some("Other system"))
+        .generate()
+        .unwrap();
+    let res = service
+        .import_recipient(other_cert.as_tsk().armored().to_vec().unwrap())
+        .await;
+    assert!(matches!(res, Err(KeyringError::InvalidKey(_))), "{res:?}");
+
+    let recipient_key = service
+        .import_recipient(other_cert.armored().to_vec().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(recipient_key.usage, KeyUsage::Recipient);
+    assert!(!recipient_key.has_secret);
+
+    // Keys are used once the service is created again.
+    let service = new_service();
+    assert_eq!(service.keys.len(), 2);
+
+    println!();
+    let signed_backup_id = {
+        let command = CreateBackupCommand {
+            prefix: "prose-backup",
+            description: "Signed backup",
+            blueprint: &blueprint,
+            additional_archive_data: Option::<()>::None,
+            profile: None,
+            // NOTE: Signatures can’t predate the signing key.
+            created_at: SystemTime::now(),
+        };
+        let CreateBackupSuccess { backup, .. } = service
+            .create_backup(command, &mut NoopEventHandler)
+            .await
+            .unwrap();
+        backup.id
+    };
+    let details = service.get_details(&signed_backup_id).await.unwrap();
+    assert!(details.metadata.is_signed);
+
+    println!();
+    let fingerprint: openpgp::Fingerprint = signing_key.fingerprint.parse().unwrap();
+    service.retire_key(&fingerprint).await.unwrap();
+
+    let res = service.retire_key(&other_cert.fingerprint()).await;
+    assert!(res.is_ok(), "Error: {:#?}", res.err().unwrap());
+
+    let res = service
+        .retire_key(&"0123456789ABCDEF0123456789ABCDEF01234567".parse().unwrap())
+        .await;
+    assert!(matches!(res, Err(KeyringError::NotFound(_))), "{res:?}");
+
+    // Retired keys no longer sign new backups, but still verify old ones.
+    let service = new_service();
+    assert!(service.keys.iter().all(|key| key.retired_at.is_some()));
+
+    println!();
+    let unsigned_backup_id = {
+        let command = CreateBackupCommand {
+            prefix: "prose-backup",
+            description: "Unsigned backup",
+            blueprint: &blueprint,
+            additional_archive_data: Option::<()>::None,
+            profile: None,
+            created_at: SystemTime::now(),
+        };
+        let CreateBackupSuccess { backup, .. } = service
+            .create_backup(command, &mut NoopEventHandler)
+            .await
+            .unwrap();
+        backup.id
+    };
+    let details = service.get_details(&unsigned_backup_id).await.unwrap();
+    assert!(!details.metadata.is_signed);
+
+    println!();
+    let res = service
+        .restore_backup(&signed_backup_id, &blueprint, &mut NoopEventHandler)
+        .await;
+    assert!(res.is_ok(), "Error: {:#?}", res.err().unwrap());
+}
+
+/// Tests that backups created elsewhere can be uploaded (and restored), but
+/// only if they match the given integrity checks.
+#[tokio::test(flavor = "multi_thread")]
+async fn happy_path_upload() {
+    use prose_backup::verification::VerificationError;
+    use prose_backup::{UploadBackupCommand, UploadBackupError, UploadBackupSuccess};
+
+    let context = init();
+    let TestContext {
+        now,
+        ref test_data_path,
+        ..
+    } = context;
+
+    println!();
+    let backup_config = |store: &str| {
+        let backups_dir = format!("{store}-backups");
+        let checks_dir = format!("{store}-checks");
+        // NOTE: Stores contain objects with the same keys, they can’t
+        //   share a cache.
+        let cache_dir = test_data_path.join(format!("{store}-cache"));
+        std::fs::create_dir_all(&cache_dir).unwrap();
+        let cache_dir = cache_dir.display().to_string();
+        let mut toml = toml! {
+            [signing]
+            pgp.enabled = true
+            pgp.tsk = "sign.pgp"
+
+            [storage.backups]
+            provider = "fs"
+            fs.directory = backups_dir
+
+            [storage.checks]
+            provider = "fs"
+            fs.directory = checks_dir
+
+            [transparency_log]
+            enabled = true
+
+            [caching]
+            cache_dir = cache_dir
+        };
+
+        map_storage_directories_in_test_dir(&mut toml, test_data_path).unwrap();
+
+        BackupConfig::try_from(toml).unwrap()
+    };
+
+    let blueprint =
+        ArchiveBlueprint::new(1, [("foo-data", "foo")]).src_relative_to(&test_data_path);
+
+    create_files(&test_data_path, ["foo/", "foo/a"]).unwrap();
+    let foo_a = test_data_path.join("foo/a");
+    std::fs::write(&foo_a, "foo v1").unwrap();
+
+    let blueprints = BlueprintsBuilder::new().insert(blueprint.clone()).build();
+
+    let certs: HashMap<PathBuf, openpgp::Cert> =
+        make_test_certs([("sign.pgp", now - Duration::from_hours(23))]).unwrap();
+    save_certs(test_data_path, &certs);
+
+    let pgp_policy = openpgp::policy::StandardPolicy::new();
+
+    let new_service = |store: &str| {
+        BackupService::from_config_custom(
+            &backup_config(store),
+            ArchivingContext {
+                blueprints: blueprints.clone(),
+            },
+            RestorationContext { migrations: vec![] },
+            |path| {
+                certs
+                    .get(path)
+                    .cloned()
+                    .ok_or(anyhow!("Unknown cert: `{}`.", path.display()))
+            },
+            || pgp_policy.clone(),
+        )
+        .unwrap()
+    };
+
+    // Create a backup on another server.
+    let old_service = new_service("old");
+    let backup_id = {
+        let command = CreateBackupCommand {
+            prefix: "prose-backup",
+            description: "Test backup",
+            blueprint: &blueprint,
+            additional_archive_data: Option::<()>::None,
+            profile: None,
+            created_at: now - Duration::from_mins(30),
+        };
+        let CreateBackupSuccess { backup, .. } = old_service
+            .create_backup(command, &mut NoopEventHandler)
+            .await
+            .unwrap();
+        backup.id
+    };
+    let file_name = backup_id.to_string();
+    let backup = std::fs::read(test_data_path.join("old-backups").join(&file_name)).unwrap();
+    let signature = std::fs::read(
+        test_data_path
+            .join("old-checks")
+            .join(format!("{file_name}.sig")),
+    )
+    .unwrap();
+    let digest = {
+        let digest = std::fs::read(
+            test_data_path
+                .join("old-checks")
+                .join(format!("{file_name}.blake3")),
+        )
+        .unwrap();
+        let hex: String = digest.iter().map(|b| format!("{b:02x}")).collect();
+        format!("blake3:{hex}")
+    };
+
+    let service = new_service("new");
+    let new_backup_path = test_data_path.join("new-backups").join(&file_name);
+
+    // Signing is mandatory.
+    let command = UploadBackupCommand {
+        backup_id: backup_id.clone(),
+        digest: Some(digest.clone()),
+        signature: None,
+    };
+    let res = service.upload_backup(command, backup.as_slice()).await;
+    assert!(
+        matches!(
+            res,
+            Err(UploadBackupError::Verification(
+                VerificationError::BackupNotSigned
+            ))
+        ),
+        "{res:?}"
+    );
+
+    // Tampered backups are deleted.
+    let mut tampered = backup.clone();
+    *tampered.last_mut().unwrap() ^= 0xff;
+    let command = UploadBackupCommand {
+        backup_id: backup_id.clone(),
+        digest: Some(digest.clone()),
+        signature: Some(signature.clone()),
+    };
+    let res = service.upload_backup(command, tampered.as_slice()).await;
+    assert!(
+        matches!(
+            res,
+            Err(UploadBackupError::Verification(
+                VerificationError::InvalidSignature(_)
+            ))
+        ),
+        "{res:?}"
+    );
+    assert!(!new_backup_path.exists());
+    assert!(service.list_backups().await.unwrap().is_empty());
+
+    println!();
+    let command = UploadBackupCommand {
+        backup_id: backup_id.clone(),
+        digest: Some(digest.clone()),
+        signature: Some(signature.clone()),
+    };
+    let UploadBackupSuccess { backup: dto, .. } = service
+        .upload_backup(command, backup.as_slice())
+        .await
+        .unwrap();
+    assert_eq!(dto.id, backup_id);
+    assert!(dto.metadata.is_signed);
+    assert_eq!(dto.metadata.is_logged, Some(true));
+    assert_eq!(std::fs::read(&new_backup_path).unwrap(), backup);
+
+    let details = service.get_details(&backup_id).await.unwrap();
+    assert!(details.metadata.is_intact);
+    assert!(details.metadata.can_be_restored);
+
+    // Existing backups are never overwritten.
+    let command = UploadBackupCommand {
+        backup_id: backup_id.clone(),
+        digest: Some(digest),
+        signature: Some(signature),
+    };
+    let res = service.upload_backup(command, backup.as_slice()).await;
+    assert!(
+        matches!(res, Err(UploadBackupError::AlreadyExists(_))),
+        "{res:?}"
+    );
+    assert!(new_backup_path.exists());
+
+    println!();
+    std::fs::write(&foo_a, "foo v2").unwrap();
+    let res = service
+        .restore_backup(&backup_id, &blueprint, &mut NoopEventHandler)
+        .await;
+    assert!(res.is_ok(), "Error: {:#?}", res.err().unwrap());
+    assert_eq!(std::fs::read_to_string(&foo_a).unwrap(), "foo v1");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn happy_path_latest_intact_backup() {
+    let context = init();
+    let TestContext {
+        now,
+        ref test_data_path,
+        ..
+    } = context;
+
+    println!();
+    let backup_config = {
+        let mut toml = toml! {
+            [signing]
+            pgp.enabled = true
+            pgp.tsk = "sign.pgp"
+
+            [storage.backups]
+            provider = "fs"
+            fs.directory = "backups"
+
+            [storage.checks]
+            provider = "fs"
+            fs.directory = "checks"
+        };
+
+        map_storage_directories_in_test_dir(&mut toml, test_data_path).unwrap();
+
+        BackupConfig::try_from(toml).unwrap()
+    };
+
+    let blueprint =
+        ArchiveBlueprint::new(1, [("foo-data", "foo")]).src_relative_to(&test_data_path);
+
+    create_files(&test_data_path, ["foo/", "foo/a"]).unwrap();
+
+    let blueprints = BlueprintsBuilder::new().insert(blueprint.clone()).build();
+
+    let certs: HashMap<PathBuf, openpgp::Cert> = make_test_certs([
+        ("sign.pgp", now - Duration::from_hours(23)),
+        ("other.pgp", now - Duration::from_hours(23)),
+    ])
+    .unwrap();
+    save_certs(test_data_path, &certs);
+
+    let pgp_policy = openpgp::policy::StandardPolicy::new();
+
+    let service = BackupService::from_config_custom(
+        &backup_config,
+        ArchivingContext {
+            blueprints: blueprints.clone(),
+        },
+        RestorationContext { migrations: vec![] },
+        |path| {
+            certs
+                .get(path)
+                .cloned()
+                .ok_or(anyhow!("Unknown cert: `{}`.", path.display()))
+        },
+        || pgp_policy.clone(),
+    )
+    .unwrap();
+
+    // No backup yet.
+    assert_eq!(
+        service.latest_intact_backup(None, None).await.unwrap(),
+        None
+    );
+
+    let create_backup = async |prefix: &str, created_at: SystemTime| {
+        let command = CreateBackupCommand {
+            prefix,
+            description: "Test backup",
+            blueprint: &blueprint,
+            additional_archive_data: Option::<()>::None,
+            profile: None,
+            created_at,
+        };
+        let CreateBackupSuccess { backup, .. } = service
+            .create_backup(command, &mut NoopEventHandler)
+            .await
+            .unwrap();
+        backup.id
+    };
+    let oldest = create_backup("prose-backup", now - Duration::from_hours(3)).await;
+    let broken = create_backup("prose-backup", now - Duration::from_hours(2)).await;
+    let other = create_backup("other-backup", now - Duration::from_hours(1)).await;
+
+    // Make a backup unrestorable (signing is mandatory).
+    std::fs::remove_file(test_data_path.join("checks").join(format!("{broken}.sig"))).unwrap();
+
+    println!();
+    assert_eq!(
+        service.latest_intact_backup(None, None).await.unwrap(),
+        Some(other),
+    );
+    assert_eq!(
+        (service
+            .latest_intact_backup(Some("prose-backup"), None)
+            .await)
+            .unwrap(),
+        Some(oldest.clone()),
+    );
+    assert_eq!(
+        (service.latest_intact_backup(Some("unknown"), None).await).unwrap(),
+        None,
+    );
+
+    println!();
+    let signer = certs[Path::new("sign.pgp")].fingerprint();
+    assert_eq!(
+        (service
+            .latest_intact_backup(Some("prose-backup"), Some(&signer))
+            .await)
+            .unwrap(),
+        Some(oldest),
+    );
+    let other_signer = certs[Path::new("other.pgp")].fingerprint();
+    assert_eq!(
+        (service
+            .latest_intact_backup(None, Some(&other_signer))
+            .await)
+            .unwrap(),
+        None,
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn happy_path_undo_restore() {
+    use prose_backup::safety_snapshot::UndoRestoreError;
+
+    let context = init();
+    let TestContext {
+        now,
+        ref test_data_path,
+        ..
+    } = context;
+
+    println!();
+    let safety_snapshot_dir = test_data_path.join("safety-snapshot");
+    let backup_config = |ttl: &str| {
+        let safety_snapshot_dir = safety_snapshot_dir.display().to_string();
+        let mut toml = toml! {
+            [storage]
+            provider = "fs"
+            fs.directory = "store"
+
+            [safety_snapshot]
+            enabled = true
+            directory = safety_snapshot_dir
+            ttl = ttl
+        };
+
+        map_storage_directories_in_test_dir(&mut toml, test_data_path).unwrap();
+
+        BackupConfig::try_from(toml).unwrap()
+    };
+
+    let blueprint =
+        ArchiveBlueprint::new(1, [("foo-data", "foo")]).src_relative_to(&test_data_path);
+
+    create_files(&test_data_path, ["foo/", "foo/a"]).unwrap();
+    let foo_a = test_data_path.join("foo/a");
+    let foo_b = test_data_path.join("foo/b");
+    std::fs::write(&foo_a, "foo v1").unwrap();
+
+    let blueprints = BlueprintsBuilder::new().insert(blueprint.clone()).build();
+
+    let new_service = |ttl: &str| {
+        BackupService::from_config_custom(
+            &backup_config(ttl),
+            ArchivingContext {
+                blueprints: blueprints.clone(),
+            },
+            RestorationContext { migrations: vec![] },
+            |_| unreachable!(),
+            || -> openpgp::policy::StandardPolicy { unreachable!() },
+        )
+        .unwrap()
+    };
+    let service = new_service("P1D");
+
+    let backup_id = {
+        let command = CreateBackupCommand {
+            prefix: "prose-backup",
+            description: "Test backup",
+            blueprint: &blueprint,
+            additional_archive_data: Option::<()>::None,
+            profile: None,
+            created_at: now - Duration::from_mins(30),
+        };
+        let CreateBackupSuccess { backup, .. } = service
+            .create_backup(command, &mut NoopEventHandler)
+            .await
+            .unwrap();
+        backup.id
+    };
+
+    // Nothing to undo yet.
+    let res = service.undo_last_restore().await;
+    assert!(matches!(res, Err(UndoRestoreError::NoSnapshot)), "{res:?}");
+
+    // Change data, then restore the backup.
+    println!();
+    std::fs::write(&foo_a, "foo v2").unwrap();
+    std::fs::write(&foo_b, "foo b").unwrap();
+    let res = service
+        .restore_backup(&backup_id, &blueprint, &mut NoopEventHandler)
+        .await;
+    assert!(res.is_ok(), "Error: {:#?}", res.err().unwrap());
+    drop(res);
+    assert_eq!(std::fs::read_to_string(&foo_a).unwrap(), "foo v1");
+    assert!(!foo_b.exists());
+    assert!(safety_snapshot_dir.join("manifest.json").exists());
+
+    // Undo the restoration.
+    println!();
+    let safety_snapshot = service.undo_last_restore().await.unwrap();
+    assert_eq!(safety_snapshot.backup_id, backup_id.to_string());
+    assert_eq!(std::fs::read_to_string(&foo_a).unwrap(), "foo v2");
+    assert_eq!(std::fs::read_to_string(&foo_b).unwrap(), "foo b");
+    assert!(!safety_snapshot_dir.exists());
+
+    // Only the last restoration can be undone, once.
+    let res = service.undo_last_restore().await;
+    assert!(matches!(res, Err(UndoRestoreError::NoSnapshot)), "{res:?}");
+
+    // Expired safety snapshots can’t be used.
+    println!();
+    let service = new_service("PT0S");
+    let res = service
+        .restore_backup(&backup_id, &blueprint, &mut NoopEventHandler)
+        .await;
+    assert!(res.is_ok(), "Error: {:#?}", res.err().unwrap());
+    drop(res);
+    let res = service.undo_last_restore().await;
+    assert!(
+        matches!(res, Err(UndoRestoreError::Expired { .. })),
+        "{res:?}"
+    );
+    assert_eq!(std::fs::read_to_string(&foo_a).unwrap(), "foo v1");
+    assert!(!safety_snapshot_dir.exists());
+}
+
 // MARK: - Helpers
 
 /// Tests all features of the library, given a configuration.
@@ -410,6 +1899,7 @@ async fn test_happy_path_(mut config_toml: toml::Table) {
             description: "Test backup",
             blueprint: &blueprint.clone(),
             additional_archive_data: Option::<()>::None,
+            profile: None,
             created_at: now - Duration::from_mins(90),
         };
         service
@@ -434,6 +1924,24 @@ async fn test_happy_path_(mut config_toml: toml::Table) {
         creation_event_handler.effective_archive_size
     );
 
+    // Progress events are emitted, and the last one reports everything
+    // as done.
+    let progress = (creation_event_handler.progress_events.last())
+        .expect("At least one progress event should be emitted");
+    assert_eq!(
+        progress.bytes_read,
+        creation_event_handler.effective_archive_size
+    );
+    assert_eq!(progress.total_bytes_estimate, progress.bytes_read);
+    assert_eq!(progress.eta_secs, Some(0));
+    assert!(progress.bytes_compressed > 0);
+    assert!(progress.bytes_uploaded > 0);
+    assert!(
+        (creation_event_handler.progress_events.windows(2))
+            .all(|w| w[0].bytes_read <= w[1].bytes_read
+                && w[0].bytes_uploaded <= w[1].bytes_uploaded)
+    );
+
     println!();
     if let EncryptionConfig::Pgp { config: pgp } = &encryption_config {
         let mut pgp_cert = certs.get(&pgp.tsk).unwrap().clone();
@@ -446,11 +1954,11 @@ async fn test_happy_path_(mut config_toml: toml::Table) {
         )
         .unwrap();
 
-        service.decryption_context.pgp = Some(PgpDecryptionContext {
+        service.decryption_context.pgp = Some(Arc::new(PgpDecryptionContext {
             tsks: vec![pgp_cert],
             policy: Box::new(pgp_policy.clone()),
             passphrases: HashMap::new(),
-        });
+        }));
     }
 
     println!();
@@ -498,6 +2006,47 @@ async fn test_happy_path_(mut config_toml: toml::Table) {
             .all(|report| report.cert_fingerprint == pgp_cert.fingerprint());
     }
 
+    // All restoration stages finish, in order.
+    {
+        use prose_backup::restore_report::{RestoreReportDto, RestoreStage};
+
+        let stage_events = &extraction_event_handler.stage_events;
+        let finished_stages = (stage_events.iter())
+            .filter(|event| event.progress == 1.)
+            .map(|event| event.stage)
+            .collect::<Vec<_>>();
+        let mut expected_stages = vec![RestoreStage::Downloading];
+        match signing_config.pgp {
+            Some(_) => expected_stages.push(RestoreStage::VerifyingSignature),
+            None => expected_stages.push(RestoreStage::VerifyingDigest),
+        }
+        if matches!(encryption_config, EncryptionConfig::Pgp { .. }) {
+            expected_stages.push(RestoreStage::Decrypting);
+        }
+        expected_stages.push(RestoreStage::Extracting);
+        expected_stages.push(RestoreStage::SwappingDirectories);
+        assert_eq!(finished_stages, expected_stages);
+
+        let extracted = (stage_events.iter())
+            .rfind(|event| event.stage == RestoreStage::Extracting)
+            .unwrap();
+        assert!(extracted.extracted_bytes.unwrap() > 0);
+        assert!(extracted.extracted_entries.unwrap() > 0);
+
+        // The report of the restoration is kept.
+        let mut report = RestoreReportDto::new(&backup_id);
+        for event in stage_events.iter() {
+            report.record(event);
+        }
+        report.finish(None);
+        service.restoration_config.report_path = Some(test_data_path.join("restore-report.json"));
+        () = service.save_restore_report(&report).await.unwrap();
+        let last_report = service.last_restore_report().await.unwrap().unwrap();
+        assert_eq!(last_report.backup_id, backup_id.to_string());
+        assert_eq!(last_report.events.len(), report.events.len());
+        assert!(last_report.finished_at.is_some());
+    }
+
     println!();
     () = service
         .delete_backup(&backup_id)
@@ -109,6 +109,7 @@ async fn s3_happy_path() {
                 description: "Test backup",
                 blueprint: &blueprint,
                 additional_archive_data: Option::<()>::None,
+                profile: None,
                 created_at: now,
             },
             &mut creation_event_handler,
@@ -246,6 +247,7 @@ async fn s3_single_bucket_same_prefix() {
                 description: "Test backup",
                 blueprint: &blueprint,
                 additional_archive_data: Option::<()>::None,
+                profile: None,
                 created_at: now,
             },
             &mut creation_event_handler,
@@ -402,6 +404,7 @@ async fn s3_object_locking() {
                 description: "Test backup",
                 blueprint: &blueprint,
                 additional_archive_data: Option::<()>::None,
+                profile: None,
                 created_at: now,
             },
             &mut creation_event_handler,
@@ -806,7 +809,6 @@ fn test_s3_store(
     }))
 }
 
-#[allow(clippy::borrowed_box)] // For convenience in call sites.
-fn as_s3_store(object_store: &Box<dyn ObjectStore>) -> &'static S3Store {
+fn as_s3_store(object_store: &Arc<dyn ObjectStore>) -> &'static S3Store {
     unsafe { &*(object_store.as_ref() as *const dyn ObjectStore as *const S3Store) }
 }
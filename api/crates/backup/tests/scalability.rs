@@ -81,6 +81,7 @@ async fn scalability_large_files() {
             description: "Test backup",
             blueprint: &blueprint.clone(),
             additional_archive_data: Option::<()>::None,
+            profile: None,
             created_at: now - Duration::from_mins(90),
         };
         service
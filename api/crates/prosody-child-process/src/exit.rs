@@ -0,0 +1,116 @@
+// prosody-child-process-rs
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+//! Observing when Prosody’s process exits.
+
+use std::{
+    os::unix::process::ExitStatusExt as _,
+    process::ExitStatus,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use nix::sys::signal::Signal;
+use tokio::sync::watch;
+
+/// How Prosody’s process exited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExitInfo {
+    /// Exit code, `None` if Prosody was terminated by a signal.
+    pub code: Option<i32>,
+
+    /// Signal which terminated Prosody, if any.
+    pub signal: Option<i32>,
+}
+
+impl From<ExitStatus> for ExitInfo {
+    fn from(status: ExitStatus) -> Self {
+        Self {
+            code: status.code(),
+            signal: status.signal(),
+        }
+    }
+}
+
+impl std::fmt::Display for ExitInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.code, self.signal) {
+            (Some(code), _) => write!(f, "exit code {code}"),
+            (None, Some(signal)) => match Signal::try_from(signal) {
+                Ok(name) => write!(f, "signal {signal} ({name})"),
+                Err(_) => write!(f, "signal {signal}"),
+            },
+            (None, None) => write!(f, "unknown exit status"),
+        }
+    }
+}
+
+/// Shared between a running Prosody and its [`ExitWatcher`]s.
+#[derive(Debug, Clone)]
+pub(crate) struct ExitState {
+    /// `None` while Prosody is running.
+    pub(crate) exit: watch::Receiver<Option<ExitInfo>>,
+
+    /// Set before Prosody is stopped on purpose, so its exit
+    /// isn’t mistaken for a crash.
+    pub(crate) stop_requested: Arc<AtomicBool>,
+}
+
+impl ExitState {
+    /// How Prosody exited, `None` if it’s still running.
+    #[inline]
+    pub(crate) fn exit(&self) -> Option<ExitInfo> {
+        *self.exit.borrow()
+    }
+
+    /// Waits for Prosody to exit.
+    ///
+    /// NOTE: Returns `None` if the task waiting on Prosody’s process ended
+    ///   without reporting an exit, which should never happen.
+    pub(crate) async fn exited(&self) -> Option<ExitInfo> {
+        let mut exit = self.exit.clone();
+        match exit.wait_for(Option::is_some).await {
+            Ok(exit) => *exit,
+            Err(_) => None,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn request_stop(&self) {
+        self.stop_requested.store(true, Ordering::SeqCst);
+    }
+
+    #[inline]
+    pub(crate) fn is_stop_requested(&self) -> bool {
+        self.stop_requested.load(Ordering::SeqCst)
+    }
+}
+
+/// Watches Prosody’s process exit without having to borrow its
+/// [`ProsodyChildProcess`](crate::ProsodyChildProcess) (e.g. in a
+/// supervisor task).
+#[derive(Debug, Clone)]
+pub struct ExitWatcher(pub(crate) ExitState);
+
+impl ExitWatcher {
+    /// Waits for Prosody to exit, returning `None` if it was stopped on
+    /// purpose (see [`ProsodyChildProcess::stop`](crate::ProsodyChildProcess::stop)).
+    pub async fn unexpected_exit(&self) -> Option<ExitInfo> {
+        let exit = self.0.exited().await;
+
+        if self.0.is_stop_requested() {
+            None
+        } else {
+            // NOTE: If the waiting task died, better assume Prosody
+            //   crashed than never noticing it did.
+            Some(exit.unwrap_or(ExitInfo {
+                code: None,
+                signal: None,
+            }))
+        }
+    }
+}
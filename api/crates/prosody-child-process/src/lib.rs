@@ -3,7 +3,12 @@
 // Copyright: 2025, Rémi Bardon <remi@remibardon.name>
 // License: Mozilla Public License v2.0 (MPL v2.0)
 
+mod exit;
+mod logs;
 mod prosody_child_process;
+mod readiness;
 mod util;
 
-pub use self::prosody_child_process::ProsodyChildProcess;
+pub use self::exit::{ExitInfo, ExitWatcher};
+pub use self::prosody_child_process::{ProsodyChildProcess, StopOutcome};
+pub use self::readiness::{NotReady, ReadinessCheck, ReadinessConfig};
@@ -0,0 +1,42 @@
+// prosody-child-process-rs
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+//! Keeping Prosody’s most recent log lines (e.g. to show them to an
+//! administrator after a crash).
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+/// Most recent lines logged by Prosody (most recent last).
+///
+/// NOTE: Shared with the task reading Prosody’s logs, and kept across
+///   restarts so logs from before a crash can still be read.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RecentLogs(Arc<Mutex<VecDeque<String>>>);
+
+impl RecentLogs {
+    /// NOTE: Lines are also sent to `tracing`, this is only a convenience
+    ///   so it doesn’t need to keep much.
+    pub(crate) const MAX_LEN: usize = 1000;
+
+    pub(crate) fn push(&self, line: String) {
+        let mut lines = self.0.lock().unwrap();
+
+        if lines.len() >= Self::MAX_LEN {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// Last `limit` lines (most recent last).
+    pub(crate) fn last(&self, limit: usize) -> Vec<String> {
+        let lines = self.0.lock().unwrap();
+
+        let skip = lines.len().saturating_sub(limit);
+        lines.iter().skip(skip).cloned().collect()
+    }
+}
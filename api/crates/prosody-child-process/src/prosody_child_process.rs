@@ -7,19 +7,24 @@ use std::{
     collections::HashMap,
     ffi::{OsStr, OsString},
     process::Stdio,
+    sync::{Arc, atomic::AtomicBool},
+    time::Duration,
 };
 
 use anyhow::{Context as _, anyhow};
 use nix::{
-    sys::signal::{Signal::SIGHUP, kill},
+    sys::signal::{
+        Signal::{self, SIGHUP, SIGKILL, SIGTERM},
+        kill,
+    },
     unistd::Pid,
 };
-use tokio::{
-    process::{Child, Command},
-    task::JoinHandle,
-};
+use tokio::{process::Command, sync::watch, task::JoinHandle};
 
-use crate::util::{debug_panic, debug_panic_or_log_warning};
+use crate::exit::{ExitInfo, ExitState, ExitWatcher};
+use crate::logs::RecentLogs;
+use crate::readiness::{NotReady, ReadinessConfig};
+use crate::util::debug_panic_or_log_warning;
 
 #[derive(Debug)]
 pub struct ProsodyChildProcess {
@@ -27,6 +32,17 @@ pub struct ProsodyChildProcess {
 
     envs: HashMap<OsString, OsString>,
 
+    /// Command used to start Prosody (program then arguments).
+    command: (OsString, Vec<OsString>),
+
+    /// How long to wait for Prosody to exit after `SIGTERM`,
+    /// before killing it (`SIGKILL`).
+    stop_grace_period: Duration,
+
+    readiness: ReadinessConfig,
+
+    recent_logs: RecentLogs,
+
     /// A unique ID that’s used in debug logs to differenciate
     /// which instance is “speaking”.
     id: UniqueId,
@@ -34,23 +50,99 @@ pub struct ProsodyChildProcess {
 
 #[derive(Debug)]
 struct ProsodyHandle {
-    process: Child,
+    pid: Pid,
+
+    /// NOTE: Prosody’s process is owned by a task which waits for it to exit
+    ///   (and reaps it), so its exit can be observed without borrowing
+    ///   [`ProsodyChildProcess`] (see [`ExitWatcher`]).
+    exit: ExitState,
 
     log_handle: JoinHandle<()>,
+
+    /// Whether Prosody logged one of [`ReadinessConfig::log_patterns`].
+    log_line_seen: watch::Receiver<bool>,
+}
+
+/// How Prosody was stopped (see [`ProsodyChildProcess::stop`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopOutcome {
+    /// Prosody exited by itself after `SIGTERM`, within the grace period.
+    Terminated,
+
+    /// Prosody did not exit within the grace period, it was killed
+    /// (`SIGKILL`).
+    Killed,
+
+    /// Prosody had already exited.
+    AlreadyExited,
 }
 
 impl ProsodyChildProcess {
+    /// Default for [`stop_grace_period`](Self::stop_grace_period).
+    pub const DEFAULT_STOP_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
     /// NOTE: This constructor is lazy. Prosody will start when you call
     ///   [`ProsodyChildProcess::start`].
     #[inline]
     pub fn new() -> Self {
+        Self::with_command("prosody", ["--no-daemonize"])
+    }
+
+    /// Like [`new`](Self::new), but running another command than
+    /// `prosody --no-daemonize` (e.g. a stub in tests).
+    pub fn with_command<P, A>(program: P, args: impl IntoIterator<Item = A>) -> Self
+    where
+        P: AsRef<OsStr>,
+        A: AsRef<OsStr>,
+    {
         Self {
             handle: None,
             envs: HashMap::new(),
+            command: (
+                program.as_ref().to_owned(),
+                args.into_iter()
+                    .map(|arg| arg.as_ref().to_owned())
+                    .collect(),
+            ),
+            stop_grace_period: Self::DEFAULT_STOP_GRACE_PERIOD,
+            readiness: ReadinessConfig::default(),
+            recent_logs: RecentLogs::default(),
             id: UniqueId::new(),
         }
     }
 
+    /// Sets how long [`stop`](Self::stop) waits for Prosody to exit after
+    /// `SIGTERM` before killing it (`SIGKILL`).
+    ///
+    /// Defaults to [`DEFAULT_STOP_GRACE_PERIOD`](Self::DEFAULT_STOP_GRACE_PERIOD).
+    #[inline]
+    pub fn set_stop_grace_period(&mut self, grace_period: Duration) {
+        self.stop_grace_period = grace_period;
+    }
+
+    /// Equivalent of [`set_stop_grace_period`](Self::set_stop_grace_period)
+    /// but returning the new value to support chaining.
+    #[inline]
+    pub fn stop_grace_period(mut self, grace_period: Duration) -> Self {
+        self.set_stop_grace_period(grace_period);
+        self
+    }
+
+    /// Sets what [`start`](Self::start) waits for before considering
+    /// Prosody ready.
+    #[inline]
+    pub fn set_readiness(&mut self, readiness: ReadinessConfig) {
+        self.readiness = readiness;
+    }
+
+    /// Equivalent of [`set_readiness`](Self::set_readiness)
+    /// but returning the new value to support chaining.
+    #[inline]
+    pub fn readiness(mut self, readiness: ReadinessConfig) -> Self {
+        self.set_readiness(readiness);
+        self
+    }
+
     /// Stores a new environment variable to attach to Prosody next time you
     /// call [`start`](Self::start).
     ///
@@ -74,91 +166,191 @@ impl ProsodyChildProcess {
     }
 
     /// Start Prosody in the background (non blocking).
+    ///
+    /// Returns once Prosody is ready (see [`ReadinessConfig`]). If it isn’t
+    /// after [`ReadinessConfig::timeout`], Prosody is stopped and a
+    /// [`NotReady`] error is returned (use [`anyhow::Error::downcast_ref`]
+    /// to find out which checks never passed).
     pub async fn start(&mut self) -> Result<(), anyhow::Error> {
         tracing::debug!(instance = %self.id, "Starting Prosody…");
 
         // Start Prosody (as a child process).
-        let mut handle = ProsodyHandle::new(self.envs.clone().into_iter()).await?;
-
-        // Check if Prosody started successfully.
-        //
-        // Prosody fails fast, therefore if it did not exit after 250ms,
-        // it likely means everything went well.
-        //
-        // TODO: Once we pipe Prosody logs to redirect it to `tracing`,
-        //   read the logs and exit as soon as the first `stdout` line
-        //   says “welcome to Prosody” (to avoid constant wait).
-        let exit_status = tokio::time::timeout(
-            tokio::time::Duration::from_millis(250),
-            handle.process.wait(),
+        let handle = ProsodyHandle::new(
+            &self.command,
+            self.envs.clone().into_iter(),
+            self.readiness.log_patterns.clone(),
+            self.recent_logs.clone(),
         )
-        .await;
-        match exit_status {
-            Ok(Ok(status)) => {
+        .await?;
+
+        // Wait for Prosody to be ready.
+        let deadline = tokio::time::Instant::now() + self.readiness.timeout;
+        let mut pending = self.readiness.checks();
+        loop {
+            // NOTE: Prosody fails fast (e.g. invalid configuration).
+            if let Some(exit) = handle.exit.exit() {
                 return Err(anyhow!(
-                    "Prosody did not start successfully: Exited early ({status})."
+                    "Prosody did not start successfully: Exited early ({exit})."
                 ));
             }
-            Ok(Err(err)) => {
-                let error = anyhow!(err).context("Failed waiting for Prosody exit");
-                debug_panic!("{error:?}");
-                return Err(error);
+
+            let mut still_pending = Vec::with_capacity(pending.len());
+            for check in pending {
+                if !(self.readiness).passes(check, &handle.log_line_seen).await {
+                    still_pending.push(check);
+                }
+            }
+            pending = still_pending;
+
+            if pending.is_empty() {
+                break;
             }
-            Err(_) => {
-                // Prosody is still running -> it started successfully.
+
+            if tokio::time::Instant::now() >= deadline {
+                let error = NotReady {
+                    timeout: self.readiness.timeout,
+                    pending,
+                };
+                tracing::warn!(instance = %self.id, "{error}");
+
+                // Do not leave a half-started Prosody behind.
+                if let Err(err) = Self::stop_(handle, self.stop_grace_period, &self.id).await {
+                    tracing::warn!(instance = %self.id, "Could not stop Prosody: {err:?}");
+                }
+
+                return Err(anyhow::Error::new(error));
             }
+
+            tokio::time::sleep(ReadinessConfig::POLL_INTERVAL).await;
         }
 
+        tracing::debug!(instance = %self.id, "Prosody is ready.");
         self.handle = Some(handle);
 
         Ok(())
     }
 
-    /// Check if Prosody is already running.
+    /// Check if Prosody is already running (and still passes
+    /// [readiness checks](ReadinessConfig)).
     pub async fn is_running(&self) -> bool {
-        // Run `prosodyctl status` as a health check.
-        Command::new("prosodyctl")
-            .arg("status")
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .status()
-            .await
-            .inspect_err(|err| tracing::debug!("{err:#}"))
-            .is_ok_and(|status| status.success())
+        let Some(handle) = self.handle.as_ref() else {
+            return false;
+        };
+
+        if handle.exit.exit().is_some() {
+            return false;
+        }
+
+        for check in self.readiness.checks() {
+            if !self.readiness.passes(check, &handle.log_line_seen).await {
+                tracing::debug!(instance = %self.id, "Readiness check failed: {check}.");
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Watches Prosody’s exit (e.g. to restart it if it crashes),
+    /// `None` if Prosody isn’t started.
+    #[inline]
+    pub fn exit_watcher(&self) -> Option<ExitWatcher> {
+        (self.handle.as_ref()).map(|handle| ExitWatcher(handle.exit.clone()))
+    }
+
+    /// How Prosody exited if it did on its own (i.e. it wasn’t
+    /// [stopped](Self::stop)), `None` if it’s running.
+    #[inline]
+    pub fn unexpected_exit(&self) -> Option<ExitInfo> {
+        let exit = &self.handle.as_ref()?.exit;
+        if exit.is_stop_requested() {
+            None
+        } else {
+            exit.exit()
+        }
+    }
+
+    /// Last `limit` lines logged by Prosody (most recent last), including
+    /// before it was restarted.
+    ///
+    /// NOTE: Only the last 1000 lines are kept.
+    #[inline]
+    pub fn recent_logs(&self, limit: usize) -> Vec<String> {
+        self.recent_logs.last(limit)
     }
 
     /// Stop Prosody gracefully.
+    ///
+    /// Sends `SIGTERM` so Prosody can flush its storage and close sessions,
+    /// then kills it (`SIGKILL`) if it didn’t exit within
+    /// [`stop_grace_period`](Self::stop_grace_period).
     #[inline]
-    pub async fn stop(&mut self) -> Result<(), anyhow::Error> {
+    pub async fn stop(&mut self) -> Result<StopOutcome, anyhow::Error> {
         match self.handle.take() {
-            Some(handle) => Self::stop_(handle, &self.id).await,
+            Some(handle) => Self::stop_(handle, self.stop_grace_period, &self.id).await,
             None => {
                 debug_panic_or_log_warning!(
                     "Not stopping Prosody: No handle (likely already stopped)."
                 );
-                Ok(())
+                Ok(StopOutcome::AlreadyExited)
             }
         }
     }
 
     /// Stop Prosody gracefully.
-    async fn stop_(mut handle: ProsodyHandle, instance: &UniqueId) -> Result<(), anyhow::Error> {
+    async fn stop_(
+        handle: ProsodyHandle,
+        grace_period: Duration,
+        instance: &UniqueId,
+    ) -> Result<StopOutcome, anyhow::Error> {
         tracing::debug!(%instance, "Stopping Prosody…");
 
-        // Stop Prosody.
-        handle.process.kill().await?;
+        handle.exit.request_stop();
+
+        let outcome = if handle.exit.exit().is_some() {
+            StopOutcome::AlreadyExited
+        } else {
+            // Ask Prosody to stop.
+            handle
+                .signal(SIGTERM)
+                .context("Failed sending SIGTERM to Prosody")?;
+
+            // Wait for Prosody to exit.
+            match tokio::time::timeout(grace_period, handle.exit.exited()).await {
+                Ok(_) => StopOutcome::Terminated,
+                Err(_) => {
+                    tracing::warn!(
+                        %instance,
+                        "Prosody did not stop within {grace_period:?}, killing it…"
+                    );
 
-        // Wait for Prosody to terminate (avoids zombies).
-        // NOTE: Prosody can still save data after it’s been killed,
-        //   during its graceful shutdown process. This ensures Prosody
-        //   is inert after this function ends.
-        handle.process.wait().await?;
+                    handle
+                        .signal(SIGKILL)
+                        .context("Failed sending SIGKILL to Prosody")?;
+
+                    // NOTE: Prosody can still save data after it’s been
+                    //   killed, during its graceful shutdown process. This
+                    //   ensures Prosody is inert after this function ends.
+                    handle.exit.exited().await;
+
+                    StopOutcome::Killed
+                }
+            }
+        };
 
         // Wait for all logs to be processed.
         handle.log_handle.await?;
 
-        tracing::info!(%instance, "Prosody stopped successfully.");
-        Ok(())
+        match outcome {
+            StopOutcome::Terminated => {
+                tracing::info!(%instance, "Prosody stopped successfully.")
+            }
+            StopOutcome::Killed => tracing::warn!(%instance, "Prosody killed."),
+            StopOutcome::AlreadyExited => {
+                tracing::info!(%instance, "Prosody had already stopped.")
+            }
+        }
+        Ok(outcome)
     }
 
     /// Reload Prosody.
@@ -170,12 +362,13 @@ impl ProsodyChildProcess {
             return self.start().await;
         };
 
-        let Some(pid) = handle.process.id() else {
-            debug_panic_or_log_warning!("Prosody not started: No PID (likely stopped).");
+        if let Some(exit) = handle.exit.exit() {
+            debug_panic_or_log_warning!("Prosody not started: Exited ({exit}).");
+            self.handle = None;
             return self.start().await;
-        };
+        }
 
-        kill(Pid::from_raw(pid as i32), SIGHUP)?;
+        handle.signal(SIGHUP)?;
 
         Ok(())
     }
@@ -189,13 +382,32 @@ impl ProsodyChildProcess {
 }
 
 impl ProsodyHandle {
+    /// NOTE: Does nothing if Prosody already exited (its PID might have
+    ///   been reused by then).
+    fn signal(&self, signal: Signal) -> Result<(), nix::Error> {
+        if self.exit.exit().is_some() {
+            return Ok(());
+        }
+
+        match kill(self.pid, signal) {
+            // NOTE: Prosody exited in the meantime.
+            Err(nix::Error::ESRCH) => Ok(()),
+            res => res,
+        }
+    }
+
     #[must_use]
     #[tracing::instrument(level = "trace", skip_all, err)]
-    async fn new(envs: impl Iterator<Item = (OsString, OsString)>) -> Result<Self, anyhow::Error> {
+    async fn new(
+        (program, args): &(OsString, Vec<OsString>),
+        envs: impl Iterator<Item = (OsString, OsString)>,
+        log_patterns: Vec<String>,
+        recent_logs: RecentLogs,
+    ) -> Result<Self, anyhow::Error> {
         use tokio::io::{AsyncBufReadExt as _, BufReader};
 
-        let mut child = Command::new("prosody")
-            .arg("--no-daemonize")
+        let mut child = Command::new(program)
+            .args(args)
             .stdin(Stdio::null())
             .stdout(Stdio::piped())
             .stderr(Stdio::inherit())
@@ -203,11 +415,19 @@ impl ProsodyHandle {
             .spawn()
             .context("Failed spawning prosody")?;
 
+        let pid = (child.id()).ok_or(anyhow!("Failed to get prosody PID"))?;
+        let pid = Pid::from_raw(pid as i32);
+
         let stdout = (child.stdout.take()).ok_or(anyhow!("Failed to get prosody stdout"))?;
         let mut reader = BufReader::new(stdout).lines();
 
         const TRACING_TARGET: &'static str = "prosody";
 
+        let (log_line_seen_tx, log_line_seen) = watch::channel(false);
+        let log_patterns = (log_patterns.iter())
+            .map(|pattern| pattern.to_lowercase())
+            .collect::<Vec<_>>();
+
         let join_handle = tokio::task::spawn(async move {
             let span = tracing::info_span!(TRACING_TARGET);
             let _span = span.enter();
@@ -215,6 +435,15 @@ impl ProsodyHandle {
             loop {
                 match reader.next_line().await {
                     Ok(Some(line)) => {
+                        if !*log_line_seen_tx.borrow() && !log_patterns.is_empty() {
+                            let lowercased = line.to_lowercase();
+                            if (log_patterns.iter()).any(|pattern| lowercased.contains(pattern)) {
+                                log_line_seen_tx.send_replace(true);
+                            }
+                        }
+
+                        recent_logs.push(line.clone());
+
                         // NOTE: Line format: `module       level\tmesssage`
                         //   (with a variable number of space characters
                         //   between the module name and the level).
@@ -280,9 +509,29 @@ impl ProsodyHandle {
             }
         });
 
+        let (exit_tx, exit) = watch::channel(None);
+        tokio::task::spawn(async move {
+            let exit = match child.wait().await {
+                Ok(status) => ExitInfo::from(status),
+                Err(err) => {
+                    tracing::warn!("Failed waiting for Prosody exit: {err:?}");
+                    ExitInfo {
+                        code: None,
+                        signal: None,
+                    }
+                }
+            };
+            exit_tx.send_replace(Some(exit));
+        });
+
         let handle = ProsodyHandle {
-            process: child,
+            pid,
+            exit: ExitState {
+                exit,
+                stop_requested: Arc::new(AtomicBool::new(false)),
+            },
             log_handle: join_handle,
+            log_line_seen,
         };
 
         Ok(handle)
@@ -322,12 +571,13 @@ impl std::fmt::Debug for UniqueId {
 impl Drop for ProsodyChildProcess {
     fn drop(&mut self) {
         let instance = self.id;
+        let grace_period = self.stop_grace_period;
         tracing::debug!(%instance, "[Drop] Dropping `ProsodyChildProcess`…");
 
         match self.handle.take() {
             Some(handle) => {
                 tokio::spawn(async move {
-                    Self::stop_(handle, &instance).await.unwrap_or_else(
+                    Self::stop_(handle, grace_period, &instance).await.map(|_| ()).unwrap_or_else(
                         |err| tracing::error!(%instance, "[Drop] Could not stop Prosody: {err:?}"),
                     );
                 });
@@ -338,3 +588,245 @@ impl Drop for ProsodyChildProcess {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::readiness::ReadinessCheck;
+
+    /// A line stubs log once ready (see [`stub`]).
+    const READY_LINE: &str = "echo 'startup  info\tProsody is ready'";
+
+    /// The greeting Prosody logs before loading modules.
+    const GREETING_LINE: &str =
+        "echo 'startup             info\tHello and welcome to prosody version 13.0.2'";
+
+    /// A stub running `script`, ready once it logs [`READY_LINE`].
+    fn stub(script: &str) -> ProsodyChildProcess {
+        ProsodyChildProcess::with_command("sh", ["-c", script]).readiness(ReadinessConfig {
+            log_patterns: vec!["Prosody is ready".to_owned()],
+            ..Default::default()
+        })
+    }
+
+    /// A stub which exits on `SIGTERM` (`sleep`’s default behavior).
+    fn stub_honouring_sigterm() -> ProsodyChildProcess {
+        stub(&format!("{READY_LINE}; exec sleep 60"))
+    }
+
+    /// A stub which ignores `SIGTERM` (ignored signals stay ignored
+    /// after `exec`).
+    fn stub_ignoring_sigterm() -> ProsodyChildProcess {
+        stub(&format!("trap '' TERM; {READY_LINE}; exec sleep 60"))
+    }
+
+    /// An address nothing listens on.
+    fn unused_address() -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_stop_terminates_gracefully() {
+        let mut prosody = stub_honouring_sigterm().stop_grace_period(Duration::from_secs(30));
+        prosody.start().await.unwrap();
+
+        let start = tokio::time::Instant::now();
+        let outcome = prosody.stop().await.unwrap();
+
+        assert_eq!(outcome, StopOutcome::Terminated);
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "Should not wait for the grace period ({:?})",
+            start.elapsed()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stop_kills_after_grace_period() {
+        let grace_period = Duration::from_millis(300);
+        let mut prosody = stub_ignoring_sigterm().stop_grace_period(grace_period);
+        prosody.start().await.unwrap();
+
+        let start = tokio::time::Instant::now();
+        let outcome = prosody.stop().await.unwrap();
+
+        assert_eq!(outcome, StopOutcome::Killed);
+        assert!(start.elapsed() >= grace_period);
+    }
+
+    #[tokio::test]
+    async fn test_exit_watcher_reports_crashes() {
+        let mut prosody = stub(&format!("{READY_LINE}; sleep 0.3; exit 3"));
+        prosody.start().await.unwrap();
+        let watcher = prosody.exit_watcher().unwrap();
+
+        let exit = watcher.unexpected_exit().await;
+
+        let expected = ExitInfo {
+            code: Some(3),
+            signal: None,
+        };
+        assert_eq!(exit, Some(expected));
+        assert_eq!(prosody.unexpected_exit(), Some(expected));
+    }
+
+    #[tokio::test]
+    async fn test_exit_watcher_reports_signals() {
+        let mut prosody = stub_honouring_sigterm();
+        prosody.start().await.unwrap();
+        let watcher = prosody.exit_watcher().unwrap();
+
+        let pid = prosody.handle.as_ref().unwrap().pid;
+        kill(pid, SIGKILL).unwrap();
+
+        let exit = watcher.unexpected_exit().await.unwrap();
+        assert_eq!(exit.signal, Some(SIGKILL as i32));
+        assert_eq!(exit.to_string(), "signal 9 (SIGKILL)");
+    }
+
+    #[tokio::test]
+    async fn test_exit_watcher_ignores_stops() {
+        let mut prosody = stub_honouring_sigterm();
+        prosody.start().await.unwrap();
+        let watcher = prosody.exit_watcher().unwrap();
+
+        prosody.stop().await.unwrap();
+
+        assert_eq!(watcher.unexpected_exit().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_recent_logs_survive_crashes() {
+        let mut prosody = stub(&format!(
+            "echo 'first'; {READY_LINE}; echo 'last'; sleep 0.3; exit 1"
+        ));
+        prosody.start().await.unwrap();
+        prosody.exit_watcher().unwrap().unexpected_exit().await;
+        prosody.stop().await.unwrap();
+
+        assert_eq!(
+            prosody.recent_logs(2),
+            vec![
+                "startup  info\tProsody is ready",
+                "last"
+            ]
+        );
+        assert_eq!(prosody.recent_logs(10).len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_start_waits_for_log_line() {
+        let mut prosody = stub(&format!("sleep 0.5; {READY_LINE}; exec sleep 60"));
+
+        let start = tokio::time::Instant::now();
+        prosody.start().await.unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(500));
+        assert!(prosody.is_running().await);
+
+        prosody.stop().await.unwrap();
+        assert!(!prosody.is_running().await);
+    }
+
+    #[tokio::test]
+    async fn test_greeting_is_not_readiness() {
+        let timeout = Duration::from_millis(500);
+        let readiness = ReadinessConfig {
+            timeout,
+            http_address: Some(unused_address()),
+            ..Default::default()
+        };
+
+        let mut prosody = ProsodyChildProcess::with_command(
+            "sh",
+            [
+                "-c",
+                &format!("{GREETING_LINE}; exec sleep 60"),
+            ],
+        )
+        .readiness(readiness);
+        let error = prosody.start().await.unwrap_err();
+
+        assert_eq!(
+            error.downcast_ref::<NotReady>(),
+            Some(&NotReady {
+                timeout,
+                pending: vec![ReadinessCheck::HttpPort],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_log_patterns_are_case_insensitive() {
+        let readiness = ReadinessConfig {
+            log_patterns: vec!["Hello and welcome to Prosody".to_owned()],
+            ..Default::default()
+        };
+
+        let mut prosody = ProsodyChildProcess::with_command(
+            "sh",
+            [
+                "-c",
+                &format!("sleep 0.3; {GREETING_LINE}; exec sleep 60"),
+            ],
+        )
+        .readiness(readiness);
+
+        let start = tokio::time::Instant::now();
+        prosody.start().await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(300));
+
+        prosody.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_start_checks_http_port() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let readiness = ReadinessConfig {
+            http_address: Some(listener.local_addr().unwrap().to_string()),
+            log_patterns: vec![],
+            ..Default::default()
+        };
+
+        let mut prosody = ProsodyChildProcess::with_command("sleep", ["60"]).readiness(readiness);
+        prosody.start().await.unwrap();
+        prosody.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_start_fails_if_not_ready() {
+        let timeout = Duration::from_millis(500);
+        let readiness = ReadinessConfig {
+            timeout,
+            http_address: Some(unused_address()),
+            log_patterns: vec!["Prosody is ready".to_owned()],
+            ..Default::default()
+        };
+
+        let mut prosody = ProsodyChildProcess::with_command("sleep", ["60"]).readiness(readiness);
+        let error = prosody.start().await.unwrap_err();
+
+        assert_eq!(
+            error.downcast_ref::<NotReady>(),
+            Some(&NotReady {
+                timeout,
+                pending: vec![
+                    ReadinessCheck::HttpPort,
+                    ReadinessCheck::LogLine
+                ],
+            })
+        );
+        assert!(!prosody.is_running().await);
+    }
+
+    #[tokio::test]
+    async fn test_start_fails_if_exited_early() {
+        let mut prosody = stub("exit 1");
+
+        let error = prosody.start().await.unwrap_err();
+
+        assert!(error.downcast_ref::<NotReady>().is_none(), "{error:?}");
+        assert!(error.to_string().contains("Exited early"), "{error:?}");
+    }
+}
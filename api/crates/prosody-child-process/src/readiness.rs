@@ -0,0 +1,147 @@
+// prosody-child-process-rs
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+//! Detecting when Prosody is ready to serve requests after it starts.
+
+use std::{path::PathBuf, time::Duration};
+
+use tokio::sync::watch;
+
+/// What makes a started Prosody “ready” (see
+/// [`ProsodyChildProcess::start`](crate::ProsodyChildProcess::start)).
+///
+/// All configured checks must pass before
+/// [`timeout`](ReadinessConfig::timeout).
+#[derive(Debug, Clone)]
+pub struct ReadinessConfig {
+    /// How long to wait for all checks to pass.
+    pub timeout: Duration,
+
+    /// Path to Prosody’s admin shell socket (`admin_socket`),
+    /// checked if set.
+    pub admin_socket: Option<PathBuf>,
+
+    /// Address of Prosody’s HTTP server (e.g. `localhost:5280`),
+    /// checked if set.
+    pub http_address: Option<String>,
+
+    /// Prosody is ready once it logs a line containing one of these
+    /// (case-insensitive), not checked if empty.
+    ///
+    /// WARN: Prosody logs its greeting (“Hello and welcome to prosody
+    ///   version …”) before loading modules, so it doesn’t mean Prosody
+    ///   is ready to serve requests.
+    pub log_patterns: Vec<String>,
+}
+
+impl Default for ReadinessConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            admin_socket: None,
+            http_address: None,
+            // NOTE: Prosody doesn’t log anything once all modules are
+            //   loaded, prefer `admin_socket` and `http_address`.
+            log_patterns: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadinessCheck {
+    AdminSocket,
+    HttpPort,
+    LogLine,
+}
+
+impl std::fmt::Display for ReadinessCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AdminSocket => write!(f, "admin shell socket"),
+            Self::HttpPort => write!(f, "HTTP port"),
+            Self::LogLine => write!(f, "readiness log line"),
+        }
+    }
+}
+
+/// Prosody was still running, but some checks didn’t pass before
+/// [`ReadinessConfig::timeout`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotReady {
+    pub timeout: Duration,
+
+    /// Checks which never passed.
+    pub pending: Vec<ReadinessCheck>,
+}
+
+impl std::fmt::Display for NotReady {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pending = (self.pending.iter())
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(
+            f,
+            "Prosody not ready after {:?}: Checks never passed: {pending}.",
+            self.timeout
+        )
+    }
+}
+
+impl std::error::Error for NotReady {}
+
+impl ReadinessConfig {
+    /// Interval at which checks are retried.
+    pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    pub(crate) fn checks(&self) -> Vec<ReadinessCheck> {
+        let mut checks = Vec::with_capacity(3);
+        if self.admin_socket.is_some() {
+            checks.push(ReadinessCheck::AdminSocket);
+        }
+        if self.http_address.is_some() {
+            checks.push(ReadinessCheck::HttpPort);
+        }
+        if !self.log_patterns.is_empty() {
+            checks.push(ReadinessCheck::LogLine);
+        }
+        checks
+    }
+
+    /// NOTE: A check which isn’t configured always passes.
+    pub(crate) async fn passes(
+        &self,
+        check: ReadinessCheck,
+        log_line_seen: &watch::Receiver<bool>,
+    ) -> bool {
+        // NOTE: Connecting to a local socket is instantaneous, this only
+        //   prevents an unreachable host from blocking other checks.
+        let connect_timeout = Duration::from_secs(1);
+
+        match check {
+            ReadinessCheck::AdminSocket => match self.admin_socket.as_ref() {
+                Some(path) => {
+                    let connect = tokio::net::UnixStream::connect(path);
+                    matches!(
+                        tokio::time::timeout(connect_timeout, connect).await,
+                        Ok(Ok(_))
+                    )
+                }
+                None => true,
+            },
+            ReadinessCheck::HttpPort => match self.http_address.as_deref() {
+                Some(address) => {
+                    let connect = tokio::net::TcpStream::connect(address);
+                    matches!(
+                        tokio::time::timeout(connect_timeout, connect).await,
+                        Ok(Ok(_))
+                    )
+                }
+                None => true,
+            },
+            ReadinessCheck::LogLine => *log_line_seen.borrow(),
+        }
+    }
+}
@@ -3,16 +3,6 @@
 // Copyright: 2025, Rémi Bardon <remi@remibardon.name>
 // License: Mozilla Public License v2.0 (MPL v2.0)
 
-/// [`panic!`] in debug mode, useless in release.
-macro_rules! debug_panic {
-    ($($args:tt)*) => {
-        if cfg!(debug_assertions) {
-            panic!("[debug_only] {}", format!($($args)*));
-        }
-    };
-}
-pub(crate) use debug_panic;
-
 /// [`panic!`] in debug mode, [`tracing::warn!`] in release.
 macro_rules! debug_panic_or_log_warning {
     ($($args:tt)*) => {
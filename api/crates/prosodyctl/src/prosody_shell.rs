@@ -316,6 +316,20 @@ impl ProsodyShell {
         Ok(response.result.map_err(anyhow::Error::msg)?)
     }
 
+    /// Reloads Prosody’s configuration file (like `SIGHUP`).
+    ///
+    /// NOTE: Modules are not reloaded.
+    #[tracing::instrument(level = "trace", skip_all, err)]
+    pub async fn config_reload(&mut self) -> anyhow::Result<()> {
+        let command = format!(r#"config:reload()"#);
+
+        let response = (self.exec(&command))
+            .await
+            .context("Error reloading Prosody config")?;
+
+        response.result_unit()
+    }
+
     /// Waits for Prosody to be ready after it starts.
     ///
     /// While starting up, Prosody loads modules and runs some initialization
@@ -30,6 +30,14 @@ pub mod defaults {
     pub const MAIN_TEAM_GROUP_ID: &'static str = "team";
 
     pub(super) const DEFAULT_MAIN_TEAM_NAME: &'static str = "Team";
+
+    pub(super) const PROSE_POD_API_DUMP_DIR: &'static str = "/var/lib/prose-pod-api-dump";
+
+    pub(super) const RESTORE_JOURNAL_PATH: &'static str =
+        "/var/lib/prose-backup/restore-journal.json";
+
+    pub(super) const RESTORE_REPORT_PATH: &'static str =
+        "/var/lib/prose-backup/restore-report.json";
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -56,7 +64,19 @@ fn default_config_static() -> Figment {
 
     let true_in_debug = cfg!(debug_assertions);
 
-    let backups_default = prose_backup::config::default_config_static();
+    let mut backups_default = prose_backup::config::default_config_static();
+    // NOTE: The journal and the report must not be in a backed up path, and
+    //   must survive reboots (e.g. machine powered off during a restoration).
+    let restore_journal_path = RESTORE_JOURNAL_PATH;
+    let restore_report_path = RESTORE_REPORT_PATH;
+    backups_default.extend(toml! {
+        [restoration]
+        journal_path = restore_journal_path
+        report_path = restore_report_path
+
+        [transparency_log]
+        enabled = true
+    });
 
     let random_oauth2_registration_key: SecretString =
         crate::util::random_oauth2_registration_key();
@@ -78,6 +98,22 @@ fn default_config_static() -> Figment {
         local_hostname = SERVER_LOCAL_HOSTNAME
         http_port = SERVER_HTTP_PORT
         log_level = "info"
+        stop_grace_period = "PT10S"
+        start_timeout = "PT30S"
+
+        [server.readiness]
+        log_patterns = []
+
+        [server.auto_restart]
+        enabled = true
+        initial_backoff = "PT1S"
+        max_backoff = "PT1M"
+        crash_loop_limit = 5
+        crash_loop_window = "PT10M"
+
+        [server.process_manager]
+        kind = "child_process"
+        unit = "prosody.service"
 
         [server_api]
         address = "0.0.0.0"
@@ -140,6 +176,7 @@ fn default_config_static() -> Figment {
         [api]
         local_hostname = "prose-pod-api"
         port = 8080
+        dump_dir = PROSE_POD_API_DUMP_DIR
     }
     .to_string();
 
@@ -187,16 +224,20 @@ fn with_dynamic_defaults(mut figment: Figment) -> Result<Figment, InvalidConfigu
 
     // Validate backups configuration.
     {
-        use crate::router::backups::{BACKUP_BLUEPRINTS, BACKUPS_VERSION};
+        use crate::router::backups::{BACKUPS_VERSION, PROSE_POD_API_DUMP_KEY, backup_blueprints};
+        use prose_backup::archiving::ArchiveBlueprint;
         use std::ffi::OsString;
         use std::os::unix::ffi::OsStrExt as _;
 
-        let blueprint = (BACKUP_BLUEPRINTS.get(&BACKUPS_VERSION))
+        let prose_pod_api_dump_dir = figment.extract_inner::<PathBuf>("api.dump_dir")?;
+        let blueprints = backup_blueprints(&prose_pod_api_dump_dir);
+        let blueprint = (blueprints.get(&BACKUPS_VERSION))
             .expect("A blueprint should always exist for BACKUPS_VERSION");
 
         // TODO: Canonicalize paths?
         fn ensure_not_in_backed_up_path(
             dir: OsString,
+            usage: &str,
             blueprint: &prose_backup::archiving::ArchiveBlueprint,
         ) -> Result<(), InvalidConfiguration> {
             let dir_bytes = dir.as_bytes();
@@ -215,14 +256,14 @@ fn with_dynamic_defaults(mut figment: Figment) -> Result<Figment, InvalidConfigu
                     if suffix.is_empty() || suffix == b"/" {
                         // Exact match.
                         return Err(InvalidConfiguration(anyhow!(
-                            "Cannot use {dir:?} as backup storage as it is itself backed up. \
-                            You would loose your backups when restoring."
+                            "Cannot use {dir:?} as {usage} as it is itself backed up. \
+                            It would be replaced when restoring."
                         )));
                     } else if suffix.starts_with(b"/") {
                         // Proper prefix.
                         return Err(InvalidConfiguration(anyhow!(
-                            "Cannot use {dir:?} as backup storage as its parent {backed_up_path:?} is backed up. \
-                            You would loose your backups when restoring."
+                            "Cannot use {dir:?} as {usage} as its parent {backed_up_path:?} is backed up. \
+                            It would be replaced when restoring."
                         )));
                     } else {
                         // Not a real prefix (e.g. `abc` matches `abcd/ef`),
@@ -236,10 +277,57 @@ fn with_dynamic_defaults(mut figment: Figment) -> Result<Figment, InvalidConfigu
         }
 
         if let Ok(dir) = figment.extract_inner::<String>("backups.storage.backups.fs.directory") {
-            ensure_not_in_backed_up_path(OsString::from(dir), blueprint)?;
+            ensure_not_in_backed_up_path(OsString::from(dir), "backup storage", blueprint)?;
         }
         if let Ok(dir) = figment.extract_inner::<String>("backups.storage.checks.fs.directory") {
-            ensure_not_in_backed_up_path(OsString::from(dir), blueprint)?;
+            ensure_not_in_backed_up_path(OsString::from(dir), "backup storage", blueprint)?;
+        }
+        if let Ok(path) = figment.extract_inner::<String>("backups.restoration.journal_path") {
+            ensure_not_in_backed_up_path(OsString::from(path), "restoration journal", blueprint)?;
+        }
+        if let Ok(path) = figment.extract_inner::<String>("backups.restoration.report_path") {
+            ensure_not_in_backed_up_path(OsString::from(path), "restore report", blueprint)?;
+        }
+        if let Ok(dir) = figment.extract_inner::<String>("backups.keys.directory") {
+            ensure_not_in_backed_up_path(OsString::from(dir), "backup keys", blueprint)?;
+        }
+        if let Ok(dir) = figment.extract_inner::<String>("backups.safety_snapshot.directory") {
+            ensure_not_in_backed_up_path(OsString::from(dir), "safety snapshot", blueprint)?;
+        }
+
+        // NOTE: The Prose Pod API’s dump is itself backed up, but must not
+        //   be nested in another backed up path.
+        let other_paths = (blueprint.paths.iter())
+            .filter(|(archive_path, _)| archive_path != PROSE_POD_API_DUMP_KEY)
+            .cloned();
+        ensure_not_in_backed_up_path(
+            OsString::from(prose_pod_api_dump_dir),
+            "Prose Pod API dump",
+            &ArchiveBlueprint::new(blueprint.version, other_paths),
+        )?;
+    }
+
+    // Backups and restorations need Prosody to be stopped.
+    if figment.contains("backups.storage")
+        && matches!(
+            (figment.extract_inner::<String>("server.process_manager.kind")).as_deref(),
+            Ok("external")
+        )
+    {
+        return Err(InvalidConfiguration(anyhow!(
+            "Backups are not supported when Prosody is managed externally \
+            (`server.process_manager.kind = \"external\"`) as it cannot be stopped. \
+            Remove `backups.storage` or use another process manager."
+        )));
+    }
+
+    // An empty pattern would match any line, making the check useless.
+    if let Ok(log_patterns) = figment.extract_inner::<Vec<String>>("server.readiness.log_patterns")
+    {
+        if (log_patterns.iter()).any(|pattern| pattern.trim().is_empty()) {
+            return Err(InvalidConfiguration(anyhow!(
+                "`server.readiness.log_patterns` cannot contain empty patterns."
+            )));
         }
     }
 
@@ -312,14 +400,8 @@ impl AppConfig {
             ));
         }
 
-        if new_config.server_api.address() != old_config.server_api.address() {
-            // TODO: Support frontend restarts.
-            tracing::warn!(
-                "The Prose Pod Server API address cannot be changed at runtime. \
-                You need to restart the Prose Pod Server for this change to be effective. \
-                If you need no-downtime restarts, contact us at <https://prose.org/contact/>."
-            );
-        }
+        // NOTE: Changes to `server_api.address` and `server_api.port` are
+        //   applied by moving the HTTP listener (see `crate::listener`).
 
         // NOTE: We can’t reload logging layers because of a bug in `tracing`
         //   (see https://github.com/tokio-rs/tracing/issues/1629). Until
@@ -489,6 +571,7 @@ pub mod proxy {
 pub use server::*;
 pub mod server {
     use serde::Deserialize;
+    use tokio::time::Duration;
 
     use crate::{app_config::LogLevel, models::JidDomain};
 
@@ -504,6 +587,85 @@ pub mod server {
         pub http_port: u16,
 
         pub log_level: LogLevel,
+
+        /// How long to wait for Prosody to exit gracefully (`SIGTERM`) when
+        /// stopping it, before killing it (`SIGKILL`).
+        #[serde(with = "crate::util::serde::iso8601_duration")]
+        pub stop_grace_period: Duration,
+
+        /// How long to wait for Prosody to be ready (admin shell socket and
+        /// HTTP port reachable, startup logged) when starting it.
+        #[serde(with = "crate::util::serde::iso8601_duration")]
+        pub start_timeout: Duration,
+
+        /// Additional checks to run before considering Prosody ready.
+        pub readiness: ReadinessConfig,
+
+        /// What to do when Prosody exits unexpectedly.
+        pub auto_restart: AutoRestartConfig,
+
+        /// What starts, stops and observes Prosody.
+        pub process_manager: ProcessManagerConfig,
+    }
+
+    /// NOTE: Only used with the `child_process` process manager.
+    #[derive(Debug)]
+    #[derive(Deserialize)]
+    pub struct ReadinessConfig {
+        /// Prosody is ready once it logs a line containing one of these
+        /// (case-insensitive). Not checked if empty (default).
+        ///
+        /// WARN: Prosody logs its greeting (“Hello and welcome to prosody
+        ///   version …”) before loading modules, so it doesn’t mean Prosody
+        ///   is ready to serve requests. Use a line logged by a module
+        ///   loaded last instead.
+        pub log_patterns: Vec<String>,
+    }
+
+    /// Prosody is restarted after `initial_backoff` when it crashes, then
+    /// the delay doubles with each crash (up to `max_backoff`). If it
+    /// crashed more than `crash_loop_limit` times in `crash_loop_window`,
+    /// it’s considered a crash loop and isn’t restarted automatically.
+    #[derive(Debug)]
+    #[derive(Deserialize)]
+    pub struct AutoRestartConfig {
+        pub enabled: bool,
+
+        #[serde(with = "crate::util::serde::iso8601_duration")]
+        pub initial_backoff: Duration,
+
+        #[serde(with = "crate::util::serde::iso8601_duration")]
+        pub max_backoff: Duration,
+
+        pub crash_loop_limit: u32,
+
+        #[serde(with = "crate::util::serde::iso8601_duration")]
+        pub crash_loop_window: Duration,
+    }
+
+    /// How Prosody is run (see [`crate::process_manager`]).
+    ///
+    /// NOTE: Prosody needs `OAUTH2_REGISTRATION_KEY` (`auth.oauth2_registration_key`)
+    ///   in its environment. It’s only set automatically for `child_process`,
+    ///   other process managers must set it themselves (e.g. `EnvironmentFile=`).
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[derive(Deserialize)]
+    #[serde(tag = "kind", rename_all = "snake_case")]
+    pub enum ProcessManagerConfig {
+        /// Prosody runs as a child process of the Server API (default).
+        ChildProcess,
+
+        /// Prosody runs as a systemd unit, managed using `systemctl`.
+        Systemd {
+            /// Name of the unit (e.g. `prosody.service`).
+            unit: String,
+        },
+
+        /// Prosody is managed elsewhere (e.g. by another container’s
+        /// orchestrator). It’s only observed, so it can’t be stopped
+        /// (i.e. backups, restorations, restarts and factory resets are not
+        /// possible). Its configuration file is not written either.
+        External,
     }
 
     impl ServerConfig {
@@ -511,6 +673,90 @@ pub mod server {
             format!("http://{}:{}", self.local_hostname, self.http_port)
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use figment::providers::{Format, Toml};
+        use toml::toml;
+
+        use crate::app_config::*;
+
+        #[test]
+        fn test_external_process_manager_without_backups() {
+            let config = config_from_toml(&toml! {
+                [server]
+                domain = "example.org"
+                process_manager.kind = "external"
+            })
+            .unwrap();
+
+            assert_eq!(
+                config.server.process_manager,
+                ProcessManagerConfig::External
+            );
+            assert!(config.backups.is_none());
+        }
+
+        #[test]
+        fn test_external_process_manager_with_backups() {
+            let error = config_from_toml(&toml! {
+                [server]
+                domain = "example.org"
+                process_manager.kind = "external"
+
+                [backups.storage]
+                provider = "fs"
+                fs.directory = "/var/backups"
+            })
+            .unwrap_err();
+
+            assert!(error.contains("managed externally"), "{error}");
+        }
+
+        #[test]
+        fn test_readiness_log_patterns() {
+            let config = config_from_toml(&toml! {
+                [server]
+                domain = "example.org"
+            })
+            .unwrap();
+            assert!(config.server.readiness.log_patterns.is_empty());
+
+            let config = config_from_toml(&toml! {
+                [server]
+                domain = "example.org"
+                readiness.log_patterns = ["Activated service 'http'"]
+            })
+            .unwrap();
+            assert_eq!(
+                config.server.readiness.log_patterns,
+                vec!["Activated service 'http'".to_owned()],
+            );
+        }
+
+        #[test]
+        fn test_readiness_empty_log_pattern() {
+            let error = config_from_toml(&toml! {
+                [server]
+                domain = "example.org"
+                readiness.log_patterns = [" "]
+            })
+            .unwrap_err();
+
+            assert!(error.contains("empty patterns"), "{error}");
+        }
+
+        fn config_from_toml(toml: &toml::Table) -> Result<AppConfig, String> {
+            let toml = toml::to_string(&toml).unwrap();
+
+            let figment = default_config_static().merge(Toml::string(&toml));
+
+            match AppConfig::from_figment(figment) {
+                Ok(app_config) => Ok(app_config),
+                Err(err) => Err(format!("{err:#}")),
+            }
+        }
+    }
 }
 
 pub use server_api::*;
@@ -538,6 +784,8 @@ pub mod server_api {
 
 pub use prose_pod_api::*;
 pub mod prose_pod_api {
+    use std::path::PathBuf;
+
     #[derive(Debug)]
     #[derive(serde::Deserialize)]
     pub struct ProsePodApiConfig {
@@ -550,6 +798,12 @@ pub mod prose_pod_api {
         pub local_hostname: String,
 
         pub port: u16,
+
+        /// Where the Prose Pod API’s data is staged, so it can be archived
+        /// with the rest of the data.
+        ///
+        /// NOTE: Must not be in another backed up path.
+        pub dump_dir: PathBuf,
     }
 
     impl ProsePodApiConfig {
@@ -557,6 +811,69 @@ pub mod prose_pod_api {
             format!("http://{}:{}", self.local_hostname, self.port)
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use std::path::Path;
+
+        use figment::providers::{Format, Toml};
+        use toml::toml;
+
+        use crate::app_config::*;
+
+        #[test]
+        fn test_dump_dir_default() {
+            let config = config_from_toml(&toml! {
+                [server]
+                domain = "example.org"
+            })
+            .unwrap();
+
+            assert_eq!(
+                config.prose_pod_api.dump_dir,
+                Path::new("/var/lib/prose-pod-api-dump")
+            );
+        }
+
+        #[test]
+        fn test_dump_dir_not_in_backed_up_path() {
+            let error = config_from_toml(&toml! {
+                [server]
+                domain = "example.org"
+
+                [api]
+                dump_dir = "/var/lib/prosody/prose-pod-api-dump"
+            })
+            .unwrap_err();
+
+            assert!(error.contains("as Prose Pod API dump"), "{error}");
+
+            let config = config_from_toml(&toml! {
+                [server]
+                domain = "example.org"
+
+                [api]
+                dump_dir = "/srv/prose-pod-api-dump"
+            })
+            .unwrap();
+
+            assert_eq!(
+                config.prose_pod_api.dump_dir,
+                Path::new("/srv/prose-pod-api-dump")
+            );
+        }
+
+        fn config_from_toml(toml: &toml::Table) -> Result<AppConfig, String> {
+            let toml = toml::to_string(&toml).unwrap();
+
+            let figment = default_config_static().merge(Toml::string(&toml));
+
+            match AppConfig::from_figment(figment) {
+                Ok(app_config) => Ok(app_config),
+                Err(err) => Err(format!("{err:#}")),
+            }
+        }
+    }
 }
 
 pub use log::*;
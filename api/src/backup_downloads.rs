@@ -0,0 +1,235 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+//! Backup downloads served by the Server API.
+//!
+//! Presigned URLs from backup stores aren’t always usable by admins (e.g.
+//! `file://` URLs, or S3 buckets not reachable from their network). Instead,
+//! admins get a short-lived token which allows downloading one backup
+//! through `GET /v1/backups/{backup_id}/download?token=…`, without having to
+//! authenticate (e.g. from a browser).
+
+use base64::Engine as _;
+use base64::prelude::BASE64_URL_SAFE_NO_PAD;
+use hmac::{Hmac, KeyInit as _, Mac as _};
+use secrecy::{ExposeSecret as _, SecretSlice};
+use sha2::Sha256;
+
+/// NOTE: The server salt is used for other purposes, this makes sure
+///   download tokens can’t be confused with anything else.
+const DOMAIN_SEPARATOR: &[u8] = b"prose-pod-server/backup-download/v1";
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub(crate) enum DownloadTokenError {
+    #[error("Invalid download token.")]
+    Invalid,
+
+    #[error("Download token expired.")]
+    Expired,
+}
+
+/// Create a token allowing to download backup `backup_id` until `expires_at`.
+///
+/// Format: `{expires_at}.{signature}`, where `expires_at` is a Unix timestamp
+/// (in seconds) and `signature` is a base64url-encoded HMAC-SHA256 of the
+/// backup ID and expiry, keyed with the server salt.
+pub(crate) fn create_download_token(
+    backup_id: &str,
+    expires_at: time::OffsetDateTime,
+    server_salt: &SecretSlice<u8>,
+) -> String {
+    let expires_at = expires_at.unix_timestamp();
+    let signature = mac(backup_id, expires_at, server_salt)
+        .finalize()
+        .into_bytes();
+
+    format!(
+        "{expires_at}.{signature}",
+        signature = BASE64_URL_SAFE_NO_PAD.encode(signature)
+    )
+}
+
+/// Check that `token` allows downloading backup `backup_id` at `now`.
+pub(crate) fn verify_download_token(
+    token: &str,
+    backup_id: &str,
+    now: time::OffsetDateTime,
+    server_salt: &SecretSlice<u8>,
+) -> Result<(), DownloadTokenError> {
+    let Some((expires_at, signature)) = token.split_once('.') else {
+        return Err(DownloadTokenError::Invalid);
+    };
+    let expires_at: i64 = (expires_at.parse()).map_err(|_| DownloadTokenError::Invalid)?;
+    let signature =
+        (BASE64_URL_SAFE_NO_PAD.decode(signature)).map_err(|_| DownloadTokenError::Invalid)?;
+
+    // NOTE: Check the signature first so we don’t tell whether a forged
+    //   token is expired or not.
+    (mac(backup_id, expires_at, server_salt).verify_slice(&signature))
+        .map_err(|_| DownloadTokenError::Invalid)?;
+
+    if expires_at <= now.unix_timestamp() {
+        return Err(DownloadTokenError::Expired);
+    }
+
+    Ok(())
+}
+
+fn mac(backup_id: &str, expires_at: i64, server_salt: &SecretSlice<u8>) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(server_salt.expose_secret())
+        .expect("HMAC accepts keys of any size");
+    mac.update(DOMAIN_SEPARATOR);
+    mac.update(b"\0");
+    mac.update(backup_id.as_bytes());
+    mac.update(b"\0");
+    mac.update(expires_at.to_string().as_bytes());
+    mac
+}
+
+// MARK: Ranges
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub(crate) enum RangeError {
+    /// NOTE: Per RFC 9110, invalid `Range` headers must be ignored.
+    #[error("Invalid or unsupported `Range` header.")]
+    Unsupported,
+
+    #[error("Range not satisfiable.")]
+    NotSatisfiable,
+}
+
+/// Parse the value of a `Range` header, for a resource of `size` bytes.
+///
+/// Only single byte ranges are supported (`bytes=a-b`, `bytes=a-` and
+/// `bytes=-n`), which is what browsers and download managers send to
+/// resume downloads.
+///
+/// Returns a half-open range, truncated to `size`.
+pub(crate) fn parse_range_header(
+    value: &str,
+    size: u64,
+) -> Result<std::ops::Range<u64>, RangeError> {
+    let Some(spec) = value.trim().strip_prefix("bytes=") else {
+        return Err(RangeError::Unsupported);
+    };
+    // NOTE: Multipart responses are not worth supporting here.
+    if spec.contains(',') {
+        return Err(RangeError::Unsupported);
+    }
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return Err(RangeError::Unsupported);
+    };
+
+    let parse = |n: &str| n.parse::<u64>().map_err(|_| RangeError::Unsupported);
+
+    let range = match (start, end) {
+        ("", "") => return Err(RangeError::Unsupported),
+        // Suffix range (last `n` bytes).
+        ("", n) => {
+            let n = parse(n)?;
+            if n == 0 {
+                return Err(RangeError::NotSatisfiable);
+            }
+            size.saturating_sub(n)..size
+        }
+        (start, "") => parse(start)?..size,
+        (start, end) => {
+            let (start, end) = (parse(start)?, parse(end)?);
+            if end < start {
+                return Err(RangeError::Unsupported);
+            }
+            // NOTE: HTTP ranges are inclusive.
+            start..end.saturating_add(1).min(size)
+        }
+    };
+
+    if range.start >= size {
+        return Err(RangeError::NotSatisfiable);
+    }
+
+    Ok(range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn salt() -> SecretSlice<u8> {
+        SecretSlice::from(b"test-salt".to_vec())
+    }
+
+    #[test]
+    fn test_download_token() {
+        let now = time::OffsetDateTime::now_utc();
+        let expires_at = now + time::Duration::minutes(5);
+        let token = create_download_token("backup-a", expires_at, &salt());
+
+        assert_eq!(
+            verify_download_token(&token, "backup-a", now, &salt()),
+            Ok(())
+        );
+        assert_eq!(
+            verify_download_token(&token, "backup-b", now, &salt()),
+            Err(DownloadTokenError::Invalid),
+            "Other backup"
+        );
+        assert_eq!(
+            verify_download_token(&token, "backup-a", expires_at, &salt()),
+            Err(DownloadTokenError::Expired),
+        );
+        assert_eq!(
+            verify_download_token(
+                &token,
+                "backup-a",
+                now,
+                &SecretSlice::from(b"other-salt".to_vec())
+            ),
+            Err(DownloadTokenError::Invalid),
+            "Other salt"
+        );
+
+        // Extending the expiry invalidates the signature.
+        let (_, signature) = token.split_once('.').unwrap();
+        let forged = format!("{}.{signature}", expires_at.unix_timestamp() + 3600);
+        assert_eq!(
+            verify_download_token(&forged, "backup-a", now, &salt()),
+            Err(DownloadTokenError::Invalid),
+        );
+
+        assert_eq!(
+            verify_download_token("garbage", "backup-a", now, &salt()),
+            Err(DownloadTokenError::Invalid),
+        );
+    }
+
+    #[test]
+    fn test_parse_range_header() {
+        assert_eq!(parse_range_header("bytes=0-9", 100), Ok(0..10));
+        assert_eq!(parse_range_header("bytes=10-", 100), Ok(10..100));
+        assert_eq!(parse_range_header("bytes=-10", 100), Ok(90..100));
+        assert_eq!(parse_range_header("bytes=-1000", 100), Ok(0..100));
+        assert_eq!(parse_range_header("bytes=90-1000", 100), Ok(90..100));
+        assert_eq!(
+            parse_range_header("bytes=100-", 100),
+            Err(RangeError::NotSatisfiable)
+        );
+        assert_eq!(
+            parse_range_header("bytes=-0", 100),
+            Err(RangeError::NotSatisfiable)
+        );
+        assert_eq!(
+            parse_range_header("bytes=0-1,5-6", 100),
+            Err(RangeError::Unsupported)
+        );
+        assert_eq!(
+            parse_range_header("bytes=9-0", 100),
+            Err(RangeError::Unsupported)
+        );
+        assert_eq!(
+            parse_range_header("items=0-9", 100),
+            Err(RangeError::Unsupported)
+        );
+    }
+}
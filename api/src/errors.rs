@@ -170,6 +170,22 @@ pub fn conflict_error(
     )
 }
 
+#[must_use]
+#[inline]
+pub fn not_found(
+    code: &'static str,
+    message: impl AsRef<str>,
+    description: impl AsRef<str>,
+) -> Error {
+    Error::new(
+        "NOT_FOUND",
+        code,
+        StatusCode::NOT_FOUND,
+        message,
+        description,
+    )
+}
+
 #[must_use]
 #[inline]
 pub fn validation_error(
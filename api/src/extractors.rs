@@ -99,17 +99,7 @@ impl<F: frontend::State> FromRequestParts<AppState<F, backend::Running>>
             Ok(res) => {
                 let jid = (BareJid::from_str(res.jid())).expect(PROSODY_JIDS_ARE_VALID);
 
-                // FIXME: Replace calls to `prosodyctl` by calls to
-                //   Prosody modules to avoid blocking shared access
-                //   to `prosodyctl` (all calls are mutating).
-                let mut prosodyctl = state.prosodyctl.write().await;
-
-                let primary_role = prosodyctl
-                    .user_role(jid.as_str(), None)
-                    .await
-                    .no_context()?;
-
-                drop(prosodyctl);
+                let primary_role = state.xmpp_server.user_role(&jid).await.no_context()?;
 
                 let caller_info = Self { jid, primary_role };
 
@@ -7,9 +7,12 @@ extern crate serde_json as json;
 
 mod analytics;
 mod app_config;
+mod backup_downloads;
 mod errors;
 mod extractors;
+mod listener;
 mod models;
+mod process_manager;
 mod prose_pod_api;
 mod responders;
 mod router;
@@ -17,7 +20,9 @@ mod secrets_service;
 mod secrets_store;
 mod startup;
 mod state;
+mod supervisor;
 mod util;
+mod xmpp_server;
 
 use std::sync::{Arc, atomic::AtomicBool};
 
@@ -44,7 +49,9 @@ pub async fn main() -> anyhow::Result<()> {
             .unwrap_or_else(|err| panic!("Failed to init tracing for OpenTelemetry: {err}"))
     };
 
-    let app_context = Arc::new(AppContext::new());
+    let (listener_handle, rebind_requests) = listener::channel();
+
+    let app_context = Arc::new(AppContext::new(listener_handle));
 
     let reload_callback = {
         let app_context = Arc::clone(&app_context);
@@ -52,7 +59,7 @@ pub async fn main() -> anyhow::Result<()> {
     };
 
     let res = tokio::select! {
-        res = main_inner(Arc::clone(&app_context), app_config, tracing_reload_handles) => res,
+        res = main_inner(Arc::clone(&app_context), app_config, tracing_reload_handles, rebind_requests) => res,
 
         // Listen for graceful shutdown signals.
         () = listen_for_graceful_shutdown() => Ok(()),
@@ -76,6 +83,7 @@ async fn main_inner(
     app_context: Arc<AppContext>,
     app_config: AppConfig,
     tracing_reload_handles: TracingReloadHandles,
+    rebind_requests: listener::RebindRequests,
 ) -> anyhow::Result<()> {
     // Bind to the API address to exit early if not available.
     let address = app_config.server_api.address();
@@ -111,8 +119,7 @@ async fn main_inner(
                 .layer(axum::middleware::from_fn(router::util::log_request))
                 .with_state(app_context);
 
-            tracing::info!("Serving the Prose Pod Server API on {address}…");
-            axum::serve(listener, app).await.context("Serve error")
+            listener::serve(listener, app, rebind_requests, listener::DRAIN_WINDOW).await
         }
     });
     main_tasks.spawn(async move { startup(startup_app_state).await.context("Startup error") });
@@ -144,6 +151,13 @@ async fn startup(app_state: AppState<f::Running, b::Starting>) -> Result<(), any
     tracing::info!("Running startup actions…");
     let start = Instant::now();
 
+    // NOTE: Only when the process starts, not during factory resets.
+    if let Err(error) = startup::restore_latest_backup_if_empty(&app_state.frontend.config).await {
+        let error = error.context("Disaster recovery failed");
+        tracing::error!("{error:?}");
+        return Err(error);
+    }
+
     match app_state.try_bootstrapping().await {
         Ok(_new_state) => {
             tracing::info!("Startup took {:.0?}.", start.elapsed());
@@ -0,0 +1,240 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+//! Serving the Prose Pod Server API, and moving it to another address
+//! without restarting (see [`ListenerHandle::rebind`]).
+
+use std::future::IntoFuture as _;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use anyhow::{Context as _, anyhow};
+use axum::Router;
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// How long the previous address is still served after a rebind, so
+/// in-flight requests and clients which didn’t reload yet still get
+/// a response.
+pub(crate) const DRAIN_WINDOW: Duration = Duration::from_secs(10);
+
+/// Sends rebind requests to [`serve`].
+#[derive(Debug, Clone)]
+pub(crate) struct ListenerHandle {
+    rebind_tx: mpsc::Sender<RebindRequest>,
+}
+
+/// Received by [`serve`].
+#[derive(Debug)]
+pub(crate) struct RebindRequests(mpsc::Receiver<RebindRequest>);
+
+#[derive(Debug)]
+struct RebindRequest {
+    address: SocketAddr,
+    reply_tx: oneshot::Sender<Result<(), anyhow::Error>>,
+}
+
+pub(crate) fn channel() -> (ListenerHandle, RebindRequests) {
+    let (rebind_tx, rebind_rx) = mpsc::channel(1);
+    (ListenerHandle { rebind_tx }, RebindRequests(rebind_rx))
+}
+
+impl ListenerHandle {
+    /// Moves the Prose Pod Server API to `address` (does nothing if it’s
+    /// already served there).
+    ///
+    /// NOTE: If `address` cannot be bound, the API is still served on
+    ///   the previous address.
+    pub(crate) async fn rebind(&self, address: SocketAddr) -> Result<(), anyhow::Error> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+
+        (self.rebind_tx)
+            .send(RebindRequest { address, reply_tx })
+            .await
+            .map_err(|_| anyhow!("The Prose Pod Server API is not being served."))?;
+
+        reply_rx
+            .await
+            .context("The Prose Pod Server API stopped while rebinding")?
+    }
+}
+
+/// Serves `app` on `listener` until it fails, moving it to other addresses
+/// when asked to (see [`ListenerHandle::rebind`]).
+///
+/// After a rebind, both addresses are served during `drain_window`. The
+/// previous one then stops accepting connections and closes once in-flight
+/// requests are done.
+pub(crate) async fn serve(
+    listener: TcpListener,
+    app: Router,
+    RebindRequests(mut rebind_rx): RebindRequests,
+    drain_window: Duration,
+) -> Result<(), anyhow::Error> {
+    let mut current = ServedListener::spawn(listener, app.clone())?;
+
+    loop {
+        tokio::select! {
+            res = &mut current.task => {
+                return res.context("Join error")?.context("Serve error");
+            }
+
+            Some(RebindRequest { address, reply_tx }) = rebind_rx.recv() => {
+                let res = rebind(&mut current, address, &app, drain_window).await;
+                // NOTE: The caller might have given up, that’s fine.
+                let _ = reply_tx.send(res);
+            }
+        }
+    }
+}
+
+async fn rebind(
+    current: &mut ServedListener,
+    address: SocketAddr,
+    app: &Router,
+    drain_window: Duration,
+) -> Result<(), anyhow::Error> {
+    if address == current.address {
+        return Ok(());
+    }
+
+    // NOTE: Bind before touching the current listener so we can keep
+    //   serving on it if the new address is not available.
+    let listener =
+        (TcpListener::bind(address).await).context(format!("Could not bind to {address}"))?;
+    let new = ServedListener::spawn(listener, app.clone())?;
+
+    let old = std::mem::replace(current, new);
+    tracing::info!(
+        "Still serving on {old_address} for {drain_window:.0?}…",
+        old_address = old.address,
+    );
+    tokio::spawn(old.drain(drain_window));
+
+    Ok(())
+}
+
+#[derive(Debug)]
+struct ServedListener {
+    address: SocketAddr,
+    shutdown: CancellationToken,
+    task: JoinHandle<Result<(), std::io::Error>>,
+}
+
+impl ServedListener {
+    fn spawn(listener: TcpListener, app: Router) -> Result<Self, anyhow::Error> {
+        let address = listener
+            .local_addr()
+            .context("Could not read listener address")?;
+        let shutdown = CancellationToken::new();
+
+        tracing::info!("Serving the Prose Pod Server API on {address}…");
+        let task = tokio::spawn(
+            axum::serve(listener, app)
+                .with_graceful_shutdown(shutdown.clone().cancelled_owned())
+                .into_future(),
+        );
+
+        Ok(Self {
+            address,
+            shutdown,
+            task,
+        })
+    }
+
+    async fn drain(self, drain_window: Duration) {
+        tokio::time::sleep(drain_window).await;
+
+        tracing::info!("No longer serving on {}.", self.address);
+        self.shutdown.cancel();
+
+        match self.task.await {
+            Ok(Ok(())) => tracing::debug!("Closed listener on {}.", self.address),
+            Ok(Err(err)) => tracing::error!("Serve error on {}: {err:?}", self.address),
+            Err(err) => tracing::error!("Join error: {err:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::routing::get;
+    use tokio::net::TcpStream;
+
+    use super::*;
+
+    const TEST_DRAIN_WINDOW: Duration = Duration::from_millis(200);
+
+    async fn get_ok(address: SocketAddr) -> bool {
+        match reqwest::get(format!("http://{address}/")).await {
+            Ok(response) => response.status().is_success(),
+            Err(_) => false,
+        }
+    }
+
+    /// Finds an address which can be bound.
+    fn free_address() -> SocketAddr {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap()
+    }
+
+    async fn start() -> (SocketAddr, ListenerHandle) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let app = Router::new().route("/", get(|| async { "OK" }));
+
+        let (handle, rebind_requests) = channel();
+        tokio::spawn(serve(listener, app, rebind_requests, TEST_DRAIN_WINDOW));
+
+        (address, handle)
+    }
+
+    #[tokio::test]
+    async fn test_rebind() {
+        let (old_address, handle) = start().await;
+        assert!(get_ok(old_address).await);
+
+        let new_address = free_address();
+        handle.rebind(new_address).await.unwrap();
+
+        // Both addresses are served during the drain window.
+        assert!(get_ok(new_address).await);
+        assert!(get_ok(old_address).await);
+
+        tokio::time::sleep(TEST_DRAIN_WINDOW * 2).await;
+
+        assert!(get_ok(new_address).await);
+        assert!(TcpStream::connect(old_address).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rebind_failure() {
+        let (address, handle) = start().await;
+
+        // Occupy an address so binding to it fails.
+        let occupied = TcpListener::bind("127.0.0.1:0").await.unwrap();
+
+        let res = handle.rebind(occupied.local_addr().unwrap()).await;
+        assert!(res.is_err(), "{res:?}");
+
+        tokio::time::sleep(TEST_DRAIN_WINDOW * 2).await;
+
+        // Still served on the previous address.
+        assert!(get_ok(address).await);
+    }
+
+    #[tokio::test]
+    async fn test_rebind_same_address() {
+        let (address, handle) = start().await;
+
+        handle.rebind(address).await.unwrap();
+
+        tokio::time::sleep(TEST_DRAIN_WINDOW * 2).await;
+
+        assert!(get_ok(address).await);
+    }
+}
@@ -0,0 +1,84 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use prosody_child_process::{ExitInfo, ExitWatcher, ProsodyChildProcess, ReadinessConfig};
+
+use crate::AppConfig;
+use crate::startup::PROSODY_ADMIN_SOCKET_PATH;
+
+use super::{BackendProcessManager, BackendStatus, StopOutcome};
+
+/// Prosody runs as a child process of the Server API.
+#[derive(Debug)]
+pub(crate) struct ChildProcessManager {
+    prosody: ProsodyChildProcess,
+}
+
+impl ChildProcessManager {
+    pub(crate) fn new(app_config: &AppConfig) -> Self {
+        use secrecy::ExposeSecret as _;
+
+        let mut prosody = ProsodyChildProcess::new();
+
+        prosody.set_stop_grace_period(app_config.server.stop_grace_period);
+        prosody.set_readiness(ReadinessConfig {
+            timeout: app_config.server.start_timeout,
+            admin_socket: Some(PROSODY_ADMIN_SOCKET_PATH.into()),
+            http_address: Some(format!(
+                "{}:{}",
+                app_config.server.local_hostname, app_config.server.http_port
+            )),
+            log_patterns: app_config.server.readiness.log_patterns.clone(),
+        });
+        prosody.set_env(
+            "OAUTH2_REGISTRATION_KEY",
+            app_config.auth.oauth2_registration_key.expose_secret(),
+        );
+
+        Self { prosody }
+    }
+}
+
+#[async_trait::async_trait]
+impl BackendProcessManager for ChildProcessManager {
+    async fn start(&mut self) -> Result<(), anyhow::Error> {
+        self.prosody.start().await
+    }
+
+    async fn stop(&mut self) -> Result<StopOutcome, anyhow::Error> {
+        self.prosody.stop().await
+    }
+
+    async fn reload(&mut self) -> Result<(), anyhow::Error> {
+        self.prosody.reload().await
+    }
+
+    async fn status(&self) -> Result<BackendStatus, anyhow::Error> {
+        if let Some(exit) = self.prosody.unexpected_exit() {
+            Ok(BackendStatus::from(exit))
+        } else if self.prosody.is_running().await {
+            Ok(BackendStatus::Running)
+        } else if self.prosody.exit_watcher().is_some() {
+            // NOTE: Still running, but failing readiness checks.
+            Ok(BackendStatus::Unknown {
+                details: "not ready".to_owned(),
+            })
+        } else {
+            Ok(BackendStatus::Stopped)
+        }
+    }
+
+    async fn logs(&self, limit: usize) -> Result<Vec<String>, anyhow::Error> {
+        Ok(self.prosody.recent_logs(limit))
+    }
+
+    fn exit_watcher(&self) -> Option<ExitWatcher> {
+        self.prosody.exit_watcher()
+    }
+
+    fn unexpected_exit(&self) -> Option<ExitInfo> {
+        self.prosody.unexpected_exit()
+    }
+}
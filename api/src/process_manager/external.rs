@@ -0,0 +1,64 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use anyhow::anyhow;
+use prosodyctl::ProsodyShell;
+
+use crate::startup::PROSODY_ADMIN_SOCKET_PATH;
+
+use super::{BackendProcessManager, BackendStatus, StopOutcome};
+
+/// Prosody is managed elsewhere, the Server API only observes it
+/// (through its admin shell).
+#[derive(Debug)]
+pub(crate) struct ExternalProcessManager {}
+
+impl ExternalProcessManager {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait::async_trait]
+impl BackendProcessManager for ExternalProcessManager {
+    async fn start(&mut self) -> Result<(), anyhow::Error> {
+        // NOTE: Nothing to start, the caller waits for
+        //   Prosody to be ready (like for other process managers).
+        tracing::info!("Not starting Prosody: Managed externally.");
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<StopOutcome, anyhow::Error> {
+        Err(anyhow!(
+            "Cannot stop Prosody: Managed externally (`server.process_manager.kind = \"external\"`)."
+        ))
+    }
+
+    fn can_stop(&self) -> bool {
+        false
+    }
+
+    async fn reload(&mut self) -> Result<(), anyhow::Error> {
+        ProsodyShell::new().config_reload().await
+    }
+
+    async fn status(&self) -> Result<BackendStatus, anyhow::Error> {
+        // NOTE: Prosody could be running on another host, the admin
+        //   socket being reachable is the best we can check.
+        match tokio::net::UnixStream::connect(PROSODY_ADMIN_SOCKET_PATH).await {
+            Ok(_) => Ok(BackendStatus::Running),
+            Err(err) => Ok(BackendStatus::Unknown {
+                details: format!("admin shell unreachable: {err}"),
+            }),
+        }
+    }
+
+    async fn logs(&self, _limit: usize) -> Result<Vec<String>, anyhow::Error> {
+        Err(anyhow!(
+            "Prosody’s logs are not available: Managed externally \
+            (`server.process_manager.kind = \"external\"`)."
+        ))
+    }
+}
@@ -0,0 +1,77 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use anyhow::anyhow;
+
+use super::{BackendProcessManager, BackendStatus, StopOutcome};
+
+/// In-memory process manager, for tests.
+#[derive(Debug)]
+pub(crate) struct FakeProcessManager {
+    pub status: BackendStatus,
+
+    pub logs: Vec<String>,
+
+    /// Whether [`stop`](BackendProcessManager::stop) fails.
+    pub stop_fails: bool,
+
+    /// Whether Prosody can be stopped at all (`false` for
+    /// [`ExternalProcessManager`](super::ExternalProcessManager)).
+    pub can_stop: bool,
+}
+
+impl Default for FakeProcessManager {
+    fn default() -> Self {
+        Self {
+            status: BackendStatus::Stopped,
+            logs: Vec::new(),
+            stop_fails: false,
+            can_stop: true,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl BackendProcessManager for FakeProcessManager {
+    async fn start(&mut self) -> Result<(), anyhow::Error> {
+        self.status = BackendStatus::Running;
+        self.logs.push("startup  info\tProsody is ready".to_owned());
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<StopOutcome, anyhow::Error> {
+        if self.stop_fails || !self.can_stop {
+            return Err(anyhow!("Cannot stop Prosody."));
+        }
+
+        let outcome = match self.status {
+            BackendStatus::Running => StopOutcome::Terminated,
+            _ => StopOutcome::AlreadyExited,
+        };
+        self.status = BackendStatus::Stopped;
+        Ok(outcome)
+    }
+
+    fn can_stop(&self) -> bool {
+        self.can_stop
+    }
+
+    async fn reload(&mut self) -> Result<(), anyhow::Error> {
+        if self.status != BackendStatus::Running {
+            return Err(anyhow!("Prosody not running."));
+        }
+
+        Ok(())
+    }
+
+    async fn status(&self) -> Result<BackendStatus, anyhow::Error> {
+        Ok(self.status.clone())
+    }
+
+    async fn logs(&self, limit: usize) -> Result<Vec<String>, anyhow::Error> {
+        let skip = self.logs.len().saturating_sub(limit);
+        Ok(self.logs.iter().skip(skip).cloned().collect())
+    }
+}
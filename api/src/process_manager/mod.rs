@@ -0,0 +1,120 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+//! Running Prosody.
+//!
+//! By default, Prosody runs as a child process of the Server API, but it can
+//! also be managed by systemd or by something else entirely (see
+//! [`ProcessManagerConfig`]).
+
+mod child_process;
+mod external;
+#[cfg(test)]
+pub(crate) mod fake;
+mod systemd;
+
+pub(crate) use self::child_process::ChildProcessManager;
+pub(crate) use self::external::ExternalProcessManager;
+pub(crate) use self::systemd::SystemdProcessManager;
+pub(crate) use prosody_child_process::StopOutcome;
+
+use prosody_child_process::{ExitInfo, ExitWatcher};
+
+use crate::AppConfig;
+use crate::app_config::ProcessManagerConfig;
+
+pub(crate) type DynBackendProcessManager = dyn BackendProcessManager;
+
+/// Starts, stops and observes Prosody.
+#[async_trait::async_trait]
+pub(crate) trait BackendProcessManager: std::fmt::Debug + Send + Sync {
+    /// Starts Prosody and waits for it to be ready (as far as the process
+    /// manager can tell).
+    async fn start(&mut self) -> Result<(), anyhow::Error>;
+
+    /// Stops Prosody gracefully.
+    async fn stop(&mut self) -> Result<StopOutcome, anyhow::Error>;
+
+    /// Whether or not [`stop`](Self::stop) can work at all.
+    ///
+    /// NOTE: Operations which need Prosody to be stopped (e.g. backups)
+    ///   check this before doing anything, to fail early.
+    fn can_stop(&self) -> bool {
+        true
+    }
+
+    /// Makes Prosody reload its configuration.
+    async fn reload(&mut self) -> Result<(), anyhow::Error>;
+
+    async fn status(&self) -> Result<BackendStatus, anyhow::Error>;
+
+    /// Last `limit` lines logged by Prosody (most recent last).
+    async fn logs(&self, limit: usize) -> Result<Vec<String>, anyhow::Error>;
+
+    /// Watches Prosody’s exit (see [`crate::supervisor`]), `None` if it’s
+    /// not started or the process manager can’t tell.
+    ///
+    /// NOTE: Process managers which restart Prosody by themselves
+    ///   (e.g. systemd) don’t need to support this.
+    fn exit_watcher(&self) -> Option<ExitWatcher> {
+        None
+    }
+
+    /// How Prosody exited if it did on its own, `None` if it’s running
+    /// or the process manager can’t tell.
+    fn unexpected_exit(&self) -> Option<ExitInfo> {
+        None
+    }
+}
+
+/// Status of Prosody, according to its process manager.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(serde::Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub(crate) enum BackendStatus {
+    Running,
+
+    /// Prosody is not running (stopped on purpose, or never started).
+    Stopped,
+
+    /// Prosody exited on its own.
+    Failed {
+        /// Exit code, `None` if Prosody was terminated by a signal
+        /// (or if the process manager can’t tell).
+        exit_code: Option<i32>,
+
+        /// Signal which terminated Prosody, if any.
+        signal: Option<i32>,
+    },
+
+    /// The process manager can’t tell (e.g. Prosody is starting).
+    Unknown {
+        /// Raw status, as reported by the process manager.
+        details: String,
+    },
+}
+
+impl From<ExitInfo> for BackendStatus {
+    fn from(exit: ExitInfo) -> Self {
+        Self::Failed {
+            exit_code: exit.code,
+            signal: exit.signal,
+        }
+    }
+}
+
+/// Creates the process manager selected in `server.process_manager`.
+///
+/// NOTE: This constructor is lazy. Prosody will start when you call
+///   [`BackendProcessManager::start`].
+pub(crate) fn from_config(app_config: &AppConfig) -> Box<DynBackendProcessManager> {
+    match &app_config.server.process_manager {
+        ProcessManagerConfig::ChildProcess => Box::new(ChildProcessManager::new(app_config)),
+        ProcessManagerConfig::Systemd { unit } => {
+            Box::new(SystemdProcessManager::new(unit.clone()))
+        }
+        ProcessManagerConfig::External => Box::new(ExternalProcessManager::new()),
+    }
+}
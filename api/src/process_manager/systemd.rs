@@ -0,0 +1,178 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use anyhow::{Context as _, anyhow};
+use tokio::process::Command;
+
+use super::{BackendProcessManager, BackendStatus, StopOutcome};
+
+/// Prosody runs as a systemd unit, managed using `systemctl` (logs are
+/// read using `journalctl`).
+///
+/// NOTE: systemd restarts Prosody by itself if the unit says so
+///   (`Restart=`), therefore crashes are not reported to the supervisor.
+#[derive(Debug)]
+pub(crate) struct SystemdProcessManager {
+    unit: String,
+}
+
+impl SystemdProcessManager {
+    pub(crate) fn new(unit: String) -> Self {
+        Self { unit }
+    }
+
+    async fn systemctl(&self, args: &[&str]) -> Result<String, anyhow::Error> {
+        let output = Command::new("systemctl")
+            .args(args)
+            .arg(&self.unit)
+            .output()
+            .await
+            .context("Failed running `systemctl`")?;
+
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        } else {
+            Err(anyhow!(
+                "`systemctl {args} {unit}` failed ({status}): {stderr}",
+                args = args.join(" "),
+                unit = self.unit,
+                status = output.status,
+                stderr = String::from_utf8_lossy(&output.stderr).trim(),
+            ))
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl BackendProcessManager for SystemdProcessManager {
+    async fn start(&mut self) -> Result<(), anyhow::Error> {
+        tracing::debug!("Starting `{}`…", self.unit);
+
+        // NOTE: Returns once the unit is started (as defined by its `Type=`).
+        self.systemctl(&["start"]).await.map(drop)
+    }
+
+    async fn stop(&mut self) -> Result<StopOutcome, anyhow::Error> {
+        tracing::debug!("Stopping `{}`…", self.unit);
+
+        if self.status().await? != BackendStatus::Running {
+            return Ok(StopOutcome::AlreadyExited);
+        }
+
+        // NOTE: systemd kills Prosody if it doesn’t stop within the unit’s
+        //   `TimeoutStopSec=`, but doesn’t tell us if it did.
+        self.systemctl(&["stop"]).await?;
+
+        Ok(StopOutcome::Terminated)
+    }
+
+    async fn reload(&mut self) -> Result<(), anyhow::Error> {
+        tracing::debug!("Reloading `{}`…", self.unit);
+
+        // NOTE: Requires `ExecReload=` in the unit (Prosody’s unit sends `SIGHUP`).
+        self.systemctl(&["reload"]).await.map(drop)
+    }
+
+    async fn status(&self) -> Result<BackendStatus, anyhow::Error> {
+        let output = self
+            .systemctl(&[
+                "show",
+                "--property=ActiveState,ExecMainCode,ExecMainStatus",
+            ])
+            .await?;
+
+        Ok(parse_status(&output))
+    }
+
+    async fn logs(&self, limit: usize) -> Result<Vec<String>, anyhow::Error> {
+        let output = Command::new("journalctl")
+            .arg("--unit")
+            .arg(&self.unit)
+            .arg("--lines")
+            .arg(limit.to_string())
+            .args([
+                "--no-pager",
+                "--output",
+                "cat",
+            ])
+            .output()
+            .await
+            .context("Failed running `journalctl`")?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "`journalctl` failed ({status}): {stderr}",
+                status = output.status,
+                stderr = String::from_utf8_lossy(&output.stderr).trim(),
+            ));
+        }
+
+        Ok((String::from_utf8_lossy(&output.stdout).lines())
+            .map(ToOwned::to_owned)
+            .collect())
+    }
+}
+
+/// Parses the output of `systemctl show --property=ActiveState,ExecMainCode,ExecMainStatus`.
+///
+/// NOTE: `ExecMainCode` is a `siginfo` code (`1` = exited, `2` = killed,
+///   `3` = dumped core) which says if `ExecMainStatus` is an exit code
+///   or a signal.
+fn parse_status(output: &str) -> BackendStatus {
+    let property = |name: &str| {
+        (output.lines())
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix('='))
+            .unwrap_or_default()
+    };
+
+    let status = property("ExecMainStatus").parse::<i32>().ok();
+    let (exit_code, signal) = match property("ExecMainCode") {
+        "1" => (status, None),
+        "2" | "3" => (None, status),
+        _ => (None, None),
+    };
+
+    match property("ActiveState") {
+        "active" | "reloading" => BackendStatus::Running,
+        "inactive" => BackendStatus::Stopped,
+        "failed" => BackendStatus::Failed { exit_code, signal },
+        details => BackendStatus::Unknown {
+            details: details.to_owned(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_status() {
+        assert_eq!(
+            parse_status("ActiveState=active\nExecMainCode=0\nExecMainStatus=0\n"),
+            BackendStatus::Running
+        );
+        assert_eq!(
+            parse_status("ActiveState=failed\nExecMainCode=1\nExecMainStatus=3\n"),
+            BackendStatus::Failed {
+                exit_code: Some(3),
+                signal: None,
+            }
+        );
+        assert_eq!(
+            parse_status("ActiveState=failed\nExecMainCode=2\nExecMainStatus=9\n"),
+            BackendStatus::Failed {
+                exit_code: None,
+                signal: Some(9),
+            }
+        );
+        assert_eq!(
+            parse_status("ActiveState=activating\nExecMainCode=0\nExecMainStatus=0\n"),
+            BackendStatus::Unknown {
+                details: "activating".to_owned(),
+            }
+        );
+    }
+}
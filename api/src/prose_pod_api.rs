@@ -5,6 +5,7 @@
 
 use std::sync::Arc;
 
+use axum::body::Bytes;
 use axum::http::HeaderValue;
 use reqwest::header::ACCEPT;
 use serde::de::DeserializeOwned;
@@ -20,6 +21,21 @@ pub struct ProsePodApi {
 // MARK: Users
 
 impl ProsePodApi {
+    /// Gets a consistent dump of the Prose Pod API’s data, as a tar archive.
+    pub async fn get_dump(&self, prose_token: &HeaderValue) -> Result<Bytes, self::Error> {
+        // NOTE: Not using `Self::get` as it accepts JSON only.
+        let response = (self.http_client)
+            .get(self.url("/v1/backups-internal/dump"))
+            .header(ACCEPT, "application/x-tar")
+            .header("x-prose-token", prose_token)
+            .send()
+            .await?;
+
+        Ok(response.error_for_status()?.bytes().await?)
+    }
+
+    /// Sends restored data to the Prose Pod API (in the same format as
+    /// [`ProsePodApi::get_dump`]).
     pub async fn put_restore(
         &self,
         prose_token: &HeaderValue,
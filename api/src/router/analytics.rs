@@ -27,18 +27,14 @@ pub async fn proxy_analytics_event(
 
     let domain = &app_state.frontend.config.server.domain;
 
-    let mut prosodyctl = app_state.backend.prosodyctl.write().await;
-
     // NOTE: We need to filter out service accounts,
     //   which don’t have the `prosody:member` role.
-    let user_count = prosodyctl
-        .user_get_jids_with_role(domain, "prosody:member")
+    let user_count = (app_state.backend.xmpp_server)
+        .users_with_role(domain, "prosody:member")
         .await
         .no_context()?
         .len();
 
-    drop(prosodyctl);
-
     let Some(event) = process_event(
         event,
         &app_state.frontend.config.vendor_analytics,
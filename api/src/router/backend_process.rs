@@ -0,0 +1,204 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+//! Observing Prosody through its process manager
+//! (see [`crate::process_manager`]).
+
+use axum::Json;
+use axum::extract::{Query, State};
+use tokio::sync::RwLock;
+
+use crate::errors;
+use crate::models::CallerInfo;
+use crate::process_manager::{BackendStatus, DynBackendProcessManager, StopOutcome};
+use crate::responders::Error;
+use crate::state::prelude::*;
+
+/// Default `limit` of `GET /v1/backend/logs`.
+const DEFAULT_LOGS_LIMIT: usize = 100;
+
+/// NOTE: The child process manager doesn’t keep more lines anyway.
+const MAX_LOGS_LIMIT: usize = 1000;
+
+// MARK: - Routes
+
+/// `GET /v1/backend/status`.
+pub(super) async fn get_backend_status<F: frontend::State>(
+    State(AppState { ref backend, .. }): State<AppState<F, b::Running>>,
+    caller_info: CallerInfo,
+) -> Result<Json<BackendStatus>, Error> {
+    caller_info.check_is_admin()?;
+
+    let prosody = backend.prosody.read().await;
+    let status = backend_status(prosody.as_ref()).await?;
+
+    Ok(Json(status))
+}
+
+#[derive(Debug)]
+#[derive(serde::Deserialize)]
+pub(super) struct GetBackendLogsQuery {
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+/// `GET /v1/backend/logs?limit=…`: Last lines logged by Prosody
+/// (most recent last).
+pub(super) async fn get_backend_logs<F: frontend::State>(
+    State(AppState { ref backend, .. }): State<AppState<F, b::Running>>,
+    caller_info: CallerInfo,
+    Query(GetBackendLogsQuery { limit }): Query<GetBackendLogsQuery>,
+) -> Result<Json<Vec<String>>, Error> {
+    caller_info.check_is_admin()?;
+
+    let prosody = backend.prosody.read().await;
+    let logs = backend_logs(prosody.as_ref(), limit).await?;
+
+    Ok(Json(logs))
+}
+
+// MARK: - Helpers
+
+/// Fails if Prosody can’t be stopped (see [`stop_backend`]).
+///
+/// NOTE: Call this before doing anything in routes which need Prosody to be
+///   stopped, so they don’t fail halfway through.
+pub(in crate::router) async fn ensure_backend_can_stop(
+    prosody: &RwLock<Box<DynBackendProcessManager>>,
+) -> Result<(), Error> {
+    if prosody.read().await.can_stop() {
+        Ok(())
+    } else {
+        Err(errors::configuration_error(
+            "BACKEND_STOP_UNSUPPORTED",
+            "Not supported",
+            "Your Prose Server is managed externally (`server.process_manager.kind = \"external\"`), \
+            it cannot be stopped by the Prose Pod Server. \
+            Backups, restorations, restarts and factory resets are not supported.",
+        ))
+    }
+}
+
+/// Stops Prosody (e.g. before making a backup).
+///
+/// NOTE: Some process managers can’t stop Prosody
+///   (see [`ExternalProcessManager`](crate::process_manager::ExternalProcessManager)).
+pub(in crate::router) async fn stop_backend(
+    prosody: &RwLock<Box<DynBackendProcessManager>>,
+) -> Result<StopOutcome, Error> {
+    ensure_backend_can_stop(prosody).await?;
+
+    let mut prosody = prosody.write().await;
+
+    prosody.stop().await.map_err(|error| {
+        errors::internal_server_error(
+            &error.context("Could not stop Prosody"),
+            "BACKEND_STOP_FAILED",
+            "Your Prose Server could not be stopped. Contact an administrator to fix this.",
+        )
+    })
+}
+
+async fn backend_status(prosody: &DynBackendProcessManager) -> Result<BackendStatus, Error> {
+    prosody.status().await.map_err(|error| {
+        errors::internal_server_error(
+            &error.context("Could not get Prosody’s status"),
+            "BACKEND_STATUS_FAILED",
+            "Could not get the status of your Prose Server.",
+        )
+    })
+}
+
+async fn backend_logs(
+    prosody: &DynBackendProcessManager,
+    limit: Option<usize>,
+) -> Result<Vec<String>, Error> {
+    let limit = limit.unwrap_or(DEFAULT_LOGS_LIMIT).min(MAX_LOGS_LIMIT);
+
+    prosody.logs(limit).await.map_err(|error| {
+        errors::internal_server_error(
+            &error.context("Could not read Prosody’s logs"),
+            "BACKEND_LOGS_FAILED",
+            "Could not read the logs of your Prose Server.",
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::process_manager::BackendProcessManager as _;
+    use crate::process_manager::fake::FakeProcessManager;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stop_backend() {
+        let mut fake = FakeProcessManager::default();
+        fake.start().await.unwrap();
+        let prosody: RwLock<Box<DynBackendProcessManager>> = RwLock::new(Box::new(fake));
+
+        assert_eq!(
+            stop_backend(&prosody).await.unwrap(),
+            StopOutcome::Terminated
+        );
+        assert_eq!(
+            backend_status(prosody.read().await.as_ref()).await.unwrap(),
+            BackendStatus::Stopped
+        );
+        assert_eq!(
+            stop_backend(&prosody).await.unwrap(),
+            StopOutcome::AlreadyExited
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stop_backend_failure() {
+        let prosody: RwLock<Box<DynBackendProcessManager>> =
+            RwLock::new(Box::new(FakeProcessManager {
+                stop_fails: true,
+                ..Default::default()
+            }));
+
+        let error = stop_backend(&prosody).await.unwrap_err();
+
+        assert_eq!(error.into_json()["code"], "BACKEND_STOP_FAILED");
+    }
+
+    #[tokio::test]
+    async fn test_stop_backend_unsupported() {
+        let prosody: RwLock<Box<DynBackendProcessManager>> =
+            RwLock::new(Box::new(FakeProcessManager {
+                status: BackendStatus::Running,
+                can_stop: false,
+                ..Default::default()
+            }));
+
+        let error = ensure_backend_can_stop(&prosody).await.unwrap_err();
+        assert_eq!(error.into_json()["code"], "BACKEND_STOP_UNSUPPORTED");
+
+        let error = stop_backend(&prosody).await.unwrap_err();
+        assert_eq!(error.into_json()["code"], "BACKEND_STOP_UNSUPPORTED");
+
+        assert_eq!(
+            backend_status(prosody.read().await.as_ref()).await.unwrap(),
+            BackendStatus::Running
+        );
+    }
+
+    #[tokio::test]
+    async fn test_backend_logs_limit() {
+        let fake = FakeProcessManager {
+            logs: (0..2000).map(|i| format!("line {i}")).collect(),
+            ..Default::default()
+        };
+
+        let logs = backend_logs(&fake, None).await.unwrap();
+        assert_eq!(logs.len(), DEFAULT_LOGS_LIMIT);
+        assert_eq!(logs.last().map(String::as_str), Some("line 1999"));
+
+        let logs = backend_logs(&fake, Some(5000)).await.unwrap();
+        assert_eq!(logs.len(), MAX_LOGS_LIMIT);
+    }
+}
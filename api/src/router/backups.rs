@@ -3,37 +3,63 @@
 // Copyright: 2026, Rémi Bardon <remi@remibardon.name>
 // License: Mozilla Public License v2.0 (MPL v2.0)
 
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, LazyLock};
 
 use anyhow::Context as _;
 use axum::Json;
-use axum::body::Bytes;
+use axum::body::{Body, Bytes};
 use axum::extract::{Path, Query, State};
 use axum::http::{HeaderMap, HeaderValue};
 use axum::response::sse::{self, Sse};
-use axum_extra::either::Either;
+use axum_extra::either::{Either, Either3};
 use json::json;
-use prose_backup::archiving::{AdditionalData, ArchiveBlueprint, TarSizeCalculator};
-use prose_backup::dtos::{BackupDto, BackupMetadataFullDto, BackupMetadataPartialDto};
+use prose_backup::archiving::ArchiveBlueprint;
+use prose_backup::diff::{BackupDiffDto, DiffBackupsError};
+use prose_backup::dtos::{
+    BackupDto, BackupMetadataFullDto, BackupMetadataPartialDto, CreateBackupProgressDto,
+    StorageReportDto,
+};
 use prose_backup::event_handlers::NoopEventHandler;
+use prose_backup::keys::{KeyDto, KeyUsage, KeyringError};
+use prose_backup::restoration::ArchiveMigration;
+use prose_backup::restore_report::{RestoreReportDto, RestoreStage, RestoreStageEventDto};
+use prose_backup::safety_snapshot::{SafetySnapshotDto, UndoRestoreError};
+use prose_backup::stores::ReadObjectError;
+use prose_backup::transparency::LogVerificationReport;
 use prose_backup::{
     BackupId, BackupService, CreateBackupCommand, CreateBackupError, CreateBackupEventHandler,
-    CreateBackupSuccess, RestoreBackupEventHandler, RestoreBackupPartialSuccess, tar,
+    CreateBackupSuccess, RestoreBackupEventHandler, RestoreBackupPartialSuccess,
+    UploadBackupCommand, UploadBackupError, UploadBackupSuccess, tar,
 };
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 
-use crate::errors;
+use crate::backup_downloads::DownloadTokenError;
 use crate::models::CallerInfo;
 use crate::prose_pod_api::ProsePodApi;
+use crate::router::backend_process::{ensure_backend_can_stop, stop_backend};
 use crate::state::prelude::*;
 use crate::util::{NoContext as _, debug_panic_or_log_error};
+use crate::{AppConfig, errors};
+
+pub const BACKUPS_VERSION: u8 = 2;
 
-pub const BACKUPS_VERSION: u8 = 1;
+/// Backup blueprints, by version.
+///
+/// `prose_pod_api_dump_dir` is where the Prose Pod API’s data is staged, so
+/// it can be archived with the rest of the data (see `[api].dump_dir` and
+/// [`stage_prose_pod_api_dump`]).
+///
+/// NOTE: The Prose Pod API runs in another container, this is only a copy of
+///   the consistent dump it provides.
+pub fn backup_blueprints(
+    prose_pod_api_dump_dir: &std::path::Path,
+) -> HashMap<u8, ArchiveBlueprint> {
+    use std::path::PathBuf;
 
-pub static BACKUP_BLUEPRINTS: LazyLock<HashMap<u8, ArchiveBlueprint>> = LazyLock::new(|| {
-    let mut hash_map = HashMap::with_capacity(1);
+    let mut hash_map = HashMap::with_capacity(2);
 
     hash_map.insert(
         1,
@@ -48,21 +74,66 @@ pub static BACKUP_BLUEPRINTS: LazyLock<HashMap<u8, ArchiveBlueprint>> = LazyLock
         ),
     );
 
+    hash_map.insert(
+        2,
+        ArchiveBlueprint::new(
+            2,
+            [
+                (
+                    "prose-pod-server-data",
+                    PathBuf::from("/var/lib/prose-pod-server"),
+                ),
+                ("prosody-data", PathBuf::from("/var/lib/prosody")),
+                ("prose-config", PathBuf::from("/etc/prose")),
+                ("prosody-config", PathBuf::from("/etc/prosody")),
+                (PROSE_POD_API_DUMP_KEY, prose_pod_api_dump_dir.to_path_buf()),
+            ],
+        ),
+    );
+
     hash_map
+}
+
+/// The blueprint of backups created by `backup_service`.
+fn latest_blueprint(backup_service: &BackupService) -> &ArchiveBlueprint {
+    let blueprints = &backup_service.archiving_context.blueprints;
+    (blueprints.get(&BACKUPS_VERSION)).expect("A blueprint should always exist for BACKUPS_VERSION")
+}
+
+/// Where the Prose Pod API’s data is staged (see [`backup_blueprints`]).
+fn prose_pod_api_dump_dir(backup_service: &BackupService) -> &std::path::Path {
+    (latest_blueprint(backup_service).paths.iter())
+        .find(|(archive_path, _)| archive_path == PROSE_POD_API_DUMP_KEY)
+        .map(|(_, path)| path.as_path())
+        .expect("The latest blueprint should always contain the Prose Pod API’s data")
+}
+
+pub static BACKUP_MIGRATIONS: LazyLock<Vec<ArchiveMigration>> = LazyLock::new(|| {
+    vec![
+        // NOTE: In v1, the Prose Pod API’s data was stored as additional
+        //   data (not part of the blueprint).
+        ArchiveMigration::new(2, [(PROSE_POD_API_ARCHIVE_KEY, PROSE_POD_API_DUMP_KEY)]),
+    ]
 });
 
 #[derive(serde::Deserialize)]
 pub struct CreateBackupRequest {
     pub description: String,
+
+    /// Name of the backup profile to use (see `[backups.profiles]`).
+    #[serde(default)]
+    pub profile: Option<String>,
 }
 
 pub(super) async fn post_backups_all(
     headers: HeaderMap,
     State(app_state): State<AppState<f::Running, b::Running>>,
     caller_info: CallerInfo,
-    // NOTE: We pass that in the query because the body is already used to pass
-    //   a byte stream. It’s internal and only temporary so let’s ignore it.
+    // NOTE: We pass that in the query for historical reasons (the body used
+    //   to contain the Prose Pod API’s data).
     Query(req): Query<CreateBackupRequest>,
+    // NOTE: Still accepted, for Prose Pod APIs which don’t provide a dump
+    //   (see `GET /v1/backups-internal/dump`).
     prose_pod_api_data: Bytes,
 ) -> Either<
     Result<Json<CreateBackupSuccess>, crate::responders::Error>,
@@ -72,30 +143,42 @@ pub(super) async fn post_backups_all(
         return Either::E1(Err(err));
     };
 
-    match headers.get(axum::http::header::CONTENT_TYPE) {
-        Some(value) if value == "application/x-tar" => {}
-        _ => {
-            return Either::E1(Err(errors::unsupported_media_type(
+    // NOTE: The backup would replace data restored on startup.
+    if let Err(err) =
+        send_pending_prose_pod_api_restore_(&app_state, headers.get("x-prose-token")).await
+    {
+        return Either::E1(Err(err));
+    }
+
+    let dump_source = if prose_pod_api_data.is_empty() {
+        // NOTE: Needed to get the Prose Pod API’s data.
+        let Some(prose_token) = headers.get("x-prose-token").cloned() else {
+            return Either::E1(Err(errors::validation_error(
                 "BAD_REQUEST",
                 "Bad request",
-                "Body should be `application/x-tar`.",
+                "Missing Prose token.",
             )));
+        };
+        ProsePodApiDumpSource::Pull { prose_token }
+    } else {
+        match headers.get(axum::http::header::CONTENT_TYPE) {
+            Some(value) if value == "application/x-tar" => {}
+            _ => {
+                return Either::E1(Err(errors::unsupported_media_type(
+                    "BAD_REQUEST",
+                    "Bad request",
+                    "Body should be `application/x-tar`.",
+                )));
+            }
         }
-    }
-
-    if prose_pod_api_data.is_empty() {
-        return Either::E1(Err(errors::validation_error(
-            "BAD_REQUEST",
-            "Bad request",
-            "Missing Prose Pod API data.",
-        )));
-    }
+        ProsePodApiDumpSource::Body(prose_pod_api_data)
+    };
 
     match headers.get(reqwest::header::ACCEPT) {
         Some(val) if val.as_bytes() == b"text/event-stream" => {
-            Either::E2(post_backups_stream(app_state, req, prose_pod_api_data).await)
+            Either::E2(post_backups_stream(app_state, req, dump_source).await)
         }
-        _ => Either::E1(post_backups(app_state, req, prose_pod_api_data).await),
+        _ => Either::E1(post_backups(app_state, req, dump_source).await),
     }
 }
 
@@ -103,13 +186,15 @@ pub(super) async fn post_backups_all(
 async fn post_backups(
     app_state: AppState<f::Running, b::Running>,
     req: CreateBackupRequest,
-    prose_pod_api_data: Bytes,
+    dump_source: ProsePodApiDumpSource,
 ) -> Result<Json<CreateBackupSuccess>, crate::responders::Error> {
     post_backups_(
         app_state,
         req.description,
-        prose_pod_api_data,
+        req.profile,
+        dump_source,
         &mut NoopEventHandler,
+        std::future::pending(),
     )
     .await
     .map(Json)
@@ -119,7 +204,7 @@ async fn post_backups(
 async fn post_backups_stream(
     app_state: AppState<f::Running, b::Running>,
     req: CreateBackupRequest,
-    prose_pod_api_data: Bytes,
+    dump_source: ProsePodApiDumpSource,
 ) -> Result<Sse<ReceiverStream<Result<sse::Event, anyhow::Error>>>, crate::responders::Error> {
     // Stream backup progress.
     let (mut event_handler, sender, receiver) = {
@@ -131,10 +216,7 @@ async fn post_backups_stream(
             StreamingCreateBackupEventHandler {
                 backup_id: None,
                 total: 0,
-                progress: 0,
-                // TODO: Parameterize this?
-                interval: tokio::time::Duration::from_millis(100),
-                last_event_sent: (0, tokio::time::Instant::now()),
+                last_progress_sent: 0,
                 progress_sender: Arc::clone(&sender),
             },
             sender,
@@ -144,14 +226,22 @@ async fn post_backups_stream(
 
     // NOTE: No need to get the `JoinHandle`, we can fire-and-forget this.
     tokio::task::spawn(async move {
+        // NOTE: Stop creating the backup if the client disconnects.
         let result = post_backups_(
             app_state,
             req.description,
-            prose_pod_api_data,
+            req.profile,
+            dump_source,
             &mut event_handler,
+            sender.closed(),
         )
         .await;
 
+        if sender.is_closed() {
+            tracing::debug!("Client disconnected, not sending end event.");
+            return;
+        }
+
         let backup_id = event_handler.backup_id.unwrap_or_else(|| {
             debug_panic_or_log_error!("`backup_id` should be assigned by now.");
             String::new()
@@ -169,8 +259,10 @@ async fn post_backups_stream(
 async fn post_backups_<F: frontend::State>(
     app_state: AppState<F, b::Running>,
     description: String,
-    prose_pod_api_data: Bytes,
+    profile: Option<String>,
+    dump_source: ProsePodApiDumpSource,
     event_handler: &mut impl CreateBackupEventHandler,
+    cancelled: impl Future<Output = ()>,
 ) -> Result<CreateBackupSuccess, crate::responders::Error>
 where
     F: AsRef<f::Running>,
@@ -180,31 +272,222 @@ where
     AppState<F, b::Restarting>: AppStateTrait,
     AppState<F, b::RestartFailed>: AppStateTrait,
 {
-    let blueprint = (BACKUP_BLUEPRINTS.get(&BACKUPS_VERSION))
-        .expect("A blueprint should always exist for BACKUPS_VERSION");
+    ensure_backend_can_stop(&app_state.backend.prosody).await?;
+
+    let backup_service = app_state.backend.backup_service()?;
+    let blueprint = latest_blueprint(&backup_service);
+
+    // NOTE: Check the profile before stopping Prosody.
+    let blueprint = match profile.as_deref() {
+        Some(name) => match backup_service.profiles.get(name) {
+            Some(profile) => Cow::Owned(profile.filter_blueprint(blueprint)),
+            None => {
+                return Err(errors::validation_error(
+                    "BAD_REQUEST",
+                    "Bad request",
+                    format!("Unknown backup profile `{name}`."),
+                ));
+            }
+        },
+        None => Cow::Borrowed(blueprint),
+    };
 
-    let backup_service = Arc::clone(app_state.backend.backup_service()?);
+    // Get a consistent dump of the Prose Pod API’s data, as close as possible
+    // to when Prosody is stopped.
+    if (blueprint.paths.iter()).any(|(archive_path, _)| archive_path == PROSE_POD_API_DUMP_KEY) {
+        let prose_pod_api = Arc::clone(&app_state.backend.prose_pod_api);
+        let dump_dir = prose_pod_api_dump_dir(&backup_service);
 
-    // Stop Prosody.
-    {
-        let mut prosody = app_state.backend.prosody.write().await;
-        prosody.stop().await.unwrap();
+        stage_prose_pod_api_dump(&prose_pod_api, dump_source, dump_dir).await?;
     }
 
+    // Stop Prosody.
+    stop_backend(&app_state.backend.prosody).await?;
+
     let app_state = app_state.with_backend(b::UndergoingBackup {});
 
+    if backup_service.snapshot_config.enabled {
+        // Restart the backend as soon as data is snapshotted, and create
+        // the backup from the snapshot.
+        let snapshot = backup_service.create_snapshot(&blueprint).await;
+
+        let _app_state = app_state.do_restart_backend().await;
+
+        let snapshot = snapshot.map_err(|error| {
+            errors::internal_server_error(
+                &error.context("Failed creating snapshot"),
+                "BACKUP_CREATE_FAILED",
+                "Something went wrong while creating the backup. Contact an administrator to fix this.",
+            )
+        })?;
+
+        let response = create_backup(
+            &backup_service,
+            &description,
+            profile.as_deref(),
+            snapshot.blueprint(),
+            event_handler,
+            cancelled,
+        )
+        .await;
+
+        snapshot.discard().await;
+
+        response
+    } else {
+        let response = create_backup(
+            &backup_service,
+            &description,
+            profile.as_deref(),
+            &blueprint,
+            event_handler,
+            cancelled,
+        )
+        .await;
+
+        let _app_state = app_state.do_restart_backend().await;
+
+        response
+    }
+}
+
+async fn create_backup(
+    backup_service: &BackupService,
+    description: &str,
+    profile: Option<&str>,
+    blueprint: &ArchiveBlueprint,
+    event_handler: &mut impl CreateBackupEventHandler,
+    cancelled: impl Future<Output = ()>,
+) -> Result<CreateBackupSuccess, crate::responders::Error> {
     let command = CreateBackupCommand {
         prefix: "prose_backup",
-        description: &description,
+        description,
         blueprint,
-        additional_archive_data: Some(ProsePodApiData(prose_pod_api_data)),
+        additional_archive_data: Option::<()>::None,
+        profile,
     };
 
-    let response = backup_service.create_backup(command, event_handler).await;
+    // NOTE: Only cancel backup creation, the backend must be restarted.
+    tokio::select! {
+        response = backup_service.create_backup(command, event_handler) => {
+            response.map_err(crate::responders::Error::from)
+        }
+        () = cancelled => Err(errors::internal_server_error(
+            &anyhow::anyhow!("Client disconnected."),
+            "BACKUP_CREATE_CANCELLED",
+            "Backup creation was cancelled.",
+        )),
+    }
+}
+
+/// Where to get the Prose Pod API’s data from.
+enum ProsePodApiDumpSource {
+    /// Ask the Prose Pod API for a consistent dump.
+    Pull { prose_token: HeaderValue },
 
-    let _app_state = app_state.do_restart_backend().await;
+    /// The Prose Pod API sent its data along the request.
+    Body(Bytes),
+}
+
+/// Gets a consistent dump of the Prose Pod API’s data and stores it in
+/// `dump_dir` (replacing the previous one), so it can be archived like
+/// any other blueprint entry.
+async fn stage_prose_pod_api_dump(
+    prose_pod_api: &ProsePodApi,
+    source: ProsePodApiDumpSource,
+    dump_dir: &std::path::Path,
+) -> Result<(), crate::responders::Error> {
+    let dump = match source {
+        ProsePodApiDumpSource::Pull { ref prose_token } => prose_pod_api
+            .get_dump(prose_token)
+            .await
+            .context("Could not get Prose Pod API data")
+            .map_err(|error| {
+                errors::internal_server_error(
+                    &error,
+                    "BACKUP_CREATE_FAILED",
+                    "Something went wrong while creating the backup. Contact an administrator to fix this.",
+                )
+            })?,
+        ProsePodApiDumpSource::Body(dump) => dump,
+    };
+
+    let dump_dir = dump_dir.to_path_buf();
+    tokio::task::spawn_blocking(move || unpack_prose_pod_api_dump(dump, &dump_dir))
+        .await
+        .map_err(anyhow::Error::from)
+        .flatten()
+        .context("Could not stage Prose Pod API data")
+        .map_err(|error| {
+            errors::internal_server_error(
+                &error,
+                "BACKUP_CREATE_FAILED",
+                "Something went wrong while creating the backup. Contact an administrator to fix this.",
+            )
+        })
+}
+
+/// Stages the Prose Pod API’s current data before a restoration, so it’s
+/// in the safety snapshot (see `POST /v1/backups/undo-last-restore`).
+///
+/// NOTE: Not critical, the restoration happens anyway.
+async fn stage_prose_pod_api_dump_before_restore(
+    prose_pod_api: &ProsePodApi,
+    prose_token: &HeaderValue,
+    dump_dir: &std::path::Path,
+) {
+    let source = ProsePodApiDumpSource::Pull {
+        prose_token: prose_token.to_owned(),
+    };
+
+    if let Err(error) = stage_prose_pod_api_dump(prose_pod_api, source, dump_dir).await {
+        tracing::warn!(
+            "Could not stage the Prose Pod API’s data before restoring, \
+            it won’t be restored if the restoration is undone: {error:?}"
+        );
+
+        // NOTE: Data staged for a previous backup must not end up in the
+        //   safety snapshot, it would be sent to the Prose Pod API when
+        //   undoing the restoration (see `post_backups_undo_last_restore`).
+        match tokio::fs::remove_dir_all(dump_dir).await {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => tracing::error!("Could not delete stale Prose Pod API data: {err:?}"),
+        }
+    }
+}
+
+/// NOTE: The dump is a tar archive containing a single directory named
+///   [`PROSE_POD_API_ARCHIVE_KEY`] (the format it has in v1 backups).
+fn unpack_prose_pod_api_dump(dump: Bytes, dump_dir: &std::path::Path) -> Result<(), anyhow::Error> {
+    use tokio_util::bytes::Buf as _;
+
+    let staging_dir = dump_dir.with_added_extension("tmp");
 
-    response.map_err(crate::responders::Error::from)
+    // NOTE: A previous staging might have been interrupted.
+    if staging_dir.exists() {
+        std::fs::remove_dir_all(&staging_dir)?;
+    }
+    std::fs::create_dir_all(&staging_dir)?;
+
+    // NOTE: `unpack` skips entries which would end up outside of
+    //   the destination directory.
+    tar::Archive::new(dump.reader()).unpack(&staging_dir)?;
+
+    let unpacked_dir = staging_dir.join(PROSE_POD_API_ARCHIVE_KEY);
+    anyhow::ensure!(
+        unpacked_dir.is_dir(),
+        "Invalid Prose Pod API dump: `{PROSE_POD_API_ARCHIVE_KEY}` is missing.",
+    );
+
+    if dump_dir.exists() {
+        std::fs::remove_dir_all(dump_dir)?;
+    }
+    std::fs::rename(&unpacked_dir, dump_dir)?;
+
+    std::fs::remove_dir_all(&staging_dir)?;
+
+    Ok(())
 }
 
 /// `GET /v1/backups`.
@@ -221,6 +504,34 @@ pub(super) async fn get_backups(
     Ok(Json(backups))
 }
 
+/// `GET /v1/backups-stats`.
+pub(super) async fn get_backups_stats(
+    State(AppState { ref backend, .. }): State<AppState>,
+    caller_info: CallerInfo,
+) -> Result<Json<StorageReportDto>, crate::responders::Error> {
+    caller_info.check_is_admin()?;
+
+    let backup_service = backend.backup_service()?;
+
+    let report = backup_service.storage_report().await.no_context()?;
+
+    Ok(Json(report))
+}
+
+/// `GET /v1/backups-log`.
+pub(super) async fn get_backups_log(
+    State(AppState { ref backend, .. }): State<AppState>,
+    caller_info: CallerInfo,
+) -> Result<Json<LogVerificationReport>, crate::responders::Error> {
+    caller_info.check_is_admin()?;
+
+    let backup_service = backend.backup_service()?;
+
+    let report = backup_service.verify_log().await.no_context()?;
+
+    Ok(Json(report))
+}
+
 /// `GET /v1/backups/{backup_id}`.
 pub(super) async fn get_backup(
     State(AppState { ref backend, .. }): State<AppState>,
@@ -236,6 +547,23 @@ pub(super) async fn get_backup(
     Ok(Json(backup))
 }
 
+/// `GET /v1/backups/{a}/diff/{b}`: what changed in `b` compared to `a`.
+///
+/// NOTE: Both backups are downloaded and read entirely, this can take time.
+pub(super) async fn get_backups_diff(
+    State(AppState { ref backend, .. }): State<AppState>,
+    caller_info: CallerInfo,
+    Path((a, b)): Path<(BackupId, BackupId)>,
+) -> Result<Json<BackupDiffDto>, crate::responders::Error> {
+    caller_info.check_is_admin()?;
+
+    let backup_service = backend.backup_service()?;
+
+    let diff = backup_service.diff(&a, &b).await?;
+
+    Ok(Json(diff))
+}
+
 /// `DELETE /v1/backups/{backup_id}`.
 pub(super) async fn delete_backup(
     State(AppState { ref backend, .. }): State<AppState>,
@@ -275,6 +603,12 @@ pub(super) async fn put_backup_restore_all(
         )));
     };
 
+    // NOTE: The restoration would replace data restored on startup (and
+    //   undoing it would bring back data the Prose Pod API never had).
+    if let Err(err) = send_pending_prose_pod_api_restore_(&app_state, Some(prose_token)).await {
+        return Either::E1(Err(err));
+    }
+
     match headers.get(reqwest::header::ACCEPT) {
         Some(val) if val.as_bytes() == b"text/event-stream" => {
             Either::E2(put_backup_restore_stream(app_state, prose_token, backup_id).await)
@@ -289,7 +623,7 @@ async fn put_backup_restore(
     prose_token: &HeaderValue,
     backup_id: BackupId,
 ) -> Result<(), crate::responders::Error> {
-    let backup_service = Arc::clone(app_state.backend.backup_service()?);
+    let backup_service = app_state.backend.backup_service()?;
     let prose_pod_api = Arc::clone(&app_state.backend.prose_pod_api);
 
     put_backup_restore_(
@@ -309,7 +643,7 @@ async fn put_backup_restore_stream(
     prose_token: &HeaderValue,
     backup_id: BackupId,
 ) -> Result<Sse<ReceiverStream<Result<sse::Event, axum::Error>>>, crate::responders::Error> {
-    let backup_service = Arc::clone(app_state.backend.backup_service()?);
+    let backup_service = app_state.backend.backup_service()?;
     let prose_pod_api = Arc::clone(&app_state.backend.prose_pod_api);
 
     // Stream restore progress.
@@ -326,6 +660,7 @@ async fn put_backup_restore_stream(
                 interval: tokio::time::Duration::from_millis(100),
                 last_event_sent: (0, tokio::time::Instant::now()),
                 progress_sender: Arc::clone(&sender),
+                pending_stages: VecDeque::new(),
             },
             sender,
             receiver,
@@ -339,6 +674,8 @@ async fn put_backup_restore_stream(
         let backup_id_str = backup_id.to_string();
 
         async move {
+            // NOTE: Stops restoring the backup if the client disconnects
+            //   (changes are reverted, see `is_cancelled`).
             let result = put_backup_restore_(
                 app_state,
                 &backup_service,
@@ -349,6 +686,13 @@ async fn put_backup_restore_stream(
             )
             .await;
 
+            event_handler.flush().await;
+
+            if sender.is_closed() {
+                tracing::debug!("Client disconnected, not sending end event.");
+                return;
+            }
+
             sender
                 .send(RestoreBackupEvent::end(&backup_id_str, result))
                 .await
@@ -370,24 +714,87 @@ async fn put_backup_restore_<EventHandler>(
 where
     EventHandler: RestoreBackupEventHandler + RestoreBackupEventHandler,
 {
-    // Stop Prosody.
-    {
-        let mut prosody = app_state.backend.prosody.write().await;
-        prosody.stop().await.unwrap();
+    use crate::util::either::Either::{E1, E2};
+
+    ensure_backend_can_stop(&app_state.backend.prosody).await?;
+
+    // Stage the Prose Pod API’s current data, so it’s in the safety
+    // snapshot (see `POST /v1/backups/undo-last-restore`).
+    if backup_service.safety_snapshot_config.enabled {
+        let dump_dir = prose_pod_api_dump_dir(backup_service);
+        stage_prose_pod_api_dump_before_restore(prose_pod_api, prose_token, dump_dir).await;
     }
 
+    // Stop Prosody.
+    stop_backend(&app_state.backend.prosody).await?;
+
     let app_state = app_state.with_backend(b::UndergoingRestore {});
 
+    let mut report = RestoreReportDto::new(&backup_id);
+    let mut event_handler = WithProsePodApiData {
+        inner: event_handler,
+        report: &mut report,
+        prose_pod_api_data_size: 0,
+    };
+
+    // NOTE: Cancellation is cooperative (see `is_cancelled`), so changes
+    //   are reverted before the backend is restarted.
     let res = put_backup_restore_inner(
         backup_service,
         prose_token,
-        backup_id,
-        event_handler,
+        &backup_id,
+        &mut event_handler,
         prose_pod_api,
     )
     .await;
+    let res = match res {
+        Err(_) if event_handler.is_cancelled() => Err(errors::internal_server_error(
+            &anyhow::anyhow!("Client disconnected."),
+            "BACKUP_RESTORE_CANCELLED",
+            "Backup restoration was cancelled.",
+        )),
+        res => res,
+    };
 
-    let _app_state = app_state.do_restart_backend().await;
+    event_handler.on_stage(
+        &backup_id,
+        &RestoreStageEventDto::new(RestoreStage::RestartingBackend, 0.),
+    );
+    let restart_res = app_state
+        .do_restart_backend_with(|| {
+            event_handler.on_stage(
+                &backup_id,
+                &RestoreStageEventDto::new(RestoreStage::RestartingBackend, 1.),
+            );
+            event_handler.on_stage(
+                &backup_id,
+                &RestoreStageEventDto::new(RestoreStage::RunningMigrations, 0.),
+            );
+        })
+        .await;
+    let restart_error = match restart_res {
+        Ok(ref _app_state) => {
+            event_handler.on_stage(
+                &backup_id,
+                &RestoreStageEventDto::new(RestoreStage::RunningMigrations, 1.),
+            );
+            None
+        }
+        Err(E1(FailState { ref error, .. }) | E2(FailState { ref error, .. })) => {
+            Some(error.to_string())
+        }
+    };
+
+    // Keep the report of this restoration (see
+    // `GET /v1/backups/last-restore-report`).
+    drop(event_handler);
+    report.finish(match res {
+        Ok(()) => restart_error,
+        Err(ref error) => Some(error.to_string()),
+    });
+    if let Err(error) = backup_service.save_restore_report(&report).await {
+        tracing::warn!("Could not save restore report: {error:?}");
+    }
 
     res
 }
@@ -395,26 +802,20 @@ where
 async fn put_backup_restore_inner<EventHandler>(
     backup_service: &BackupService,
     prose_token: &HeaderValue,
-    backup_id: BackupId,
-    event_handler: &mut EventHandler,
+    backup_id: &BackupId,
+    event_handler: &mut WithProsePodApiData<'_, EventHandler>,
     prose_pod_api: &ProsePodApi,
 ) -> Result<(), crate::responders::Error>
 where
     EventHandler: RestoreBackupEventHandler + RestoreBackupEventHandler,
 {
-    let blueprint = (BACKUP_BLUEPRINTS.get(&BACKUPS_VERSION))
-        .expect("A blueprint should always exist for BACKUPS_VERSION");
-
-    let mut event_handler = WithAdditionalData {
-        inner: event_handler,
-        additional_data_size: 0,
-    };
+    let blueprint = latest_blueprint(backup_service);
 
     let RestoreBackupPartialSuccess {
         mut restoration_output,
         ..
     } = backup_service
-        .restore_backup_partial(&backup_id, blueprint, &mut event_handler)
+        .restore_backup_partial(backup_id, blueprint, event_handler)
         .await
         .map_err(|error| {
             crate::errors::internal_server_error(
@@ -424,41 +825,12 @@ where
             )
         })?;
 
-    let Some((tmp_dir, revert_guard)) = restoration_output.additional_data.as_mut() else {
-        tracing::error!("Invalid backup: Missing Prose Pod API data.");
-        return Err(crate::errors::validation_error(
-            "INVALID_BACKUP",
-            "Invalid backup",
-            "This backup is missing data. It can’t be restored.",
-        ));
-    };
-
-    {
-        let prose_pod_api_data_path = tmp_dir.path().join(PROSE_POD_API_ARCHIVE_KEY);
-
-        if !prose_pod_api_data_path.is_dir() {
-            tracing::error!(
-                "Invalid backup: `{path}` is missing.",
-                path = prose_pod_api_data_path.display()
-            );
-            return Err(crate::errors::validation_error(
-                "INVALID_BACKUP",
-                "Invalid backup",
-                "This backup is missing data. It can’t be restored.",
-            ));
-        }
-
-        let prose_pod_api_data = {
-            let mut tar = tar::Builder::new(Vec::<u8>::new());
-            tar.append_dir_all(PROSE_POD_API_ARCHIVE_KEY, &prose_pod_api_data_path)
-                .no_context()?;
-            tar.into_inner().no_context()?
-        };
-
-        () = prose_pod_api
-            .put_restore(prose_token, std::io::Cursor::new(prose_pod_api_data))
+    // Send the restored data to the Prose Pod API.
+    // NOTE: Backups created using a profile might not contain it.
+    if restoration_output.includes(PROSE_POD_API_DUMP_KEY) {
+        let dump_dir = prose_pod_api_dump_dir(backup_service);
+        send_prose_pod_api_data(prose_pod_api, prose_token, dump_dir)
             .await
-            .context("Prose Pod API restoration failed.")
             .map_err(|error| {
                 crate::errors::internal_server_error(
                     &error,
@@ -466,111 +838,763 @@ where
                     "Something went wrong while restoring the backup.",
                 )
             })?;
-
-        std::fs::remove_dir_all(prose_pod_api_data_path).no_context()?;
-
-        event_handler.on_restoration_progress(&backup_id, event_handler.additional_data_size);
     }
 
-    revert_guard.defuse();
-
-    event_handler.on_restoration_finished(&backup_id);
-
-    match std::fs::read_dir(tmp_dir) {
-        Ok(entries) => {
-            for entry in entries {
-                match entry {
-                    Ok(entry) => {
-                        let file_name = entry.file_name();
-                        tracing::warn!("Extracted unknown entry {file_name:?}.")
+    (event_handler.inner).on_restoration_progress(backup_id, event_handler.prose_pod_api_data_size);
+
+    // NOTE: Unknown data was extracted in a temporary directory.
+    if let Some((tmp_dir, mut revert_guard)) = restoration_output.additional_data.take() {
+        revert_guard.defuse();
+
+        match std::fs::read_dir(&tmp_dir) {
+            Ok(entries) => {
+                for entry in entries {
+                    match entry {
+                        Ok(entry) => {
+                            let file_name = entry.file_name();
+                            tracing::warn!("Extracted unknown entry {file_name:?}.")
+                        }
+                        Err(err) => debug_panic_or_log_error!("{err:?}"),
                     }
-                    Err(err) => debug_panic_or_log_error!("{err:?}"),
                 }
             }
+            Err(err) => debug_panic_or_log_error!("Error reading temporary directory: {err:?}"),
         }
-        Err(err) => debug_panic_or_log_error!("Error reading temporary directory: {err:?}"),
+
+        // NOTE: Replaced data is deleted (or kept) when the guard is dropped.
+        revert_guard.release().await;
+        event_handler.on_stage(
+            backup_id,
+            &RestoreStageEventDto::new(RestoreStage::SwappingDirectories, 1.),
+        );
     }
 
+    event_handler.inner.on_restoration_finished(backup_id);
+
     Ok(())
 }
 
-#[derive(Debug)]
-#[derive(serde::Deserialize)]
-#[serde(deny_unknown_fields)]
-pub struct GetBackupDownloadUrlRequest {
-    #[serde(with = "crate::util::serde::iso8601_duration_or_secs::option")]
-    pub ttl: Option<std::time::Duration>,
+/// Sends the data staged in `dump_dir` to the Prose Pod API, which
+/// replaces its own with it.
+async fn send_prose_pod_api_data(
+    prose_pod_api: &ProsePodApi,
+    prose_token: &HeaderValue,
+    dump_dir: &std::path::Path,
+) -> Result<(), anyhow::Error> {
+    let prose_pod_api_data = {
+        let mut tar = tar::Builder::new(Vec::<u8>::new());
+        tar.append_dir_all(PROSE_POD_API_ARCHIVE_KEY, dump_dir)
+            .with_context(|| format!("Could not archive {dump_dir:?}"))?;
+        tar.into_inner()
+            .context("Could not archive Prose Pod API data")?
+    };
+
+    prose_pod_api
+        .put_restore(prose_token, std::io::Cursor::new(prose_pod_api_data))
+        .await
+        .context("Prose Pod API restoration failed.")
 }
 
-/// `GET /v1/backups/{backup_id}/download-url`.
-pub(super) async fn get_backup_download_url(
-    State(AppState { ref backend, .. }): State<AppState>,
-    caller_info: CallerInfo,
-    Path(backup_id): Path<BackupId>,
-    Query(req): Query<GetBackupDownloadUrlRequest>,
-) -> Result<Json<String>, crate::responders::Error> {
-    caller_info.check_is_admin()?;
+// MARK: Pending Prose Pod API restoration
 
-    let backup_service = backend.backup_service()?;
+/// Prose Pod API data restored on startup (see
+/// [`crate::startup::restore_latest_backup_if_empty`]) but not sent to the
+/// Prose Pod API yet, as there was no Prose token to do it.
+///
+/// Persisted next to `[api].dump_dir`, so it survives restarts. The data is
+/// sent the next time the Prose Pod API calls a route which would replace it
+/// (see [`send_pending_prose_pod_api_restore`]).
+#[derive(Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct PendingProsePodApiRestore {
+    pub backup_id: String,
+}
 
-    let ttl = req.ttl.unwrap_or(std::time::Duration::from_mins(5));
+impl PendingProsePodApiRestore {
+    fn marker_path(dump_dir: &std::path::Path) -> std::path::PathBuf {
+        dump_dir.with_added_extension("pending-restore")
+    }
 
-    let download_url = backup_service
-        .get_download_url(&backup_id, ttl)
-        .await
-        .no_context()?;
+    pub(crate) fn save(&self, dump_dir: &std::path::Path) -> Result<(), anyhow::Error> {
+        let path = Self::marker_path(dump_dir);
+        let json = serde_json::to_vec(self)?;
+        std::fs::write(&path, json).with_context(|| format!("Could not write {path:?}"))
+    }
 
-    Ok(Json(download_url))
+    pub(crate) fn read(dump_dir: &std::path::Path) -> Result<Option<Self>, anyhow::Error> {
+        let path = Self::marker_path(dump_dir);
+        match std::fs::read(&path) {
+            Ok(json) => (serde_json::from_slice(&json).map(Some))
+                .with_context(|| format!("Could not parse {path:?}")),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(anyhow::Error::new(err).context(format!("Could not read {path:?}"))),
+        }
+    }
 }
 
-// MARK: - Boilerplate
-
-pub(super) const PROSE_POD_API_ARCHIVE_KEY: &str = "prose-pod-api-data";
+/// Sends Prose Pod API data restored on startup to the Prose Pod API, if it
+/// wasn’t yet (see [`PendingProsePodApiRestore`]).
+///
+/// NOTE: Must be called before anything replaces the data staged in
+///   `[api].dump_dir`, or it would be lost.
+async fn send_pending_prose_pod_api_restore(
+    pending: &arc_swap::ArcSwapOption<PendingProsePodApiRestore>,
+    prose_pod_api: &ProsePodApi,
+    prose_token: Option<&HeaderValue>,
+    dump_dir: &std::path::Path,
+) -> Result<(), crate::responders::Error> {
+    let Some(restore) = pending.load_full() else {
+        return Ok(());
+    };
 
-#[repr(transparent)]
-struct ProsePodApiData(Bytes);
+    let Some(prose_token) = prose_token else {
+        return Err(errors::conflict_error(
+            "PROSE_POD_API_RESTORE_PENDING",
+            "Restoration pending",
+            "The Prose Pod API’s data restored on startup wasn’t sent to it yet. \
+            Retry with a Prose token.",
+        ));
+    };
 
-impl AdditionalData for ProsePodApiData {
-    fn expected_size(&self) -> Result<u64, anyhow::Error> {
-        let archive_len = self.0.len() as u64;
-        Ok(TarSizeCalculator::archive_contents_size(archive_len))
+    if let Err(error) = send_prose_pod_api_data(prose_pod_api, prose_token, dump_dir).await {
+        return Err(errors::internal_server_error(
+            &error.context(format!(
+                "Could not send the Prose Pod API’s data from backup `{backup_id}`",
+                backup_id = restore.backup_id,
+            )),
+            "PROSE_POD_API_RESTORE_FAILED",
+            "The Prose Pod API’s data restored on startup could not be sent to it. \
+            Contact an administrator to fix this.",
+        ));
     }
 
-    fn append<W: std::io::Write>(self, builder: &mut tar::Builder<W>) -> Result<(), anyhow::Error> {
-        use tokio_util::bytes::Buf as _;
-
-        let mut archive = tar::Archive::new(self.0.reader());
-        let entries = archive.entries()?;
+    tracing::info!(
+        "Sent the Prose Pod API’s data from backup `{backup_id}` to the Prose Pod API.",
+        backup_id = restore.backup_id,
+    );
+    pending.store(None);
+
+    // NOTE: If the marker stays, the data is sent again after a restart.
+    //   Not a problem, the Prose Pod API already has it.
+    let marker_path = PendingProsePodApiRestore::marker_path(dump_dir);
+    match tokio::fs::remove_file(&marker_path).await {
+        Ok(()) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => tracing::error!("Could not delete {marker_path:?}: {err:?}"),
+    }
 
-        for entry in entries {
-            let entry = entry?;
+    Ok(())
+}
 
-            builder.append(&entry.header().clone(), entry)?;
+/// Forgets Prose Pod API data restored on startup, so it isn’t sent to the
+/// Prose Pod API anymore (e.g. after a factory reset).
+pub(crate) fn discard_pending_prose_pod_api_restore(
+    backend: &b::Operational,
+) -> Result<(), anyhow::Error> {
+    let Some(backup_service) = backend.backup_service.as_ref() else {
+        return Ok(());
+    };
+    let backup_service = backup_service.load_full();
+    let marker_path =
+        PendingProsePodApiRestore::marker_path(prose_pod_api_dump_dir(&backup_service));
+
+    backend.pending_prose_pod_api_restore.store(None);
+    match std::fs::remove_file(&marker_path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => {
+            Err(anyhow::Error::new(err).context(format!("Could not delete {marker_path:?}")))
         }
-
-        Ok(())
     }
 }
 
-enum CreateBackupEvent {}
+async fn send_pending_prose_pod_api_restore_(
+    app_state: &AppState<f::Running, b::Running>,
+    prose_token: Option<&HeaderValue>,
+) -> Result<(), crate::responders::Error> {
+    let backend = &app_state.backend;
+    let backup_service = backend.backup_service()?;
 
-impl CreateBackupEvent {
-    fn progress(backup_id: &str, progress: u64, total: u64) -> Result<sse::Event, anyhow::Error> {
-        sse::Event::default()
-            .event("backup-create-progress")
-            .id(backup_id)
-            .json_data(json!({
-                "progress": progress,
-                "total": total,
-            }))
-            .map_err(|err| {
-                debug_panic_or_log_error!("{err:#}");
-                anyhow::Error::from(err)
-            })
-    }
+    send_pending_prose_pod_api_restore(
+        &backend.pending_prose_pod_api_restore,
+        &backend.prose_pod_api,
+        prose_token,
+        prose_pod_api_dump_dir(&backup_service),
+    )
+    .await
+}
 
-    fn end(
-        backup_id: &str,
+/// `POST /v1/backups/undo-last-restore`.
+pub(super) async fn post_backups_undo_last_restore(
+    headers: HeaderMap,
+    State(app_state): State<AppState>,
+    caller_info: CallerInfo,
+) -> Result<Json<SafetySnapshotDto>, crate::responders::Error> {
+    caller_info.check_is_admin()?;
+
+    let Some(prose_token) = headers.get("x-prose-token") else {
+        return Err(errors::validation_error(
+            "BAD_REQUEST",
+            "Bad request",
+            "Missing Prose token.",
+        ));
+    };
+
+    // NOTE: Undoing would replace data restored on startup.
+    send_pending_prose_pod_api_restore_(&app_state, Some(prose_token)).await?;
+
+    let backup_service = app_state.backend.backup_service()?;
+    let prose_pod_api = Arc::clone(&app_state.backend.prose_pod_api);
+
+    // Stop Prosody.
+    stop_backend(&app_state.backend.prosody).await?;
+
+    let app_state = app_state.with_backend(b::UndergoingRestore {});
+
+    let res = async {
+        let safety_snapshot = backup_service.undo_last_restore().await?;
+
+        // Send the previous data back to the Prose Pod API.
+        // NOTE: It was staged before the restoration, unless the Prose Pod API
+        //   was unreachable (in which case the restoration is only partially
+        //   undone).
+        let dump_dir = prose_pod_api_dump_dir(&backup_service);
+        let res = if dump_dir.is_dir() {
+            send_prose_pod_api_data(&prose_pod_api, prose_token, dump_dir).await
+        } else {
+            Err(anyhow::anyhow!(
+                "Prose Pod API data was not staged before the restoration ({dump_dir:?} missing)."
+            ))
+        };
+        if let Err(error) = res {
+            return Err(errors::internal_server_error(
+                &error.context(format!(
+                    "Restoration of backup `{backup_id}` only partially undone",
+                    backup_id = safety_snapshot.backup_id,
+                )),
+                "UNDO_RESTORE_PARTIAL",
+                "The restoration was undone, except for the Prose Pod API’s data \
+                (it still contains the restored backup’s data).",
+            ));
+        }
+
+        Ok::<_, crate::responders::Error>(Json(safety_snapshot))
+    }
+    .await;
+
+    let _app_state = app_state.do_restart_backend().await;
+
+    res
+}
+
+/// `GET /v1/backups/last-restore-report`.
+pub(super) async fn get_backups_last_restore_report(
+    State(AppState { ref backend, .. }): State<AppState>,
+    caller_info: CallerInfo,
+) -> Result<Json<RestoreReportDto>, crate::responders::Error> {
+    caller_info.check_is_admin()?;
+
+    let backup_service = backend.backup_service()?;
+
+    match backup_service.last_restore_report().await.no_context()? {
+        Some(report) => Ok(Json(report)),
+        None => Err(errors::not_found(
+            "RESTORE_REPORT_NOT_FOUND",
+            "Restore report not found",
+            "No restore report was kept (no backup was restored yet, or \
+            `backups.restoration.report_path` is not set).",
+        )),
+    }
+}
+
+#[derive(Debug)]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GetBackupDownloadUrlRequest {
+    #[serde(with = "crate::util::serde::iso8601_duration_or_secs::option")]
+    pub ttl: Option<std::time::Duration>,
+}
+
+/// `GET /v1/backups/{backup_id}/download-url`.
+pub(super) async fn get_backup_download_url(
+    State(AppState { ref backend, .. }): State<AppState>,
+    caller_info: CallerInfo,
+    Path(backup_id): Path<BackupId>,
+    Query(req): Query<GetBackupDownloadUrlRequest>,
+) -> Result<Json<String>, crate::responders::Error> {
+    caller_info.check_is_admin()?;
+
+    let backup_service = backend.backup_service()?;
+
+    let ttl = req.ttl.unwrap_or(std::time::Duration::from_mins(5));
+
+    let download_url = backup_service
+        .get_download_url(&backup_id, ttl)
+        .await
+        .no_context()?;
+
+    Ok(Json(download_url))
+}
+
+/// Link allowing to download a backup through the Server API.
+#[derive(Debug)]
+#[derive(serde::Serialize)]
+pub struct BackupDownloadLink {
+    /// Path to `GET` (relative to the Server API), including the token.
+    ///
+    /// E.g. `/v1/backups/prose%252Dbackup-…/download?token=…`.
+    pub url: String,
+
+    /// UTC timestamp after which the link cannot be used anymore.
+    #[serde(with = "time::serde::rfc3339")]
+    pub expires_at: time::OffsetDateTime,
+}
+
+/// `GET /v1/backups/{backup_id}/download-link`.
+///
+/// Like `GET /v1/backups/{backup_id}/download-url`, but works with all
+/// backup stores as the backup is served by the Server API.
+pub(super) async fn get_backup_download_link(
+    State(AppState { ref backend, .. }): State<AppState>,
+    caller_info: CallerInfo,
+    Path(backup_id): Path<BackupId>,
+    Query(req): Query<GetBackupDownloadUrlRequest>,
+) -> Result<Json<BackupDownloadLink>, crate::responders::Error> {
+    use crate::backup_downloads::create_download_token;
+
+    caller_info.check_is_admin()?;
+
+    let backup_service = backend.backup_service()?;
+
+    // NOTE: Fail early if the backup doesn’t exist.
+    backup_service.get_download_info(&backup_id).await?;
+
+    // Apply max TTL from configuration, like for download URLs.
+    let ttl = (req.ttl.unwrap_or(std::time::Duration::from_mins(5)))
+        .min(backup_service.download_config.url_max_ttl);
+    let expires_at = time::OffsetDateTime::now_utc() + ttl;
+
+    let backup_id = backup_id.to_string();
+    let token = create_download_token(&backup_id, expires_at, &backend.server_salt);
+
+    // NOTE: Backup IDs are percent-encoded already (so they contain only
+    //   unreserved characters and `%`), but path segments are decoded.
+    let url = format!(
+        "/v1/backups/{backup_id}/download?token={token}",
+        backup_id = backup_id.replace('%', "%25"),
+    );
+
+    Ok(Json(BackupDownloadLink { url, expires_at }))
+}
+
+#[derive(Debug)]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GetBackupDownloadRequest {
+    /// See [`get_backup_download_link`].
+    pub token: String,
+}
+
+/// `GET /v1/backups/{backup_id}/download`.
+///
+/// Streams the backup as stored (compressed and maybe encrypted), from any
+/// backup store. Supports single byte ranges (`Range`, `If-Range`) so
+/// downloads can be resumed. The ETag is the backup’s digest, if any.
+///
+/// NOTE: Authenticated by a token (see [`get_backup_download_link`]) instead
+///   of a `Authorization` header, so it can be opened in a browser.
+pub(super) async fn get_backup_download(
+    State(AppState { ref backend, .. }): State<AppState>,
+    headers: HeaderMap,
+    Path(backup_id): Path<BackupId>,
+    Query(req): Query<GetBackupDownloadRequest>,
+) -> Result<axum::response::Response, crate::responders::Error> {
+    use axum::http::{StatusCode, header};
+    use axum::response::IntoResponse as _;
+    use tokio_util::io::ReaderStream;
+
+    use crate::backup_downloads::{RangeError, parse_range_header, verify_download_token};
+
+    verify_download_token(
+        &req.token,
+        &backup_id.to_string(),
+        time::OffsetDateTime::now_utc(),
+        &backend.server_salt,
+    )?;
+
+    let backup_service = backend.backup_service()?;
+
+    let info = backup_service.get_download_info(&backup_id).await?;
+    let size = info.size_bytes;
+
+    let etag: Option<HeaderValue> = (info.digest.as_ref())
+        .and_then(|digest| HeaderValue::try_from(format!("\"{digest}\"")).ok());
+
+    // NOTE: If the backup changed since the first request (which shouldn’t
+    //   happen), send it all.
+    let if_range_matches = match headers.get(header::IF_RANGE) {
+        Some(if_range) => etag.as_ref() == Some(if_range),
+        None => true,
+    };
+
+    let range = match headers.get(header::RANGE).map(HeaderValue::to_str) {
+        Some(Ok(value)) if if_range_matches => match parse_range_header(value, size) {
+            Ok(range) => Some(range),
+            Err(RangeError::Unsupported) => None,
+            Err(RangeError::NotSatisfiable) => {
+                return Ok((
+                    StatusCode::RANGE_NOT_SATISFIABLE,
+                    [(header::CONTENT_RANGE, format!("bytes */{size}"))],
+                )
+                    .into_response());
+            }
+        },
+        _ => None,
+    };
+
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/octet-stream"),
+    );
+    response_headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    if let Ok(value) = HeaderValue::try_from(format!("attachment; filename=\"{backup_id}\"")) {
+        response_headers.insert(header::CONTENT_DISPOSITION, value);
+    }
+    if let Some(etag) = etag {
+        response_headers.insert(header::ETAG, etag);
+    }
+
+    let (status, range) = match range {
+        Some(range) => {
+            response_headers.insert(
+                header::CONTENT_RANGE,
+                HeaderValue::try_from(format!(
+                    "bytes {start}-{end}/{size}",
+                    start = range.start,
+                    end = range.end - 1,
+                ))
+                .expect("`Content-Range` is visible ASCII"),
+            );
+            (StatusCode::PARTIAL_CONTENT, range)
+        }
+        None => (StatusCode::OK, 0..size),
+    };
+    response_headers.insert(
+        header::CONTENT_LENGTH,
+        HeaderValue::from(range.end - range.start),
+    );
+
+    let reader = backup_service.read_backup_range(&backup_id, range).await?;
+
+    Ok((
+        status,
+        response_headers,
+        Body::from_stream(ReaderStream::new(reader)),
+    )
+        .into_response())
+}
+
+#[derive(Debug)]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UploadBackupRequest {
+    /// File name of the backup, as it was created.
+    pub backup_id: BackupId,
+
+    /// Whether or not to restore the backup once uploaded (requires
+    /// a Prose token, like `PUT /v1/backups/{backup_id}/restore`).
+    #[serde(default)]
+    pub restore: bool,
+}
+
+/// `POST /v1/backups/upload`.
+///
+/// Body: the backup file. Integrity checks are passed in headers:
+/// `X-Backup-Digest` (e.g. `blake3:…`) and/or `X-Backup-Signature` (detached
+/// OpenPGP signature, base64-encoded).
+pub(super) async fn post_backups_upload(
+    headers: HeaderMap,
+    State(app_state): State<AppState>,
+    caller_info: CallerInfo,
+    Query(req): Query<UploadBackupRequest>,
+    body: Body,
+) -> Either3<
+    Result<Json<UploadBackupSuccess>, crate::responders::Error>,
+    Result<(), crate::responders::Error>,
+    Result<Sse<ReceiverStream<Result<sse::Event, axum::Error>>>, crate::responders::Error>,
+> {
+    if let Err(err) = caller_info.check_is_admin() {
+        return Either3::E1(Err(err));
+    };
+
+    // NOTE: Check everything before uploading, as it might take a while.
+    let prose_token = match headers.get("x-prose-token") {
+        Some(prose_token) => Some(prose_token),
+        None if req.restore => {
+            return Either3::E2(Err(errors::validation_error(
+                "BAD_REQUEST",
+                "Bad request",
+                "Missing Prose token.",
+            )));
+        }
+        None => None,
+    };
+
+    let command = match upload_backup_command(&headers, req.backup_id) {
+        Ok(command) => command,
+        Err(err) => return Either3::E1(Err(err)),
+    };
+    let backup_id = command.backup_id.clone();
+
+    let res = post_backups_upload_(&app_state, command, body).await;
+
+    match (res, prose_token) {
+        (Ok(success), Some(prose_token)) if req.restore => {
+            tracing::info!("Restoring uploaded backup {:?}…", success.backup.id);
+            match headers.get(reqwest::header::ACCEPT) {
+                Some(val) if val.as_bytes() == b"text/event-stream" => {
+                    Either3::E3(put_backup_restore_stream(app_state, prose_token, backup_id).await)
+                }
+                _ => Either3::E2(put_backup_restore(app_state, prose_token, backup_id).await),
+            }
+        }
+        (res, _) => Either3::E1(res.map(Json)),
+    }
+}
+
+async fn post_backups_upload_(
+    app_state: &AppState<f::Running, b::Running>,
+    command: UploadBackupCommand,
+    body: Body,
+) -> Result<UploadBackupSuccess, crate::responders::Error> {
+    use tokio_stream::StreamExt as _;
+
+    let backup_service = app_state.backend.backup_service()?;
+
+    let backup = tokio_util::io::StreamReader::new(
+        (body.into_data_stream()).map(|res| res.map_err(std::io::Error::other)),
+    );
+
+    let success = backup_service.upload_backup(command, backup).await?;
+
+    Ok(success)
+}
+
+fn upload_backup_command(
+    headers: &HeaderMap,
+    backup_id: BackupId,
+) -> Result<UploadBackupCommand, crate::responders::Error> {
+    use base64::{Engine as _, prelude::BASE64_STANDARD};
+
+    let bad_request =
+        |description: &str| errors::validation_error("BAD_REQUEST", "Bad request", description);
+
+    let digest = match headers.get("x-backup-digest") {
+        Some(digest) => Some(
+            (digest.to_str())
+                .map_err(|_| bad_request("Invalid `X-Backup-Digest` header."))?
+                .to_owned(),
+        ),
+        None => None,
+    };
+
+    let signature = match headers.get("x-backup-signature") {
+        Some(signature) => Some(
+            (BASE64_STANDARD.decode(signature.as_bytes()))
+                .map_err(|_| bad_request("`X-Backup-Signature` is not valid base64."))?,
+        ),
+        None => None,
+    };
+
+    Ok(UploadBackupCommand {
+        backup_id,
+        digest,
+        signature,
+    })
+}
+
+// MARK: Keys
+
+/// `GET /v1/backups-keys`.
+pub(super) async fn get_backup_keys(
+    State(AppState { ref backend, .. }): State<AppState>,
+    caller_info: CallerInfo,
+) -> Result<Json<Vec<KeyDto>>, crate::responders::Error> {
+    caller_info.check_is_admin()?;
+
+    let backup_service = backend.backup_service()?;
+
+    Ok(Json(backup_service.keys.clone()))
+}
+
+#[derive(Debug)]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenerateBackupKeyRequest {
+    /// `"encryption"` or `"signing"`.
+    pub usage: KeyUsage,
+
+    pub user_id: String,
+
+    /// `None` for a key which never expires.
+    #[serde(default, with = "crate::util::serde::iso8601_duration_or_secs::option")]
+    pub validity: Option<std::time::Duration>,
+}
+
+/// `POST /v1/backups-keys`.
+pub(super) async fn post_backup_keys(
+    State(AppState {
+        ref frontend,
+        ref backend,
+        ..
+    }): State<AppState>,
+    caller_info: CallerInfo,
+    Json(req): Json<GenerateBackupKeyRequest>,
+) -> Result<Json<KeyDto>, crate::responders::Error> {
+    caller_info.check_is_admin()?;
+
+    let _guard = backend.backup_keys_lock.lock().await;
+
+    let backup_service = backend.backup_service()?;
+
+    let key = backup_service
+        .generate_key(req.usage, req.user_id, req.validity)
+        .await?;
+
+    reload_backup_service(&frontend.config, backend)?;
+
+    Ok(Json(key))
+}
+
+/// `POST /v1/backups-keys/import`.
+///
+/// Body: an OpenPGP certificate (public key only), armored or binary.
+pub(super) async fn post_backup_keys_import(
+    State(AppState {
+        ref frontend,
+        ref backend,
+        ..
+    }): State<AppState>,
+    caller_info: CallerInfo,
+    key: Bytes,
+) -> Result<Json<KeyDto>, crate::responders::Error> {
+    caller_info.check_is_admin()?;
+
+    let _guard = backend.backup_keys_lock.lock().await;
+
+    let backup_service = backend.backup_service()?;
+
+    let key = backup_service.import_recipient(key.to_vec()).await?;
+
+    reload_backup_service(&frontend.config, backend)?;
+
+    Ok(Json(key))
+}
+
+/// `PUT /v1/backups-keys/{fingerprint}/retire`.
+pub(super) async fn put_backup_key_retire(
+    State(AppState {
+        ref frontend,
+        ref backend,
+        ..
+    }): State<AppState>,
+    caller_info: CallerInfo,
+    Path(fingerprint): Path<String>,
+) -> Result<(), crate::responders::Error> {
+    caller_info.check_is_admin()?;
+
+    let Ok(fingerprint) = fingerprint.parse::<prose_backup::openpgp::Fingerprint>() else {
+        return Err(errors::validation_error(
+            "BAD_REQUEST",
+            "Bad request",
+            format!("Invalid key fingerprint `{fingerprint}`."),
+        ));
+    };
+
+    let _guard = backend.backup_keys_lock.lock().await;
+
+    let backup_service = backend.backup_service()?;
+
+    backup_service.retire_key(&fingerprint).await?;
+
+    reload_backup_service(&frontend.config, backend)?;
+
+    Ok(())
+}
+
+/// Re-creates the backup service, so it uses the latest backup keys.
+///
+/// NOTE: Operations already running keep using the previous service.
+pub(crate) fn reload_backup_service(
+    app_config: &AppConfig,
+    backend: &b::Operational,
+) -> Result<(), crate::responders::Error> {
+    let (Some(config), Some(backup_service)) =
+        (app_config.backups.as_ref(), backend.backup_service.as_ref())
+    else {
+        return Ok(());
+    };
+
+    let service = BackupService::from_config(
+        config,
+        backup_blueprints(&app_config.prose_pod_api.dump_dir),
+        BACKUP_MIGRATIONS.clone(),
+    )
+        .map_err(|error| {
+            errors::internal_server_error(
+                &error.context("Could not reload backup service"),
+                "BACKUP_KEYS_RELOAD_FAILED",
+                "Backup keys were changed but could not be loaded. Contact an administrator to fix this.",
+            )
+        })?;
+
+    backup_service.store(Arc::new(service));
+
+    Ok(())
+}
+
+// MARK: - Boilerplate
+
+/// Name of the Prose Pod API’s data in its dumps (and in v1 backups).
+pub(super) const PROSE_POD_API_ARCHIVE_KEY: &str = "prose-pod-api-data";
+
+/// Name of the Prose Pod API’s data in backups (since v2).
+pub(crate) const PROSE_POD_API_DUMP_KEY: &str = "prose-pod-api-dump";
+
+enum CreateBackupEvent {}
+
+impl CreateBackupEvent {
+    fn progress(
+        backup_id: &str,
+        progress: u64,
+        total: u64,
+        details: Option<&CreateBackupProgressDto>,
+    ) -> Result<sse::Event, anyhow::Error> {
+        #[derive(serde::Serialize)]
+        struct CreateBackupProgressEventData<'a> {
+            progress: u64,
+            total: u64,
+            #[serde(flatten)]
+            details: Option<&'a CreateBackupProgressDto>,
+        }
+
+        sse::Event::default()
+            .event("backup-create-progress")
+            .id(backup_id)
+            .json_data(CreateBackupProgressEventData {
+                progress,
+                total,
+                details,
+            })
+            .map_err(|err| {
+                debug_panic_or_log_error!("{err:#}");
+                anyhow::Error::from(err)
+            })
+    }
+
+    fn end(
+        backup_id: &str,
         result: Result<CreateBackupSuccess, crate::responders::Error>,
     ) -> Result<sse::Event, anyhow::Error> {
         match result {
@@ -591,69 +1615,50 @@ impl CreateBackupEvent {
 }
 
 /// This [`CreateBackupEventHandler`] sends a [`sse::Event`] on
-/// progress, throttling them while ensuring one still receives the
-/// last event (100% progress).
+/// progress, while ensuring one still receives the last event
+/// (100% progress).
 ///
-/// NOTE: The throttle is subject to drift, but we don’t care.
-///   It’s simple and effective, just what we want.
+/// NOTE: Progress events are throttled by the backup service (see
+///   [`ProgressConfig`](prose_backup::config::ProgressConfig)).
 struct StreamingCreateBackupEventHandler {
     backup_id: Option<String>,
     total: u64,
-    progress: u64,
-    interval: tokio::time::Duration,
-    last_event_sent: (u64, tokio::time::Instant),
+    last_progress_sent: u64,
     progress_sender: Arc<mpsc::Sender<Result<sse::Event, anyhow::Error>>>,
 }
 
+impl StreamingCreateBackupEventHandler {
+    fn send_progress(
+        &mut self,
+        backup_id: &BackupId,
+        progress: u64,
+        details: Option<&CreateBackupProgressDto>,
+    ) {
+        self.last_progress_sent = progress;
+
+        try_send_progress(
+            &self.progress_sender,
+            CreateBackupEvent::progress(&backup_id.to_string(), progress, self.total, details),
+        );
+    }
+}
+
 impl CreateBackupEventHandler for StreamingCreateBackupEventHandler {
     fn on_archive_start(&mut self, backup_id: &BackupId, expected_archive_size: u64) {
-        debug_assert_eq!(self.progress, 0);
+        debug_assert_eq!(self.last_progress_sent, 0);
 
         self.backup_id = Some(backup_id.to_string());
         self.total = expected_archive_size;
 
-        self.last_event_sent = (0, tokio::time::Instant::now());
-
-        tokio::task::block_in_place(move || {
-            tokio::runtime::Handle::current().block_on(async move {
-                self.progress_sender
-                    .send(CreateBackupEvent::progress(
-                        &backup_id.to_string(),
-                        0,
-                        expected_archive_size,
-                    ))
-                    .await
-                    .unwrap_or_else(|err| {
-                        debug_panic_or_log_error!("Progress init error: {err:#}")
-                    });
-            })
-        })
+        self.send_progress(backup_id, 0, None);
     }
 
-    fn on_archive_progress(&mut self, backup_id: &BackupId, archived_bytes: usize) {
-        debug_assert_ne!(self.total, 0);
-
-        self.progress = self.progress.saturating_add(archived_bytes as u64);
-        debug_assert!(self.progress <= self.total);
-
-        if self.last_event_sent.1.elapsed() > self.interval {
-            self.last_event_sent = (self.progress, tokio::time::Instant::now());
+    fn on_progress(&mut self, backup_id: &BackupId, progress: &CreateBackupProgressDto) {
+        // NOTE: The expected archive size is an estimate, make sure we
+        //   never send `progress > total`.
+        self.total = self.total.max(progress.total_bytes_estimate);
 
-            tokio::task::block_in_place(move || {
-                tokio::runtime::Handle::current().block_on(async move {
-                    self.progress_sender
-                        .send(CreateBackupEvent::progress(
-                            &backup_id.to_string(),
-                            self.progress,
-                            self.total,
-                        ))
-                        .await
-                        .unwrap_or_else(|err| {
-                            debug_panic_or_log_error!("Progress send error: {err:#}")
-                        });
-                })
-            })
-        }
+        self.send_progress(backup_id, progress.bytes_read, Some(progress));
     }
 
     fn on_backup_uploaded(
@@ -662,47 +1667,35 @@ impl CreateBackupEventHandler for StreamingCreateBackupEventHandler {
         _size_bytes: u64,
         _duration: std::time::Duration,
     ) {
-        if self.last_event_sent.0 < self.total {
-            self.last_event_sent = (self.total, tokio::time::Instant::now());
-
-            tokio::task::block_in_place(move || {
-                tokio::runtime::Handle::current().block_on(async move {
-                    self.progress_sender
-                        .send(CreateBackupEvent::progress(
-                            &backup_id.to_string(),
-                            self.total,
-                            self.total,
-                        ))
-                        .await
-                        .unwrap_or_else(|err| {
-                            debug_panic_or_log_error!("Progress send error: {err:#}")
-                        });
-                })
-            })
+        if self.last_progress_sent < self.total {
+            self.send_progress(backup_id, self.total, None);
         }
     }
 }
 
 // A wrapper which takes into account the fact that we also
-// have to restore the Prose Pod API’s data.
-struct WithAdditionalData<'a, Inner> {
+// have to send the Prose Pod API’s data once restored. It also
+// records restoration stages in the restore report.
+struct WithProsePodApiData<'a, Inner> {
     inner: &'a mut Inner,
-    additional_data_size: usize,
+    report: &'a mut RestoreReportDto,
+    prose_pod_api_data_size: usize,
 }
 
-impl<'a, Inner> RestoreBackupEventHandler for WithAdditionalData<'a, Inner>
+impl<'a, Inner> RestoreBackupEventHandler for WithProsePodApiData<'a, Inner>
 where
     Inner: RestoreBackupEventHandler,
 {
     fn on_restoration_start(&mut self, backup_id: &BackupId, mut total: u64) {
         // This is just an estimate. It doesn’t have to be exact, just
         // to be there so the progress bar doesn’t reach 100% before
-        // the Prose Pod API data is restored.
-        let additional_data_size = total / 10;
+        // the Prose Pod API data is restored.
+        let prose_pod_api_data_size = total / 10;
 
-        self.additional_data_size = usize::try_from(additional_data_size).unwrap_or(usize::MAX);
+        self.prose_pod_api_data_size =
+            usize::try_from(prose_pod_api_data_size).unwrap_or(usize::MAX);
 
-        total = total.saturating_add(additional_data_size);
+        total = total.saturating_add(prose_pod_api_data_size);
 
         self.inner.on_restoration_start(backup_id, total);
     }
@@ -736,8 +1729,19 @@ where
         self.inner.on_extraction_finished(backup_id, report);
     }
 
-    fn on_restoration_finished(&mut self, backup_id: &BackupId) {
-        self.inner.on_restoration_finished(backup_id);
+    fn on_restoration_finished(&mut self, _backup_id: &BackupId) {
+        // NOTE: Sent once the Prose Pod API restored its data
+        //   (see `put_backup_restore_inner`).
+    }
+
+    fn on_stage(&mut self, backup_id: &BackupId, event: &RestoreStageEventDto) {
+        self.report.record(event);
+        self.inner.on_stage(backup_id, event);
+    }
+
+    #[inline]
+    fn is_cancelled(&self) -> bool {
+        self.inner.is_cancelled()
     }
 }
 
@@ -755,6 +1759,14 @@ impl RestoreBackupEvent {
             .inspect_err(|e| debug_panic_or_log_error!("Restore progress send error: {e:#}"))
     }
 
+    fn stage(backup_id: &str, event: &RestoreStageEventDto) -> Result<sse::Event, axum::Error> {
+        sse::Event::default()
+            .event("backup-restore-stage")
+            .id(backup_id)
+            .json_data(event)
+            .inspect_err(|e| debug_panic_or_log_error!("Restore stage send error: {e:#}"))
+    }
+
     fn end(
         backup_id: &str,
         result: Result<(), crate::responders::Error>,
@@ -786,6 +1798,56 @@ struct StreamingRestoreBackupEventHandler {
     interval: tokio::time::Duration,
     last_event_sent: (u64, tokio::time::Instant),
     progress_sender: Arc<mpsc::Sender<Result<sse::Event, axum::Error>>>,
+    /// Stage events which could not be sent yet (the client doesn’t keep up).
+    ///
+    /// NOTE: Unlike progress events, stage events must not be dropped. They
+    ///   are sent as soon as possible, in order, and before the end event
+    ///   (see [`flush`](Self::flush)).
+    pending_stages: VecDeque<Result<sse::Event, axum::Error>>,
+}
+
+impl StreamingRestoreBackupEventHandler {
+    /// Sends pending stage events without blocking, returns `false` if some
+    /// are still pending.
+    fn try_send_pending(&mut self) -> bool {
+        use mpsc::error::TrySendError;
+
+        while let Some(event) = self.pending_stages.pop_front() {
+            match self.progress_sender.try_send(event) {
+                Ok(()) => {}
+                Err(TrySendError::Full(event)) => {
+                    self.pending_stages.push_front(event);
+                    return false;
+                }
+                // NOTE: The client disconnected, the operation will be cancelled.
+                Err(TrySendError::Closed(_)) => self.pending_stages.clear(),
+            }
+        }
+
+        true
+    }
+
+    /// Sends a progress event, unless stage events are pending (a progress
+    /// event must not be sent before the stage it belongs to).
+    fn try_send_progress(&mut self, event: Result<sse::Event, axum::Error>) {
+        if self.try_send_pending() {
+            try_send_progress(&self.progress_sender, event);
+        } else {
+            tracing::debug!("Progress event dropped: Stage events pending.");
+        }
+    }
+
+    /// Sends pending stage events, waiting for the client if needed.
+    ///
+    /// NOTE: Call this before sending the end event.
+    async fn flush(&mut self) {
+        while let Some(event) = self.pending_stages.pop_front() {
+            if self.progress_sender.send(event).await.is_err() {
+                // NOTE: The client disconnected.
+                self.pending_stages.clear();
+            }
+        }
+    }
 }
 
 impl RestoreBackupEventHandler for StreamingRestoreBackupEventHandler {
@@ -796,20 +1858,11 @@ impl RestoreBackupEventHandler for StreamingRestoreBackupEventHandler {
 
         self.last_event_sent = (0, tokio::time::Instant::now());
 
-        tokio::task::block_in_place(move || {
-            tokio::runtime::Handle::current().block_on(async move {
-                self.progress_sender
-                    .send(RestoreBackupEvent::progress(
-                        &backup_id.to_string(),
-                        0,
-                        total,
-                    ))
-                    .await
-                    .unwrap_or_else(|err| {
-                        debug_panic_or_log_error!("Progress init error: {err:#}")
-                    });
-            })
-        })
+        self.try_send_progress(RestoreBackupEvent::progress(
+            &backup_id.to_string(),
+            0,
+            total,
+        ));
     }
 
     fn on_restoration_progress(&mut self, backup_id: &BackupId, len: usize) {
@@ -825,20 +1878,11 @@ impl RestoreBackupEventHandler for StreamingRestoreBackupEventHandler {
         if self.last_event_sent.1.elapsed() > self.interval {
             self.last_event_sent = (self.progress, tokio::time::Instant::now());
 
-            tokio::task::block_in_place(move || {
-                tokio::runtime::Handle::current().block_on(async move {
-                    self.progress_sender
-                        .send(RestoreBackupEvent::progress(
-                            &backup_id.to_string(),
-                            self.progress,
-                            self.total,
-                        ))
-                        .await
-                        .unwrap_or_else(|err| {
-                            debug_panic_or_log_error!("Progress send error: {err:#}")
-                        });
-                })
-            })
+            self.try_send_progress(RestoreBackupEvent::progress(
+                &backup_id.to_string(),
+                self.progress,
+                self.total,
+            ));
         }
     }
 
@@ -846,26 +1890,66 @@ impl RestoreBackupEventHandler for StreamingRestoreBackupEventHandler {
         if self.last_event_sent.0 < self.total {
             self.last_event_sent = (self.total, tokio::time::Instant::now());
 
-            tokio::task::block_in_place(move || {
-                tokio::runtime::Handle::current().block_on(async move {
-                    self.progress_sender
-                        .send(RestoreBackupEvent::progress(
-                            &backup_id.to_string(),
-                            self.total,
-                            self.total,
-                        ))
-                        .await
-                        .unwrap_or_else(|err| {
-                            debug_panic_or_log_error!("Progress send error: {err:#}")
-                        });
-                })
-            })
+            self.try_send_progress(RestoreBackupEvent::progress(
+                &backup_id.to_string(),
+                self.total,
+                self.total,
+            ));
         }
     }
+
+    fn on_stage(&mut self, backup_id: &BackupId, event: &RestoreStageEventDto) {
+        (self.pending_stages).push_back(RestoreBackupEvent::stage(&backup_id.to_string(), event));
+        self.try_send_pending();
+    }
+
+    /// NOTE: The client disconnected.
+    #[inline]
+    fn is_cancelled(&self) -> bool {
+        self.progress_sender.is_closed()
+    }
+}
+
+/// Sends a progress event without blocking (event handlers are called
+/// from async code).
+///
+/// NOTE: If the client doesn’t keep up, progress events are dropped. This
+///   is fine as the next one will contain the up-to-date progress, and the
+///   end event is always sent.
+fn try_send_progress<E>(
+    sender: &mpsc::Sender<Result<sse::Event, E>>,
+    event: Result<sse::Event, E>,
+) {
+    use mpsc::error::TrySendError;
+
+    match sender.try_send(event) {
+        Ok(()) => {}
+        Err(TrySendError::Full(_)) => tracing::debug!("Progress event dropped: Channel full."),
+        // NOTE: The client disconnected, the operation will be cancelled.
+        Err(TrySendError::Closed(_)) => {}
+    }
 }
 
 impl From<CreateBackupError> for crate::responders::Error {
     fn from(error: CreateBackupError) -> Self {
+        match error {
+            CreateBackupError::AlreadyExists(_) => {
+                return errors::conflict_error(
+                    "BACKUP_ALREADY_EXISTS",
+                    "Backup already exists",
+                    "Another backup was created at the same time. Try again.",
+                );
+            }
+            CreateBackupError::UnknownProfile(ref name) => {
+                return errors::validation_error(
+                    "BAD_REQUEST",
+                    "Bad request",
+                    format!("Unknown backup profile `{name}`."),
+                );
+            }
+            _ => {}
+        }
+
         errors::internal_server_error(
             &anyhow::Error::new(error),
             "BACKUP_CREATE_FAILED",
@@ -873,3 +1957,418 @@ impl From<CreateBackupError> for crate::responders::Error {
         )
     }
 }
+
+impl From<UploadBackupError> for crate::responders::Error {
+    fn from(error: UploadBackupError) -> Self {
+        match error {
+            UploadBackupError::InvalidBackupId(_)
+            | UploadBackupError::InvalidDigest(_)
+            | UploadBackupError::InvalidSignature(_)
+            | UploadBackupError::MissingIntegrityCheck => errors::validation_error(
+                "BAD_REQUEST",
+                "Bad request",
+                format!("{:#}", anyhow::Error::new(error)),
+            ),
+            UploadBackupError::AlreadyExists(_) => errors::conflict_error(
+                "BACKUP_ALREADY_EXISTS",
+                "Backup already exists",
+                "A backup with the same ID already exists.",
+            ),
+            UploadBackupError::Verification(_) => errors::validation_error(
+                "BACKUP_VERIFICATION_FAILED",
+                "Backup verification failed",
+                format!(
+                    "The backup doesn’t match its integrity checks or isn’t trusted, \
+                    it was not kept. ({:#})",
+                    anyhow::Error::new(error),
+                ),
+            ),
+            error => errors::internal_server_error(
+                &anyhow::Error::new(error),
+                "BACKUP_UPLOAD_FAILED",
+                "Something went wrong while uploading the backup. Contact an administrator to fix this.",
+            ),
+        }
+    }
+}
+
+impl From<UndoRestoreError> for crate::responders::Error {
+    fn from(error: UndoRestoreError) -> Self {
+        match error {
+            UndoRestoreError::NoSnapshot => errors::not_found(
+                "SAFETY_SNAPSHOT_NOT_FOUND",
+                "Nothing to undo",
+                "No restoration can be undone (safety snapshots might be disabled, \
+                see `backups.safety_snapshot`).",
+            ),
+            UndoRestoreError::Expired { ref backup_id } => errors::too_late(
+                "SAFETY_SNAPSHOT_EXPIRED",
+                "Too late",
+                format!("Restoration of backup `{backup_id}` cannot be undone anymore."),
+            ),
+            error => errors::internal_server_error(
+                &anyhow::Error::new(error),
+                "BACKUP_RESTORE_UNDO_FAILED",
+                "Something went wrong while undoing the last restoration. Contact an administrator to fix this.",
+            ),
+        }
+    }
+}
+
+impl From<DiffBackupsError> for crate::responders::Error {
+    fn from(error: DiffBackupsError) -> Self {
+        match error {
+            DiffBackupsError::BackupNotFound(ref backup_id) => errors::not_found(
+                "BACKUP_NOT_FOUND",
+                "Backup not found",
+                format!("Backup `{backup_id}` doesn’t exist (anymore)."),
+            ),
+            error @ DiffBackupsError::InvalidBackup { .. } => errors::internal_server_error(
+                &anyhow::Error::new(error),
+                "BACKUP_DIFF_FAILED",
+                "Something went wrong while comparing backups (one might be corrupted or \
+                not trusted). Contact an administrator to fix this.",
+            ),
+        }
+    }
+}
+
+impl From<ReadObjectError> for crate::responders::Error {
+    fn from(error: ReadObjectError) -> Self {
+        match error {
+            ReadObjectError::ObjectNotFound(_) => errors::not_found(
+                "BACKUP_NOT_FOUND",
+                "Backup not found",
+                "This backup doesn’t exist (anymore).",
+            ),
+            ReadObjectError::Other(error) => errors::internal_server_error(
+                &error,
+                "BACKUP_READ_FAILED",
+                "Something went wrong while reading the backup. Contact an administrator to fix this.",
+            ),
+        }
+    }
+}
+
+impl From<DownloadTokenError> for crate::responders::Error {
+    fn from(error: DownloadTokenError) -> Self {
+        match error {
+            DownloadTokenError::Invalid => errors::forbidden("Invalid download token."),
+            DownloadTokenError::Expired => errors::too_late(
+                "DOWNLOAD_TOKEN_EXPIRED",
+                "Link expired",
+                "This download link expired, generate a new one.",
+            ),
+        }
+    }
+}
+
+impl From<KeyringError> for crate::responders::Error {
+    fn from(error: KeyringError) -> Self {
+        match error {
+            KeyringError::Disabled => errors::configuration_error(
+                "MISSING_CONFIG",
+                "Missing configuration",
+                "Backup key management is disabled (see `backups.keys.directory`).",
+            ),
+            KeyringError::NotFound(ref fingerprint) => errors::not_found(
+                "BACKUP_KEY_NOT_FOUND",
+                "Backup key not found",
+                format!("No managed backup key has fingerprint `{fingerprint}`."),
+            ),
+            KeyringError::AlreadyExists(ref fingerprint) => errors::conflict_error(
+                "BACKUP_KEY_ALREADY_EXISTS",
+                "Backup key already exists",
+                format!("Backup key `{fingerprint}` already exists."),
+            ),
+            KeyringError::Configured(ref fingerprint) => errors::conflict_error(
+                "BACKUP_KEY_CONFIGURED",
+                "Backup key is configured",
+                format!(
+                    "Backup key `{fingerprint}` is configured in `prose.toml`, \
+                    it cannot be changed at runtime."
+                ),
+            ),
+            KeyringError::InvalidKey(ref error) => {
+                errors::validation_error("BAD_REQUEST", "Invalid key", format!("{error:#}"))
+            }
+            KeyringError::Other(error) => errors::internal_server_error(
+                &error,
+                "BACKUP_KEYS_ERROR",
+                "Something went wrong while managing backup keys. Contact an administrator to fix this.",
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use axum::Router;
+    use axum::http::StatusCode;
+    use axum::routing::{get, put};
+
+    use super::*;
+
+    const DATABASE: &[u8] = b"Prose Pod API database";
+
+    /// A dump like the Prose Pod API provides.
+    fn dump() -> Bytes {
+        let mut tar = tar::Builder::new(Vec::<u8>::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(DATABASE.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        tar.append_data(
+            &mut header,
+            format!("{PROSE_POD_API_ARCHIVE_KEY}/database.sqlite"),
+            DATABASE,
+        )
+        .unwrap();
+        Bytes::from(tar.into_inner().unwrap())
+    }
+
+    /// A Prose Pod API answering dump and restore requests with `status`.
+    async fn serve_prose_pod_api(status: StatusCode) -> ProsePodApi {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let app = Router::new()
+            .route(
+                "/v1/backups-internal/dump",
+                get(move || async move { (status, dump()) }),
+            )
+            .route(
+                "/v1/backups-internal/restore",
+                put(move || async move { (status, Json(())) }),
+            );
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        prose_pod_api(address)
+    }
+
+    fn prose_pod_api(address: SocketAddr) -> ProsePodApi {
+        ProsePodApi {
+            http_client: Arc::new(reqwest::Client::new()),
+            url: format!("http://{address}"),
+        }
+    }
+
+    fn pull() -> ProsePodApiDumpSource {
+        ProsePodApiDumpSource::Pull {
+            prose_token: HeaderValue::from_static("token"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stage_dump_pulled() {
+        let prose_pod_api = serve_prose_pod_api(StatusCode::OK).await;
+        let tmp = tempfile::tempdir().unwrap();
+        let dump_dir = tmp.path().join("prose-pod-api-dump");
+
+        stage_prose_pod_api_dump(&prose_pod_api, pull(), &dump_dir)
+            .await
+            .unwrap();
+
+        let database = std::fs::read(dump_dir.join("database.sqlite")).unwrap();
+        assert_eq!(database, DATABASE);
+    }
+
+    #[tokio::test]
+    async fn test_stage_dump_from_body() {
+        // NOTE: The Prose Pod API isn’t called, the data is in the body.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let prose_pod_api = prose_pod_api(listener.local_addr().unwrap());
+        drop(listener);
+        let tmp = tempfile::tempdir().unwrap();
+        let dump_dir = tmp.path().join("prose-pod-api-dump");
+
+        // NOTE: Replaces the previous dump.
+        std::fs::create_dir_all(dump_dir.join("stale")).unwrap();
+
+        let source = ProsePodApiDumpSource::Body(dump());
+        stage_prose_pod_api_dump(&prose_pod_api, source, &dump_dir)
+            .await
+            .unwrap();
+
+        let database = std::fs::read(dump_dir.join("database.sqlite")).unwrap();
+        assert_eq!(database, DATABASE);
+        assert!(!dump_dir.join("stale").exists());
+    }
+
+    #[tokio::test]
+    async fn test_stage_dump_prose_pod_api_failure() {
+        let prose_pod_api = serve_prose_pod_api(StatusCode::INTERNAL_SERVER_ERROR).await;
+        let tmp = tempfile::tempdir().unwrap();
+        let dump_dir = tmp.path().join("prose-pod-api-dump");
+
+        let error = stage_prose_pod_api_dump(&prose_pod_api, pull(), &dump_dir)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.into_json()["code"], "BACKUP_CREATE_FAILED");
+        assert!(!dump_dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_stage_dump_prose_pod_api_unreachable() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let prose_pod_api = prose_pod_api(listener.local_addr().unwrap());
+        drop(listener);
+        let tmp = tempfile::tempdir().unwrap();
+        let dump_dir = tmp.path().join("prose-pod-api-dump");
+
+        let error = stage_prose_pod_api_dump(&prose_pod_api, pull(), &dump_dir)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.into_json()["code"], "BACKUP_CREATE_FAILED");
+        assert!(!dump_dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_stage_dump_before_restore_failure() {
+        let prose_pod_api = serve_prose_pod_api(StatusCode::INTERNAL_SERVER_ERROR).await;
+        let tmp = tempfile::tempdir().unwrap();
+        let dump_dir = tmp.path().join("prose-pod-api-dump");
+
+        // NOTE: Staged for a previous backup.
+        std::fs::create_dir_all(dump_dir.join("stale")).unwrap();
+
+        stage_prose_pod_api_dump_before_restore(
+            &prose_pod_api,
+            &HeaderValue::from_static("token"),
+            &dump_dir,
+        )
+        .await;
+
+        // NOTE: Undoing the restoration must not send stale data.
+        assert!(!dump_dir.exists());
+    }
+
+    /// Prose Pod API data restored on startup, with its marker saved.
+    fn pending_restore(
+        dump_dir: &std::path::Path,
+    ) -> arc_swap::ArcSwapOption<PendingProsePodApiRestore> {
+        std::fs::create_dir_all(dump_dir).unwrap();
+        std::fs::write(dump_dir.join("database.sqlite"), DATABASE).unwrap();
+        let pending = PendingProsePodApiRestore {
+            backup_id: "prose%2Dbackup-1772432392-Test.tar.zst".to_owned(),
+        };
+        pending.save(dump_dir).unwrap();
+        arc_swap::ArcSwapOption::from_pointee(pending)
+    }
+
+    #[tokio::test]
+    async fn test_send_pending_restore() {
+        let prose_pod_api = serve_prose_pod_api(StatusCode::OK).await;
+        let tmp = tempfile::tempdir().unwrap();
+        let dump_dir = tmp.path().join("prose-pod-api-dump");
+        let pending = pending_restore(&dump_dir);
+
+        send_pending_prose_pod_api_restore(
+            &pending,
+            &prose_pod_api,
+            Some(&HeaderValue::from_static("token")),
+            &dump_dir,
+        )
+        .await
+        .unwrap();
+
+        assert!(pending.load().is_none());
+        assert!(
+            PendingProsePodApiRestore::read(&dump_dir)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_pending_restore_failure() {
+        let prose_pod_api = serve_prose_pod_api(StatusCode::INTERNAL_SERVER_ERROR).await;
+        let tmp = tempfile::tempdir().unwrap();
+        let dump_dir = tmp.path().join("prose-pod-api-dump");
+        let pending = pending_restore(&dump_dir);
+
+        let error = send_pending_prose_pod_api_restore(
+            &pending,
+            &prose_pod_api,
+            Some(&HeaderValue::from_static("token")),
+            &dump_dir,
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(error.into_json()["code"], "PROSE_POD_API_RESTORE_FAILED");
+        // NOTE: Kept, so the data is sent on the next request.
+        assert!(pending.load().is_some());
+        assert!(
+            PendingProsePodApiRestore::read(&dump_dir)
+                .unwrap()
+                .is_some()
+        );
+        let database = std::fs::read(dump_dir.join("database.sqlite")).unwrap();
+        assert_eq!(database, DATABASE);
+    }
+
+    #[tokio::test]
+    async fn test_send_pending_restore_without_token() {
+        let prose_pod_api = serve_prose_pod_api(StatusCode::OK).await;
+        let tmp = tempfile::tempdir().unwrap();
+        let dump_dir = tmp.path().join("prose-pod-api-dump");
+        let pending = pending_restore(&dump_dir);
+
+        let error = send_pending_prose_pod_api_restore(&pending, &prose_pod_api, None, &dump_dir)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.into_json()["code"], "PROSE_POD_API_RESTORE_PENDING");
+        assert!(pending.load().is_some());
+        assert!(
+            PendingProsePodApiRestore::read(&dump_dir)
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restore_stage_events_not_dropped() {
+        let (sender, mut receiver) = mpsc::channel(1);
+        let mut event_handler = StreamingRestoreBackupEventHandler {
+            total: 0,
+            progress: 0,
+            interval: tokio::time::Duration::ZERO,
+            last_event_sent: (0, tokio::time::Instant::now()),
+            progress_sender: Arc::new(sender),
+            pending_stages: VecDeque::new(),
+        };
+        let backup_id: BackupId = "prose%2Dbackup-1772432392-Test.tar.zst".parse().unwrap();
+
+        // NOTE: The client doesn’t read events, the channel is full after this.
+        event_handler.on_restoration_start(&backup_id, 10);
+        for stage in [
+            RestoreStage::Downloading,
+            RestoreStage::Extracting,
+        ] {
+            event_handler.on_stage(&backup_id, &RestoreStageEventDto::new(stage, 0.));
+            event_handler.on_restoration_progress(&backup_id, 1);
+        }
+
+        let events = tokio::task::spawn(async move {
+            let mut events = Vec::new();
+            while let Some(event) = receiver.recv().await {
+                events.push(format!("{:?}", event.unwrap()));
+            }
+            events
+        });
+        event_handler.flush().await;
+        drop(event_handler);
+        let events = events.await.unwrap();
+
+        // NOTE: Progress events sent while stages were pending were dropped.
+        assert_eq!(events.len(), 3, "{events:#?}");
+        assert!(events[0].contains("backup-restore-progress"), "{events:#?}");
+        assert!(events[1].contains("backup-restore-stage"), "{events:#?}");
+        assert!(events[2].contains("backup-restore-stage"), "{events:#?}");
+    }
+}
@@ -25,7 +25,79 @@ pub(crate) trait HealthTrait {
 
 impl HealthTrait for backend::Running {
     fn health(&self) -> axum::response::Response {
-        StatusCode::OK.into_response()
+        let mut body = serde_json::Map::new();
+
+        // NOTE: Still healthy, but administrators should know data
+        //   might not be what they expect.
+        if let Some(ref recovery) = self.restore_recovery {
+            body.insert("restore_recovery".to_owned(), serde_json::json!(recovery));
+        }
+
+        // NOTE: Still healthy, but the Prose Pod API doesn’t have its data
+        //   yet (see `PendingProsePodApiRestore`).
+        if let Some(pending) = self.pending_prose_pod_api_restore.load_full() {
+            body.insert(
+                "pending_prose_pod_api_restore".to_owned(),
+                serde_json::json!(pending),
+            );
+        }
+
+        // NOTE: Still healthy, but administrators should know
+        //   Prosody had to be restarted.
+        let crashes = self.crash_history.crashes();
+        if !crashes.is_empty() {
+            body.insert("crashes".to_owned(), serde_json::json!(crashes));
+        }
+
+        if body.is_empty() {
+            StatusCode::OK.into_response()
+        } else {
+            (StatusCode::OK, axum::Json(body)).into_response()
+        }
+    }
+}
+
+// MARK: Backend crashed
+
+impl HealthTrait for backend::Crashed {
+    fn health(&self) -> axum::response::Response {
+        let (error, retry_after) = match self.restart_at {
+            Some(restart_at) => {
+                let delay = restart_at - time::OffsetDateTime::now_utc();
+                (
+                    errors::service_unavailable(
+                        "SERVER_CRASHED",
+                        "Prose Server crashed",
+                        "Your Prose Server crashed, it will restart shortly.",
+                    ),
+                    // NOTE: `+ 1` to account for the restart itself.
+                    Some((delay.whole_seconds().clamp(0, 254) + 1) as u8),
+                )
+            }
+            None => (
+                errors::service_unavailable(
+                    "SERVER_CRASH_LOOP",
+                    "Prose Server crashed",
+                    "Your Prose Server crashed repeatedly and won’t restart \
+                    automatically. Contact an administrator to fix this.",
+                ),
+                None,
+            ),
+        };
+
+        let mut body = error.into_json();
+        body["crashes"] = serde_json::json!(self.crash_history.crashes());
+        if let Some(restart_at) = self.restart_at {
+            body["restart_at"] = serde_json::json!(
+                (restart_at.format(&time::format_description::well_known::Rfc3339)).ok()
+            );
+        }
+
+        let response = (StatusCode::SERVICE_UNAVAILABLE, axum::Json(body)).into_response();
+        match retry_after {
+            Some(seconds) => response.retry_after(seconds),
+            None => response,
+        }
     }
 }
 
@@ -5,15 +5,15 @@
 
 use axum::Json;
 use axum::extract::State;
-use prosodyctl::UserCreateError;
 use serde::{Deserialize, Serialize};
 
 use crate::app_config::defaults::MAIN_TEAM_GROUP_ID;
 use crate::errors;
-use crate::models::{BareJid, JidNode, Password};
+use crate::models::{BareJid, JidDomain, JidNode, Password};
 use crate::responders::Error;
 use crate::state::prelude::*;
 use crate::util::NoContext as _;
+use crate::xmpp_server::{DynXmppServerBackend, UserCreateError};
 
 #[serde_with::serde_as]
 #[derive(Debug, Deserialize)]
@@ -31,6 +31,10 @@ pub struct CreateAccountResponse {
     pub role: String,
 }
 
+/// Held between checking that no admin exists and creating the first admin
+/// account, so concurrent requests can’t both succeed.
+static FIRST_ACCOUNT_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
 pub async fn init_first_account(
     State(AppState {
         ref frontend,
@@ -40,10 +44,21 @@ pub async fn init_first_account(
     Json(dto): Json<CreateAccountRequest>,
 ) -> Result<Json<CreateAccountResponse>, Error> {
     let ref server_domain = frontend.config.server.domain;
-    let mut prosodyctl = backend.prosodyctl.write().await;
 
+    let response = create_first_account(backend.xmpp_server.as_ref(), server_domain, dto).await?;
+
+    Ok(Json(response))
+}
+
+async fn create_first_account(
+    xmpp_server: &DynXmppServerBackend,
+    server_domain: &JidDomain,
+    dto: CreateAccountRequest,
+) -> Result<CreateAccountResponse, Error> {
     let first_account_role = "prosody:admin";
 
+    let _guard = FIRST_ACCOUNT_LOCK.lock().await;
+
     // Ensure no user already exists.
     // FIX: While it shouldn’t be possible to delete the last admin
     //   (see [prose-im/prose-pod-api#344](https://github.com/prose-im/prose-pod-api/issues/344)),
@@ -51,8 +66,8 @@ pub async fn init_first_account(
     //   for convenience. I (@RemiBardon) feel like it’s going to
     //   save us from a bad situation one day and that day I’ll
     //   thank myself for taking this decision.
-    let user_count = prosodyctl
-        .user_get_jids_with_role(server_domain, first_account_role)
+    let user_count = xmpp_server
+        .users_with_role(server_domain, first_account_role)
         .await
         .no_context()?
         .len();
@@ -66,15 +81,12 @@ pub async fn init_first_account(
 
     // Create first admin account.
     let jid = BareJid::from_parts(Some(&dto.username), server_domain);
-    let summary = prosodyctl
-        .user_create(jid.as_str(), &dto.password, Some(first_account_role))
+    xmpp_server
+        .create_user(&jid, &dto.password, Some(first_account_role))
         .await
         .map_err(|err| match err {
             UserCreateError::Conflict => {
-                // // NOTE: Even more so since we lock the prosodyctl shell between
-                // //   listing users and creating the first admin account.
-                // unreachable!("There shouldn’t be any user")
-                // NOTE: Because we now check for admins only,
+                // NOTE: Because we check for admins only,
                 //   there might still be a conflict.
                 errors::conflict_error(
                     "USERNAME_ALREADY_TAKEN",
@@ -84,22 +96,72 @@ pub async fn init_first_account(
             }
             UserCreateError::Internal(error) => error.no_context(),
         })?;
-    tracing::info!("{summary}");
 
     // Add first account to main group.
     // TODO: Move this to a hook in Prosody?
-    let summary = prosodyctl
-        .groups_add_member(server_domain, MAIN_TEAM_GROUP_ID, &dto.username, None)
+    xmpp_server
+        .add_group_member(server_domain, MAIN_TEAM_GROUP_ID, &dto.username, false)
         .await
         .no_context()?;
-    tracing::info!("{summary}");
 
-    // Release lock ASAP.
-    drop(prosodyctl);
-
-    let response = CreateAccountResponse {
+    Ok(CreateAccountResponse {
         username: dto.username,
         role: first_account_role.to_owned(),
-    };
-    Ok(Json(response))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr as _;
+
+    use secrecy::SecretString;
+
+    use crate::xmpp_server::XmppServerBackend as _;
+    use crate::xmpp_server::in_memory::InMemoryXmppServer;
+
+    use super::*;
+
+    fn request(username: &str) -> CreateAccountRequest {
+        CreateAccountRequest {
+            username: JidNode::from_str(username).unwrap(),
+            password: Password::from(SecretString::from("password".to_owned())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_first_account() {
+        let xmpp_server = InMemoryXmppServer::default();
+        let ref domain = JidDomain::from_str("example.org").unwrap();
+        let key = (domain.to_string(), MAIN_TEAM_GROUP_ID.to_owned());
+        xmpp_server.with_state(|state| state.groups.insert(key.clone(), Default::default()));
+
+        let response = create_first_account(&xmpp_server, domain, request("alice"))
+            .await
+            .unwrap();
+        assert_eq!(response.role, "prosody:admin");
+
+        let ref jid = BareJid::new("alice@example.org").unwrap();
+        assert_eq!(xmpp_server.user_role(jid).await.unwrap(), "prosody:admin");
+        xmpp_server.with_state(|state| {
+            assert!(state.groups[&key].members.contains("alice"));
+        });
+
+        let error = create_first_account(&xmpp_server, domain, request("bob"))
+            .await
+            .unwrap_err();
+        assert_eq!(error.into_json()["code"], "FIRST_ACCOUNT_ALREADY_CREATED");
+    }
+
+    #[tokio::test]
+    async fn test_create_first_account_username_taken() {
+        let xmpp_server = InMemoryXmppServer::default();
+        let ref domain = JidDomain::from_str("example.org").unwrap();
+        let jid = BareJid::new("alice@example.org").unwrap();
+        xmpp_server.with_state(|state| state.users.insert(jid, "prosody:member".to_owned()));
+
+        let error = create_first_account(&xmpp_server, domain, request("alice"))
+            .await
+            .unwrap_err();
+        assert_eq!(error.into_json()["code"], "USERNAME_ALREADY_TAKEN");
+    }
 }
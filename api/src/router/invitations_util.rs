@@ -20,16 +20,13 @@ pub async fn invitations_stats(
 ) -> Result<Json<GetInvitationsStatsResponse>, Error> {
     let domain = &frontend.config.server.domain;
 
-    let mut prosodyctl = backend.prosodyctl.write().await;
+    let count = backend
+        .xmpp_server
+        .invite_count(domain)
+        .await
+        .no_context()?;
 
-    let invites = prosodyctl.invite_list(domain).await.no_context()?;
-
-    // Release lock ASAP.
-    drop(prosodyctl);
-
-    Ok(Json(GetInvitationsStatsResponse {
-        count: invites.len(),
-    }))
+    Ok(Json(GetInvitationsStatsResponse { count }))
 }
 
 #[derive(Serialize)]
@@ -0,0 +1,122 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use std::sync::Arc;
+
+use axum::extract::State;
+
+use crate::errors;
+use crate::responders::Error;
+use crate::state::prelude::*;
+use crate::supervisor::restart_delay;
+use crate::util::either::Either;
+
+// MARK: - Routes
+
+/// Called by the supervisor (see [`crate::supervisor`]) when Prosody
+/// exits unexpectedly.
+pub(in crate::router) async fn backend_crashed<F>(
+    State(app_state): State<AppState<F, b::Running>>,
+) -> Result<(), Error>
+where
+    F: frontend::State + AsRef<f::Running>,
+    (F, ()): Into<f::Running>,
+{
+    // NOTE: This route is reachable from the outside,
+    //   make sure Prosody really crashed.
+    let exit = app_state.backend.prosody.read().await.unexpected_exit();
+    if exit.is_none() {
+        return Err(errors::conflict_error(
+            "BACKEND_RUNNING",
+            "Backend running",
+            "Prosody did not exit.",
+        ));
+    }
+
+    let _new_state = app_state.do_handle_backend_crash();
+
+    Ok(())
+}
+
+pub(in crate::router) async fn backend_restart_after_crash(
+    State(app_state): State<AppState<f::Running, b::Crashed>>,
+) -> Result<(), Error> {
+    if !app_state.backend.claim_restart() {
+        return Err(errors::conflict_error(
+            "RESTART_IN_PROGRESS",
+            "Restart in progress",
+            "Your Prose Server is already restarting.",
+        ));
+    }
+
+    match app_state.do_restart_backend().await {
+        Ok(_new_state) => Ok(()),
+
+        Err(Either::E1(FailState { error, .. }) | Either::E2(FailState { error, .. })) => {
+            Err(error)
+        }
+    }
+}
+
+// MARK: - State transitions
+
+impl<F> AppState<F, b::Running>
+where
+    F: frontend::State + AsRef<f::Running>,
+    (F, ()): Into<f::Running>,
+{
+    /// ```txt
+    /// AppState<F, Running>
+    ///   F ∈ { Running, RunningWithMisconfiguration }
+    /// -------------------------------------------- (Handle backend crash)
+    /// AppState<Running, Crashed>
+    /// ```
+    ///
+    /// Then restarts the backend after a backoff delay, unless automatic
+    /// restarts are disabled or Prosody is crash-looping.
+    ///
+    /// NOTE: The frontend keeps its configuration, but a misconfiguration
+    ///   isn’t reported anymore (it will be on next reload).
+    pub(crate) fn do_handle_backend_crash(self) -> AppState<f::Running, b::Crashed> {
+        let config = &self.frontend.as_ref().config.server.auto_restart;
+        let crash_history = Arc::clone(&self.backend.crash_history);
+
+        let recent_crashes = crash_history.recent_crash_count(config.crash_loop_window);
+        let delay = restart_delay(config, recent_crashes);
+        if delay.is_none() && config.enabled {
+            tracing::error!(
+                "Prosody crashed {recent_crashes} times in {window:?}, \
+                not restarting it automatically (crash loop).",
+                window = config.crash_loop_window,
+            );
+        }
+
+        let crashed = b::Crashed {
+            crash_history,
+            restart_at: delay.map(|delay| time::OffsetDateTime::now_utc() + delay),
+            restart_claimed: Default::default(),
+        };
+        let new_state: AppState<f::Running, b::Crashed> = self.transition_with(((), crashed));
+
+        if let Some(delay) = delay {
+            tracing::info!("Restarting Prosody in {delay:?}…");
+
+            let app_state = new_state.clone();
+            tokio::task::spawn(async move {
+                tokio::time::sleep(delay).await;
+
+                // NOTE: An administrator might have restarted it already.
+                if !app_state.backend.claim_restart() {
+                    return;
+                }
+
+                // NOTE: Errors are logged already.
+                let _ = app_state.do_restart_backend().await;
+            });
+        }
+
+        new_state
+    }
+}
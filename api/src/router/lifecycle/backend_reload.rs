@@ -50,27 +50,21 @@ impl AppState<f::Running, b::Running> {
         }
 
         // Reload Prosody modules (not done automatically).
-        let main_host = self.frontend.config.server.domain.as_str();
-        let mut prosodyctl = self.backend.prosodyctl.write().await;
+        let ref main_host = self.frontend.config.server.domain;
+        let xmpp_server = self.backend.xmpp_server.as_ref();
 
-        prosodyctl
+        xmpp_server
             .wait_for_readiness()
             .await
             .unwrap_or_else(|err| debug_panic_or_log_error!("{err:?}"));
 
         // TODO: Impact of runnning every time?
-        match prosodyctl.module_load_modules_for_host(main_host).await {
-            Ok(()) => drop(prosodyctl),
-            Err(error) => {
-                drop(prosodyctl);
-
-                let error =
-                    error.context(format!("Could not load Prosody modules for `{main_host}`"));
+        if let Err(error) = xmpp_server.load_host_modules(main_host).await {
+            let error = error.context(format!("Could not load Prosody modules for `{main_host}`"));
 
-                debug_panic_or_log_error!("{error:?}");
+            debug_panic_or_log_error!("{error:?}");
 
-                return Err(self.with_error(error.no_context()));
-            }
+            return Err(self.with_error(error.no_context()));
         }
 
         Ok(self)
@@ -5,8 +5,9 @@
 
 use axum::extract::State;
 
-use crate::errors;
 use crate::responders::Error;
+use crate::router::backend_process::stop_backend;
+use crate::startup::backend_start_error;
 use crate::state::prelude::*;
 use crate::util::either::Either;
 
@@ -35,10 +36,8 @@ pub(in crate::router) async fn backend_restart(
     State(app_state): State<AppState<f::Running, b::Running>>,
 ) -> Result<(), Error> {
     // Stop Prosody.
-    {
-        let mut prosody = app_state.backend.prosody.write().await;
-        prosody.stop().await.unwrap();
-    }
+    // NOTE: Fails early if Prosody is managed externally.
+    stop_backend(&app_state.backend.prosody).await?;
 
     match app_state
         .with_backend(b::Restarting {})
@@ -95,12 +94,34 @@ impl<F: frontend::State, B: backend::State> AppState<F, B> {
     /// ```
     ///
     /// NOTE: This method **does** log errors.
+    #[inline]
     pub(crate) async fn do_restart_backend(
         self,
     ) -> Result<
         AppState<F, b::Running>,
         Either<FailState<F, b::Running>, FailState<F, b::RestartFailed>>,
     >
+    where
+        F: AsRef<f::Running>,
+        for<'a> F: From<(F, &'a crate::responders::Error)>,
+        B: Into<b::Restarting>,
+        AppState<F, b::Running>: AppStateTrait,
+        AppState<F, b::Restarting>: AppStateTrait,
+        AppState<F, b::RestartFailed>: AppStateTrait,
+    {
+        self.do_restart_backend_with(|| {}).await
+    }
+
+    /// Same as [`do_restart_backend`](Self::do_restart_backend), but calls
+    /// `on_migrations` once the backend started, right before migrations
+    /// run (see [`try_bootstrapping_with`](Self::try_bootstrapping_with)).
+    pub(crate) async fn do_restart_backend_with(
+        self,
+        on_migrations: impl FnOnce() + Send,
+    ) -> Result<
+        AppState<F, b::Running>,
+        Either<FailState<F, b::Running>, FailState<F, b::RestartFailed>>,
+    >
     where
         F: AsRef<f::Running>,
         for<'a> F: From<(F, &'a crate::responders::Error)>,
@@ -111,7 +132,7 @@ impl<F: frontend::State, B: backend::State> AppState<F, B> {
     {
         let app_state = self.set_backend_restarting();
 
-        match app_state.try_bootstrapping().await {
+        match app_state.try_bootstrapping_with(on_migrations).await {
             Ok(new_state) => Ok(new_state),
 
             Err((new_state, err)) => {
@@ -121,7 +142,7 @@ impl<F: frontend::State, B: backend::State> AppState<F, B> {
                 tracing::error!("{error:?}");
 
                 Err(Either::E2(new_state.transition_failed(
-                    errors::internal_server_error(
+                    backend_start_error(
                         &error,
                         "RESTART_FAILED",
                         "Something went wrong while restarting your Prose Server. \
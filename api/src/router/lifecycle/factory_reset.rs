@@ -10,6 +10,7 @@ use tokio::time::Instant;
 
 use crate::errors;
 use crate::responders::Error;
+use crate::router::backend_process::ensure_backend_can_stop;
 use crate::state::prelude::*;
 use crate::util::either::Either;
 
@@ -18,6 +19,9 @@ use crate::util::either::Either;
 pub(in crate::router) async fn factory_reset(
     State(app_state): State<AppState<f::Running, b::Running>>,
 ) -> Result<StatusCode, Error> {
+    // NOTE: Fail before transitioning, as Prosody is stopped during the reset.
+    ensure_backend_can_stop(&app_state.backend.prosody).await?;
+
     match app_state.do_factory_reset().await {
         Ok(_new_state) => Ok(StatusCode::RESET_CONTENT),
         Err(Either::E1(FailState { error, .. })) | Err(Either::E2(FailState { error, .. })) => {
@@ -34,11 +38,11 @@ impl<F: frontend::State, B: backend::State> AppState<F, B> {
         use crate::util::empty_dir;
 
         let mut prosody = backend.as_ref().prosody.write().await;
-        let mut prosodyctl = backend.as_ref().prosodyctl.write().await;
+        let xmpp_server = backend.as_ref().xmpp_server.as_ref();
 
         // Read Prosody paths early to abort before doing anything non-recoverable.
-        let config_path = prosodyctl.prosody_paths_config().await?;
-        let data_path = prosodyctl.prosody_paths_data().await?;
+        let config_path = xmpp_server.config_dir().await?;
+        let data_path = xmpp_server.data_dir().await?;
 
         prosody.stop().await?;
 
@@ -50,6 +54,10 @@ impl<F: frontend::State, B: backend::State> AppState<F, B> {
 
         reset_config_file().await?;
 
+        // NOTE: The Prose Pod API is reset too, it must not receive data
+        //   restored on startup afterwards.
+        crate::router::backups::discard_pending_prose_pod_api_restore(backend.as_ref())?;
+
         Ok(())
     }
 
@@ -103,7 +111,7 @@ impl<F: frontend::State, B: backend::State> AppState<F, B> {
         }
 
         // Transition app to “Starting”.
-        match app_state.try_reload_frontend() {
+        match app_state.try_reload_frontend().await {
             Ok(new_state) => {
                 // NOTE: After a factory reset, the default configuration is,
                 //   at least, missing the Server domain. However, in some cases
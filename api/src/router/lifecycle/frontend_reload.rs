@@ -5,7 +5,7 @@
 
 use std::sync::Arc;
 
-use anyhow::Context as _;
+use anyhow::{Context as _, anyhow};
 use axum::extract::State;
 
 use crate::responders::Error;
@@ -18,7 +18,10 @@ use crate::{AppConfig, errors};
 pub(in crate::router) async fn frontend_reload(
     State(app_state): State<AppState<f::Running, b::Running>>,
 ) -> Result<(), Error> {
-    match app_state.do_reload_frontend::<f::RunningWithMisconfiguration, b::Running, b::Running>() {
+    match app_state
+        .do_reload_frontend::<f::RunningWithMisconfiguration, b::Running, b::Running>()
+        .await
+    {
         Ok(_new_state) => Ok(()),
         Err(FailState { error, .. }) => Err(error),
     }
@@ -31,11 +34,17 @@ where
     AppState<F, B>: AppStateTrait,
 {
     /// NOTE: This method does **not** log errors.
-    fn reload_frontend(app_state: &Self) -> Result<f::Running, anyhow::Error> {
+    async fn reload_frontend(app_state: &Self) -> Result<f::Running, anyhow::Error> {
         let app_config = AppConfig::from_default_figment()?;
 
         app_state.validate_config_changes(&app_config)?;
 
+        let Some(app_context) = app_state.context() else {
+            return Err(anyhow!(
+                "Could not move the Prose Pod Server API: app context dropped."
+            ));
+        };
+
         let tracing_reload_handles = app_state.frontend.tracing_reload_handles();
         update_tracing_config(
             &app_config.log,
@@ -44,6 +53,16 @@ where
         )
         .context("Could not update tracing config")?;
 
+        // Move the HTTP API if its address changed.
+        // NOTE: Must be the last fallible step, so the HTTP API isn’t moved
+        //   if the new configuration isn’t applied. If the new address can’t
+        //   be bound, we fail here and the HTTP API is still served on the
+        //   previous address.
+        let address = app_config.server_api.address();
+        (app_context.listener().rebind(address).await).context(format!(
+            "Could not serve the Prose Pod Server API on {address}"
+        ))?;
+
         Ok(f::Running {
             config: Arc::new(app_config),
             tracing_reload_handles: Arc::clone(app_state.frontend.tracing_reload_handles()),
@@ -60,7 +79,7 @@ where
     /// ```
     ///
     /// NOTE: This method does **not** log errors.
-    pub(crate) fn try_reload_frontend<B2>(
+    pub(crate) async fn try_reload_frontend<B2>(
         self,
     ) -> Result<AppState<f::Running, B2>, (Self, anyhow::Error)>
     where
@@ -68,7 +87,7 @@ where
         B2: backend::State,
         AppState<f::Running, B2>: AppStateTrait,
     {
-        match Self::reload_frontend(&self) {
+        match Self::reload_frontend(&self).await {
             Ok(frontend) => Ok(self.with_frontend(frontend).with_auto_transition()),
 
             Err(err) => {
@@ -92,7 +111,7 @@ where
     /// ```
     ///
     /// NOTE: This method **does** log errors.
-    pub(crate) fn do_reload_frontend<FrontendFailure, BackendFailure, BackendSuccess>(
+    pub(crate) async fn do_reload_frontend<FrontendFailure, BackendFailure, BackendSuccess>(
         self,
     ) -> Result<AppState<f::Running, BackendSuccess>, FailState<FrontendFailure, BackendFailure>>
     where
@@ -105,7 +124,7 @@ where
         AppState<f::Running, BackendSuccess>: AppStateTrait,
         AppState<FrontendFailure, BackendFailure>: AppStateTrait,
     {
-        match self.try_reload_frontend() {
+        match self.try_reload_frontend().await {
             Ok(new_state) => Ok(new_state.with_auto_transition()),
 
             Err((app_state, error)) => {
@@ -3,12 +3,14 @@
 // Copyright: 2025, Rémi Bardon <remi@remibardon.name>
 // License: Mozilla Public License v2.0 (MPL v2.0)
 
+mod backend_crash;
 mod backend_reload;
 mod backend_restart;
 mod factory_reset;
 mod frontend_reload;
 mod reload;
 
+pub(in crate::router) use self::backend_crash::*;
 pub(in crate::router) use self::backend_reload::*;
 pub(in crate::router) use self::backend_restart::*;
 pub(in crate::router) use self::factory_reset::*;
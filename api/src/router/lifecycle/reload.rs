@@ -16,7 +16,7 @@ where
     for<'a> (F, &'a Error): Into<F>,
     AppState<F, b::Running>: AppStateTrait,
 {
-    match app_state.do_reload_frontend() {
+    match app_state.do_reload_frontend().await {
         Ok(new_state) => match new_state.do_reload_backend().await {
             Ok(_new_state) => Ok(()),
             Err(FailState { error, .. }) => Err(error),
@@ -34,6 +34,7 @@ impl AppState<f::Misconfigured, b::Stopped> {
     > {
         let app_state = self
             .do_reload_frontend::<f::Misconfigured, b::Stopped, b::Starting>()
+            .await
             .map_err(Either::E1)?;
 
         app_state
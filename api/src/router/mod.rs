@@ -4,6 +4,7 @@
 // License: Mozilla Public License v2.0 (MPL v2.0)
 
 mod analytics;
+mod backend_process;
 pub(crate) mod backups;
 mod cloud_api_proxy;
 mod health;
@@ -18,10 +19,10 @@ use std::sync::{Arc, Weak};
 
 use axum::Router;
 use axum::routing::{MethodRouter, any, get, post, put};
-use prosody_child_process::ProsodyChildProcess;
 use tokio::sync::RwLock;
 
 use crate::AppConfig;
+use crate::process_manager::DynBackendProcessManager;
 use crate::router::util::{backend_health, frontend_health};
 use crate::state::prelude::*;
 
@@ -74,6 +75,7 @@ impl AppStateTrait for AppState<f::Running, b::Running> {
             .route("/lifecycle/frontend-reload", post(lifecycle::frontend_reload))
             .route("/lifecycle/backend-reload", post(lifecycle::backend_reload))
             .route("/lifecycle/backend-restart", post(lifecycle::backend_restart))
+            .route("/lifecycle/backend-crashed", post(lifecycle::backend_crashed))
             .route("/lifecycle/reload", post(lifecycle::reload))
             .route("/lifecycle/factory-reset", post(lifecycle::factory_reset))
             .route(
@@ -82,6 +84,9 @@ impl AppStateTrait for AppState<f::Running, b::Running> {
                     .post(backups::post_backups_all)
                     .get(backups::get_backups)
             )
+            .route("/v1/backups/upload", post(backups::post_backups_upload))
+            .route("/v1/backups/undo-last-restore", post(backups::post_backups_undo_last_restore))
+            .route("/v1/backups/last-restore-report", get(backups::get_backups_last_restore_report))
             .route(
                 "/v1/backups/{backup_id}",
                 MethodRouter::new()
@@ -89,7 +94,22 @@ impl AppStateTrait for AppState<f::Running, b::Running> {
                     .delete(backups::delete_backup)
             )
             .route("/v1/backups/{backup_id}/restore", put(backups::put_backup_restore_all))
+            .route("/v1/backups/{backup_id}/diff/{other_backup_id}", get(backups::get_backups_diff))
             .route("/v1/backups/{backup_id}/download-url", get(backups::get_backup_download_url))
+            .route("/v1/backups/{backup_id}/download-link", get(backups::get_backup_download_link))
+            .route("/v1/backups/{backup_id}/download", get(backups::get_backup_download))
+            .route("/v1/backups-stats", get(backups::get_backups_stats))
+            .route("/v1/backups-log", get(backups::get_backups_log))
+            .route(
+                "/v1/backups-keys",
+                MethodRouter::new()
+                    .get(backups::get_backup_keys)
+                    .post(backups::post_backup_keys)
+            )
+            .route("/v1/backups-keys/import", post(backups::post_backup_keys_import))
+            .route("/v1/backups-keys/{fingerprint}/retire", put(backups::put_backup_key_retire))
+            .route("/v1/backend/status", get(backend_process::get_backend_status))
+            .route("/v1/backend/logs", get(backend_process::get_backend_logs))
             .route(
                 "/cloud-api-proxy/v1/analytics/event",
                 MethodRouter::new()
@@ -106,7 +126,7 @@ impl AppStateTrait for AppState<f::Running, b::Running> {
         AppConfig::validate_config_changes(&self.frontend.config, new_config)
     }
 
-    fn prosody_weak(&self) -> Option<Weak<RwLock<ProsodyChildProcess>>> {
+    fn prosody_weak(&self) -> Option<Weak<RwLock<Box<DynBackendProcessManager>>>> {
         Some(Arc::downgrade(&self.backend.prosody))
     }
 }
@@ -131,7 +151,7 @@ impl AppStateTrait for AppState<f::Running, b::Starting> {
         AppConfig::validate_config_changes(&self.frontend.config, new_config)
     }
 
-    fn prosody_weak(&self) -> Option<Weak<RwLock<ProsodyChildProcess>>> {
+    fn prosody_weak(&self) -> Option<Weak<RwLock<Box<DynBackendProcessManager>>>> {
         None
     }
 }
@@ -156,7 +176,31 @@ impl AppStateTrait for AppState<f::Running, b::Restarting> {
         AppConfig::validate_config_changes(&self.frontend.config, new_config)
     }
 
-    fn prosody_weak(&self) -> Option<Weak<RwLock<ProsodyChildProcess>>> {
+    fn prosody_weak(&self) -> Option<Weak<RwLock<Box<DynBackendProcessManager>>>> {
+        None
+    }
+}
+
+/// **Crashed** (after Prosody exited unexpectedly,
+/// until it’s restarted).
+impl AppStateTrait for AppState<f::Running, b::Crashed> {
+    fn state_name() -> &'static str {
+        "Crashed"
+    }
+
+    #[rustfmt::skip]
+    fn into_router(self) -> axum::Router {
+        Router::<Self>::new()
+            .route("/lifecycle/backend-restart", post(lifecycle::backend_restart_after_crash))
+            .fallback(backend_health)
+            .with_state(self)
+    }
+
+    fn validate_config_changes(&self, new_config: &AppConfig) -> Result<(), anyhow::Error> {
+        AppConfig::validate_config_changes(&self.frontend.config, new_config)
+    }
+
+    fn prosody_weak(&self) -> Option<Weak<RwLock<Box<DynBackendProcessManager>>>> {
         None
     }
 }
@@ -179,7 +223,7 @@ impl AppStateTrait for AppState<f::Running, b::RestartFailed> {
         AppConfig::validate_config_changes(&self.frontend.config, new_config)
     }
 
-    fn prosody_weak(&self) -> Option<Weak<RwLock<ProsodyChildProcess>>> {
+    fn prosody_weak(&self) -> Option<Weak<RwLock<Box<DynBackendProcessManager>>>> {
         None
     }
 }
@@ -201,6 +245,9 @@ impl AppStateTrait for AppState<f::RunningWithMisconfiguration, b::Running> {
     fn into_router(self) -> axum::Router {
         Router::new()
             .route("/lifecycle/reload", post(lifecycle::reload))
+            .route("/lifecycle/backend-crashed", post(lifecycle::backend_crashed))
+            .route("/v1/backend/status", get(backend_process::get_backend_status))
+            .route("/v1/backend/logs", get(backend_process::get_backend_logs))
             .fallback(frontend_health)
             .with_state(self)
     }
@@ -209,7 +256,7 @@ impl AppStateTrait for AppState<f::RunningWithMisconfiguration, b::Running> {
         AppConfig::validate_config_changes(&self.frontend.config, new_config)
     }
 
-    fn prosody_weak(&self) -> Option<Weak<RwLock<ProsodyChildProcess>>> {
+    fn prosody_weak(&self) -> Option<Weak<RwLock<Box<DynBackendProcessManager>>>> {
         Some(Arc::downgrade(&self.backend.prosody))
     }
 }
@@ -229,7 +276,7 @@ impl AppStateTrait for AppState<f::Restarting, b::UndergoingFactoryReset> {
         Ok(())
     }
 
-    fn prosody_weak(&self) -> Option<Weak<RwLock<ProsodyChildProcess>>> {
+    fn prosody_weak(&self) -> Option<Weak<RwLock<Box<DynBackendProcessManager>>>> {
         None
     }
 }
@@ -249,7 +296,7 @@ impl AppStateTrait for AppState<f::Running, b::UndergoingBackup> {
         Ok(())
     }
 
-    fn prosody_weak(&self) -> Option<Weak<RwLock<ProsodyChildProcess>>> {
+    fn prosody_weak(&self) -> Option<Weak<RwLock<Box<DynBackendProcessManager>>>> {
         None
     }
 }
@@ -269,7 +316,7 @@ impl AppStateTrait for AppState<f::Running, b::UndergoingRestore> {
         Ok(())
     }
 
-    fn prosody_weak(&self) -> Option<Weak<RwLock<ProsodyChildProcess>>> {
+    fn prosody_weak(&self) -> Option<Weak<RwLock<Box<DynBackendProcessManager>>>> {
         None
     }
 }
@@ -292,7 +339,7 @@ impl AppStateTrait for AppState<f::Misconfigured, b::Stopped> {
         Ok(())
     }
 
-    fn prosody_weak(&self) -> Option<Weak<RwLock<ProsodyChildProcess>>> {
+    fn prosody_weak(&self) -> Option<Weak<RwLock<Box<DynBackendProcessManager>>>> {
         None
     }
 }
@@ -315,7 +362,7 @@ impl AppStateTrait for AppState<f::Running, b::StartFailed> {
         AppConfig::validate_config_changes(&self.frontend.config, new_config)
     }
 
-    fn prosody_weak(&self) -> Option<Weak<RwLock<ProsodyChildProcess>>> {
+    fn prosody_weak(&self) -> Option<Weak<RwLock<Box<DynBackendProcessManager>>>> {
         None
     }
 }
@@ -7,10 +7,11 @@ use axum::Json;
 use axum::extract::State;
 use serde::Serialize;
 
-use crate::models::{BareJid, CallerInfo};
+use crate::models::{BareJid, CallerInfo, JidDomain};
 use crate::responders::Error;
 use crate::state::prelude::*;
-use crate::util::{NoContext as _, PROSODY_JIDS_ARE_VALID};
+use crate::util::NoContext as _;
+use crate::xmpp_server::DynXmppServerBackend;
 
 pub async fn users_stats(
     State(AppState {
@@ -21,11 +22,21 @@ pub async fn users_stats(
 ) -> Result<Json<GetUsersStatsResponse>, Error> {
     let domain = &frontend.config.server.domain;
 
-    let mut prosodyctl = backend.prosodyctl.write().await;
+    let count = count_users(backend.xmpp_server.as_ref(), domain)
+        .await
+        .no_context()?;
+
+    Ok(Json(GetUsersStatsResponse { count }))
+}
 
+/// Counts users, except service accounts.
+async fn count_users(
+    xmpp_server: &DynXmppServerBackend,
+    domain: &JidDomain,
+) -> Result<usize, anyhow::Error> {
     // Filter out service accounts.
     // NOTE: Given how roles are attributed at the moment,
-    //   `.user_get_jids_with_role(domain, "prosody:member")` doesn’t return
+    //   `.users_with_role(domain, "prosody:member")` doesn’t return
     //   what we want. `prosody:admin` accounts inherit the `prosody:member`
     //   role, but it’s not taken into account as it’s not an explicit
     //   secondary role. As a workaround, we’ll count all `prosody:member`,
@@ -41,17 +52,10 @@ pub async fn users_stats(
         "prosody:admin",
         "prosody:operator",
     ] {
-        user_count += prosodyctl
-            .user_get_jids_with_role(domain, role)
-            .await
-            .no_context()?
-            .len();
+        user_count += xmpp_server.users_with_role(domain, role).await?.len();
     }
 
-    // Release lock ASAP.
-    drop(prosodyctl);
-
-    Ok(Json(GetUsersStatsResponse { count: user_count }))
+    Ok(user_count)
 }
 
 #[derive(Serialize)]
@@ -66,28 +70,45 @@ pub async fn list_admin_jids(
         ..
     }): State<AppState>,
 ) -> Result<Json<Vec<BareJid>>, Error> {
-    use std::str::FromStr as _;
-
     let domain = &frontend.config.server.domain;
 
-    let mut prosodyctl = backend.prosodyctl.write().await;
-
-    let jids: Vec<String> = prosodyctl
-        .user_get_jids_with_role(domain, "prosody:admin")
+    let jids = (backend.xmpp_server)
+        .users_with_role(domain, "prosody:admin")
         .await
         .no_context()?;
 
-    // Release lock ASAP.
-    drop(prosodyctl);
-
-    let jids: Vec<BareJid> = jids
-        .iter()
-        .map(|str| BareJid::from_str(str).expect(PROSODY_JIDS_ARE_VALID))
-        .collect();
-
     Ok(Json(jids))
 }
 
 pub async fn self_user_info(caller_info: CallerInfo) -> Json<CallerInfo> {
     Json(caller_info)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr as _;
+
+    use crate::startup::SERVICE_ACCOUNT_ROLE;
+    use crate::xmpp_server::in_memory::InMemoryXmppServer;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_count_users_excludes_service_accounts() {
+        let xmpp_server = InMemoryXmppServer::default();
+        xmpp_server.with_state(|state| {
+            for (jid, role) in [
+                ("alice@example.org", "prosody:admin"),
+                ("bob@example.org", "prosody:member"),
+                ("carol@example.org", "prosody:operator"),
+                ("prose-workspace@example.org", SERVICE_ACCOUNT_ROLE),
+                ("dave@other.example.org", "prosody:member"),
+            ] {
+                (state.users).insert(BareJid::new(jid).unwrap(), role.to_owned());
+            }
+        });
+
+        let ref domain = JidDomain::from_str("example.org").unwrap();
+        assert_eq!(count_users(&xmpp_server, domain).await.unwrap(), 3);
+    }
+}
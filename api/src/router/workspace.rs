@@ -17,6 +17,7 @@ use crate::models::{Avatar, BareJid, CallerInfo, Color};
 use crate::responders;
 use crate::state::prelude::*;
 use crate::util::NoContext;
+use crate::xmpp_server::CallerCredentials;
 
 const ACCENT_COLOR_EXTENSION_KEY: &'static str = "x-accent-color";
 const PROSE_POD_DASHBOARD_URL_EXTENSION_KEY: &'static str = "x-prose-pod-dashboard-url";
@@ -84,10 +85,9 @@ async fn init_workspace(
     }): State<AppState>,
     Json(req): Json<InitWorkspaceRequest>,
 ) -> Result<(), Error> {
-    let server_domain = frontend.config.server.domain.as_str();
-    let mut prosodyctl = backend.state.prosodyctl.write().await;
-    let user_count = prosodyctl
-        .user_get_jids_with_role(server_domain, "prosody:member")
+    let ref server_domain = frontend.config.server.domain;
+    let user_count = (backend.xmpp_server)
+        .users_with_role(server_domain, "prosody:member")
         .await
         .no_context()?
         .len();
@@ -343,7 +343,7 @@ pub async fn set_workspace_icon(
         .no_context()?;
 
     backend
-        .prosody_rest
+        .xmpp_server
         .set_own_avatar(icon.into_bytes(), ctx)
         .await
         .context("Could not set Workspace icon")
@@ -359,9 +359,9 @@ pub async fn set_workspace_icon(
 pub(crate) async fn service_account_credentials(
     backend: &backend::Running,
     jid: &BareJid,
-) -> Result<prosody_rest::CallerCredentials, anyhow::Error> {
+) -> Result<CallerCredentials, anyhow::Error> {
     let token = backend.secrets_service.get_token(jid).await?;
-    Ok(prosody_rest::CallerCredentials {
+    Ok(CallerCredentials {
         bare_jid: jid.to_owned(),
         auth_token: token.inner().to_owned(),
     })
@@ -371,10 +371,10 @@ pub(crate) async fn service_account_credentials(
 #[inline]
 async fn service_account_vcard(
     backend: &backend::Running,
-    creds: &prosody_rest::CallerCredentials,
+    creds: &CallerCredentials,
 ) -> Result<Option<VCard4>, anyhow::Error> {
     backend
-        .prosody_rest
+        .xmpp_server
         .get_vcard(&creds.bare_jid, creds)
         .await
         .context("Could not get service account vCard")
@@ -385,10 +385,10 @@ async fn service_account_vcard(
 #[inline]
 async fn service_account_avatar(
     backend: &backend::Running,
-    creds: &prosody_rest::CallerCredentials,
+    creds: &CallerCredentials,
 ) -> Result<Option<Avatar>, Error> {
     match backend
-        .prosody_rest
+        .xmpp_server
         .get_avatar(&creds.bare_jid, creds)
         .await
         .context("Could not get service account avatar")
@@ -404,7 +404,7 @@ async fn service_account_avatar(
 #[inline]
 async fn get_workspace_profile_minimal(
     backend: &backend::Running,
-    creds: &prosody_rest::CallerCredentials,
+    creds: &CallerCredentials,
 ) -> Result<WorkspaceProfile, Error> {
     match service_account_vcard(backend, creds).await.no_context()? {
         Some(vcard) => WorkspaceProfile::try_from(vcard),
@@ -416,7 +416,7 @@ async fn get_workspace_profile_minimal(
 #[inline]
 pub(crate) async fn patch_workspace_vcard_unchecked(
     backend: &backend::Running,
-    creds: &prosody_rest::CallerCredentials,
+    creds: &CallerCredentials,
     PatchWorkspaceCommand {
         name,
         accent_color,
@@ -511,7 +511,7 @@ pub(crate) async fn patch_workspace_vcard_unchecked(
 
     if vcard != vcard_before {
         backend
-            .prosody_rest
+            .xmpp_server
             .set_own_vcard(vcard, creds)
             .await
             .context("Could not set Workspace vCard")?;
@@ -10,30 +10,31 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::Context as _;
-use arc_swap::ArcSwap;
+use arc_swap::{ArcSwap, ArcSwapOption};
 use prose_backup::BackupService;
-use prosody_child_process::ProsodyChildProcess;
+use prosody_child_process::NotReady;
 use prosody_http::ProsodyHttpConfig;
 use prosody_http::oauth2::{self, OAuth2ClientConfig, ProsodyOAuth2};
-use prosody_rest::ProsodyRest;
-use prosodyctl::Prosodyctl;
 use secrecy::SecretSlice;
 use tokio::sync::RwLock;
 use tokio::time::Instant;
 use tokio_util::sync::CancellationToken;
 
+use crate::app_config::ProcessManagerConfig;
 use crate::models::{BareJid, JidDomain, JidNode, Password};
+use crate::process_manager::{self, DynBackendProcessManager};
 use crate::prose_pod_api::ProsePodApi;
-use crate::router::backups::BACKUP_BLUEPRINTS;
+use crate::router::backups::{BACKUP_MIGRATIONS, PendingProsePodApiRestore, backup_blueprints};
 use crate::secrets_service::SecretsService;
 use crate::secrets_store::SecretsStore;
 use crate::state::prelude::*;
-use crate::util::unix_timestamp;
+use crate::xmpp_server::{CallerCredentials, DynXmppServerBackend, ProsodyXmppServer};
 use crate::{AppConfig, errors};
 
 pub(crate) const SERVER_DATA_DIR: &'static str = "/var/lib/prose-pod-server";
-const PROSODY_CONFIG_FILE_PATH: &'static str = "/etc/prosody/prosody.cfg.lua";
 const PROSODY_CERTS_DIR: &'static str = "/etc/prosody/certs";
+/// NOTE: Must match `admin_socket` in `prosody-bootstrap.cfg.lua`.
+pub(crate) const PROSODY_ADMIN_SOCKET_PATH: &'static str = "/var/run/prosody/prosody.sock";
 
 // MARK: - State transitions
 
@@ -47,7 +48,10 @@ where
     /// about bootstrapping.
     ///
     /// NOTE: This method does **not** log errors.
-    async fn bootstrap(app_state: &Self) -> Result<b::Running, anyhow::Error> {
+    async fn bootstrap(
+        app_state: &Self,
+        on_migrations: impl FnOnce() + Send,
+    ) -> Result<b::Running, anyhow::Error> {
         use crate::util::sync::AutoCancelToken;
 
         let app_config = Arc::deref(&app_state.frontend.as_ref().config);
@@ -57,12 +61,59 @@ where
 
         let backup_service = match app_config.backups.as_ref() {
             Some(config) => {
-                let service =
-                    BackupService::from_config(config, BACKUP_BLUEPRINTS.clone(), vec![])?;
-                Some(Arc::new(service))
+                let service = BackupService::from_config(
+                    config,
+                    backup_blueprints(&app_config.prose_pod_api.dump_dir),
+                    BACKUP_MIGRATIONS.clone(),
+                )?;
+                Some(ArcSwap::from_pointee(service))
             }
             None => None,
         };
+        // NOTE: Must happen before Prosody starts, as an interrupted backup
+        //   restoration can leave its data half-restored.
+        let restore_recovery = match backup_service.as_ref() {
+            Some(service) => service
+                .load_full()
+                .recover_interrupted_restoration()
+                .await
+                .context("Could not recover interrupted backup restoration")?,
+            None => None,
+        };
+        if let Some(ref recovery) = restore_recovery {
+            tracing::warn!(
+                "Recovered interrupted restoration of backup `{backup_id}` ({outcome:?}).",
+                backup_id = recovery.backup_id,
+                outcome = recovery.outcome,
+            );
+        }
+        if let Some(service) = backup_service.as_ref() {
+            // NOTE: Not critical, a failure only means disk space is wasted.
+            if let Err(err) = service.load_full().purge_expired_safety_snapshot().await {
+                tracing::error!("Could not purge expired safety snapshot: {err:?}");
+            }
+            // NOTE: Not critical either, but stale snapshots can hold a full
+            //   copy of the data (or LVM copy-on-write space).
+            if let Err(err) = service.load_full().purge_stale_snapshots().await {
+                tracing::error!("Could not purge stale snapshots: {err:?}");
+            }
+        }
+        // NOTE: Prose Pod API data restored on startup must not be replaced
+        //   before it’s sent to the Prose Pod API, even after a restart.
+        let pending_prose_pod_api_restore = match backup_service.as_ref() {
+            Some(_) => PendingProsePodApiRestore::read(&app_config.prose_pod_api.dump_dir)
+                .context("Could not read pending Prose Pod API restoration")?,
+            None => None,
+        };
+        if let Some(ref pending) = pending_prose_pod_api_restore {
+            tracing::warn!(
+                "The Prose Pod API’s data from backup `{backup_id}` wasn’t sent to it yet.",
+                backup_id = pending.backup_id,
+            );
+        }
+        let pending_prose_pod_api_restore =
+            ArcSwapOption::from_pointee(pending_prose_pod_api_restore);
+
         let prose_pod_api = Arc::new(ProsePodApi {
             http_client: Arc::clone(&http_client),
             url: app_config.prose_pod_api.http_url(),
@@ -72,9 +123,15 @@ where
 
         let server_salt = generate_server_salt_if_needed()?;
 
-        backup_prosody_conf_if_needed()?;
+        let xmpp_server: Box<DynXmppServerBackend> =
+            Box::new(ProsodyXmppServer::new(app_config.server.http_url()));
 
-        apply_bootstrap_config(server_domain)?;
+        // NOTE: If Prosody is managed externally, so is its configuration file.
+        if app_config.server.process_manager == ProcessManagerConfig::External {
+            tracing::info!("Not writing Prosody’s bootstrap configuration: Managed externally.");
+        } else {
+            xmpp_server.apply_bootstrap_config(server_domain)?;
+        }
 
         // NOTE: While it’s here that we could delete the `localhost` data
         //   generated during a factory reset, it’s better to not do it to
@@ -87,17 +144,12 @@ where
         //   happens).
 
         // Launch Prosody.
-        let mut prosody = ProsodyChildProcess::new();
-        start_prosody(&app_state.frontend.as_ref().config, &mut prosody).await?;
+        let prosody = start_prosody(&app_state.frontend.as_ref().config).await?;
 
-        let mut prosodyctl = Prosodyctl::new();
-
-        prosodyctl.wait_for_readiness().await?;
+        xmpp_server.wait_for_readiness().await?;
 
         let cancellation_token = CancellationToken::new();
 
-        let prosody_rest = ProsodyRest::standard(app_config.server.http_url());
-
         let prosody_http_config = Arc::new(ProsodyHttpConfig {
             url: "http://prose-pod-server:5280".to_owned(),
         });
@@ -115,17 +167,12 @@ where
         // Run cache purge tasks in the background.
         tokio::spawn(secrets.run_purge_tasks(cancellation_token.child_token()));
 
-        let service_accounts = create_service_accounts(
-            app_config,
-            &mut prosodyctl,
-            &prosody_rest,
-            &oauth2_client,
-            &secrets,
-        )
-        .await?;
+        let service_accounts =
+            create_service_accounts(app_config, xmpp_server.as_ref(), &oauth2_client, &secrets)
+                .await?;
 
         let groups = Groups::new(app_config.as_ref());
-        create_groups(&mut prosodyctl, &groups, server_domain).await?;
+        create_groups(xmpp_server.as_ref(), &groups, server_domain).await?;
 
         {
             let service_accounts_usernames = service_accounts
@@ -133,7 +180,7 @@ where
                 .flat_map(|jid| jid.node().map(JidNode::from));
             let group_ids = groups.keys().into_iter();
             add_service_accounts_to_groups(
-                &mut prosodyctl,
+                xmpp_server.as_ref(),
                 service_accounts_usernames,
                 group_ids,
                 server_domain,
@@ -143,24 +190,28 @@ where
 
         {
             let group_ids = groups.keys().into_iter();
-            synchronize_rosters(&mut prosodyctl, group_ids, server_domain).await?;
+            synchronize_rosters(xmpp_server.as_ref(), group_ids, server_domain).await?;
         }
 
         let backend = b::Running {
             state: Arc::new(b::Operational {
                 prosody: Arc::new(RwLock::new(prosody)),
-                prosodyctl: Arc::new(RwLock::new(prosodyctl)),
-                prosody_rest,
+                xmpp_server,
                 oauth2_client,
                 secrets_service: secrets,
                 http_client,
                 server_salt,
                 backup_service,
+                backup_keys_lock: Default::default(),
+                restore_recovery,
+                pending_prose_pod_api_restore,
+                crash_history: app_state.crash_history(),
                 prose_pod_api,
                 cancellation_token: AutoCancelToken(cancellation_token),
             }),
         };
 
+        on_migrations();
         run_migrations(app_config, &backend).await?;
 
         Ok(backend)
@@ -171,21 +222,37 @@ where
     /// about bootstrapping.
     ///
     /// NOTE: This method does **not** log errors.
+    #[inline]
     pub(crate) async fn try_bootstrapping(
         self,
     ) -> Result<AppState<F, b::Running>, (Self, anyhow::Error)>
+    where
+        AppState<F, b::Running>: AppStateTrait,
+    {
+        self.try_bootstrapping_with(|| {}).await
+    }
+
+    /// Same as [`try_bootstrapping`](Self::try_bootstrapping), but calls
+    /// `on_migrations` once the backend started, right before migrations
+    /// run (e.g. to report backup restoration stages).
+    pub(crate) async fn try_bootstrapping_with(
+        self,
+        on_migrations: impl FnOnce() + Send,
+    ) -> Result<AppState<F, b::Running>, (Self, anyhow::Error)>
     where
         AppState<F, b::Running>: AppStateTrait,
     {
         tracing::info!("Bootstrapping…");
         let start = Instant::now();
 
-        match Self::bootstrap(&self).await {
+        match Self::bootstrap(&self, on_migrations).await {
             Ok(backend) => {
                 let new_state = self
                     .with_backend(backend)
                     .with_auto_transition::<F, b::Running>();
 
+                new_state.supervise_backend().await;
+
                 tracing::info!("Bootstrapping took {:.0?}.", start.elapsed());
                 Ok(new_state)
             }
@@ -219,7 +286,7 @@ where
                 // Log debug info.
                 tracing::error!("{error:?}");
 
-                Err(app_state.transition_failed(errors::internal_server_error(
+                Err(app_state.transition_failed(backend_start_error(
                     &error,
                     "START_FAILED",
                     "Something went wrong while starting your Prose Server. \
@@ -230,8 +297,142 @@ where
     }
 }
 
+/// Like [`errors::internal_server_error`], but with a distinct code (and
+/// description) when Prosody started but never became ready, saying which
+/// checks never passed.
+pub(crate) fn backend_start_error(
+    error: &anyhow::Error,
+    code: &'static str,
+    public_description: &str,
+) -> crate::responders::Error {
+    match error.downcast_ref::<NotReady>() {
+        Some(not_ready) => {
+            let pending = (not_ready.pending.iter())
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            errors::internal_server_error(
+                error,
+                "BACKEND_NOT_READY",
+                format!(
+                    "Your Prose Server did not become ready in time \
+                    (never passed: {pending}). \
+                    Contact an administrator to fix this."
+                ),
+            )
+        }
+        None => errors::internal_server_error(error, code, public_description),
+    }
+}
+
 // MARK: - Steps
 
+/// Directories which, if all empty, mean the Prose Pod has never started.
+const DATA_DIRS: [&'static str; 2] = [
+    SERVER_DATA_DIR,
+    "/var/lib/prosody",
+];
+
+/// Restores the most recent intact backup if `[backups.bootstrap]` is
+/// enabled and the Prose Pod has no data (e.g. when recovering from a
+/// disaster on new hardware).
+///
+/// NOTE: Must run before bootstrapping, only when the process starts
+///   (a factory reset would otherwise restore the latest backup).
+pub(crate) async fn restore_latest_backup_if_empty(
+    app_config: &AppConfig,
+) -> Result<(), anyhow::Error> {
+    use crate::router::backups::BACKUPS_VERSION;
+    use prose_backup::restore_report::RestoreReportDto;
+
+    let Some(config) = app_config.backups.as_ref() else {
+        return Ok(());
+    };
+    let bootstrap_config = &config.bootstrap;
+    if !bootstrap_config.enabled {
+        return Ok(());
+    }
+
+    let blueprints = backup_blueprints(&app_config.prose_pod_api.dump_dir);
+    let service = BackupService::from_config(config, blueprints, BACKUP_MIGRATIONS.clone())?;
+
+    // NOTE: An interrupted restoration (e.g. a previous disaster recovery)
+    //   leaves data behind, which would make the Prose Pod look non-empty.
+    if let Some(recovery) = (service.recover_interrupted_restoration().await)
+        .context("Could not recover interrupted backup restoration")?
+    {
+        tracing::warn!(
+            "Recovered interrupted restoration of backup `{backup_id}` ({outcome:?}).",
+            backup_id = recovery.backup_id,
+            outcome = recovery.outcome,
+        );
+    }
+
+    for dir in DATA_DIRS {
+        if !is_empty_dir(Path::new(dir))? {
+            tracing::debug!("Not restoring latest backup: <{dir}> is not empty.");
+            return Ok(());
+        }
+    }
+
+    tracing::info!("Data directories are empty, looking for a backup to restore…");
+    let backup_id = service
+        .latest_intact_backup(
+            bootstrap_config.prefix.as_deref(),
+            bootstrap_config.required_signer.as_ref(),
+        )
+        .await
+        .context("Could not find a backup to restore")?;
+    let Some(backup_id) = backup_id else {
+        tracing::warn!("No backup to restore, starting from scratch.");
+        return Ok(());
+    };
+
+    tracing::info!("Restoring backup `{backup_id}`…");
+    let start = Instant::now();
+
+    let blueprint = (service.archiving_context.blueprints.get(&BACKUPS_VERSION))
+        .expect("A blueprint should always exist for BACKUPS_VERSION");
+    // NOTE: Keep a report, like for restorations requested via the API.
+    let mut report = RestoreReportDto::new(&backup_id);
+    let res = service
+        .restore_backup(&backup_id, blueprint, &mut report)
+        .await;
+    report.finish(res.as_ref().err().map(|error| format!("{error:#}")));
+    if let Err(error) = service.save_restore_report(&report).await {
+        tracing::warn!("Could not save restore report: {error:?}");
+    }
+    res.context(format!("Could not restore backup `{backup_id}`"))?;
+
+    // NOTE: There is no Prose token at this point, the Prose Pod API’s data
+    //   stays staged until the next request which can send it (see
+    //   `PendingProsePodApiRestore`).
+    let pending = PendingProsePodApiRestore {
+        backup_id: backup_id.to_string(),
+    };
+    (pending.save(&app_config.prose_pod_api.dump_dir))
+        .context("Could not mark the Prose Pod API’s data as pending restoration")?;
+
+    tracing::warn!(
+        "Restored backup `{backup_id}` in {elapsed:.0?}. \
+        The Prose Pod API’s data was left in <{dump_dir}>, \
+        it will be sent to the Prose Pod API on its next backup request.",
+        elapsed = start.elapsed(),
+        dump_dir = app_config.prose_pod_api.dump_dir.display(),
+    );
+
+    Ok(())
+}
+
+fn is_empty_dir(path: &Path) -> Result<bool, anyhow::Error> {
+    match fs::read_dir(path) {
+        Ok(mut entries) => Ok(entries.next().is_none()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(true),
+        Err(err) => Err(anyhow::Error::new(err)
+            .context(format!("Could not read <{path}>", path = path.display()))),
+    }
+}
+
 fn create_required_dirs() -> Result<(), anyhow::Error> {
     fs::create_dir_all(PROSODY_CERTS_DIR).context(format!(
         "Could not create Prosody certs dir at <{path}>",
@@ -271,87 +472,16 @@ fn generate_server_salt_if_needed() -> Result<SecretSlice<u8>, anyhow::Error> {
     }
 }
 
-fn backup_prosody_conf_if_needed() -> Result<(), anyhow::Error> {
-    use std::fs::File;
-    use std::io::{self, Read as _};
-
-    let prosody_config_file_path = Path::new(PROSODY_CONFIG_FILE_PATH);
-
-    // Back up the Prosody configuration if it was not generated by Prose.
-    // This is just to avoid a bad surprise to anyone deploying Prose on an
-    // existing Prosody instance.
-    match File::options().read(true).open(prosody_config_file_path) {
-        Ok(mut prosody_config_file) => {
-            let prose_header = "-- Prose Pod Server";
-            let mut buffer = vec![0u8; prose_header.len()];
-
-            // Read the first few bytes to check the header.
-            let bytes_read = prosody_config_file
-                .read(&mut buffer)
-                .context("Error reading Prosody config file")?;
-            buffer.truncate(bytes_read);
-
-            if buffer != prose_header.as_bytes() {
-                let mut new_path = prosody_config_file_path.to_path_buf();
-                let unix_timestamp = unix_timestamp();
-                new_path.set_file_name(format!("prosody.prose-backup-{unix_timestamp}.cfg.lua"));
-
-                tracing::info!(
-                    "The Prosody configuration file at <{old_path}> was not generated by Prose. \
-                    To prevent data loss, it will be backed up as <{new_path}>.",
-                    old_path = prosody_config_file_path.display(),
-                    new_path = new_path.display(),
-                )
-            }
-
-            Ok(())
-        }
-        Err(err) if err.kind() == io::ErrorKind::NotFound => {
-            // Prosody config file does not exist already, nothing to back up.
-            Ok(())
-        }
-        Err(err) => Err(anyhow::Error::new(err).context(format!(
-            "Error opening <{path}>",
-            path = prosody_config_file_path.display(),
-        ))),
-    }
-}
-
-fn apply_bootstrap_config(server_domain: &JidDomain) -> Result<(), anyhow::Error> {
-    use std::fs::File;
-    use std::io::Write as _;
-
-    let mut prosody_config_file = File::options()
-        .write(true)
-        .truncate(true)
-        .create(true)
-        .open(PROSODY_CONFIG_FILE_PATH)
-        .context("Error opening Prosody config file")?;
-
-    let bootstrap_config_template = include_str!("prosody-bootstrap.cfg.lua");
-
-    let bootstrap_config = bootstrap_config_template.replace("{{server_domain}}", server_domain);
-
-    prosody_config_file
-        .write_all(bootstrap_config.as_bytes())
-        .context("Error writing Prosody config file")?;
-
-    Ok(())
-}
-
+/// Starts Prosody using the process manager selected in
+/// `server.process_manager`.
 async fn start_prosody(
     app_config: &AppConfig,
-    prosody: &mut ProsodyChildProcess,
-) -> Result<(), anyhow::Error> {
-    use secrecy::ExposeSecret as _;
+) -> Result<Box<DynBackendProcessManager>, anyhow::Error> {
+    let mut prosody = process_manager::from_config(app_config);
 
-    prosody.set_env(
-        "OAUTH2_REGISTRATION_KEY",
-        app_config.auth.oauth2_registration_key.expose_secret(),
-    );
     prosody.start().await.context("Failed starting Prosody")?;
 
-    Ok(())
+    Ok(prosody)
 }
 
 async fn register_oauth2_client(
@@ -387,8 +517,7 @@ pub(crate) const SERVICE_ACCOUNT_ROLE: &'static str = "prosody:registered";
 /// Creates the “prose-workspace” user for now, maybe more later.
 async fn create_service_accounts(
     app_config: &AppConfig,
-    prosodyctl: &mut Prosodyctl,
-    prosody_rest: &ProsodyRest,
+    xmpp_server: &DynXmppServerBackend,
     oauth2: &ProsodyOAuth2,
     secrets: &SecretsService,
 ) -> Result<Vec<BareJid>, anyhow::Error> {
@@ -417,20 +546,15 @@ async fn create_service_accounts(
         };
 
         // Create the account if needed, or update password.
-        if prosodyctl.user_exists(username, jid.domain()).await? {
+        if xmpp_server.user_exists(jid).await? {
             tracing::debug!("Setting user `{jid}` password…");
-            let summary = prosodyctl.user_password(jid.as_str(), &password).await?;
-            tracing::info!("user_password: {summary}");
+            xmpp_server.set_user_password(jid, &password).await?;
 
             tracing::debug!("Setting user `{jid}` role…");
-            let summary = prosodyctl.user_set_role(jid.as_str(), None, role).await?;
-            tracing::info!("user_set_role: {summary}");
+            xmpp_server.set_user_role(jid, role).await?;
         } else {
             tracing::debug!("Creating user `{jid}`…");
-            let summary = prosodyctl
-                .user_create(jid.as_str(), &password, Some(role))
-                .await?;
-            tracing::info!("user_create: {summary}");
+            xmpp_server.create_user(jid, &password, Some(role)).await?;
         };
 
         // Store the password in the secrets store for later use.
@@ -467,14 +591,14 @@ async fn create_service_accounts(
         );
 
         // Create vCard if necessary.
-        let creds = prosody_rest::CallerCredentials {
+        let creds = CallerCredentials {
             bare_jid: jid.to_owned(),
             auth_token: token.clone(),
         };
         {
             tracing::debug!("Getting vCard for `{jid}`…");
-            let vcard_opt = prosody_rest
-                .get_own_vcard(&creds)
+            let vcard_opt = xmpp_server
+                .get_vcard(jid, &creds)
                 .await
                 .context(format!("Error getting vCard for `{jid}`"))?;
             if vcard_opt.is_none() {
@@ -488,7 +612,7 @@ async fn create_service_accounts(
                     ..Default::default()
                 };
                 tracing::debug!("Creating vCard for `{jid}`…");
-                prosody_rest
+                xmpp_server
                     .set_own_vcard(vcard, &creds)
                     .await
                     .context(format!("Error creating vCard for `{jid}`"))?;
@@ -504,19 +628,16 @@ async fn create_service_accounts(
 
 /// Creates the “Team” group for now, maybe more later.
 async fn create_groups(
-    prosodyctl: &mut Prosodyctl,
+    xmpp_server: &DynXmppServerBackend,
     groups: &Groups,
-    server_domain: &JidDomain,
+    host: &JidDomain,
 ) -> Result<(), anyhow::Error> {
-    let host: &str = server_domain.as_str();
-
     for (group_id, group_info) in groups.iter() {
-        if !prosodyctl.groups_exists(host, group_id).await? {
+        if !xmpp_server.group_exists(host, group_id).await? {
             tracing::debug!("Creating group `{group_id}` on host `{host}`…");
-            let summary = prosodyctl
-                .groups_create(host, &group_info.name, None, Some(group_id))
+            xmpp_server
+                .create_group(host, group_id, &group_info.name)
                 .await?;
-            tracing::info!("groups_create: {summary}");
         }
     }
 
@@ -529,24 +650,21 @@ async fn create_groups(
 ///   required for them to receive Workspace icon/accent color updates
 ///   (and all future PEP-based features).
 async fn add_service_accounts_to_groups<'a, A, G>(
-    prosodyctl: &mut Prosodyctl,
+    xmpp_server: &DynXmppServerBackend,
     service_accounts: A,
     group_ids: G,
-    server_domain: &JidDomain,
+    host: &JidDomain,
 ) -> Result<(), anyhow::Error>
 where
     A: Iterator<Item = JidNode>,
     G: Iterator<Item = &'a String> + Clone,
 {
-    let host: &str = server_domain.as_str();
-
     for ref username in service_accounts {
         for group_id in group_ids.clone() {
             tracing::debug!("Adding `{username}` to group `{group_id}`…");
-            let summary = prosodyctl
-                .groups_add_member(host, group_id, username, Some(true))
+            xmpp_server
+                .add_group_member(host, group_id, username, true)
                 .await?;
-            tracing::info!("groups_add_member: {summary}");
         }
     }
 
@@ -564,19 +682,16 @@ where
 /// NOTE: Rosters resynchronization (for groups) is an expensive operation
 ///   (`O(n^2)`), so it might take a while.
 async fn synchronize_rosters<'a, G>(
-    prosodyctl: &mut Prosodyctl,
+    xmpp_server: &DynXmppServerBackend,
     group_ids: G,
-    server_domain: &JidDomain,
+    host: &JidDomain,
 ) -> Result<(), anyhow::Error>
 where
     G: Iterator<Item = &'a String>,
 {
-    let host: &str = server_domain.as_str();
-
     for group_id in group_ids {
         tracing::debug!("Synchronizing groups…");
-        let summary = prosodyctl.groups_sync(host, group_id).await?;
-        tracing::info!("groups_sync: {summary}");
+        xmpp_server.sync_group(host, group_id).await?;
     }
 
     Ok(())
@@ -586,10 +701,7 @@ async fn run_migrations(
     app_config: &AppConfig,
     backend: &backend::Running,
 ) -> Result<(), anyhow::Error> {
-    let prosody_data_dir = {
-        let mut prosodyctl = backend.prosodyctl.write().await;
-        prosodyctl.prosody_paths_data().await?
-    };
+    let prosody_data_dir = backend.xmpp_server.data_dir().await?;
 
     // Delete foundations of the previous architecture.
     {
@@ -677,3 +789,52 @@ impl std::ops::Deref for Groups {
         &self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr as _;
+
+    use crate::xmpp_server::in_memory::InMemoryXmppServer;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_groups_setup() {
+        let xmpp_server = InMemoryXmppServer::default();
+        let ref domain = JidDomain::from_str("example.org").unwrap();
+        let groups = Groups(HashMap::from([(
+            "team".to_owned(),
+            GroupInfo {
+                name: "Team".to_owned(),
+            },
+        )]));
+        let service_accounts = [JidNode::from_str("prose-workspace").unwrap()];
+
+        // NOTE: Runs at every startup, so it has to be idempotent.
+        for _ in 0..2 {
+            create_groups(&xmpp_server, &groups, domain).await.unwrap();
+            add_service_accounts_to_groups(
+                &xmpp_server,
+                service_accounts.iter().cloned(),
+                groups.keys(),
+                domain,
+            )
+            .await
+            .unwrap();
+
+            let key = ("example.org".to_owned(), "team".to_owned());
+            xmpp_server.with_state(|state| {
+                let group = &state.groups[&key];
+                assert_eq!(group.name, "Team");
+                assert!(group.members.contains("prose-workspace"));
+                assert!(group.needs_sync);
+            });
+
+            synchronize_rosters(&xmpp_server, groups.keys(), domain)
+                .await
+                .unwrap();
+
+            xmpp_server.with_state(|state| assert!(!state.groups[&key].needs_sync));
+        }
+    }
+}
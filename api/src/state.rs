@@ -13,10 +13,12 @@ use std::sync::{Arc, Weak};
 
 use arc_swap::ArcSwapOption;
 use axum_hot_swappable_router::HotSwappableRouter;
-use prosody_child_process::ProsodyChildProcess;
 use tokio::sync::RwLock;
 
 use crate::AppConfig;
+use crate::listener::ListenerHandle;
+use crate::process_manager::DynBackendProcessManager;
+use crate::supervisor::CrashHistory;
 
 /// “App state“ of the global immutable `axum::Router`.
 ///
@@ -31,7 +33,9 @@ use crate::AppConfig;
 #[derive(Clone)]
 pub struct AppContext {
     router: HotSwappableRouter,
-    prosody: Arc<ArcSwapOption<Weak<RwLock<ProsodyChildProcess>>>>,
+    listener: ListenerHandle,
+    prosody: Arc<ArcSwapOption<Weak<RwLock<Box<DynBackendProcessManager>>>>>,
+    crash_history: Arc<CrashHistory>,
 }
 
 impl Drop for AppContext {
@@ -46,10 +50,12 @@ impl Drop for AppContext {
 
 impl AppContext {
     #[inline(always)]
-    pub fn new() -> Self {
+    pub fn new(listener: ListenerHandle) -> Self {
         Self {
             router: HotSwappableRouter::default(),
+            listener,
             prosody: Arc::default(),
+            crash_history: Arc::default(),
         }
     }
 
@@ -93,10 +99,20 @@ impl AppContext {
         self.router.clone()
     }
 
+    /// Moves the HTTP API to another address (see [`ListenerHandle::rebind`]).
+    #[inline(always)]
+    pub(crate) fn listener(&self) -> &ListenerHandle {
+        &self.listener
+    }
+
     pub async fn cleanup(&self) -> Result<(), anyhow::Error> {
         match self.prosody.load().as_deref().map(Weak::upgrade) {
             Some(Some(prosody)) => {
-                prosody.write().await.stop().await?;
+                let mut prosody = prosody.write().await;
+                // NOTE: Prosody keeps running if it’s managed externally.
+                if prosody.can_stop() {
+                    prosody.stop().await?;
+                }
             }
             _ => {}
         }
@@ -134,9 +150,16 @@ impl<F: frontend::State, B: backend::State> AppState<F, B> {
     }
 
     #[inline(always)]
-    fn context(&self) -> Option<Arc<AppContext>> {
+    pub(crate) fn context(&self) -> Option<Arc<AppContext>> {
         self.app_context.upgrade()
     }
+
+    /// Crashes of Prosody since the app started (see [`crate::supervisor`]).
+    #[inline]
+    pub(crate) fn crash_history(&self) -> Arc<CrashHistory> {
+        let app_context = self.context().expect(STATIC_APP_CONTEXT);
+        Arc::clone(&app_context.crash_history)
+    }
 }
 
 impl<F1: frontend::State, B1: backend::State> AppState<F1, B1> {
@@ -168,7 +191,7 @@ pub trait AppStateTrait {
 
     fn validate_config_changes(&self, new_config: &AppConfig) -> Result<(), anyhow::Error>;
 
-    fn prosody_weak(&self) -> Option<Weak<RwLock<ProsodyChildProcess>>>;
+    fn prosody_weak(&self) -> Option<Weak<RwLock<Box<DynBackendProcessManager>>>>;
 }
 
 /// NOTE:
@@ -325,6 +348,14 @@ pub mod frontend {
         }
     }
 
+    /// NOTE: Used when the backend crashes, the frontend keeps running
+    ///   with the configuration it had.
+    impl From<(FrontendRunningWithMisconfiguration, ())> for FrontendRunning {
+        fn from((state, _): (FrontendRunningWithMisconfiguration, ())) -> Self {
+            state.inner
+        }
+    }
+
     impl From<FrontendRunning> for FrontendRestarting {
         fn from(state: FrontendRunning) -> Self {
             Self {
@@ -354,9 +385,10 @@ pub mod backend {
     pub mod prelude {
         pub use super::substates::*;
         pub use super::{
-            BackendRestartFailed as RestartFailed, BackendRestarting as Restarting,
-            BackendRunning as Running, BackendStartFailed as StartFailed,
-            BackendStarting as Starting, BackendStateTrait as State, BackendStopped as Stopped,
+            BackendCrashed as Crashed, BackendRestartFailed as RestartFailed,
+            BackendRestarting as Restarting, BackendRunning as Running,
+            BackendStartFailed as StartFailed, BackendStarting as Starting,
+            BackendStateTrait as State, BackendStopped as Stopped,
             BackendUndergoingBackup as UndergoingBackup,
             BackendUndergoingFactoryReset as UndergoingFactoryReset,
             BackendUndergoingRestore as UndergoingRestore,
@@ -364,14 +396,15 @@ pub mod backend {
     }
 
     use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
 
-    use prosody_child_process::ProsodyChildProcess;
     use prosody_http::mod_http_oauth2::ProsodyOAuth2;
-    use prosody_rest::ProsodyRest;
-    use prosodyctl::Prosodyctl;
     use tokio::sync::RwLock;
 
+    use crate::process_manager::DynBackendProcessManager;
     use crate::secrets_service::SecretsService;
+    use crate::supervisor::CrashHistory;
+    use crate::xmpp_server::DynXmppServerBackend;
 
     use super::{StateTrait, macros::*};
 
@@ -390,14 +423,27 @@ pub mod backend {
 
         #[derive(Debug)]
         pub struct Operational {
-            pub prosody: Arc<RwLock<ProsodyChildProcess>>,
-            pub prosodyctl: Arc<RwLock<Prosodyctl>>,
-            pub prosody_rest: ProsodyRest,
+            pub prosody: Arc<RwLock<Box<DynBackendProcessManager>>>,
+            pub xmpp_server: Box<DynXmppServerBackend>,
             pub oauth2_client: Arc<ProsodyOAuth2>,
             pub secrets_service: SecretsService,
             pub server_salt: secrecy::SecretSlice<u8>,
             pub http_client: Arc<reqwest::Client>,
-            pub backup_service: Option<Arc<prose_backup::BackupService>>,
+            /// NOTE: Swapped when backup keys change (see
+            ///   [`crate::router::backups::reload_backup_service`]).
+            pub backup_service: Option<arc_swap::ArcSwap<prose_backup::BackupService>>,
+            /// Held while changing backup keys, so changes (and reloads of
+            /// the backup service) don’t happen concurrently.
+            pub backup_keys_lock: tokio::sync::Mutex<()>,
+            /// Set if an interrupted backup restoration had to be recovered
+            /// before the backend started.
+            pub restore_recovery: Option<prose_backup::journal::RestoreRecovery>,
+            /// Set if Prose Pod API data was restored on startup but not sent
+            /// to the Prose Pod API yet.
+            pub pending_prose_pod_api_restore:
+                arc_swap::ArcSwapOption<crate::router::backups::PendingProsePodApiRestore>,
+            /// Shared with the app context, so it survives restarts.
+            pub crash_history: Arc<CrashHistory>,
             pub prose_pod_api: Arc<ProsePodApi>,
             #[allow(dead_code)]
             pub cancellation_token: AutoCancelToken,
@@ -406,14 +452,15 @@ pub mod backend {
         impl Operational {
             pub fn backup_service(
                 &self,
-            ) -> Result<&Arc<prose_backup::BackupService>, crate::responders::Error> {
-                self.backup_service.as_ref().ok_or_else(|| {
-                    crate::errors::configuration_error(
+            ) -> Result<Arc<prose_backup::BackupService>, crate::responders::Error> {
+                match self.backup_service.as_ref() {
+                    Some(backup_service) => Ok(backup_service.load_full()),
+                    None => Err(crate::errors::configuration_error(
                         "MISSING_CONFIG",
                         "Missing configuration",
                         "Backups configuration not initialized.",
-                    )
-                })
+                    )),
+                }
             }
         }
     }
@@ -449,6 +496,32 @@ pub mod backend {
 
     impl BackendStateTrait for BackendRunning {}
 
+    // MARK: Crashed
+
+    /// Prosody exited unexpectedly (see [`crate::supervisor`]).
+    #[derive(Debug, Clone)]
+    pub struct BackendCrashed {
+        pub crash_history: Arc<CrashHistory>,
+        /// When Prosody will be restarted automatically, `None` if it won’t
+        /// (automatic restarts disabled or crash loop).
+        pub restart_at: Option<time::OffsetDateTime>,
+        /// Set once a restart starts (automatic or not),
+        /// so only one happens.
+        pub restart_claimed: Arc<AtomicBool>,
+    }
+
+    state_boilerplate!(BackendCrashed);
+
+    impl BackendStateTrait for BackendCrashed {}
+
+    impl BackendCrashed {
+        /// Returns `false` if a restart already started.
+        #[must_use]
+        pub fn claim_restart(&self) -> bool {
+            !self.restart_claimed.swap(true, Ordering::SeqCst)
+        }
+    }
+
     // MARK: Restarting
 
     #[derive(Debug, Clone, Default)]
@@ -513,6 +586,15 @@ pub mod backend {
 
     impl_trivial_transition!(BackendRestartFailed => default BackendRestarting);
 
+    impl From<(BackendRunning, BackendCrashed)> for BackendCrashed {
+        #[inline(always)]
+        fn from((_, crashed): (BackendRunning, BackendCrashed)) -> Self {
+            crashed
+        }
+    }
+
+    impl_trivial_transition!(BackendCrashed => default BackendRestarting);
+
     impl_trivial_transition!(BackendStopped => default BackendStarting);
     impl_trivial_transition!(BackendStopped => default BackendRestarting);
     impl_fail_state_from_pair!((BackendStopped, &'a crate::responders::Error) use left);
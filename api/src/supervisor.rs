@@ -0,0 +1,331 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+//! Supervision of Prosody.
+//!
+//! While the backend is running, a task waits for Prosody to exit. If it
+//! exits without being stopped (i.e. it crashed), the crash is recorded and
+//! reported through `POST /lifecycle/backend-crashed` (on the current
+//! router, like [`AppContext::reload`]), which moves the backend into the
+//! `Crashed` state and restarts it after a backoff delay (see
+//! [`AutoRestartConfig`]).
+//!
+//! NOTE: Only some states accept crash reports (e.g. not during a backup),
+//!   so the report is retried until one does.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context as _, anyhow};
+use axum::http::StatusCode;
+use axum_hot_swappable_router::HotSwappableRouter;
+use tokio::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+use crate::app_config::AutoRestartConfig;
+use crate::state::prelude::*;
+
+// MARK: Crash history
+
+/// Crashes of Prosody since the Server API started (most recent last).
+///
+/// NOTE: Kept in the [`AppContext`] so it survives backend restarts.
+#[derive(Debug, Default)]
+pub(crate) struct CrashHistory {
+    crashes: Mutex<VecDeque<CrashDto>>,
+}
+
+#[derive(Debug, Clone)]
+#[derive(serde::Serialize)]
+pub(crate) struct CrashDto {
+    /// UTC timestamp at which the crash was noticed.
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: time::OffsetDateTime,
+
+    /// Exit code, `None` if Prosody was terminated by a signal.
+    pub exit_code: Option<i32>,
+
+    /// Signal which terminated Prosody, if any.
+    pub signal: Option<i32>,
+}
+
+impl CrashHistory {
+    /// NOTE: Old crashes don’t matter much, and this prevents the history
+    ///   from growing forever.
+    const MAX_LEN: usize = 32;
+
+    pub(crate) fn record(&self, exit: prosody_child_process::ExitInfo) {
+        let mut crashes = self.crashes.lock().unwrap();
+
+        if crashes.len() >= Self::MAX_LEN {
+            crashes.pop_front();
+        }
+        crashes.push_back(CrashDto {
+            timestamp: time::OffsetDateTime::now_utc(),
+            exit_code: exit.code,
+            signal: exit.signal,
+        });
+    }
+
+    pub(crate) fn crashes(&self) -> Vec<CrashDto> {
+        self.crashes.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Number of crashes in the last `window`.
+    pub(crate) fn recent_crash_count(&self, window: Duration) -> u32 {
+        let since = time::OffsetDateTime::now_utc() - window;
+
+        let crashes = self.crashes.lock().unwrap();
+        (crashes.iter())
+            .filter(|crash| crash.timestamp >= since)
+            .count() as u32
+    }
+}
+
+// MARK: Restart policy
+
+/// How long to wait before restarting Prosody after its `recent_crashes`-th
+/// crash in [`AutoRestartConfig::crash_loop_window`], `None` if it must not
+/// be restarted automatically (disabled or crash loop).
+pub(crate) fn restart_delay(config: &AutoRestartConfig, recent_crashes: u32) -> Option<Duration> {
+    if !config.enabled || recent_crashes > config.crash_loop_limit {
+        return None;
+    }
+
+    // NOTE: Exponential backoff (1st crash -> `initial_backoff`,
+    //   2nd -> 2 × `initial_backoff`…).
+    let exponent = recent_crashes.saturating_sub(1).min(31);
+    let delay = (config.initial_backoff)
+        .checked_mul(1 << exponent)
+        .unwrap_or(config.max_backoff);
+
+    Some(delay.min(config.max_backoff))
+}
+
+// MARK: Supervisor
+
+impl<F: frontend::State> AppState<F, b::Running> {
+    /// Spawns a task which reports if Prosody exits unexpectedly.
+    ///
+    /// NOTE: The task stops with the backend (and ignores Prosody
+    ///   exiting because it was stopped on purpose).
+    pub(crate) async fn supervise_backend(&self) {
+        let Some(exit_watcher) = self.backend.prosody.read().await.exit_watcher() else {
+            // NOTE: Some process managers (e.g. systemd) restart Prosody
+            //   by themselves.
+            tracing::warn!("Not supervising Prosody: Not started or supervised elsewhere.");
+            return;
+        };
+        let cancellation_token = self.backend.cancellation_token.token().child_token();
+        let crash_history = Arc::clone(&self.backend.crash_history);
+        let Some(app_context) = self.context() else {
+            tracing::warn!("Not supervising Prosody: No app context.");
+            return;
+        };
+        let router = app_context.router();
+
+        tokio::task::spawn(async move {
+            let exit = tokio::select! {
+                () = cancellation_token.cancelled() => return,
+                exit = exit_watcher.unexpected_exit() => exit,
+            };
+            let Some(exit) = exit else {
+                // Prosody was stopped on purpose.
+                return;
+            };
+
+            tracing::error!("Prosody exited unexpectedly ({exit}).");
+            crash_history.record(exit);
+
+            report_crash_until_accepted(router, cancellation_token, CRASH_REPORT_RETRY_INTERVAL)
+                .await;
+        });
+    }
+}
+
+/// How long to wait before reporting a crash again, if the current state
+/// didn’t accept it.
+const CRASH_REPORT_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Reports a crash until the current state accepts it.
+///
+/// NOTE: Stops when `cancellation_token` is cancelled (i.e. the backend
+///   was replaced, and so was its supervisor) or if Prosody is running
+///   again (e.g. restarted by an administrator in the meantime).
+async fn report_crash_until_accepted(
+    router: HotSwappableRouter,
+    cancellation_token: CancellationToken,
+    retry_interval: Duration,
+) {
+    let mut attempts: u32 = 0;
+    loop {
+        attempts += 1;
+
+        match report_crash(router.clone()).await {
+            Ok(()) => return,
+
+            Err((StatusCode::CONFLICT, err)) => {
+                tracing::info!("Not reporting Prosody crash anymore: {err:#}");
+                return;
+            }
+
+            // NOTE: Logged once, to avoid flooding logs if the current
+            //   state lasts (e.g. a long backup restoration).
+            Err((_, err)) if attempts == 1 => {
+                tracing::warn!("{err:#}. Retrying every {retry_interval:?}…");
+            }
+            Err((_, err)) => {
+                tracing::debug!("{err:#} (attempt {attempts}).");
+            }
+        }
+
+        tokio::select! {
+            () = cancellation_token.cancelled() => return,
+            () = tokio::time::sleep(retry_interval) => {}
+        }
+    }
+}
+
+async fn report_crash(router: HotSwappableRouter) -> Result<(), (StatusCode, anyhow::Error)> {
+    use tower::ServiceExt as _;
+
+    let request = axum::http::Request::builder()
+        .method("POST")
+        .uri("/lifecycle/backend-crashed")
+        .body(axum::body::Body::empty())
+        .unwrap();
+
+    let response = router
+        .oneshot(request)
+        .await
+        .unwrap_or_else(|err| match err {});
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), 64 * 1024)
+            .await
+            .context("Could not read HTTP response body bytes")
+            .map_err(|err| (status, err))?;
+
+        Err((
+            status,
+            anyhow!(
+                "Could not report Prosody crash ({status}): {body}",
+                body = String::from_utf8_lossy(&bytes),
+            ),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use axum::routing::post;
+
+    use super::*;
+
+    #[test]
+    fn test_restart_delay() {
+        let config = AutoRestartConfig {
+            enabled: true,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5),
+            crash_loop_limit: 4,
+            crash_loop_window: Duration::from_secs(600),
+        };
+
+        let delays = (1..=5)
+            .map(|crashes| restart_delay(&config, crashes))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            delays,
+            vec![
+                Some(Duration::from_secs(1)),
+                Some(Duration::from_secs(2)),
+                Some(Duration::from_secs(4)),
+                Some(Duration::from_secs(5)),
+                None,
+            ]
+        );
+
+        let config = AutoRestartConfig {
+            enabled: false,
+            ..config
+        };
+        assert_eq!(restart_delay(&config, 1), None);
+    }
+
+    /// Prosody crashes while the current state doesn’t accept crash reports
+    /// (e.g. `UndergoingRestore`), then the app goes back to `Running`.
+    #[tokio::test]
+    async fn test_crash_reported_once_accepted() {
+        let router = HotSwappableRouter::default();
+        router.set(axum::Router::new().fallback(|| async { StatusCode::SERVICE_UNAVAILABLE }));
+
+        let reports = Arc::new(AtomicU32::new(0));
+        let task = tokio::task::spawn(report_crash_until_accepted(
+            router.clone(),
+            CancellationToken::new(),
+            Duration::from_millis(10),
+        ));
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!task.is_finished());
+
+        router.set(axum::Router::new().route(
+            "/lifecycle/backend-crashed",
+            post({
+                let reports = Arc::clone(&reports);
+                || async move {
+                    reports.fetch_add(1, Ordering::Relaxed);
+                }
+            }),
+        ));
+
+        tokio::time::timeout(Duration::from_secs(1), task)
+            .await
+            .expect("Crash should be reported")
+            .unwrap();
+        assert_eq!(reports.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_crash_report_stops() {
+        // Prosody is running again.
+        let router = HotSwappableRouter::default();
+        router.set(axum::Router::new().route(
+            "/lifecycle/backend-crashed",
+            post(|| async { StatusCode::CONFLICT }),
+        ));
+        tokio::time::timeout(
+            Duration::from_secs(1),
+            report_crash_until_accepted(
+                router,
+                CancellationToken::new(),
+                Duration::from_millis(10),
+            ),
+        )
+        .await
+        .expect("Crash report should stop on conflict");
+
+        // The backend was replaced.
+        let router = HotSwappableRouter::default();
+        router.set(axum::Router::new().fallback(|| async { StatusCode::SERVICE_UNAVAILABLE }));
+        let cancellation_token = CancellationToken::new();
+        let task = tokio::task::spawn(report_crash_until_accepted(
+            router,
+            cancellation_token.clone(),
+            Duration::from_millis(10),
+        ));
+        cancellation_token.cancel();
+        tokio::time::timeout(Duration::from_secs(1), task)
+            .await
+            .expect("Crash report should stop once cancelled")
+            .unwrap();
+    }
+}
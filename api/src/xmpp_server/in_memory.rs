@@ -0,0 +1,259 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+
+use anyhow::anyhow;
+use prosody_rest::prose_xmpp::mods::AvatarData;
+use prosody_rest::prose_xmpp::stanza::VCard4;
+
+use crate::models::{BareJid, JidDomain, JidNode, Password};
+
+use super::{CallerCredentials, UserCreateError, XmppServerBackend};
+
+/// In-memory XMPP server, for tests.
+///
+/// NOTE: Credentials are not checked.
+#[derive(Debug, Default)]
+pub(crate) struct InMemoryXmppServer {
+    state: Mutex<InMemoryState>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct InMemoryState {
+    /// Primary role of each user.
+    pub users: HashMap<BareJid, String>,
+
+    /// Groups, by host then group ID.
+    pub groups: HashMap<(String, String), Group>,
+
+    pub invites: HashMap<String, usize>,
+
+    pub vcards: HashMap<BareJid, VCard4>,
+
+    pub avatars: HashMap<BareJid, Box<[u8]>>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Group {
+    pub name: String,
+
+    pub members: BTreeSet<String>,
+
+    /// Whether members were added since the last
+    /// [`sync_group`](XmppServerBackend::sync_group).
+    pub needs_sync: bool,
+}
+
+impl InMemoryXmppServer {
+    /// Inspects or modifies the server’s data.
+    pub(crate) fn with_state<T>(&self, f: impl FnOnce(&mut InMemoryState) -> T) -> T {
+        f(&mut self.state.lock().unwrap())
+    }
+}
+
+#[async_trait::async_trait]
+impl XmppServerBackend for InMemoryXmppServer {
+    // MARK: Health
+
+    async fn wait_for_readiness(&self) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    // MARK: Configuration
+
+    fn apply_bootstrap_config(&self, _server_domain: &JidDomain) -> Result<(), anyhow::Error> {
+        // NOTE: Nothing to configure.
+        Ok(())
+    }
+
+    async fn load_host_modules(&self, _host: &JidDomain) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    async fn config_dir(&self) -> Result<String, anyhow::Error> {
+        Err(anyhow!("In-memory XMPP server has no config directory."))
+    }
+
+    async fn data_dir(&self) -> Result<String, anyhow::Error> {
+        Err(anyhow!("In-memory XMPP server has no data directory."))
+    }
+
+    // MARK: Users
+
+    async fn user_exists(&self, jid: &BareJid) -> Result<bool, anyhow::Error> {
+        Ok(self.with_state(|state| state.users.contains_key(jid)))
+    }
+
+    async fn create_user(
+        &self,
+        jid: &BareJid,
+        _password: &Password,
+        role: Option<&str>,
+    ) -> Result<(), UserCreateError> {
+        self.with_state(|state| {
+            if state.users.contains_key(jid) {
+                return Err(UserCreateError::Conflict);
+            }
+
+            let role = role.unwrap_or("prosody:member").to_owned();
+            state.users.insert(jid.to_owned(), role);
+
+            Ok(())
+        })
+    }
+
+    async fn set_user_password(
+        &self,
+        jid: &BareJid,
+        _password: &Password,
+    ) -> Result<(), anyhow::Error> {
+        match self.user_exists(jid).await? {
+            true => Ok(()),
+            false => Err(anyhow!("User `{jid}` does not exist.")),
+        }
+    }
+
+    // MARK: Roles
+
+    async fn user_role(&self, jid: &BareJid) -> Result<String, anyhow::Error> {
+        self.with_state(|state| state.users.get(jid).cloned())
+            .ok_or(anyhow!("User `{jid}` does not exist."))
+    }
+
+    async fn set_user_role(&self, jid: &BareJid, role: &str) -> Result<(), anyhow::Error> {
+        self.with_state(|state| match state.users.get_mut(jid) {
+            Some(user_role) => {
+                *user_role = role.to_owned();
+                Ok(())
+            }
+            None => Err(anyhow!("User `{jid}` does not exist.")),
+        })
+    }
+
+    async fn users_with_role(
+        &self,
+        host: &JidDomain,
+        role: &str,
+    ) -> Result<Vec<BareJid>, anyhow::Error> {
+        Ok(self.with_state(|state| {
+            (state.users.iter())
+                .filter(|(jid, user_role)| {
+                    jid.domain().as_str() == host.as_str() && user_role.as_str() == role
+                })
+                .map(|(jid, _)| jid.to_owned())
+                .collect()
+        }))
+    }
+
+    // MARK: Groups
+
+    async fn group_exists(&self, host: &JidDomain, group_id: &str) -> Result<bool, anyhow::Error> {
+        let key = (host.to_string(), group_id.to_owned());
+        Ok(self.with_state(|state| state.groups.contains_key(&key)))
+    }
+
+    async fn create_group(
+        &self,
+        host: &JidDomain,
+        group_id: &str,
+        group_name: &str,
+    ) -> Result<(), anyhow::Error> {
+        let key = (host.to_string(), group_id.to_owned());
+        self.with_state(|state| {
+            if state.groups.contains_key(&key) {
+                return Err(anyhow!("Group `{group_id}` already exists."));
+            }
+
+            let group = Group {
+                name: group_name.to_owned(),
+                ..Default::default()
+            };
+            state.groups.insert(key, group);
+
+            Ok(())
+        })
+    }
+
+    async fn add_group_member(
+        &self,
+        host: &JidDomain,
+        group_id: &str,
+        username: &JidNode,
+        delay_update: bool,
+    ) -> Result<(), anyhow::Error> {
+        let key = (host.to_string(), group_id.to_owned());
+        self.with_state(|state| match state.groups.get_mut(&key) {
+            Some(group) => {
+                group.members.insert(username.to_string());
+                group.needs_sync |= delay_update;
+                Ok(())
+            }
+            None => Err(anyhow!("Group `{group_id}` does not exist.")),
+        })
+    }
+
+    async fn sync_group(&self, host: &JidDomain, group_id: &str) -> Result<(), anyhow::Error> {
+        let key = (host.to_string(), group_id.to_owned());
+        self.with_state(|state| match state.groups.get_mut(&key) {
+            Some(group) => {
+                group.needs_sync = false;
+                Ok(())
+            }
+            None => Err(anyhow!("Group `{group_id}` does not exist.")),
+        })
+    }
+
+    // MARK: Invites
+
+    async fn invite_count(&self, host: &JidDomain) -> Result<usize, anyhow::Error> {
+        Ok(self.with_state(|state| {
+            state
+                .invites
+                .get(host.as_str())
+                .copied()
+                .unwrap_or_default()
+        }))
+    }
+
+    // MARK: vCard
+
+    async fn get_vcard(
+        &self,
+        jid: &BareJid,
+        _caller: &CallerCredentials,
+    ) -> Result<Option<VCard4>, anyhow::Error> {
+        Ok(self.with_state(|state| state.vcards.get(jid).cloned()))
+    }
+
+    async fn set_own_vcard(
+        &self,
+        vcard: VCard4,
+        caller: &CallerCredentials,
+    ) -> Result<(), anyhow::Error> {
+        let jid = caller.bare_jid.to_owned();
+        self.with_state(|state| state.vcards.insert(jid, vcard));
+        Ok(())
+    }
+
+    async fn get_avatar(
+        &self,
+        jid: &BareJid,
+        _caller: &CallerCredentials,
+    ) -> Result<Option<AvatarData>, anyhow::Error> {
+        Ok(self.with_state(|state| state.avatars.get(jid).cloned().map(AvatarData::Data)))
+    }
+
+    async fn set_own_avatar(
+        &self,
+        avatar: Box<[u8]>,
+        caller: &CallerCredentials,
+    ) -> Result<(), anyhow::Error> {
+        let jid = caller.bare_jid.to_owned();
+        self.with_state(|state| state.avatars.insert(jid, avatar));
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+//! Talking to the XMPP server.
+//!
+//! The Server API only supports Prosody for now (see [`ProsodyXmppServer`]),
+//! but everything it needs from the XMPP server goes through
+//! [`XmppServerBackend`] so another server could be plugged in.
+//!
+//! NOTE: Roles are still named after
+//!   [Prosody’s built-in roles](https://prosody.im/doc/roles#built-in-roles)
+//!   (e.g. `prosody:admin`) as they are part of the public API.
+//!   Other implementations have to map them.
+//!
+//! NOTE: Starting and stopping the XMPP server is not done here,
+//!   see [`crate::process_manager`].
+
+#[cfg(test)]
+pub(crate) mod in_memory;
+mod prosody;
+
+pub(crate) use self::prosody::ProsodyXmppServer;
+pub(crate) use prosody_rest::CallerCredentials;
+
+use prosody_rest::prose_xmpp::mods::AvatarData;
+use prosody_rest::prose_xmpp::stanza::VCard4;
+
+use crate::models::{BareJid, JidDomain, JidNode, Password};
+
+pub(crate) type DynXmppServerBackend = dyn XmppServerBackend;
+
+/// Everything the Server API needs from the XMPP server.
+#[async_trait::async_trait]
+pub(crate) trait XmppServerBackend: std::fmt::Debug + Send + Sync {
+    // MARK: Health
+
+    /// Waits for the XMPP server to accept commands (e.g. after it started
+    /// or reloaded).
+    async fn wait_for_readiness(&self) -> Result<(), anyhow::Error>;
+
+    // MARK: Configuration
+
+    /// Generates the minimal configuration the XMPP server needs to start
+    /// before the Server API configures it.
+    ///
+    /// NOTE: Called before the XMPP server starts.
+    fn apply_bootstrap_config(&self, server_domain: &JidDomain) -> Result<(), anyhow::Error>;
+
+    /// Loads modules enabled for `host` after a reload (not all servers
+    /// do it automatically).
+    async fn load_host_modules(&self, host: &JidDomain) -> Result<(), anyhow::Error>;
+
+    /// Directory where the XMPP server reads its configuration from.
+    async fn config_dir(&self) -> Result<String, anyhow::Error>;
+
+    /// Directory where the XMPP server stores its data.
+    async fn data_dir(&self) -> Result<String, anyhow::Error>;
+
+    // MARK: Users
+
+    async fn user_exists(&self, jid: &BareJid) -> Result<bool, anyhow::Error>;
+
+    /// Creates a user account, with an optional primary role.
+    async fn create_user(
+        &self,
+        jid: &BareJid,
+        password: &Password,
+        role: Option<&str>,
+    ) -> Result<(), UserCreateError>;
+
+    async fn set_user_password(
+        &self,
+        jid: &BareJid,
+        password: &Password,
+    ) -> Result<(), anyhow::Error>;
+
+    // MARK: Roles
+
+    /// Primary role of a user.
+    async fn user_role(&self, jid: &BareJid) -> Result<String, anyhow::Error>;
+
+    async fn set_user_role(&self, jid: &BareJid, role: &str) -> Result<(), anyhow::Error>;
+
+    /// Users of `host` whose primary role is `role`.
+    ///
+    /// NOTE: Inherited roles are not taken into account (e.g. admins are not
+    ///   listed as `prosody:member`).
+    async fn users_with_role(
+        &self,
+        host: &JidDomain,
+        role: &str,
+    ) -> Result<Vec<BareJid>, anyhow::Error>;
+
+    // MARK: Groups
+
+    async fn group_exists(&self, host: &JidDomain, group_id: &str) -> Result<bool, anyhow::Error>;
+
+    async fn create_group(
+        &self,
+        host: &JidDomain,
+        group_id: &str,
+        group_name: &str,
+    ) -> Result<(), anyhow::Error>;
+
+    /// Adds a user to a group. If `delay_update` is `true`, rosters won’t
+    /// be updated until [`sync_group`](Self::sync_group) is called.
+    async fn add_group_member(
+        &self,
+        host: &JidDomain,
+        group_id: &str,
+        username: &JidNode,
+        delay_update: bool,
+    ) -> Result<(), anyhow::Error>;
+
+    /// Subscribes all group members to each other.
+    ///
+    /// NOTE: Can be slow (`O(n^2)`).
+    async fn sync_group(&self, host: &JidDomain, group_id: &str) -> Result<(), anyhow::Error>;
+
+    // MARK: Invites
+
+    /// Number of pending invites for `host`.
+    async fn invite_count(&self, host: &JidDomain) -> Result<usize, anyhow::Error>;
+
+    // MARK: vCard
+
+    async fn get_vcard(
+        &self,
+        jid: &BareJid,
+        caller: &CallerCredentials,
+    ) -> Result<Option<VCard4>, anyhow::Error>;
+
+    async fn set_own_vcard(
+        &self,
+        vcard: VCard4,
+        caller: &CallerCredentials,
+    ) -> Result<(), anyhow::Error>;
+
+    /// NOTE: Avatars are not stored in vCards.
+    async fn get_avatar(
+        &self,
+        jid: &BareJid,
+        caller: &CallerCredentials,
+    ) -> Result<Option<AvatarData>, anyhow::Error>;
+
+    async fn set_own_avatar(
+        &self,
+        avatar: Box<[u8]>,
+        caller: &CallerCredentials,
+    ) -> Result<(), anyhow::Error>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum UserCreateError {
+    #[error("User already exists.")]
+    Conflict,
+    #[error("{0:#}")]
+    Internal(#[from] anyhow::Error),
+}
@@ -0,0 +1,306 @@
+// prose-pod-server
+//
+// Copyright: 2026, Rémi Bardon <remi@remibardon.name>
+// License: Mozilla Public License v2.0 (MPL v2.0)
+
+use std::path::Path;
+use std::str::FromStr as _;
+
+use anyhow::Context as _;
+use prosody_rest::ProsodyRest;
+use prosody_rest::prose_xmpp::mods::AvatarData;
+use prosody_rest::prose_xmpp::stanza::VCard4;
+use prosodyctl::Prosodyctl;
+use tokio::sync::Mutex;
+
+use crate::models::{BareJid, JidDomain, JidNode, Password};
+use crate::util::{PROSODY_JIDS_ARE_VALID, unix_timestamp};
+
+use super::{CallerCredentials, UserCreateError, XmppServerBackend};
+
+const PROSODY_CONFIG_FILE_PATH: &'static str = "/etc/prosody/prosody.cfg.lua";
+
+/// Prosody, driven through its admin shell (`prosodyctl shell`) and
+/// [`mod_rest`](https://hg.prosody.im/prosody-modules/file/tip/mod_rest).
+#[derive(Debug)]
+pub(crate) struct ProsodyXmppServer {
+    // FIXME: Replace calls to `prosodyctl` by calls to
+    //   Prosody modules to avoid blocking shared access
+    //   to `prosodyctl` (all calls are mutating).
+    prosodyctl: Mutex<Prosodyctl>,
+    prosody_rest: ProsodyRest,
+}
+
+impl ProsodyXmppServer {
+    /// NOTE: This constructor is lazy. It doesn’t connect to Prosody.
+    pub(crate) fn new(server_http_url: String) -> Self {
+        Self {
+            prosodyctl: Mutex::new(Prosodyctl::new()),
+            prosody_rest: ProsodyRest::standard(server_http_url),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl XmppServerBackend for ProsodyXmppServer {
+    // MARK: Health
+
+    async fn wait_for_readiness(&self) -> Result<(), anyhow::Error> {
+        self.prosodyctl.lock().await.wait_for_readiness().await
+    }
+
+    // MARK: Configuration
+
+    fn apply_bootstrap_config(&self, server_domain: &JidDomain) -> Result<(), anyhow::Error> {
+        use std::fs::File;
+        use std::io::Write as _;
+
+        backup_prosody_conf_if_needed()?;
+
+        let mut prosody_config_file = File::options()
+            .write(true)
+            .truncate(true)
+            .create(true)
+            .open(PROSODY_CONFIG_FILE_PATH)
+            .context("Error opening Prosody config file")?;
+
+        let bootstrap_config_template = include_str!("../prosody-bootstrap.cfg.lua");
+
+        let bootstrap_config =
+            bootstrap_config_template.replace("{{server_domain}}", server_domain);
+
+        prosody_config_file
+            .write_all(bootstrap_config.as_bytes())
+            .context("Error writing Prosody config file")?;
+
+        Ok(())
+    }
+
+    async fn load_host_modules(&self, host: &JidDomain) -> Result<(), anyhow::Error> {
+        let mut prosodyctl = self.prosodyctl.lock().await;
+        prosodyctl.module_load_modules_for_host(host).await
+    }
+
+    async fn config_dir(&self) -> Result<String, anyhow::Error> {
+        self.prosodyctl.lock().await.prosody_paths_config().await
+    }
+
+    async fn data_dir(&self) -> Result<String, anyhow::Error> {
+        self.prosodyctl.lock().await.prosody_paths_data().await
+    }
+
+    // MARK: Users
+
+    async fn user_exists(&self, jid: &BareJid) -> Result<bool, anyhow::Error> {
+        let Some(username) = jid.node() else {
+            return Ok(false);
+        };
+
+        let mut prosodyctl = self.prosodyctl.lock().await;
+        prosodyctl.user_exists(username, jid.domain()).await
+    }
+
+    async fn create_user(
+        &self,
+        jid: &BareJid,
+        password: &Password,
+        role: Option<&str>,
+    ) -> Result<(), UserCreateError> {
+        let mut prosodyctl = self.prosodyctl.lock().await;
+
+        let summary = prosodyctl
+            .user_create(jid.as_str(), password, role)
+            .await
+            .map_err(|err| match err {
+                prosodyctl::UserCreateError::Conflict => UserCreateError::Conflict,
+                prosodyctl::UserCreateError::Internal(error) => UserCreateError::Internal(error),
+            })?;
+        tracing::info!("user_create: {summary}");
+
+        Ok(())
+    }
+
+    async fn set_user_password(
+        &self,
+        jid: &BareJid,
+        password: &Password,
+    ) -> Result<(), anyhow::Error> {
+        let mut prosodyctl = self.prosodyctl.lock().await;
+
+        let summary = prosodyctl.user_password(jid.as_str(), password).await?;
+        tracing::info!("user_password: {summary}");
+
+        Ok(())
+    }
+
+    // MARK: Roles
+
+    async fn user_role(&self, jid: &BareJid) -> Result<String, anyhow::Error> {
+        let mut prosodyctl = self.prosodyctl.lock().await;
+        prosodyctl.user_role(jid.as_str(), None).await
+    }
+
+    async fn set_user_role(&self, jid: &BareJid, role: &str) -> Result<(), anyhow::Error> {
+        let mut prosodyctl = self.prosodyctl.lock().await;
+
+        let summary = prosodyctl.user_set_role(jid.as_str(), None, role).await?;
+        tracing::info!("user_set_role: {summary}");
+
+        Ok(())
+    }
+
+    async fn users_with_role(
+        &self,
+        host: &JidDomain,
+        role: &str,
+    ) -> Result<Vec<BareJid>, anyhow::Error> {
+        let mut prosodyctl = self.prosodyctl.lock().await;
+
+        let jids = prosodyctl.user_get_jids_with_role(host, role).await?;
+
+        // Release lock ASAP.
+        drop(prosodyctl);
+
+        Ok((jids.iter())
+            .map(|str| BareJid::from_str(str).expect(PROSODY_JIDS_ARE_VALID))
+            .collect())
+    }
+
+    // MARK: Groups
+
+    async fn group_exists(&self, host: &JidDomain, group_id: &str) -> Result<bool, anyhow::Error> {
+        let mut prosodyctl = self.prosodyctl.lock().await;
+        prosodyctl.groups_exists(host, group_id).await
+    }
+
+    async fn create_group(
+        &self,
+        host: &JidDomain,
+        group_id: &str,
+        group_name: &str,
+    ) -> Result<(), anyhow::Error> {
+        let mut prosodyctl = self.prosodyctl.lock().await;
+
+        let summary = prosodyctl
+            .groups_create(host, group_name, None, Some(group_id))
+            .await?;
+        tracing::info!("groups_create: {summary}");
+
+        Ok(())
+    }
+
+    async fn add_group_member(
+        &self,
+        host: &JidDomain,
+        group_id: &str,
+        username: &JidNode,
+        delay_update: bool,
+    ) -> Result<(), anyhow::Error> {
+        let mut prosodyctl = self.prosodyctl.lock().await;
+
+        let summary = prosodyctl
+            .groups_add_member(host, group_id, username, delay_update.then_some(true))
+            .await?;
+        tracing::info!("groups_add_member: {summary}");
+
+        Ok(())
+    }
+
+    async fn sync_group(&self, host: &JidDomain, group_id: &str) -> Result<(), anyhow::Error> {
+        let mut prosodyctl = self.prosodyctl.lock().await;
+
+        let summary = prosodyctl.groups_sync(host, group_id).await?;
+        tracing::info!("groups_sync: {summary}");
+
+        Ok(())
+    }
+
+    // MARK: Invites
+
+    async fn invite_count(&self, host: &JidDomain) -> Result<usize, anyhow::Error> {
+        let mut prosodyctl = self.prosodyctl.lock().await;
+        Ok(prosodyctl.invite_list(host).await?.len())
+    }
+
+    // MARK: vCard
+
+    async fn get_vcard(
+        &self,
+        jid: &BareJid,
+        caller: &CallerCredentials,
+    ) -> Result<Option<VCard4>, anyhow::Error> {
+        Ok(self.prosody_rest.get_vcard(jid, caller).await?)
+    }
+
+    async fn set_own_vcard(
+        &self,
+        vcard: VCard4,
+        caller: &CallerCredentials,
+    ) -> Result<(), anyhow::Error> {
+        Ok(self.prosody_rest.set_own_vcard(vcard, caller).await?)
+    }
+
+    async fn get_avatar(
+        &self,
+        jid: &BareJid,
+        caller: &CallerCredentials,
+    ) -> Result<Option<AvatarData>, anyhow::Error> {
+        Ok(self.prosody_rest.get_avatar(jid, caller).await?)
+    }
+
+    async fn set_own_avatar(
+        &self,
+        avatar: Box<[u8]>,
+        caller: &CallerCredentials,
+    ) -> Result<(), anyhow::Error> {
+        Ok(self.prosody_rest.set_own_avatar(avatar, caller).await?)
+    }
+}
+
+// MARK: - Helpers
+
+fn backup_prosody_conf_if_needed() -> Result<(), anyhow::Error> {
+    use std::fs::File;
+    use std::io::{self, Read as _};
+
+    let prosody_config_file_path = Path::new(PROSODY_CONFIG_FILE_PATH);
+
+    // Back up the Prosody configuration if it was not generated by Prose.
+    // This is just to avoid a bad surprise to anyone deploying Prose on an
+    // existing Prosody instance.
+    match File::options().read(true).open(prosody_config_file_path) {
+        Ok(mut prosody_config_file) => {
+            let prose_header = "-- Prose Pod Server";
+            let mut buffer = vec![0u8; prose_header.len()];
+
+            // Read the first few bytes to check the header.
+            let bytes_read = prosody_config_file
+                .read(&mut buffer)
+                .context("Error reading Prosody config file")?;
+            buffer.truncate(bytes_read);
+
+            if buffer != prose_header.as_bytes() {
+                let mut new_path = prosody_config_file_path.to_path_buf();
+                let unix_timestamp = unix_timestamp();
+                new_path.set_file_name(format!("prosody.prose-backup-{unix_timestamp}.cfg.lua"));
+
+                tracing::info!(
+                    "The Prosody configuration file at <{old_path}> was not generated by Prose. \
+                    To prevent data loss, it will be backed up as <{new_path}>.",
+                    old_path = prosody_config_file_path.display(),
+                    new_path = new_path.display(),
+                )
+            }
+
+            Ok(())
+        }
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            // Prosody config file does not exist already, nothing to back up.
+            Ok(())
+        }
+        Err(err) => Err(anyhow::Error::new(err).context(format!(
+            "Error opening <{path}>",
+            path = prosody_config_file_path.display(),
+        ))),
+    }
+}